@@ -0,0 +1,76 @@
+//! Compares the broad phase strategies on randomly placed boxes at a constant density.
+//! Run with `cargo bench --bench broad_phase`.
+
+use std::time::{Duration, Instant};
+
+use mathematics::linear_algebra::vec3;
+use mathematics::physics::broad_phase::BroadPhaseType;
+use mathematics::physics::collider::bounds::Bounds;
+
+///Small deterministic xorshift generator so runs are comparable.
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 11) as f64 / (1_u64 << 53) as f64
+    }
+}
+
+fn scene(count: usize, random: &mut Random) -> Vec<Bounds> {
+    //Keep roughly the same number of neighbours per box regardless of count.
+    let side = (count as f64).cbrt() * 4.0;
+    (0..count).map(|_| Bounds {
+        center: vec3(random.next() * side, random.next() * side, random.next() * side),
+        extents: vec3(0.5, 0.5, 0.5),
+    }).collect()
+}
+
+fn measure(broad_phase_type: BroadPhaseType, boxes: &[Bounds], random: &mut Random) -> (Duration, Duration, usize) {
+    let mut broad_phase = broad_phase_type.create();
+
+    let start = Instant::now();
+    for (id, bounds) in boxes.iter().enumerate() {
+        broad_phase.insert(id as u32, *bounds);
+    }
+    let pairs = broad_phase.overlapping_pairs().len();
+    let build = start.elapsed();
+
+    //Ten steps of small coherent motion, as a simulation would produce.
+    let mut moved = boxes.to_vec();
+    let start = Instant::now();
+    for _ in 0..10 {
+        for (id, bounds) in moved.iter_mut().enumerate() {
+            bounds.center += vec3(random.next() - 0.5, random.next() - 0.5, random.next() - 0.5) * 0.05;
+            broad_phase.update(id as u32, *bounds);
+        }
+        broad_phase.overlapping_pairs();
+    }
+    let step = start.elapsed() / 10;
+
+    (build, step, pairs)
+}
+
+fn main() {
+    let types = [BroadPhaseType::BruteForce, BroadPhaseType::SweepAndPrune, BroadPhaseType::DynamicTree];
+
+    println!("{:>7} {:>15} {:>12} {:>12} {:>8}", "bodies", "broad phase", "build", "step", "pairs");
+    for count in [100, 1_000, 10_000] {
+        let mut random = Random(0x2545F4914F6CDD1D);
+        let boxes = scene(count, &mut random);
+
+        for broad_phase_type in types {
+            let (build, step, pairs) = measure(broad_phase_type, &boxes, &mut random);
+            println!(
+                "{:>7} {:>15} {:>12} {:>12} {:>8}",
+                count,
+                format!("{:?}", broad_phase_type),
+                format!("{:.3?}", build),
+                format!("{:.3?}", step),
+                pairs
+            );
+        }
+    }
+}
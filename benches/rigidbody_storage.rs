@@ -0,0 +1,61 @@
+//! Compares integrating rigidbodies kept behind Arc<Mutex> against the World's structure of arrays body store, serially and across threads.
+//! Run with `cargo bench --bench rigidbody_storage`.
+
+use std::time::{Duration, Instant};
+
+use mathematics::linear_algebra::vec3;
+use mathematics::physics::body_store::BodyStore;
+use mathematics::physics::rigidbody::Rigidbody;
+
+const STEPS: usize = 100;
+const DELTA: f64 = 1.0 / 60.0;
+
+fn rigidbodies(count: usize) -> Vec<Rigidbody> {
+    (0..count).map(|i| {
+        let rigidbody = Rigidbody::default();
+        rigidbody.set_position(vec3(i as f64, 0.0, 0.0));
+        rigidbody.set_angular_velocity(vec3(0.0, 1.0, 0.0));
+        rigidbody
+    }).collect()
+}
+
+fn measure_locked(count: usize) -> Duration {
+    let mut rigidbodies = rigidbodies(count);
+    let gravity = vec3(0.0, -9.81, 0.0);
+    let start = Instant::now();
+    for _ in 0..STEPS {
+        for rigidbody in &mut rigidbodies {
+            rigidbody.update(DELTA, &gravity);
+        }
+    }
+    start.elapsed()
+}
+
+fn measure_store(count: usize, threads: usize) -> Duration {
+    let mut store = BodyStore::new();
+    for rigidbody in rigidbodies(count) {
+        store.insert(&rigidbody);
+    }
+    let gravity = vec3(0.0, -9.81, 0.0);
+    let start = Instant::now();
+    for _ in 0..STEPS {
+        store.integrate_velocities(gravity, DELTA, threads);
+        store.integrate_positions(DELTA, threads);
+    }
+    start.elapsed()
+}
+
+fn main() {
+    let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    println!("{:>8} {:>14} {:>14} {:>14}", "bodies", "Arc<Mutex>", "store", format!("store x{threads}"));
+    for count in [1_000, 10_000, 100_000, 1_000_000] {
+        let per_step = |duration: Duration| format!("{:.3?}", duration / STEPS as u32);
+        println!(
+            "{:>8} {:>14} {:>14} {:>14}",
+            count,
+            per_step(measure_locked(count)),
+            per_step(measure_store(count, 1)),
+            per_step(measure_store(count, threads)),
+        );
+    }
+}
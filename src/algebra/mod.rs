@@ -27,4 +27,10 @@ pub fn slope_xx_yy<N: Number>(x1: N, y1: N, x2: N, y2: N) -> N {
 
 pub fn slope_deltax_deltay<N: Number>(delta_x: N, delta_y: N) -> N {
     delta_y / delta_x
-}
\ No newline at end of file
+}
+
+///The binomial coefficient n choose k, the number of ways to pick k of n things, for k up to n.
+pub(crate) fn binomial(n: usize, k: usize) -> f64 {
+    let k = k.min(n - k);
+    (0..k).fold(1_f64, |value, i| value * (n - i) as f64 / (i + 1) as f64)
+}
@@ -0,0 +1,208 @@
+use crate::geometry::bezier::Bezier;
+use crate::linear_algebra::vector::Vector;
+use crate::shared::traits::number::Number;
+
+///Nodes and weights of 5 point Gauss-Legendre quadrature on [-1, 1].
+const GAUSS_LEGENDRE: [(f64, f64); 5] = [
+    (0_f64, 0.568_888_888_888_888_9),
+    (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+    (0.906_179_845_938_664, 0.236_926_885_056_189_1),
+];
+
+///Error in a length, in multiples of the number type's epsilon relative to the length of the control polygon,
+///past which the integral is split further, so it stays within what the type can resolve.
+const LENGTH_TOLERANCE: f64 = 16_f64;
+
+const MAX_LENGTH_DEPTH: usize = 16;
+
+const MAX_FLATTEN_DEPTH: usize = 24;
+
+const MAX_NEWTON_ITERATIONS: usize = 32;
+
+impl<const L: usize, N: Number> Bezier<L, N> {
+    ///Length of the curve.
+    pub fn arc_length(&self) -> N {
+        self.length_between(N::ZERO, N::ONE)
+    }
+
+    ///Length of the curve from its start to t.
+    pub fn length_at(&self, t: N) -> N {
+        self.length_between(N::ZERO, t.clamp(N::ZERO, N::ONE))
+    }
+
+    ///Length of the curve between two values of t, integrating its speed with adaptive Gauss-Legendre quadrature.
+    pub fn length_between(&self, from: N, to: N) -> N {
+        if to <= from {
+            return N::ZERO;
+        }
+        integrate(&self.derivative(), from, to, self.length_tolerance())
+    }
+
+    fn length_tolerance(&self) -> N {
+        let polygon = self.points().windows(2).map(|pair| pair[0].distance(&pair[1])).sum::<N>();
+        polygon * N::EPSILON * N::from_f64(LENGTH_TOLERANCE)
+    }
+
+    ///The t at which the curve has covered a distance from its start, clamped to its ends.
+    pub fn parameter_at_distance(&self, distance: N) -> N {
+        self.parameter_after(&self.derivative(), N::ZERO, distance)
+    }
+
+    ///The point a distance along the curve from its start.
+    pub fn point_at_distance(&self, distance: N) -> Vector<L, N> {
+        self.interpolate(self.parameter_at_distance(distance))
+    }
+
+    ///Points spaced evenly along the curve by distance rather than t, including both ends.
+    pub fn sample_evenly(&self, count: usize) -> Vec<Vector<L, N>> {
+        if count < 2 {
+            return self.sample(count);
+        }
+        let derivative = self.derivative();
+        let spacing = self.arc_length() / N::from_f64((count - 1) as f64);
+        let mut t = N::ZERO;
+        let mut output = Vec::with_capacity(count);
+        output.push(*self.first());
+        for _ in 1..count - 1 {
+            t = self.parameter_after(&derivative, t, spacing);
+            output.push(self.interpolate(t));
+        }
+        output.push(*self.last());
+        output
+    }
+
+    ///The t a distance past another t, found with Newton's method on the length, kept inside a shrinking bracket.
+    fn parameter_after(&self, derivative: &Self, start: N, distance: N) -> N {
+        if distance <= N::ZERO {
+            return start;
+        }
+        let tolerance = self.length_tolerance();
+        let total = integrate(derivative, start, N::ONE, tolerance);
+        if distance >= total {
+            return N::ONE;
+        }
+
+        let (mut low, mut high) = (start, N::ONE);
+        let mut t = start + (N::ONE - start) * distance / total;
+        for _ in 0..MAX_NEWTON_ITERATIONS {
+            let error = integrate(derivative, start, t, tolerance) - distance;
+            if error.absolute() <= tolerance {
+                break;
+            }
+            if error > N::ZERO {
+                high = t;
+            } else {
+                low = t;
+            }
+            let speed = derivative.interpolate(t).magnitude();
+            let next = if speed > N::ZERO { t - error / speed } else { low - N::ONE };
+            //Newton stepped outside what's known to hold the answer, so bisect instead.
+            t = if next > low && next < high { next } else { (low + high) / N::TWO };
+            if high - low <= N::EPSILON {
+                break;
+            }
+        }
+        t
+    }
+
+    ///The curve as a polyline whose segments stray from it by no more than the tolerance, with more points where it bends more.
+    ///<br>The curve is halved until the control points of each piece lie within the tolerance of the line between its ends,
+    ///which the curve, staying within its control points, then also does.
+    pub fn flatten(&self, tolerance: N) -> Vec<Vector<L, N>> {
+        let mut output = vec![*self.first()];
+        self.flatten_into(tolerance, 0, &mut output);
+        output
+    }
+
+    fn flatten_into(&self, tolerance: N, depth: usize, output: &mut Vec<Vector<L, N>>) {
+        let (first, last) = (*self.first(), *self.last());
+        let flat = self.points().iter().all(|point| distance_to_segment(*point, first, last) <= tolerance);
+        if flat || depth == MAX_FLATTEN_DEPTH {
+            output.push(last);
+            return;
+        }
+        let (before, after) = self.split(N::ONE / N::TWO);
+        before.flatten_into(tolerance, depth + 1, output);
+        after.flatten_into(tolerance, depth + 1, output);
+    }
+
+    ///The point of the curve nearest to a point, as its t, the point itself and the distance to it.
+    ///<br>The curve is sampled to find roughly where, then Newton's method finds where the offset to the point is perpendicular to the curve.
+    pub fn closest_point(&self, point: Vector<L, N>) -> (N, Vector<L, N>, N) {
+        let count = 8 * (self.degree() + 1);
+        let step = N::ONE / N::from_f64(count as f64);
+        let (mut best, mut best_distance) = (N::ZERO, N::ZERO);
+        for i in 0..=count {
+            let t = N::from_f64(i as f64) * step;
+            let distance = self.interpolate(t).distance(&point);
+            if i == 0 || distance < best_distance {
+                (best, best_distance) = (t, distance);
+            }
+        }
+
+        let first = self.derivative();
+        let second = first.derivative();
+        let (low, high) = ((best - step).num_max(&N::ZERO), (best + step).num_min(&N::ONE));
+        let mut t = best;
+        for _ in 0..MAX_NEWTON_ITERATIONS {
+            let offset = self.interpolate(t) - point;
+            let velocity = first.interpolate(t);
+            let slope = velocity.dot(&offset);
+            let curvature = second.interpolate(t).dot(&offset) + velocity.dot(&velocity);
+            if curvature <= N::ZERO {
+                break;
+            }
+            let next = (t - slope / curvature).clamp(low, high);
+            if (next - t).absolute() < N::from_f64(1e-15) {
+                t = next;
+                break;
+            }
+            t = next;
+        }
+
+        let refined = self.interpolate(t);
+        let distance = refined.distance(&point);
+        if distance <= best_distance {
+            (t, refined, distance)
+        } else {
+            (best, self.interpolate(best), best_distance)
+        }
+    }
+}
+
+fn gauss_legendre<const L: usize, N: Number>(derivative: &Bezier<L, N>, from: N, to: N) -> N {
+    let half = (to - from) / N::TWO;
+    let middle = (to + from) / N::TWO;
+    GAUSS_LEGENDRE.iter()
+        .map(|(node, weight)| N::from_f64(*weight) * derivative.interpolate(middle + half * N::from_f64(*node)).magnitude())
+        .sum::<N>() * half
+}
+
+fn integrate<const L: usize, N: Number>(derivative: &Bezier<L, N>, from: N, to: N, tolerance: N) -> N {
+    adaptive_length(derivative, from, to, gauss_legendre(derivative, from, to), tolerance, 0)
+}
+
+fn adaptive_length<const L: usize, N: Number>(derivative: &Bezier<L, N>, from: N, to: N, whole: N, tolerance: N, depth: usize) -> N {
+    if depth >= MAX_LENGTH_DEPTH {
+        return whole;
+    }
+    let middle = (from + to) / N::TWO;
+    let (before, after) = (gauss_legendre(derivative, from, middle), gauss_legendre(derivative, middle, to));
+    if (before + after - whole).absolute() <= tolerance {
+        return before + after;
+    }
+    let tolerance = tolerance / N::TWO;
+    adaptive_length(derivative, from, middle, before, tolerance, depth + 1) + adaptive_length(derivative, middle, to, after, tolerance, depth + 1)
+}
+
+fn distance_to_segment<const L: usize, N: Number>(point: Vector<L, N>, a: Vector<L, N>, b: Vector<L, N>) -> N {
+    let along = b - a;
+    let length = along.magnitude_squared();
+    if length <= N::ZERO {
+        return point.distance(&a);
+    }
+    let t = ((point - a).dot(&along) / length).clamp(N::ZERO, N::ONE);
+    point.distance(&(a + along * t))
+}
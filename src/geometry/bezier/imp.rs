@@ -0,0 +1,290 @@
+use crate::algebra::binomial;
+use std::ops::{AddAssign, Mul};
+
+use crate::geometry::bezier::Bezier;
+use crate::linear_algebra::vector::Vector;
+use crate::shared::traits::number::Number;
+
+///How many times a curve is halved while searching for its extremes.
+const BOUNDS_DEPTH: usize = 24;
+
+impl<const L: usize, N: Number> Bezier<L, N> {
+    ///A polynomial curve through the provided control points, of degree one less than their count.
+    pub fn new(points: Vec<Vector<L, N>>) -> Self {
+        assert!(!points.is_empty(), "A Bézier curve needs at least one control point");
+        Bezier::Polynomial { points }
+    }
+
+    ///A rational curve with a weight for every control point.
+    pub fn rational(points: Vec<Vector<L, N>>, weights: Vec<N>) -> Self {
+        assert!(!points.is_empty(), "A Bézier curve needs at least one control point");
+        assert_eq!(points.len(), weights.len(), "A rational Bézier curve needs a weight for every control point");
+        Bezier::Rational { points, weights }
+    }
+
+    pub fn points(&self) -> &[Vector<L, N>] {
+        match self {
+            Bezier::Polynomial { points } => points,
+            Bezier::Rational { points, .. } => points,
+        }
+    }
+
+    ///The weights of a rational curve, none for a polynomial one where they are all one.
+    pub fn weights(&self) -> Option<&[N]> {
+        match self {
+            Bezier::Polynomial { .. } => None,
+            Bezier::Rational { weights, .. } => Some(weights),
+        }
+    }
+
+    pub fn is_rational(&self) -> bool {
+        matches!(self, Bezier::Rational { .. })
+    }
+
+    pub fn degree(&self) -> usize {
+        self.points().len() - 1
+    }
+
+    pub fn first(&self) -> &Vector<L, N> {
+        &self.points()[0]
+    }
+
+    pub fn last(&self) -> &Vector<L, N> {
+        &self.points()[self.degree()]
+    }
+
+    ///The point on the curve at t, between 0 at the first control point and 1 at the last.
+    pub fn interpolate(&self, t: N) -> Vector<L, N> {
+        let (point, weight) = de_casteljau(&self.homogeneous(), t);
+        point / weight
+    }
+
+    ///The curve's derivative with respect to t, itself a curve.
+    ///<br>A polynomial curve's derivative is its hodograph, one degree lower.
+    ///A rational curve's is the quotient rule's (A'W - AW') / W², a rational curve of twice the degree.
+    pub fn derivative(&self) -> Self {
+        let n = self.degree();
+        match self {
+            Bezier::Polynomial { points } => {
+                if n == 0 {
+                    return Bezier::Polynomial { points: vec![Vector::ZERO] };
+                }
+                let degree = N::from_f64(n as f64);
+                Bezier::Polynomial {
+                    points: points.windows(2).map(|pair| (pair[1] - pair[0]) * degree).collect(),
+                }
+            }
+            Bezier::Rational { weights, .. } => {
+                let homogeneous = self.homogeneous();
+                if n == 0 {
+                    return Bezier::Rational { points: vec![Vector::ZERO], weights: vec![weights[0] * weights[0]] };
+                }
+                let degree = N::from_f64(n as f64);
+                let numerator: Vec<Vector<L, N>> = homogeneous.windows(2).map(|pair| (pair[1].0 - pair[0].0) * degree).collect();
+                let weight_derivative: Vec<N> = weights.windows(2).map(|pair| (pair[1] - pair[0]) * degree).collect();
+
+                //Both products are of degree 2n - 1, raised to 2n to match the squared weights.
+                let a = bernstein_product(&numerator, weights);
+                let b = bernstein_product(&homogeneous.iter().map(|(point, _)| *point).collect::<Vec<_>>(), &weight_derivative);
+                let difference: Vec<(Vector<L, N>, N)> = a.iter().zip(&b).map(|(a, b)| (*a - *b, N::ONE)).collect();
+                let difference = elevate(&difference);
+                let squared = bernstein_product(weights, weights);
+
+                Bezier::Rational {
+                    points: difference.iter().zip(&squared).map(|((point, _), weight)| *point / *weight).collect(),
+                    weights: squared,
+                }
+            }
+        }
+    }
+
+    ///The first derivative at t, the direction and speed the curve moves in there.
+    ///<br>Cheaper than [Bezier::derivative] for a single point of a rational curve.
+    pub fn derivative_at(&self, t: N) -> Vector<L, N> {
+        let n = self.degree();
+        if n == 0 {
+            return Vector::ZERO;
+        }
+        let homogeneous = self.homogeneous();
+        let degree = N::from_f64(n as f64);
+        let (point, weight) = de_casteljau(&homogeneous, t);
+        let hodograph: Vec<(Vector<L, N>, N)> = homogeneous.windows(2)
+            .map(|pair| ((pair[1].0 - pair[0].0) * degree, (pair[1].1 - pair[0].1) * degree))
+            .collect();
+        let (point_derivative, weight_derivative) = de_casteljau(&hodograph, t);
+        (point_derivative - point / weight * weight_derivative) / weight
+    }
+
+    ///The same curve with one more control point.
+    pub fn elevate(&self) -> Self {
+        self.from_homogeneous(elevate(&self.homogeneous()))
+    }
+
+    ///A curve with one less control point as close to this one as the endpoints allow, exactly this one if it was raised from it.
+    ///<br>Uses Forrest's method, which solves the elevation equations from the start for the first half of the points and from the end for the rest.
+    ///<br>Returns none for a single point, which can't be lowered further.
+    pub fn reduce(&self) -> Option<Self> {
+        let n = self.degree();
+        if n == 0 {
+            return None;
+        }
+        let homogeneous = self.homogeneous();
+        let degree = N::from_f64(n as f64);
+
+        let mut forward = vec![homogeneous[0]; n];
+        for i in 1..n {
+            let index = N::from_f64(i as f64);
+            let remaining = N::from_f64((n - i) as f64);
+            forward[i] = (
+                (homogeneous[i].0 * degree - forward[i - 1].0 * index) / remaining,
+                (homogeneous[i].1 * degree - forward[i - 1].1 * index) / remaining,
+            );
+        }
+
+        let mut backward = vec![homogeneous[n]; n];
+        for i in (1..n).rev() {
+            let index = N::from_f64(i as f64);
+            let remaining = N::from_f64((n - i) as f64);
+            backward[i - 1] = (
+                (homogeneous[i].0 * degree - backward[i].0 * remaining) / index,
+                (homogeneous[i].1 * degree - backward[i].1 * remaining) / index,
+            );
+        }
+
+        let half = (n - 1) / 2;
+        let reduced = (0..n).map(|i| if i <= half { forward[i] } else { backward[i] }).collect();
+        Some(self.from_homogeneous(reduced))
+    }
+
+    ///Splits the curve at t into the part before and the part after, each of the same degree and together tracing the same path.
+    pub fn split(&self, t: N) -> (Self, Self) {
+        let mut points = self.homogeneous();
+        let n = points.len();
+        let mut left = Vec::with_capacity(n);
+        let mut right = Vec::with_capacity(n);
+        left.push(points[0]);
+        right.push(points[n - 1]);
+        for level in 1..n {
+            for i in 0..n - level {
+                points[i] = lerp(points[i], points[i + 1], t);
+            }
+            left.push(points[0]);
+            right.push(points[n - level - 1]);
+        }
+        right.reverse();
+        (self.from_homogeneous(left), self.from_homogeneous(right))
+    }
+
+    ///The portion of the curve between two values of t.
+    pub fn segment(&self, from: N, to: N) -> Self {
+        let (_, after) = self.split(from);
+        if from >= N::ONE {
+            return after;
+        }
+        let (before, _) = after.split((to - from) / (N::ONE - from));
+        before
+    }
+
+    ///The box around the control points, which always contains the curve but is usually larger than it.
+    pub fn hull_bounds(&self) -> (Vector<L, N>, Vector<L, N>) {
+        let points = self.points();
+        points.iter().fold((points[0], points[0]), |(min, max), point| (min.min(point), max.max(point)))
+    }
+
+    ///The smallest box around the curve, as its lowest then highest corner.
+    ///<br>Found by halving the curve wherever its control points stick out of the box so far, until they don't or the pieces are tiny.
+    pub fn bounds(&self) -> (Vector<L, N>, Vector<L, N>) {
+        let (first, last) = (self.first(), self.last());
+        let mut bounds = (first.min(last), first.max(last));
+        self.expand_bounds(&mut bounds, 0);
+        bounds
+    }
+
+    fn expand_bounds(&self, bounds: &mut (Vector<L, N>, Vector<L, N>), depth: usize) {
+        let (min, max) = self.hull_bounds();
+        if (0..L).all(|i| min[i] >= bounds.0[i] && max[i] <= bounds.1[i]) {
+            return;
+        }
+        if depth == BOUNDS_DEPTH {
+            *bounds = (bounds.0.min(&min), bounds.1.max(&max));
+            return;
+        }
+        let (before, after) = self.split(N::ONE / N::TWO);
+        let middle = after.first();
+        *bounds = (bounds.0.min(middle), bounds.1.max(middle));
+        before.expand_bounds(bounds, depth + 1);
+        after.expand_bounds(bounds, depth + 1);
+    }
+
+    ///The curve's points evenly spaced in t, including both ends.
+    pub fn sample(&self, count: usize) -> Vec<Vector<L, N>> {
+        match count {
+            0 => vec![],
+            1 => vec![*self.first()],
+            _ => (0..count).map(|i| self.interpolate(N::from_f64(i as f64 / (count - 1) as f64))).collect(),
+        }
+    }
+
+    ///Control points multiplied by their weights, paired with the weights.
+    fn homogeneous(&self) -> Vec<(Vector<L, N>, N)> {
+        match self {
+            Bezier::Polynomial { points } => points.iter().map(|point| (*point, N::ONE)).collect(),
+            Bezier::Rational { points, weights } => points.iter().zip(weights).map(|(point, weight)| (*point * *weight, *weight)).collect(),
+        }
+    }
+
+    ///A curve of the same kind as this one from homogeneous control points.
+    fn from_homogeneous(&self, homogeneous: Vec<(Vector<L, N>, N)>) -> Self {
+        match self {
+            Bezier::Polynomial { .. } => Bezier::Polynomial {
+                points: homogeneous.into_iter().map(|(point, weight)| point / weight).collect(),
+            },
+            Bezier::Rational { .. } => Bezier::Rational {
+                points: homogeneous.iter().map(|(point, weight)| *point / *weight).collect(),
+                weights: homogeneous.iter().map(|(_, weight)| *weight).collect(),
+            },
+        }
+    }
+}
+
+fn lerp<const L: usize, N: Number>(a: (Vector<L, N>, N), b: (Vector<L, N>, N), t: N) -> (Vector<L, N>, N) {
+    let inv_t = N::ONE - t;
+    (a.0 * inv_t + b.0 * t, a.1 * inv_t + b.1 * t)
+}
+
+fn de_casteljau<const L: usize, N: Number>(points: &[(Vector<L, N>, N)], t: N) -> (Vector<L, N>, N) {
+    let mut points = points.to_vec();
+    for level in 1..points.len() {
+        for i in 0..points.len() - level {
+            points[i] = lerp(points[i], points[i + 1], t);
+        }
+    }
+    points[0]
+}
+
+///Control points of the same curve one degree higher: Q_i = i/(n+1) P_(i-1) + (1 - i/(n+1)) P_i.
+fn elevate<const L: usize, N: Number>(points: &[(Vector<L, N>, N)]) -> Vec<(Vector<L, N>, N)> {
+    let n = points.len();
+    let mut elevated = Vec::with_capacity(n + 1);
+    elevated.push(points[0]);
+    for i in 1..n {
+        let t = N::from_f64(i as f64 / n as f64);
+        elevated.push(lerp(points[i], points[i - 1], t));
+    }
+    elevated.push(points[n - 1]);
+    elevated
+}
+
+///Bernstein coefficients of the product of two polynomials in Bernstein form, of degrees m and n.
+///<br>Uses B_i^m B_j^n = C(m, i) C(n, j) / C(m + n, i + j) B_(i+j)^(m+n).
+fn bernstein_product<T: Copy + Default + AddAssign + Mul<N, Output = T>, N: Number>(a: &[T], b: &[N]) -> Vec<T> {
+    let (m, n) = (a.len() - 1, b.len() - 1);
+    let mut product = vec![T::default(); m + n + 1];
+    for (i, a) in a.iter().enumerate() {
+        for (j, b) in b.iter().enumerate() {
+            let scale = binomial(m, i) * binomial(n, j) / binomial(m + n, i + j);
+            product[i + j] += *a * (*b * N::from_f64(scale));
+        }
+    }
+    product
+}
@@ -1,3 +1,22 @@
-pub enum Bezier {
+use crate::linear_algebra::vector::Vector;
+use crate::shared::traits::number::Number;
 
-}
\ No newline at end of file
+pub mod imp;
+pub mod arc_length;
+
+///A Bézier curve of any degree through any number of dimensions, with as many control points as its degree plus one.
+///<br>Points along it are found with de Casteljau's algorithm, which only ever interpolates between control points and so stays stable at high degrees.
+///<br>See: <a href="https://en.wikipedia.org/wiki/B%C3%A9zier_curve">Bézier curve</a>
+#[derive(Debug, Clone, PartialEq)]
+pub enum Bezier<const L: usize, N: Number> {
+    Polynomial {
+        points: Vec<Vector<L, N>>,
+    },
+    ///Each control point pulls the curve towards itself in proportion to its weight, which allows exact conic sections like circular arcs.
+    ///<br>Weights are expected to be positive.
+    ///<br>See: <a href="https://en.wikipedia.org/wiki/B%C3%A9zier_curve#Rational_B%C3%A9zier_curves">Rational Bézier curves</a>
+    Rational {
+        points: Vec<Vector<L, N>>,
+        weights: Vec<N>,
+    },
+}
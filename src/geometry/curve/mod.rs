@@ -1,3 +1,4 @@
+use crate::geometry::bezier::Bezier;
 use crate::linear_algebra::vector::types::Vector2;
 use crate::shared::traits::lerp::Lerp;
 use crate::shared::traits::number::Number;
@@ -68,7 +69,7 @@ impl<N: Number> Curve<N> {
                 let (p1_x, p1_y) = p1.xy();
 
                 let pf_x = inv_t * p0_x + t * p1_x;
-                let pf_y = inv_t * p0_y * t * p1_y;
+                let pf_y = inv_t * p0_y + t * p1_y;
 
                 Vector2::new([pf_x, pf_y])
             }
@@ -119,4 +120,37 @@ impl<N: Number> Curve<N> {
             Curve::Cubic { p3, .. } => p3
         }
     }
+
+    ///This curve as a [Bezier] of the same degree, which also handles any other degree and dimension.
+    pub fn to_bezier(&self) -> Bezier<2, N> {
+        match self {
+            Curve::Linear { p0, p1 } => Bezier::new(vec![*p0, *p1]),
+            Curve::Quadratic { p0, p1, p2 } => Bezier::new(vec![*p0, *p1, *p2]),
+            Curve::Cubic { p0, p1, p2, p3 } => Bezier::new(vec![*p0, *p1, *p2, *p3]),
+        }
+    }
+
+    pub fn arc_length(&self) -> N {
+        self.to_bezier().arc_length()
+    }
+
+    ///The point a distance along the curve from its start.
+    pub fn point_at_distance(&self, distance: N) -> Vector2<N> {
+        self.to_bezier().point_at_distance(distance)
+    }
+
+    ///Points spaced evenly along the curve by distance, unlike [Curve::points] which spaces them evenly in t and so bunches them up where the curve moves slowly.
+    pub fn sample_evenly(&self, count: usize) -> Vec<Vector2<N>> {
+        self.to_bezier().sample_evenly(count)
+    }
+
+    ///The curve as a polyline that strays from it by no more than the tolerance.
+    pub fn flatten(&self, tolerance: N) -> Vec<Vector2<N>> {
+        self.to_bezier().flatten(tolerance)
+    }
+
+    ///The point of the curve nearest to a point, as its t, the point itself and the distance to it.
+    pub fn closest_point(&self, point: Vector2<N>) -> (N, Vector2<N>, N) {
+        self.to_bezier().closest_point(point)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,172 @@
+use std::fmt::Write;
+
+use crate::geometry::format::{GltfFile, MeshFormatError};
+use crate::geometry::Geometry;
+use crate::shared::binary::ByteWriter;
+use crate::shared::endian::Endian;
+use crate::shared::traits::number::Number;
+
+///"glTF" read as a little endian number, which starts binary files.
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const GLB_VERSION: u32 = 2;
+///"JSON" read as a little endian number.
+const JSON_CHUNK: u32 = 0x4E4F_534A;
+///"BIN" and a zero read as a little endian number.
+const BIN_CHUNK: u32 = 0x004E_4942;
+
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const TRIANGLES: u32 = 4;
+
+///The buffer of a glTF file being written, with a buffer view and an accessor for each attribute.
+struct BufferBuilder {
+    writer: ByteWriter,
+    views: Vec<String>,
+    accessors: Vec<String>,
+}
+
+impl BufferBuilder {
+    fn new() -> Self {
+        //glTF buffers are always little endian.
+        Self { writer: ByteWriter::new(Endian::Little), views: vec![], accessors: vec![] }
+    }
+
+    ///Adds a view of what `write` writes and the accessor for it, returning the accessor's index.
+    fn add(&mut self, target: u32, accessor: String, write: impl FnOnce(&mut ByteWriter)) -> usize {
+        let offset = self.writer.len();
+        write(&mut self.writer);
+        self.views.push(format!(r#"{{"buffer":0,"byteOffset":{offset},"byteLength":{},"target":{target}}}"#, self.writer.len() - offset));
+        self.accessors.push(accessor);
+        self.accessors.len() - 1
+    }
+
+    ///Adds vectors as 32 bit floats, with their bounds if `bounded`, as glTF requires for positions.
+    fn floats<const C: usize>(&mut self, values: &[[f32; C]], bounded: bool) -> usize {
+        let mut accessor = format!(r#"{{"bufferView":{},"componentType":{FLOAT},"count":{},"type":"{}""#, self.views.len(), values.len(), match C {
+            2 => "VEC2",
+            3 => "VEC3",
+            _ => "VEC4",
+        });
+        if bounded && !values.is_empty() {
+            let (mut min, mut max) = (values[0], values[0]);
+            for value in values {
+                for c in 0..C {
+                    min[c] = min[c].min(value[c]);
+                    max[c] = max[c].max(value[c]);
+                }
+            }
+            let _ = write!(accessor, r#","min":{},"max":{}"#, array(&min), array(&max));
+        }
+        accessor.push('}');
+        self.add(ARRAY_BUFFER, accessor, |writer| {
+            for value in values.iter().flatten() {
+                writer.number(*value);
+            }
+        })
+    }
+
+    fn indices(&mut self, indices: &[u32]) -> usize {
+        let accessor = format!(r#"{{"bufferView":{},"componentType":{UNSIGNED_INT},"count":{},"type":"SCALAR"}}"#, self.views.len(), indices.len());
+        self.add(ELEMENT_ARRAY_BUFFER, accessor, |writer| {
+            for index in indices {
+                writer.number(*index);
+            }
+        })
+    }
+}
+
+impl<N: Number> Geometry<3, N> {
+    ///Writes the geometry as a glTF 2.0 file with one mesh, whose buffer is referred to by `buffer_uri`, relative to the JSON file.
+    ///<br>Positions, normals, UVs and tangents are written as 32 bit floats if the geometry has them for every vertex, and indices as 32 bit integers.
+    ///<br>Fails on a geometry without vertices, and on positions that aren't finite as 32 bit floats, which glTF has no bounds for.
+    ///<br>See: <a href="https://registry.khronos.org/glTF/specs/2.0/glTF-2.0.html">glTF 2.0</a>
+    pub fn to_gltf(&self, buffer_uri: &str) -> Result<GltfFile, MeshFormatError> {
+        let (json, buffer) = self.gltf(Some(buffer_uri))?;
+        Ok(GltfFile { json, buffer })
+    }
+
+    ///Writes the geometry as a binary glTF 2.0 file, holding the JSON and the buffer of [Geometry::to_gltf] as chunks.
+    pub fn to_glb(&self) -> Result<Vec<u8>, MeshFormatError> {
+        let (json, mut buffer) = self.gltf(None)?;
+        //Chunks are padded to 4 bytes, JSON with spaces.
+        let mut json = json.into_bytes();
+        json.resize(json.len().next_multiple_of(4), b' ');
+        buffer.resize(buffer.len().next_multiple_of(4), 0);
+
+        let mut writer = ByteWriter::new(Endian::Little);
+        writer.number(GLB_MAGIC);
+        writer.number(GLB_VERSION);
+        writer.number((12 + 8 + json.len() + 8 + buffer.len()) as u32);
+        writer.number(json.len() as u32);
+        writer.number(JSON_CHUNK);
+        writer.raw(&json);
+        writer.number(buffer.len() as u32);
+        writer.number(BIN_CHUNK);
+        writer.raw(&buffer);
+        Ok(writer.into_bytes())
+    }
+
+    ///Writes the JSON and the buffer, which is embedded in a binary file if there is no URI.
+    fn gltf(&self, buffer_uri: Option<&str>) -> Result<(String, Vec<u8>), MeshFormatError> {
+        let count = self.vertices.len();
+        if count == 0 {
+            return Err(MeshFormatError::Invalid("there are no vertices".to_string()));
+        }
+        let mut buffer = BufferBuilder::new();
+
+        let positions: Vec<[f32; 3]> = self.vertices.iter().map(|vertex| [vertex.x(), vertex.y(), vertex.z()].map(|c| c.to_f64() as f32)).collect();
+        if let Some(v) = positions.iter().position(|position| position.iter().any(|c| !c.is_finite())) {
+            return Err(MeshFormatError::Invalid(format!("vertex {v} isn't at a finite position")));
+        }
+        let mut attributes = vec![format!(r#""POSITION":{}"#, buffer.floats(&positions, true))];
+        if let Some(normals) = self.normals.as_ref().filter(|normals| normals.len() == count) {
+            let normals: Vec<[f32; 3]> = normals.iter().map(|normal| normal.0.map(|c| c.to_f64() as f32)).collect();
+            attributes.push(format!(r#""NORMAL":{}"#, buffer.floats(&normals, false)));
+        }
+        if let Some(tangents) = self.tangents.as_ref().filter(|tangents| tangents.len() == count) {
+            let tangents: Vec<[f32; 4]> = tangents.iter().map(|tangent| tangent.0.map(|c| c.to_f64() as f32)).collect();
+            attributes.push(format!(r#""TANGENT":{}"#, buffer.floats(&tangents, false)));
+        }
+        if !self.uv.is_empty() && self.uv.len() == count {
+            //glTF also counts v from the top of the texture.
+            let uv: Vec<[f32; 2]> = self.uv.iter().map(|uv| [uv.x(), uv.y()].map(|c| c.to_f64() as f32)).collect();
+            attributes.push(format!(r#""TEXCOORD_0":{}"#, buffer.floats(&uv, false)));
+        }
+        let indices = (!self.indices.is_empty()).then(|| format!(r#","indices":{}"#, buffer.indices(&self.indices))).unwrap_or_default();
+
+        let bytes = buffer.writer.into_bytes();
+        let uri = buffer_uri.map(|uri| format!(r#","uri":"{}""#, escape(uri))).unwrap_or_default();
+        let json = format!(
+            concat!(
+                r#"{{"asset":{{"version":"2.0","generator":"mathematics"}},"scene":0,"scenes":[{{"nodes":[0]}}],"nodes":[{{"mesh":0}}],"#,
+                r#""meshes":[{{"primitives":[{{"attributes":{{{}}}{},"mode":{}}}]}}],"#,
+                r#""accessors":[{}],"bufferViews":[{}],"buffers":[{{"byteLength":{}{}}}]}}"#,
+            ),
+            attributes.join(","), indices, TRIANGLES, buffer.accessors.join(","), buffer.views.join(","), bytes.len(), uri,
+        );
+        Ok((json, bytes))
+    }
+}
+
+fn array(values: &[f32]) -> String {
+    let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+    format!("[{}]", values.join(","))
+}
+
+///Escapes a string for a JSON string literal.
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,80 @@
+use std::fmt::{Display, Formatter};
+
+use crate::geometry::Geometry;
+use crate::shared::binary::UnexpectedEnd;
+use crate::shared::endian::Endian;
+use crate::shared::traits::number::Number;
+
+pub mod obj;
+pub mod stl;
+pub mod ply;
+pub mod gltf;
+
+///A named part of an OBJ file, started by an `o` or `g` line.
+#[derive(Debug, Clone)]
+pub struct ObjObject<N: Number> {
+    pub name: String,
+    pub geometry: Geometry<3, N>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StlEncoding {
+    Ascii,
+    ///Always little endian, as the format requires.
+    Binary,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum PlyEncoding {
+    Ascii,
+    Binary(Endian),
+}
+
+///A glTF file and the buffer it refers to, to be saved next to it under the name it was written with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfFile {
+    pub json: String,
+    pub buffer: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MeshFormatError {
+    ///A line of text that couldn't be read, numbered from 1, and what was wrong with it.
+    Parse { line: usize, message: String },
+    ///Binary data that ended at this offset before the mesh did.
+    UnexpectedEnd(usize),
+    ///Data that isn't in the format, or uses a part of it that isn't supported, or a mesh the format can't hold.
+    Invalid(String),
+}
+
+impl MeshFormatError {
+    pub(crate) fn parse(line: usize, message: impl Into<String>) -> Self {
+        MeshFormatError::Parse { line, message: message.into() }
+    }
+}
+
+impl From<UnexpectedEnd> for MeshFormatError {
+    fn from(value: UnexpectedEnd) -> Self {
+        MeshFormatError::UnexpectedEnd(value.offset)
+    }
+}
+
+impl Display for MeshFormatError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MeshFormatError::Parse { line, message } => write!(f, "line {line}: {message}"),
+            MeshFormatError::UnexpectedEnd(offset) => write!(f, "mesh data ends early at byte {offset}"),
+            MeshFormatError::Invalid(reason) => write!(f, "invalid mesh data: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for MeshFormatError {}
+
+///Reads the next token of a line as a number.
+pub(crate) fn number<N: Number>(token: Option<&str>, line: usize, what: &str) -> Result<N, MeshFormatError> {
+    let token = token.ok_or_else(|| MeshFormatError::parse(line, format!("missing {what}")))?;
+    token.parse::<f64>()
+        .map(N::from_f64)
+        .map_err(|_| MeshFormatError::parse(line, format!("expected a number for {what}, found '{token}'")))
+}
@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::geometry::format::{number, MeshFormatError, ObjObject};
+use crate::geometry::Geometry;
+use crate::linear_algebra::vector::types::{Vector2, Vector3};
+use crate::shared::traits::number::Number;
+
+///A corner of a face as the indices of its position, UV and normal.
+type Corner = (usize, Option<usize>, Option<usize>);
+
+///The faces of the object being read, with a vertex for every distinct corner.
+struct ObjectBuilder {
+    name: String,
+    corners: HashMap<Corner, u32>,
+    vertices: Vec<Corner>,
+    indices: Vec<u32>,
+}
+
+impl ObjectBuilder {
+    fn new(name: &str) -> Self {
+        Self { name: name.to_string(), corners: HashMap::new(), vertices: vec![], indices: vec![] }
+    }
+
+    fn corner(&mut self, corner: Corner) -> u32 {
+        let vertices = &mut self.vertices;
+        *self.corners.entry(corner).or_insert_with(|| {
+            vertices.push(corner);
+            vertices.len() as u32 - 1
+        })
+    }
+
+    fn finish<N: Number>(self, positions: &[Vector3<N>], uv: &[Vector2<N>], normals: &[Vector3<N>]) -> ObjObject<N> {
+        let textured = self.vertices.iter().any(|(_, uv, _)| uv.is_some());
+        let geometry = Geometry {
+            vertices: self.vertices.iter().map(|(position, _, _)| positions[*position]).collect(),
+            //Only if every corner has one.
+            normals: self.vertices.iter().map(|(_, _, normal)| normal.map(|normal| normals[normal])).collect(),
+            uv: if textured { self.vertices.iter().map(|(_, texture, _)| texture.map(|t| uv[t]).unwrap_or(Vector2::ZERO)).collect() } else { vec![] },
+            tangents: None,
+            indices: self.indices,
+        };
+        ObjObject { name: self.name, geometry }
+    }
+}
+
+impl<N: Number> Geometry<3, N> {
+    ///Reads all the objects of a Wavefront OBJ file into one geometry. See [read_obj].
+    pub fn from_obj(text: &str) -> Result<Geometry<3, N>, MeshFormatError> {
+        let objects: Vec<Geometry<3, N>> = read_obj(text)?.into_iter().map(|object| object.geometry).collect();
+        Ok(Geometry::merge(&objects))
+    }
+
+    ///Writes the geometry as a Wavefront OBJ file with a single object. See [write_obj].
+    pub fn to_obj(&self) -> String {
+        let mut text = String::new();
+        write_object(&mut text, "geometry", self, &mut [0; 3]);
+        text
+    }
+}
+
+///Reads the objects of a Wavefront OBJ file, each made of the faces after an `o` or `g` line, and fans polygons into triangles.
+///<br>Vertices get normals only if every face of their object gives them, and UVs if any face does.
+///The v coordinate is flipped, as OBJ counts it from the bottom of the texture.
+///Materials, smoothing groups, lines and points are skipped.
+///<br>See: <a href="https://paulbourke.net/dataformats/obj/">Object files</a>
+pub fn read_obj<N: Number>(text: &str) -> Result<Vec<ObjObject<N>>, MeshFormatError> {
+    let (mut positions, mut uv, mut normals) = (vec![], vec![], vec![]);
+    let mut objects = vec![];
+    let mut object = ObjectBuilder::new("default");
+
+    for (line, content) in text.lines().enumerate() {
+        let line = line + 1;
+        let content = content.split('#').next().unwrap_or("");
+        let mut tokens = content.split_whitespace();
+        match tokens.next() {
+            Some("v") => positions.push(Vector3::new([
+                number(tokens.next(), line, "x")?,
+                number(tokens.next(), line, "y")?,
+                number(tokens.next(), line, "z")?,
+            ])),
+            Some("vt") => {
+                let u: N = number(tokens.next(), line, "u")?;
+                let v: N = tokens.next().map(|token| number(Some(token), line, "v")).transpose()?.unwrap_or(N::ZERO);
+                uv.push(Vector2::new([u, N::ONE - v]));
+            }
+            Some("vn") => normals.push(Vector3::new([
+                number(tokens.next(), line, "x")?,
+                number(tokens.next(), line, "y")?,
+                number(tokens.next(), line, "z")?,
+            ])),
+            Some("f") => {
+                let corners = tokens.map(|token| {
+                    let mut parts = token.split('/');
+                    let position = index(parts.next(), positions.len(), line, "position")?
+                        .ok_or_else(|| MeshFormatError::parse(line, format!("face corner '{token}' has no position")))?;
+                    let texture = index(parts.next(), uv.len(), line, "texture coordinate")?;
+                    let normal = index(parts.next(), normals.len(), line, "normal")?;
+                    Ok(object.corner((position, texture, normal)))
+                }).collect::<Result<Vec<u32>, MeshFormatError>>()?;
+                if corners.len() < 3 {
+                    return Err(MeshFormatError::parse(line, format!("face has {} vertices, at least 3 are needed", corners.len())));
+                }
+                for i in 1..corners.len() - 1 {
+                    object.indices.extend_from_slice(&[corners[0], corners[i], corners[i + 1]]);
+                }
+            }
+            Some("o") | Some("g") => {
+                let name = tokens.collect::<Vec<&str>>().join(" ");
+                if object.indices.is_empty() {
+                    object.name = name;
+                } else {
+                    objects.push(std::mem::replace(&mut object, ObjectBuilder::new(&name)).finish(&positions, &uv, &normals));
+                }
+            }
+            _ => {}
+        }
+    }
+    if !object.indices.is_empty() {
+        objects.push(object.finish(&positions, &uv, &normals));
+    }
+
+    Ok(objects)
+}
+
+///Writes objects as a Wavefront OBJ file, with their UVs and normals if they have them.
+pub fn write_obj<N: Number>(objects: &[ObjObject<N>]) -> String {
+    let mut text = String::new();
+    let mut offsets = [0; 3];
+    for object in objects {
+        write_object(&mut text, &object.name, &object.geometry, &mut offsets);
+    }
+    text
+}
+
+///Writes one object, with its indices after those of the positions, UVs and normals already written.
+fn write_object<N: Number>(text: &mut String, name: &str, geometry: &Geometry<3, N>, offsets: &mut [usize; 3]) {
+    let textured = !geometry.uv.is_empty() && geometry.uv.len() == geometry.vertices.len();
+    let normals = geometry.normals.as_ref().filter(|normals| normals.len() == geometry.vertices.len());
+
+    let _ = writeln!(text, "o {name}");
+    for vertex in &geometry.vertices {
+        let _ = writeln!(text, "v {} {} {}", vertex.x(), vertex.y(), vertex.z());
+    }
+    if textured {
+        for uv in &geometry.uv {
+            let _ = writeln!(text, "vt {} {}", uv.x(), N::ONE - uv.y());
+        }
+    }
+    for normal in normals.into_iter().flatten() {
+        let _ = writeln!(text, "vn {} {} {}", normal.x(), normal.y(), normal.z());
+    }
+    for triangle in geometry.indices.chunks_exact(3) {
+        let corners: Vec<String> = triangle.iter().map(|index| {
+            let [position, uv, normal] = offsets.map(|offset| offset + *index as usize + 1);
+            match (textured, normals.is_some()) {
+                (true, true) => format!("{position}/{uv}/{normal}"),
+                (true, false) => format!("{position}/{uv}"),
+                (false, true) => format!("{position}//{normal}"),
+                (false, false) => format!("{position}"),
+            }
+        }).collect();
+        let _ = writeln!(text, "f {}", corners.join(" "));
+    }
+
+    let count = geometry.vertices.len();
+    offsets[0] += count;
+    offsets[1] += if textured { count } else { 0 };
+    offsets[2] += if normals.is_some() { count } else { 0 };
+}
+
+///Reads an index of a face corner, counting from 1 or back from the end if negative. Empty parts are missing.
+fn index(part: Option<&str>, count: usize, line: usize, what: &str) -> Result<Option<usize>, MeshFormatError> {
+    let Some(part) = part.filter(|part| !part.is_empty()) else {
+        return Ok(None);
+    };
+    let index: isize = part.parse().map_err(|_| MeshFormatError::parse(line, format!("expected an index for {what}, found '{part}'")))?;
+    let resolved = if index < 0 { count as isize + index } else { index - 1 };
+    if index == 0 || resolved < 0 || resolved as usize >= count {
+        return Err(MeshFormatError::parse(line, format!("{what} {index} doesn't exist, there are {count}")));
+    }
+    Ok(Some(resolved as usize))
+}
@@ -0,0 +1,306 @@
+use std::fmt::Write;
+
+use crate::geometry::format::{MeshFormatError, PlyEncoding};
+use crate::geometry::Geometry;
+use crate::linear_algebra::vector::types::{Vector2, Vector3};
+use crate::shared::binary::{ByteReader, ByteWriter};
+use crate::shared::endian::Endian;
+use crate::shared::traits::number::Number;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl Kind {
+    fn parse(name: &str) -> Option<Kind> {
+        Some(match name {
+            "char" | "int8" => Kind::I8,
+            "uchar" | "uint8" => Kind::U8,
+            "short" | "int16" => Kind::I16,
+            "ushort" | "uint16" => Kind::U16,
+            "int" | "int32" => Kind::I32,
+            "uint" | "uint32" => Kind::U32,
+            "float" | "float32" => Kind::F32,
+            "double" | "float64" => Kind::F64,
+            _ => return None,
+        })
+    }
+
+    fn read(self, reader: &mut ByteReader) -> Result<f64, MeshFormatError> {
+        Ok(match self {
+            Kind::I8 => reader.number::<i8>()? as f64,
+            Kind::U8 => reader.number::<u8>()? as f64,
+            Kind::I16 => reader.number::<i16>()? as f64,
+            Kind::U16 => reader.number::<u16>()? as f64,
+            Kind::I32 => reader.number::<i32>()? as f64,
+            Kind::U32 => reader.number::<u32>()? as f64,
+            Kind::F32 => reader.number::<f32>()? as f64,
+            Kind::F64 => reader.number::<f64>()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Property {
+    name: String,
+    kind: Kind,
+    ///The type of the count before the values, for list properties.
+    count: Option<Kind>,
+}
+
+#[derive(Debug, Clone)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+///One vertex, face or other item of an element, with the values of each of its properties, and the line it was on in ASCII files.
+struct Item {
+    values: Vec<Vec<f64>>,
+    line: Option<usize>,
+}
+
+impl<N: Number> Geometry<3, N> {
+    ///Reads an ASCII or binary PLY file, with normals and UVs if its vertices have them, fanning polygons into triangles.
+    ///<br>Texture coordinates may be called u and v, s and t, or texture_u and texture_v. The v coordinate is flipped, as PLY counts it from the bottom of the texture.
+    ///Other properties and elements are skipped.
+    ///<br>See: <a href="https://paulbourke.net/dataformats/ply/">PLY</a>
+    pub fn from_ply(bytes: &[u8]) -> Result<Geometry<3, N>, MeshFormatError> {
+        let (elements, encoding, body, header_lines) = read_header(bytes)?;
+        let mut items: Vec<Vec<Item>> = vec![];
+        match encoding {
+            PlyEncoding::Ascii => {
+                let text = std::str::from_utf8(&bytes[body..]).map_err(|error| MeshFormatError::Invalid(format!("ASCII PLY isn't text: {error}")))?;
+                let mut lines = text.lines().enumerate().map(|(line, content)| (line + header_lines + 1, content)).filter(|(_, content)| !content.trim().is_empty());
+                for element in &elements {
+                    items.push((0..element.count).map(|_| {
+                        let (line, content) = lines.next()
+                            .ok_or_else(|| MeshFormatError::parse(header_lines + text.lines().count(), format!("the file ends before all {} {} items", element.count, element.name)))?;
+                        read_ascii_item(element, content, line)
+                    }).collect::<Result<Vec<Item>, MeshFormatError>>()?);
+                }
+            }
+            PlyEncoding::Binary(endian) => {
+                let mut reader = ByteReader::new(bytes, endian);
+                reader.raw(body)?;
+                for element in &elements {
+                    items.push((0..element.count).map(|_| read_binary_item(element, &mut reader)).collect::<Result<Vec<Item>, MeshFormatError>>()?);
+                }
+            }
+        }
+
+        let find = |element: &Element, names: &[&str]| element.properties.iter().position(|property| names.contains(&property.name.as_str()));
+        let vertex_element = elements.iter().position(|element| element.name == "vertex")
+            .ok_or_else(|| MeshFormatError::Invalid("there is no vertex element".to_string()))?;
+        let element = &elements[vertex_element];
+        let position = [find(element, &["x"]), find(element, &["y"]), find(element, &["z"])];
+        let [Some(x), Some(y), Some(z)] = position else {
+            return Err(MeshFormatError::Invalid("vertices need x, y and z properties".to_string()));
+        };
+        let normal = [find(element, &["nx"]), find(element, &["ny"]), find(element, &["nz"])];
+        let uv = [find(element, &["u", "s", "texture_u", "texture_s"]), find(element, &["v", "t", "texture_v", "texture_t"])];
+
+        let vertices = &items[vertex_element];
+        let value = |item: &Item, property: usize| N::from_f64(item.values[property].first().copied().unwrap_or(0_f64));
+        let mut geometry = Geometry {
+            vertices: vertices.iter().map(|item| Vector3::new([value(item, x), value(item, y), value(item, z)])).collect(),
+            normals: match normal {
+                [Some(x), Some(y), Some(z)] => Some(vertices.iter().map(|item| Vector3::new([value(item, x), value(item, y), value(item, z)])).collect()),
+                _ => None,
+            },
+            uv: match uv {
+                [Some(u), Some(v)] => vertices.iter().map(|item| Vector2::new([value(item, u), N::ONE - value(item, v)])).collect(),
+                _ => vec![],
+            },
+            tangents: None,
+            indices: vec![],
+        };
+
+        if let Some(face_element) = elements.iter().position(|element| element.name == "face") {
+            let indices = find(&elements[face_element], &["vertex_indices", "vertex_index"])
+                .ok_or_else(|| MeshFormatError::Invalid("faces need a vertex_indices property".to_string()))?;
+            for (f, item) in items[face_element].iter().enumerate() {
+                let corners = &item.values[indices];
+                let error = |message: String| match item.line {
+                    Some(line) => MeshFormatError::parse(line, message),
+                    None => MeshFormatError::Invalid(format!("face {f}: {message}")),
+                };
+                if corners.len() < 3 {
+                    return Err(error(format!("face has {} vertices, at least 3 are needed", corners.len())));
+                }
+                if let Some(fraction) = corners.iter().find(|corner| !corner.is_finite() || corner.fract() != 0_f64) {
+                    return Err(error(format!("vertex index {fraction} isn't a whole number")));
+                }
+                if let Some(missing) = corners.iter().find(|corner| **corner < 0_f64 || **corner as usize >= vertices.len()) {
+                    return Err(error(format!("vertex {missing} doesn't exist, there are {}", vertices.len())));
+                }
+                for i in 1..corners.len() - 1 {
+                    geometry.indices.extend_from_slice(&[corners[0] as u32, corners[i] as u32, corners[i + 1] as u32]);
+                }
+            }
+        }
+        Ok(geometry)
+    }
+
+    ///Writes the geometry as a PLY file, with normals and UVs if it has them, as 32 bit floats if the geometry uses them and 64 bit ones otherwise.
+    pub fn to_ply(&self, encoding: PlyEncoding) -> Vec<u8> {
+        let single = N::TYPE == "f32";
+        let kind = if single { "float" } else { "double" };
+        let normals = self.normals.as_ref().filter(|normals| normals.len() == self.vertices.len());
+        let textured = !self.uv.is_empty() && self.uv.len() == self.vertices.len();
+
+        let mut header = String::from("ply\n");
+        let _ = writeln!(header, "format {} 1.0", match encoding {
+            PlyEncoding::Ascii => "ascii",
+            PlyEncoding::Binary(Endian::Little) => "binary_little_endian",
+            PlyEncoding::Binary(Endian::Big) => "binary_big_endian",
+        });
+        let _ = writeln!(header, "element vertex {}", self.vertices.len());
+        let mut names = vec!["x", "y", "z"];
+        if normals.is_some() {
+            names.extend_from_slice(&["nx", "ny", "nz"]);
+        }
+        if textured {
+            names.extend_from_slice(&["u", "v"]);
+        }
+        for name in &names {
+            let _ = writeln!(header, "property {kind} {name}");
+        }
+        let _ = writeln!(header, "element face {}", self.triangle_count());
+        header.push_str("property list uchar uint vertex_indices\nend_header\n");
+
+        let vertex = |i: usize| -> Vec<N> {
+            let mut values = self.vertices[i].0.to_vec();
+            if let Some(normals) = normals {
+                values.extend_from_slice(&normals[i].0);
+            }
+            if textured {
+                values.extend_from_slice(&[self.uv[i].x(), N::ONE - self.uv[i].y()]);
+            }
+            values
+        };
+        match encoding {
+            PlyEncoding::Ascii => {
+                for i in 0..self.vertices.len() {
+                    let values: Vec<String> = vertex(i).iter().map(|value| value.to_string()).collect();
+                    let _ = writeln!(header, "{}", values.join(" "));
+                }
+                for triangle in self.indices.chunks_exact(3) {
+                    let _ = writeln!(header, "3 {} {} {}", triangle[0], triangle[1], triangle[2]);
+                }
+                header.into_bytes()
+            }
+            PlyEncoding::Binary(endian) => {
+                let mut writer = ByteWriter::new(endian);
+                writer.raw(header.as_bytes());
+                for i in 0..self.vertices.len() {
+                    for value in vertex(i) {
+                        if single {
+                            writer.number(value.to_f64() as f32);
+                        } else {
+                            writer.number(value.to_f64());
+                        }
+                    }
+                }
+                for triangle in self.indices.chunks_exact(3) {
+                    writer.number(3_u8);
+                    triangle.iter().for_each(|index| writer.number(*index));
+                }
+                writer.into_bytes()
+            }
+        }
+    }
+}
+
+///The elements a PLY header declares, the encoding, where the body starts and how many lines the header has.
+fn read_header(bytes: &[u8]) -> Result<(Vec<Element>, PlyEncoding, usize, usize), MeshFormatError> {
+    const END: &[u8] = b"end_header";
+    let end = bytes.windows(END.len()).position(|window| window == END)
+        .ok_or_else(|| MeshFormatError::Invalid("there is no end_header".to_string()))?;
+    let body = bytes[end..].iter().position(|byte| *byte == b'\n').map(|newline| end + newline + 1).unwrap_or(bytes.len());
+    let header = std::str::from_utf8(&bytes[..body]).map_err(|error| MeshFormatError::Invalid(format!("the header isn't text: {error}")))?;
+
+    let mut elements: Vec<Element> = vec![];
+    let mut encoding = None;
+    let mut lines = 0;
+    for (line, content) in header.lines().enumerate() {
+        let line = line + 1;
+        lines = line;
+        let tokens: Vec<&str> = content.split_whitespace().collect();
+        match tokens.as_slice() {
+            ["ply"] if line == 1 => {}
+            _ if line == 1 => return Err(MeshFormatError::parse(line, "expected 'ply'")),
+            ["format", format, _] => {
+                encoding = Some(match *format {
+                    "ascii" => PlyEncoding::Ascii,
+                    "binary_little_endian" => PlyEncoding::Binary(Endian::Little),
+                    "binary_big_endian" => PlyEncoding::Binary(Endian::Big),
+                    _ => return Err(MeshFormatError::parse(line, format!("unknown format '{format}'"))),
+                });
+            }
+            ["comment", ..] | ["obj_info", ..] | ["end_header"] | [] => {}
+            ["element", name, count] => {
+                let count = count.parse().map_err(|_| MeshFormatError::parse(line, format!("expected a count of {name} items, found '{count}'")))?;
+                elements.push(Element { name: name.to_string(), count, properties: vec![] });
+            }
+            ["property", "list", count, kind, name] => {
+                let (Some(count), Some(kind)) = (Kind::parse(count), Kind::parse(kind)) else {
+                    return Err(MeshFormatError::parse(line, format!("unknown type of list property '{name}'")));
+                };
+                let element = elements.last_mut().ok_or_else(|| MeshFormatError::parse(line, "property before any element"))?;
+                element.properties.push(Property { name: name.to_string(), kind, count: Some(count) });
+            }
+            ["property", kind, name] => {
+                let kind = Kind::parse(kind).ok_or_else(|| MeshFormatError::parse(line, format!("unknown type '{kind}' of property '{name}'")))?;
+                let element = elements.last_mut().ok_or_else(|| MeshFormatError::parse(line, "property before any element"))?;
+                element.properties.push(Property { name: name.to_string(), kind, count: None });
+            }
+            _ => return Err(MeshFormatError::parse(line, format!("unexpected '{}'", content.trim()))),
+        }
+    }
+    let encoding = encoding.ok_or_else(|| MeshFormatError::Invalid("there is no format line".to_string()))?;
+    Ok((elements, encoding, body, lines))
+}
+
+fn read_ascii_item(element: &Element, content: &str, line: usize) -> Result<Item, MeshFormatError> {
+    let mut tokens = content.split_whitespace();
+    let mut next = |what: &str| -> Result<f64, MeshFormatError> {
+        let token = tokens.next().ok_or_else(|| MeshFormatError::parse(line, format!("missing {what}")))?;
+        token.parse().map_err(|_| MeshFormatError::parse(line, format!("expected a number for {what}, found '{token}'")))
+    };
+    let values = element.properties.iter().map(|property| match property.count {
+        Some(_) => {
+            let count = next(&format!("count of {}", property.name))?;
+            let count = list_count(count).ok_or_else(|| MeshFormatError::parse(line, format!("the count of {} isn't a whole number, found {count}", property.name)))?;
+            (0..count).map(|_| next(&property.name)).collect()
+        }
+        None => Ok(vec![next(&property.name)?]),
+    }).collect::<Result<Vec<Vec<f64>>, MeshFormatError>>()?;
+    Ok(Item { values, line: Some(line) })
+}
+
+///The length of a list, which can be read from a property of any type but has to be a whole number that isn't negative.
+fn list_count(count: f64) -> Option<usize> {
+    (count.is_finite() && count >= 0_f64 && count.fract() == 0_f64).then_some(count as usize)
+}
+
+fn read_binary_item(element: &Element, reader: &mut ByteReader) -> Result<Item, MeshFormatError> {
+    let values = element.properties.iter().map(|property| match property.count {
+        Some(count) => {
+            let count = count.read(reader)?;
+            let count = list_count(count).ok_or_else(|| MeshFormatError::Invalid(format!("the count of {} isn't a whole number, found {count}", property.name)))?;
+            (0..count).map(|_| property.kind.read(reader)).collect()
+        }
+        None => Ok(vec![property.kind.read(reader)?]),
+    }).collect::<Result<Vec<Vec<f64>>, MeshFormatError>>()?;
+    Ok(Item { values, line: None })
+}
@@ -0,0 +1,136 @@
+use std::fmt::Write;
+
+use crate::geometry::format::{number, MeshFormatError, StlEncoding};
+use crate::geometry::Geometry;
+use crate::linear_algebra::vector::types::Vector3;
+use crate::shared::binary::{ByteReader, ByteWriter};
+use crate::shared::endian::Endian;
+use crate::shared::traits::number::Number;
+
+///The header of binary STL files written here. It mustn't start with `solid` or readers would take the file for ASCII.
+const BINARY_HEADER: &[u8] = b"binary STL";
+
+impl<N: Number> Geometry<3, N> {
+    ///Reads an ASCII or binary STL file, telling them apart by whether the size matches the triangle count of a binary one.
+    ///<br>Every facet gets its own normal, from the file or from its winding if the file's is zero, and vertices are shared within flat areas.
+    ///All solids of an ASCII file are read into one geometry.
+    ///<br>See: <a href="https://en.wikipedia.org/wiki/STL_(file_format)">STL</a>
+    pub fn from_stl(bytes: &[u8]) -> Result<Geometry<3, N>, MeshFormatError> {
+        let binary_size = bytes.get(80..84).map(|count| 84 + 50 * u32::from_le_bytes([count[0], count[1], count[2], count[3]]) as usize);
+        let ascii = bytes.trim_ascii_start().starts_with(b"solid") && binary_size != Some(bytes.len());
+        let triangles = if ascii {
+            let text = std::str::from_utf8(bytes).map_err(|error| MeshFormatError::Invalid(format!("ASCII STL isn't text: {error}")))?;
+            read_ascii(text)?
+        } else {
+            read_binary(bytes)?
+        };
+
+        let mut geometry = Geometry { vertices: vec![], normals: Some(vec![]), uv: vec![], tangents: None, indices: vec![] };
+        for (normal, corners) in triangles {
+            let face = (corners[1] - corners[0]).cross(&(corners[2] - corners[0]));
+            let normal = if normal.magnitude_squared() > N::ZERO { normal.normalize() } else if face.magnitude_squared() > N::ZERO { face.normalize() } else { normal };
+            geometry.indices.extend(geometry.vertices.len() as u32..geometry.vertices.len() as u32 + 3);
+            geometry.vertices.extend_from_slice(&corners);
+            geometry.normals.as_mut().expect("Normals were set above").extend_from_slice(&[normal; 3]);
+        }
+        geometry.weld(N::ZERO);
+        Ok(geometry)
+    }
+
+    ///Writes the triangles as an STL file, each with its face normal. Binary files store 32 bit floats.
+    pub fn to_stl(&self, encoding: StlEncoding) -> Vec<u8> {
+        let normals = self.face_normals();
+        match encoding {
+            StlEncoding::Ascii => {
+                let mut text = String::from("solid geometry\n");
+                for (triangle, normal) in self.indices.chunks_exact(3).zip(normals) {
+                    let _ = writeln!(text, "  facet normal {} {} {}", normal.x(), normal.y(), normal.z());
+                    text.push_str("    outer loop\n");
+                    for index in triangle {
+                        let vertex = self.vertices[*index as usize];
+                        let _ = writeln!(text, "      vertex {} {} {}", vertex.x(), vertex.y(), vertex.z());
+                    }
+                    text.push_str("    endloop\n  endfacet\n");
+                }
+                text.push_str("endsolid geometry\n");
+                text.into_bytes()
+            }
+            StlEncoding::Binary => {
+                let mut writer = ByteWriter::new(Endian::Little);
+                let mut header = [0_u8; 80];
+                header[..BINARY_HEADER.len()].copy_from_slice(BINARY_HEADER);
+                writer.raw(&header);
+                writer.number(self.triangle_count() as u32);
+                for (triangle, normal) in self.indices.chunks_exact(3).zip(normals) {
+                    for vector in std::iter::once(normal).chain(triangle.iter().map(|index| self.vertices[*index as usize])) {
+                        for component in vector.0 {
+                            writer.number(component.to_f64() as f32);
+                        }
+                    }
+                    writer.number(0_u16);
+                }
+                writer.into_bytes()
+            }
+        }
+    }
+}
+
+fn read_binary<N: Number>(bytes: &[u8]) -> Result<Vec<(Vector3<N>, [Vector3<N>; 3])>, MeshFormatError> {
+    let mut reader = ByteReader::new(bytes, Endian::Little);
+    reader.raw(80)?;
+    let count: u32 = reader.number()?;
+    if reader.remaining() < count as usize * 50 {
+        return Err(MeshFormatError::Invalid(format!("binary STL claims {count} triangles but only has room for {}", reader.remaining() / 50)));
+    }
+    let mut vector = |reader: &mut ByteReader| -> Result<Vector3<N>, MeshFormatError> {
+        let vector: Vector3<f32> = reader.vector()?;
+        Ok(Vector3::new(vector.0.map(|component| N::from_f64(component as f64))))
+    };
+    let mut triangles = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let normal = vector(&mut reader)?;
+        let corners = [vector(&mut reader)?, vector(&mut reader)?, vector(&mut reader)?];
+        reader.number::<u16>()?;
+        triangles.push((normal, corners));
+    }
+    Ok(triangles)
+}
+
+fn read_ascii<N: Number>(text: &str) -> Result<Vec<(Vector3<N>, [Vector3<N>; 3])>, MeshFormatError> {
+    let mut triangles = vec![];
+    let mut facet: Option<(Vector3<N>, Vec<Vector3<N>>)> = None;
+    for (line, content) in text.lines().enumerate() {
+        let line = line + 1;
+        let mut tokens = content.split_whitespace();
+        let mut vector = |tokens: &mut std::str::SplitWhitespace| -> Result<Vector3<N>, MeshFormatError> {
+            Ok(Vector3::new([number(tokens.next(), line, "x")?, number(tokens.next(), line, "y")?, number(tokens.next(), line, "z")?]))
+        };
+        match tokens.next() {
+            None | Some("solid") | Some("endsolid") | Some("outer") | Some("endloop") => {}
+            Some("facet") => {
+                if facet.is_some() {
+                    return Err(MeshFormatError::parse(line, "facet starts before the last one ended"));
+                }
+                if tokens.next() != Some("normal") {
+                    return Err(MeshFormatError::parse(line, "expected 'facet normal'"));
+                }
+                facet = Some((vector(&mut tokens)?, vec![]));
+            }
+            Some("vertex") => {
+                let vertex = vector(&mut tokens)?;
+                facet.as_mut().ok_or_else(|| MeshFormatError::parse(line, "vertex outside a facet"))?.1.push(vertex);
+            }
+            Some("endfacet") => {
+                let (normal, corners) = facet.take().ok_or_else(|| MeshFormatError::parse(line, "endfacet without a facet"))?;
+                let corners: [Vector3<N>; 3] = corners.try_into()
+                    .map_err(|corners: Vec<Vector3<N>>| MeshFormatError::parse(line, format!("facet has {} vertices, 3 are needed", corners.len())))?;
+                triangles.push((normal, corners));
+            }
+            Some(keyword) => return Err(MeshFormatError::parse(line, format!("unexpected '{keyword}'"))),
+        }
+    }
+    if facet.is_some() {
+        return Err(MeshFormatError::parse(text.lines().count(), "the last facet never ends"));
+    }
+    Ok(triangles)
+}
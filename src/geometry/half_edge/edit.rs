@@ -0,0 +1,213 @@
+use crate::geometry::half_edge::{Face, FaceId, HalfEdge, HalfEdgeError, HalfEdgeId, HalfEdgeMesh, Vertex, VertexId};
+use crate::linear_algebra::vector::types::Vector3;
+use crate::shared::traits::number::Number;
+
+impl<N: Number> HalfEdgeMesh<N> {
+    ///Turns the edge between two triangles to join their other two vertices instead.
+    ///<br>Refused along a boundary, next to faces that aren't triangles, or where those vertices already share an edge.
+    pub fn flip_edge(&mut self, half_edge: HalfEdgeId) -> Result<(), HalfEdgeError> {
+        let twin = self.twin(half_edge);
+        let (Some(face), Some(twin_face)) = (self.face(half_edge), self.face(twin)) else {
+            return Err(HalfEdgeError::InvalidEdit("can't flip a boundary edge"));
+        };
+        if self.face_half_edges(face).len() != 3 || self.face_half_edges(twin_face).len() != 3 {
+            return Err(HalfEdgeError::InvalidEdit("can only flip an edge between triangles"));
+        }
+        //Triangles a b c and b a d become d c a and c d b.
+        let (h1, h2) = (self.next(half_edge), self.next(self.next(half_edge)));
+        let (t1, t2) = (self.next(twin), self.next(self.next(twin)));
+        let (a, b) = (self.origin(half_edge), self.origin(twin));
+        let (c, d) = (self.origin(h2), self.origin(t2));
+        if c == d || self.one_ring(c).contains(&d) {
+            return Err(HalfEdgeError::InvalidEdit("flipping would duplicate an edge"));
+        }
+
+        self.link(half_edge, h2, t1, face);
+        self.link(twin, t2, h1, twin_face);
+        self.half_edge_mut(half_edge).origin = d;
+        self.half_edge_mut(twin).origin = c;
+        if self.vertex_half_edge(a) == Some(half_edge) {
+            self.vertex_mut(a).half_edge = Some(t1);
+        }
+        if self.vertex_half_edge(b) == Some(twin) {
+            self.vertex_mut(b).half_edge = Some(h1);
+        }
+        Ok(())
+    }
+
+    ///Adds a vertex part of the way along an edge, from its origin at 0 to its destination at 1, returning it.
+    ///<br>Triangles on either side are split in two through the new vertex so the mesh stays triangulated, and other faces just gain a vertex.
+    pub fn split_edge(&mut self, half_edge: HalfEdgeId, t: N) -> VertexId {
+        let twin = self.twin(half_edge);
+        let (from, to) = (self.origin(half_edge), self.origin(twin));
+        let position = self.position(from) + (self.position(to) - self.position(from)) * t;
+        let middle = self.add_vertex(position);
+
+        //half-edge: from -> middle, after: middle -> to, twin: to -> middle, twin after: middle -> from.
+        let after = self.add_half_edge(middle, twin, self.next(half_edge), self.face(half_edge));
+        let twin_after = self.add_half_edge(middle, half_edge, self.next(twin), self.face(twin));
+        self.half_edge_mut(half_edge).next = after;
+        self.half_edge_mut(half_edge).twin = twin_after;
+        self.half_edge_mut(twin).next = twin_after;
+        self.half_edge_mut(twin).twin = after;
+        let boundary = if self.face(after).is_none() { after } else { twin_after };
+        self.vertex_mut(middle).half_edge = Some(boundary);
+
+        for (side, leaving) in [(half_edge, after), (twin, twin_after)] {
+            if let Some(face) = self.face(side) {
+                if self.face_half_edges(face).len() == 4 {
+                    //Joins the new vertex to the corner across from it.
+                    let opposite = self.next(self.next(leaving));
+                    self.split_face(face, leaving, opposite);
+                }
+            }
+        }
+        middle
+    }
+
+    ///Merges the two ends of an edge into one vertex at a position, removing the triangles on either side, and returns the vertex that's kept.
+    ///<br>Refused next to faces that aren't triangles, and where the mesh would stop being a manifold:
+    ///when the ends share neighbours other than the corners of those triangles, when an edge inside joins two boundaries,
+    ///or when a corner has no other neighbour than one more vertex, like in a tetrahedron.
+    pub fn collapse_edge(&mut self, half_edge: HalfEdgeId, position: Vector3<N>) -> Result<VertexId, HalfEdgeError> {
+        let twin = self.twin(half_edge);
+        let (keep, remove) = (self.origin(half_edge), self.origin(twin));
+        let mut corners = vec![];
+        for side in [half_edge, twin] {
+            if let Some(face) = self.face(side) {
+                if self.face_half_edges(face).len() != 3 {
+                    return Err(HalfEdgeError::InvalidEdit("can only collapse an edge between triangles"));
+                }
+                let (next, previous) = (self.next(side), self.next(self.next(side)));
+                if self.face(self.twin(next)).is_none() && self.face(self.twin(previous)).is_none() {
+                    return Err(HalfEdgeError::InvalidEdit("collapsing would leave an edge without faces"));
+                }
+                corners.push(self.origin(previous));
+            }
+        }
+        let shared = self.one_ring(keep).into_iter().filter(|vertex| self.one_ring(remove).contains(vertex)).count();
+        if shared != corners.len() {
+            return Err(HalfEdgeError::InvalidEdit("the ends of the edge share other neighbours"));
+        }
+        if !self.is_boundary_edge(half_edge) && self.is_boundary_vertex(keep) && self.is_boundary_vertex(remove) {
+            return Err(HalfEdgeError::InvalidEdit("the edge joins two boundaries"));
+        }
+        if corners.iter().any(|corner| self.valence(*corner) == 3 && !self.is_boundary_vertex(*corner)) {
+            return Err(HalfEdgeError::InvalidEdit("collapsing would fold two faces onto each other"));
+        }
+
+        let leaving: Vec<HalfEdgeId> = self.outgoing(keep).into_iter().chain(self.outgoing(remove)).collect();
+        for side in [half_edge, twin] {
+            match self.face(side) {
+                Some(face) => {
+                    //The triangle's other two edges become one, with their outer half-edges as twins.
+                    let (next, previous) = (self.next(side), self.next(self.next(side)));
+                    let (outer_next, outer_previous) = (self.twin(next), self.twin(previous));
+                    self.half_edge_mut(outer_next).twin = outer_previous;
+                    self.half_edge_mut(outer_previous).twin = outer_next;
+                    let corner = self.origin(previous);
+                    if self.vertex_half_edge(corner) == Some(previous) {
+                        self.vertex_mut(corner).half_edge = Some(outer_next);
+                    }
+                    self.half_edges[next.index()] = None;
+                    self.half_edges[previous.index()] = None;
+                    self.faces[face.index()] = None;
+                }
+                None => {
+                    let previous = self.previous(side);
+                    self.half_edge_mut(previous).next = self.next(side);
+                }
+            }
+        }
+        self.half_edges[half_edge.index()] = None;
+        self.half_edges[twin.index()] = None;
+        self.vertices[remove.index()] = None;
+
+        let remaining: Vec<HalfEdgeId> = leaving.into_iter().filter(|h| self.half_edges[h.index()].is_some()).collect();
+        for h in &remaining {
+            self.half_edge_mut(*h).origin = keep;
+        }
+        let vertex = self.vertex_mut(keep);
+        vertex.position = position;
+        vertex.half_edge = remaining.first().copied();
+        if let Some(boundary) = remaining.iter().find(|h| self.face(**h).is_none()) {
+            self.vertex_mut(keep).half_edge = Some(*boundary);
+        }
+        Ok(keep)
+    }
+
+    ///Pushes a copy of a face out by an offset, joined to where it was by a quad along each of its edges, and returns those quads.
+    ///<br>The face keeps its id and moves to the new vertices.
+    pub fn extrude_face(&mut self, face: FaceId, offset: Vector3<N>) -> Vec<FaceId> {
+        let around = self.face_half_edges(face);
+        let count = around.len();
+        let moved: Vec<VertexId> = around.iter().map(|h| self.add_vertex(self.position(self.origin(*h)) + offset)).collect();
+
+        //Side i runs along the old edge from a(i) to a(i + 1), up to b(i + 1), back to b(i) and down to a(i).
+        let sides: Vec<FaceId> = (0..count).map(|_| self.add_face(around[0])).collect();
+        let placeholder = around[0];
+        let up: Vec<HalfEdgeId> = (0..count).map(|i| self.add_half_edge(self.destination(around[i]), placeholder, placeholder, Some(sides[i]))).collect();
+        let back: Vec<HalfEdgeId> = (0..count).map(|i| self.add_half_edge(moved[(i + 1) % count], placeholder, placeholder, Some(sides[i]))).collect();
+        let down: Vec<HalfEdgeId> = (0..count).map(|i| self.add_half_edge(moved[i], placeholder, placeholder, Some(sides[i]))).collect();
+        let top: Vec<HalfEdgeId> = (0..count).map(|i| self.add_half_edge(moved[i], placeholder, placeholder, Some(face))).collect();
+
+        for i in 0..count {
+            self.half_edge_mut(around[i]).face = Some(sides[i]);
+            self.link_loop(&[around[i], up[i], back[i], down[i]]);
+            self.face_mut(sides[i]).half_edge = around[i];
+            self.half_edge_mut(back[i]).twin = top[i];
+            self.half_edge_mut(top[i]).twin = back[i];
+            self.half_edge_mut(up[i]).twin = down[(i + 1) % count];
+            self.half_edge_mut(down[(i + 1) % count]).twin = up[i];
+            self.vertex_mut(moved[i]).half_edge = Some(top[i]);
+        }
+        self.link_loop(&top);
+        self.face_mut(face).half_edge = top[0];
+        sides
+    }
+
+    ///Joins the origins of two half-edges of a face with a new edge, leaving the face on the side of the first and a new one on the side of the second.
+    fn split_face(&mut self, face: FaceId, first: HalfEdgeId, second: HalfEdgeId) -> FaceId {
+        let (before_first, before_second) = (self.previous(first), self.previous(second));
+        let created = self.add_face(second);
+        let closing = self.add_half_edge(self.origin(second), first, first, Some(face));
+        let opening = self.add_half_edge(self.origin(first), closing, second, Some(created));
+        self.half_edge_mut(closing).twin = opening;
+        self.half_edge_mut(before_second).next = closing;
+        self.half_edge_mut(before_first).next = opening;
+        for h in self.half_edge_loop(second) {
+            self.half_edge_mut(h).face = Some(created);
+        }
+        self.face_mut(face).half_edge = first;
+        created
+    }
+
+    fn link(&mut self, a: HalfEdgeId, b: HalfEdgeId, c: HalfEdgeId, face: FaceId) {
+        self.link_loop(&[a, b, c]);
+        for h in [a, b, c] {
+            self.half_edge_mut(h).face = Some(face);
+        }
+        self.face_mut(face).half_edge = a;
+    }
+
+    fn link_loop(&mut self, half_edges: &[HalfEdgeId]) {
+        for (i, h) in half_edges.iter().enumerate() {
+            self.half_edge_mut(*h).next = half_edges[(i + 1) % half_edges.len()];
+        }
+    }
+
+    fn add_vertex(&mut self, position: Vector3<N>) -> VertexId {
+        self.vertices.push(Some(Vertex { position, half_edge: None }));
+        VertexId(self.vertices.len() as u32 - 1)
+    }
+
+    fn add_half_edge(&mut self, origin: VertexId, twin: HalfEdgeId, next: HalfEdgeId, face: Option<FaceId>) -> HalfEdgeId {
+        self.half_edges.push(Some(HalfEdge { origin, twin, next, face }));
+        HalfEdgeId(self.half_edges.len() as u32 - 1)
+    }
+
+    fn add_face(&mut self, half_edge: HalfEdgeId) -> FaceId {
+        self.faces.push(Some(Face { half_edge }));
+        FaceId(self.faces.len() as u32 - 1)
+    }
+}
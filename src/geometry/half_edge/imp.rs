@@ -0,0 +1,368 @@
+use std::collections::HashMap;
+
+use crate::geometry::half_edge::{Face, FaceId, HalfEdge, HalfEdgeError, HalfEdgeId, HalfEdgeMesh, Vertex, VertexId};
+use crate::geometry::Geometry;
+use crate::linear_algebra::vector::types::Vector3;
+use crate::shared::traits::number::Number;
+
+impl<N: Number> HalfEdgeMesh<N> {
+    ///Builds a mesh from positions and polygons listing their vertices counterclockwise around their normal.
+    ///<br>Fails on faces with fewer than 3 vertices or missing ones, and on edges shared by more than two faces or by two faces wound the same way.
+    ///Vertices where separate fans of faces touch are accepted, but are reported by [HalfEdgeMesh::validate].
+    pub fn from_polygons(positions: Vec<Vector3<N>>, faces: &[Vec<u32>]) -> Result<Self, HalfEdgeError> {
+        let count = positions.len() as u32;
+        let mut mesh = Self {
+            vertices: positions.into_iter().map(|position| Some(Vertex { position, half_edge: None })).collect(),
+            half_edges: vec![],
+            faces: vec![],
+        };
+
+        let mut directed: HashMap<(u32, u32), HalfEdgeId> = HashMap::new();
+        for (f, face) in faces.iter().enumerate() {
+            if face.len() < 3 {
+                return Err(HalfEdgeError::DegenerateFace(f));
+            }
+            if let Some(index) = face.iter().find(|index| **index >= count) {
+                return Err(HalfEdgeError::MissingVertex { face: f, index: *index });
+            }
+            let first = mesh.half_edges.len() as u32;
+            let id = FaceId(mesh.faces.len() as u32);
+            for i in 0..face.len() {
+                let (a, b) = (face[i], face[(i + 1) % face.len()]);
+                if a == b {
+                    return Err(HalfEdgeError::DegenerateFace(f));
+                }
+                let half_edge = HalfEdgeId(first + i as u32);
+                if directed.insert((a, b), half_edge).is_some() {
+                    return Err(HalfEdgeError::NonManifoldEdge(a, b));
+                }
+                mesh.half_edges.push(Some(HalfEdge {
+                    origin: VertexId(a),
+                    twin: half_edge,
+                    next: HalfEdgeId(first + ((i + 1) % face.len()) as u32),
+                    face: Some(id),
+                }));
+            }
+            mesh.faces.push(Some(Face { half_edge: HalfEdgeId(first) }));
+        }
+
+        //Twins, with a half-edge without a face along every edge only one face uses.
+        let interior = mesh.half_edges.len() as u32;
+        for h in (0..interior).map(HalfEdgeId) {
+            let (a, b) = (mesh.origin(h).0, mesh.origin(mesh.next(h)).0);
+            let twin = match directed.get(&(b, a)) {
+                Some(twin) => *twin,
+                None => {
+                    mesh.half_edges.push(Some(HalfEdge { origin: VertexId(b), twin: h, next: h, face: None }));
+                    HalfEdgeId(mesh.half_edges.len() as u32 - 1)
+                }
+            };
+            mesh.half_edge_mut(h).twin = twin;
+        }
+
+        //A boundary half-edge runs on to the boundary half-edge leaving its end, found by turning around that vertex through its faces.
+        for b in (interior..mesh.half_edges.len() as u32).map(HalfEdgeId) {
+            let mut around = mesh.twin(b);
+            while mesh.face(around).is_some() {
+                around = mesh.twin(mesh.previous(around));
+            }
+            mesh.half_edge_mut(b).next = around;
+        }
+
+        for h in (0..mesh.half_edges.len() as u32).map(HalfEdgeId) {
+            let boundary = mesh.face(h).is_none();
+            let vertex = mesh.vertex_mut(mesh.origin(h));
+            if vertex.half_edge.is_none() || boundary {
+                vertex.half_edge = Some(h);
+            }
+        }
+        Ok(mesh)
+    }
+
+    ///Builds a mesh from the triangles of a geometry, joining vertices at exactly the same position so the sides of UV seams and hard edges meet.
+    pub fn from_geometry(geometry: &Geometry<3, N>) -> Result<Self, HalfEdgeError> {
+        let (positions, remap) = geometry.shared_positions();
+        let faces: Vec<Vec<u32>> = geometry.indices.chunks_exact(3)
+            .map(|triangle| triangle.iter().map(|index| remap[*index as usize]).collect())
+            .collect();
+        Self::from_polygons(positions, &faces)
+    }
+
+    ///The positions of the vertices still in the mesh and its faces as lists of indices into them, in the order of their ids.
+    pub fn polygons(&self) -> (Vec<Vector3<N>>, Vec<Vec<u32>>) {
+        let mut compacted = vec![0_u32; self.vertices.len()];
+        let positions = self.vertices().enumerate().map(|(new, vertex)| {
+            compacted[vertex.index()] = new as u32;
+            self.position(vertex)
+        }).collect();
+        let faces = self.faces()
+            .map(|face| self.face_vertices(face).iter().map(|vertex| compacted[vertex.index()]).collect())
+            .collect();
+        (positions, faces)
+    }
+
+    ///Triangulates every face as a fan from its first vertex, with smooth normals.
+    pub fn to_geometry(&self) -> Geometry<3, N> {
+        let (vertices, faces) = self.polygons();
+        let indices = faces.iter()
+            .flat_map(|face| (1..face.len() - 1).flat_map(move |i| [face[0], face[i], face[i + 1]]))
+            .collect();
+        let mut geometry = Geometry { vertices, normals: None, uv: vec![], tangents: None, indices };
+        geometry.compute_normals();
+        geometry
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.vertices.iter().flatten().count()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.half_edges.iter().flatten().count() / 2
+    }
+
+    pub fn face_count(&self) -> usize {
+        self.faces.iter().flatten().count()
+    }
+
+    pub fn vertices(&self) -> impl Iterator<Item = VertexId> + '_ {
+        self.vertices.iter().enumerate().filter(|(_, vertex)| vertex.is_some()).map(|(i, _)| VertexId(i as u32))
+    }
+
+    pub fn half_edges(&self) -> impl Iterator<Item = HalfEdgeId> + '_ {
+        self.half_edges.iter().enumerate().filter(|(_, half_edge)| half_edge.is_some()).map(|(i, _)| HalfEdgeId(i as u32))
+    }
+
+    ///One half-edge of every edge.
+    pub fn edges(&self) -> impl Iterator<Item = HalfEdgeId> + '_ {
+        self.half_edges().filter(|h| *h < self.twin(*h))
+    }
+
+    pub fn faces(&self) -> impl Iterator<Item = FaceId> + '_ {
+        self.faces.iter().enumerate().filter(|(_, face)| face.is_some()).map(|(i, _)| FaceId(i as u32))
+    }
+
+    pub fn position(&self, vertex: VertexId) -> Vector3<N> {
+        self.vertex_ref(vertex).position
+    }
+
+    pub fn set_position(&mut self, vertex: VertexId, position: Vector3<N>) {
+        self.vertex_mut(vertex).position = position;
+    }
+
+    ///An outgoing half-edge of a vertex, one along the boundary if it's on one. None if no face uses the vertex.
+    pub fn vertex_half_edge(&self, vertex: VertexId) -> Option<HalfEdgeId> {
+        self.vertex_ref(vertex).half_edge
+    }
+
+    pub fn face_half_edge(&self, face: FaceId) -> HalfEdgeId {
+        self.face_ref(face).half_edge
+    }
+
+    pub fn origin(&self, half_edge: HalfEdgeId) -> VertexId {
+        self.half_edge_ref(half_edge).origin
+    }
+
+    pub fn destination(&self, half_edge: HalfEdgeId) -> VertexId {
+        self.origin(self.twin(half_edge))
+    }
+
+    pub fn twin(&self, half_edge: HalfEdgeId) -> HalfEdgeId {
+        self.half_edge_ref(half_edge).twin
+    }
+
+    pub fn next(&self, half_edge: HalfEdgeId) -> HalfEdgeId {
+        self.half_edge_ref(half_edge).next
+    }
+
+    ///The half-edge before this one in its face or boundary loop, found by walking the loop.
+    pub fn previous(&self, half_edge: HalfEdgeId) -> HalfEdgeId {
+        let mut previous = half_edge;
+        loop {
+            let next = self.next(previous);
+            if next == half_edge {
+                return previous;
+            }
+            previous = next;
+        }
+    }
+
+    ///The face on the left of a half-edge, None along a boundary.
+    pub fn face(&self, half_edge: HalfEdgeId) -> Option<FaceId> {
+        self.half_edge_ref(half_edge).face
+    }
+
+    ///The half-edges of a loop, starting from one of them.
+    pub fn half_edge_loop(&self, start: HalfEdgeId) -> Vec<HalfEdgeId> {
+        let mut half_edges = vec![start];
+        let mut next = self.next(start);
+        while next != start && half_edges.len() <= self.half_edges.len() {
+            half_edges.push(next);
+            next = self.next(next);
+        }
+        half_edges
+    }
+
+    ///The half-edges of a face, counterclockwise around its normal.
+    pub fn face_half_edges(&self, face: FaceId) -> Vec<HalfEdgeId> {
+        self.half_edge_loop(self.face_half_edge(face))
+    }
+
+    ///The vertices of a face, counterclockwise around its normal.
+    pub fn face_vertices(&self, face: FaceId) -> Vec<VertexId> {
+        self.face_half_edges(face).into_iter().map(|half_edge| self.origin(half_edge)).collect()
+    }
+
+    ///The half-edges leaving a vertex, turning clockwise around its normal, starting with the boundary one if it's on a boundary.
+    pub fn outgoing(&self, vertex: VertexId) -> Vec<HalfEdgeId> {
+        let Some(start) = self.vertex_half_edge(vertex) else {
+            return vec![];
+        };
+        let mut outgoing = vec![start];
+        let mut next = self.next(self.twin(start));
+        while next != start && outgoing.len() <= self.half_edges.len() {
+            outgoing.push(next);
+            next = self.next(self.twin(next));
+        }
+        outgoing
+    }
+
+    ///The vertices sharing an edge with a vertex, in the order of [HalfEdgeMesh::outgoing].
+    pub fn one_ring(&self, vertex: VertexId) -> Vec<VertexId> {
+        self.outgoing(vertex).into_iter().map(|half_edge| self.destination(half_edge)).collect()
+    }
+
+    ///The faces around a vertex, in the order of [HalfEdgeMesh::outgoing].
+    pub fn vertex_faces(&self, vertex: VertexId) -> Vec<FaceId> {
+        self.outgoing(vertex).into_iter().filter_map(|half_edge| self.face(half_edge)).collect()
+    }
+
+    ///How many edges meet at a vertex.
+    pub fn valence(&self, vertex: VertexId) -> usize {
+        self.outgoing(vertex).len()
+    }
+
+    pub fn is_boundary_vertex(&self, vertex: VertexId) -> bool {
+        self.vertex_half_edge(vertex).map(|half_edge| self.face(half_edge).is_none()).unwrap_or(false)
+    }
+
+    pub fn is_boundary_edge(&self, half_edge: HalfEdgeId) -> bool {
+        self.face(half_edge).is_none() || self.face(self.twin(half_edge)).is_none()
+    }
+
+    ///The vertices around every hole, in the order the boundary half-edges run, which is clockwise seen from the side the faces face.
+    pub fn boundary_loops(&self) -> Vec<Vec<VertexId>> {
+        let mut visited = vec![false; self.half_edges.len()];
+        let mut loops = vec![];
+        for half_edge in self.half_edges() {
+            if visited[half_edge.index()] || self.face(half_edge).is_some() {
+                continue;
+            }
+            let boundary = self.half_edge_loop(half_edge);
+            boundary.iter().for_each(|h| visited[h.index()] = true);
+            loops.push(boundary.into_iter().map(|h| self.origin(h)).collect());
+        }
+        loops
+    }
+
+    ///The normal of a face scaled by its area, from Newell's method so faces that aren't quite flat still get a sensible one.
+    pub fn face_area_normal(&self, face: FaceId) -> Vector3<N> {
+        let vertices = self.face_vertices(face);
+        let mut normal = Vector3::ZERO;
+        for (i, vertex) in vertices.iter().enumerate() {
+            normal += self.position(*vertex).cross(&self.position(vertices[(i + 1) % vertices.len()]));
+        }
+        normal / N::TWO
+    }
+
+    pub fn face_normal(&self, face: FaceId) -> Vector3<N> {
+        self.face_area_normal(face).normalize()
+    }
+
+    ///The average of the vertices of a face.
+    pub fn face_centroid(&self, face: FaceId) -> Vector3<N> {
+        let vertices = self.face_vertices(face);
+        vertices.iter().fold(Vector3::ZERO, |sum, vertex| sum + self.position(*vertex)) / N::from_f64(vertices.len() as f64)
+    }
+
+    ///Checks that every link between half-edges, faces and vertices agrees, and that the faces around every vertex form a single fan,
+    ///so the mesh is a manifold with or without boundary.
+    pub fn validate(&self) -> Result<(), HalfEdgeError> {
+        let alive = |h: HalfEdgeId| self.half_edges.get(h.index()).map(|half_edge| half_edge.is_some()).unwrap_or(false);
+        for h in self.half_edges() {
+            let half_edge = self.half_edge_ref(h);
+            if !alive(half_edge.twin) || !alive(half_edge.next) {
+                return Err(HalfEdgeError::Inconsistent(h, "links to a removed half-edge"));
+            }
+            if self.vertices.get(half_edge.origin.index()).map(|vertex| vertex.is_none()).unwrap_or(true) {
+                return Err(HalfEdgeError::Inconsistent(h, "starts at a removed vertex"));
+            }
+            if self.twin(half_edge.twin) != h || half_edge.twin == h {
+                return Err(HalfEdgeError::Inconsistent(h, "isn't its twin's twin"));
+            }
+            if self.origin(half_edge.next) != self.destination(h) {
+                return Err(HalfEdgeError::Inconsistent(h, "next half-edge doesn't start at its end"));
+            }
+            if self.face(half_edge.next) != half_edge.face {
+                return Err(HalfEdgeError::Inconsistent(h, "next half-edge is on another face"));
+            }
+            if half_edge.face.is_none() && self.face(half_edge.twin).is_none() {
+                return Err(HalfEdgeError::Inconsistent(h, "edge has no face on either side"));
+            }
+            if half_edge.face.map(|face| self.faces.get(face.index()).map(|face| face.is_none()).unwrap_or(true)).unwrap_or(false) {
+                return Err(HalfEdgeError::Inconsistent(h, "is on a removed face"));
+            }
+        }
+        for face in self.faces() {
+            let start = self.face_half_edge(face);
+            if !alive(start) || self.face(start) != Some(face) {
+                return Err(HalfEdgeError::Inconsistent(start, "face's half-edge isn't on it"));
+            }
+            if self.face_half_edges(face).len() < 3 {
+                return Err(HalfEdgeError::Inconsistent(start, "face has fewer than 3 sides"));
+            }
+        }
+
+        let mut leaving = vec![0_usize; self.vertices.len()];
+        self.half_edges().for_each(|h| leaving[self.origin(h).index()] += 1);
+        for vertex in self.vertices() {
+            match self.vertex_half_edge(vertex) {
+                Some(h) if !alive(h) || self.origin(h) != vertex => {
+                    return Err(HalfEdgeError::Inconsistent(h, "vertex's half-edge doesn't leave it"));
+                }
+                _ => {}
+            }
+            if self.outgoing(vertex).len() != leaving[vertex.index()] {
+                return Err(HalfEdgeError::NonManifoldVertex(vertex));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_manifold(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    pub(crate) fn vertex_ref(&self, vertex: VertexId) -> &Vertex<N> {
+        self.vertices[vertex.index()].as_ref().expect("The vertex was removed")
+    }
+
+    pub(crate) fn vertex_mut(&mut self, vertex: VertexId) -> &mut Vertex<N> {
+        self.vertices[vertex.index()].as_mut().expect("The vertex was removed")
+    }
+
+    pub(crate) fn half_edge_ref(&self, half_edge: HalfEdgeId) -> &HalfEdge {
+        self.half_edges[half_edge.index()].as_ref().expect("The half-edge was removed")
+    }
+
+    pub(crate) fn half_edge_mut(&mut self, half_edge: HalfEdgeId) -> &mut HalfEdge {
+        self.half_edges[half_edge.index()].as_mut().expect("The half-edge was removed")
+    }
+
+    pub(crate) fn face_ref(&self, face: FaceId) -> &Face {
+        self.faces[face.index()].as_ref().expect("The face was removed")
+    }
+
+    pub(crate) fn face_mut(&mut self, face: FaceId) -> &mut Face {
+        self.faces[face.index()].as_mut().expect("The face was removed")
+    }
+}
@@ -0,0 +1,98 @@
+use std::fmt::{Display, Formatter};
+
+use crate::linear_algebra::vector::types::Vector3;
+use crate::shared::traits::number::Number;
+
+pub mod imp;
+pub mod edit;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct VertexId(pub(crate) u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct HalfEdgeId(pub(crate) u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FaceId(pub(crate) u32);
+
+impl VertexId {
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl HalfEdgeId {
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl FaceId {
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Vertex<N: Number> {
+    pub position: Vector3<N>,
+    ///An outgoing half-edge, one without a face if the vertex is on a boundary. None for vertices no face uses.
+    pub half_edge: Option<HalfEdgeId>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct HalfEdge {
+    pub origin: VertexId,
+    pub twin: HalfEdgeId,
+    pub next: HalfEdgeId,
+    ///None for half-edges running along a boundary, which are linked into loops around each hole.
+    pub face: Option<FaceId>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Face {
+    pub half_edge: HalfEdgeId,
+}
+
+///A polygon mesh that knows which vertices, edges and faces are next to each other.
+///<br>Every edge is a pair of twin half-edges running opposite ways, and every face is a loop of half-edges running counterclockwise around its normal.
+///Edges on a boundary have a twin without a face, so walking around any vertex or hole never runs off the mesh.
+///<br>Ids stay valid while the mesh is edited, until what they refer to is removed.
+///<br>See: <a href="https://en.wikipedia.org/wiki/Doubly_connected_edge_list">Doubly connected edge list</a>
+#[derive(Debug, Clone)]
+pub struct HalfEdgeMesh<N: Number> {
+    pub(crate) vertices: Vec<Option<Vertex<N>>>,
+    pub(crate) half_edges: Vec<Option<HalfEdge>>,
+    pub(crate) faces: Vec<Option<Face>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HalfEdgeError {
+    ///A face with fewer than 3 vertices, or the same vertex twice in a row.
+    DegenerateFace(usize),
+    ///A face using a vertex index past the end of the positions.
+    MissingVertex { face: usize, index: u32 },
+    ///An edge between these vertices used by more than two faces, or twice the same way by faces wound inconsistently.
+    NonManifoldEdge(u32, u32),
+    ///A vertex whose faces don't form a single fan, like the tip where two cones touch.
+    NonManifoldVertex(VertexId),
+    ///Links between half-edges that don't agree, found by [HalfEdgeMesh::validate].
+    Inconsistent(HalfEdgeId, &'static str),
+    ///An edit that would leave the mesh degenerate or non-manifold, which is refused without changing anything.
+    InvalidEdit(&'static str),
+}
+
+impl Display for HalfEdgeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HalfEdgeError::DegenerateFace(face) => write!(f, "face {face} is degenerate"),
+            HalfEdgeError::MissingVertex { face, index } => write!(f, "face {face} uses missing vertex {index}"),
+            HalfEdgeError::NonManifoldEdge(a, b) => write!(f, "edge from vertex {a} to {b} is non-manifold"),
+            HalfEdgeError::NonManifoldVertex(vertex) => write!(f, "vertex {} is non-manifold", vertex.0),
+            HalfEdgeError::Inconsistent(half_edge, reason) => write!(f, "half-edge {} is inconsistent: {reason}", half_edge.0),
+            HalfEdgeError::InvalidEdit(reason) => write!(f, "invalid edit: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for HalfEdgeError {}
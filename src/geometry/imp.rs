@@ -0,0 +1,374 @@
+use std::collections::HashMap;
+
+use crate::geometry::Geometry;
+use crate::linear_algebra::vector::types::{Vector2, Vector3, Vector4};
+use crate::linear_algebra::vector::Vector;
+use crate::shared::traits::number::Number;
+
+///Vertices the post transform cache of a GPU is assumed to hold when ordering triangles.
+const VERTEX_CACHE_SIZE: usize = 32;
+
+impl<const L: usize, N: Number> Geometry<L, N> {
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    ///The smallest box around the vertices, as its lowest then highest corner. Both are zero without vertices.
+    pub fn bounds(&self) -> (Vector<L, N>, Vector<L, N>) {
+        let Some(first) = self.vertices.first() else {
+            return (Vector::ZERO, Vector::ZERO);
+        };
+        self.vertices.iter().fold((*first, *first), |(min, max), vertex| (min.min(vertex), max.max(vertex)))
+    }
+
+    ///A sphere around the vertices, as its center and radius, with Ritter's algorithm.
+    ///<br>It starts from the two vertices furthest apart along a rough diameter and grows to take in any vertex left outside,
+    ///which lands within a few percent of the smallest sphere.
+    ///<br>See: <a href="https://en.wikipedia.org/wiki/Bounding_sphere#Ritter's_bounding_sphere">Ritter's bounding sphere</a>
+    pub fn bounding_sphere(&self) -> (Vector<L, N>, N) {
+        let Some(first) = self.vertices.first() else {
+            return (Vector::ZERO, N::ZERO);
+        };
+        let furthest = |from: &Vector<L, N>| *self.vertices.iter()
+            .max_by(|a, b| a.distance(from).partial_cmp(&b.distance(from)).unwrap_or(std::cmp::Ordering::Equal))
+            .unwrap_or(from);
+        let a = furthest(first);
+        let b = furthest(&a);
+        let mut center = (a + b) / N::TWO;
+        let mut radius = a.distance(&b) / N::TWO;
+        for vertex in &self.vertices {
+            let distance = vertex.distance(&center);
+            if distance > radius {
+                let grown = (radius + distance) / N::TWO;
+                center = center + (*vertex - center) * ((grown - radius) / distance);
+                radius = grown;
+            }
+        }
+        (center, radius)
+    }
+
+    ///Appends another geometry's vertices and triangles to this one's.
+    ///<br>Normals and tangents are kept only if both have them, and UVs are filled in with zeros for whichever lacks them.
+    pub fn append(&mut self, other: &Geometry<L, N>) {
+        let offset = self.vertices.len() as u32;
+        let (count, other_count) = (self.vertices.len(), other.vertices.len());
+
+        if !self.uv.is_empty() || !other.uv.is_empty() {
+            self.uv.resize(count, Vector2::ZERO);
+            self.uv.extend(other.uv.iter().copied().chain(std::iter::repeat(Vector2::ZERO)).take(other_count));
+        }
+        self.normals = match (self.normals.take(), &other.normals) {
+            (Some(mut normals), Some(other)) => {
+                normals.extend_from_slice(other);
+                Some(normals)
+            }
+            (None, Some(other)) if count == 0 => Some(other.clone()),
+            (Some(normals), None) if other_count == 0 => Some(normals),
+            _ => None,
+        };
+        self.tangents = match (self.tangents.take(), &other.tangents) {
+            (Some(mut tangents), Some(other)) => {
+                tangents.extend_from_slice(other);
+                Some(tangents)
+            }
+            (None, Some(other)) if count == 0 => Some(other.clone()),
+            (Some(tangents), None) if other_count == 0 => Some(tangents),
+            _ => None,
+        };
+        self.vertices.extend_from_slice(&other.vertices);
+        self.indices.extend(other.indices.iter().map(|index| index + offset));
+    }
+
+    ///A single geometry holding all of the provided ones, as if each were [Geometry::append]ed in turn.
+    pub fn merge(geometries: &[Geometry<L, N>]) -> Geometry<L, N> {
+        let mut merged = Geometry { vertices: vec![], normals: None, uv: vec![], tangents: None, indices: vec![] };
+        for geometry in geometries {
+            merged.append(geometry);
+        }
+        merged
+    }
+
+    ///Joins vertices whose positions, normals, UVs and tangents all lie within the tolerance of each other, then drops the triangles that collapse.
+    ///<br>Vertices differing in anything but position, like on either side of a UV seam or a hard edge, stay apart.
+    ///<br>Returns how many vertices were removed.
+    pub fn weld(&mut self, tolerance: N) -> usize {
+        let cell = if tolerance > N::ZERO { tolerance } else { N::ONE };
+        let key = |vertex: &Vector<L, N>| vertex.0.map(|component| (component / cell).to_f64().floor() as i64);
+
+        let mut grid: HashMap<[i64; L], Vec<u32>> = HashMap::new();
+        let mut remap = Vec::with_capacity(self.vertices.len());
+        let mut kept: Vec<u32> = vec![];
+        for (index, vertex) in self.vertices.iter().enumerate() {
+            let home = key(vertex);
+            let mut found = None;
+            'search: for offset in 0..3_usize.pow(L as u32) {
+                let mut neighbour = home;
+                let mut rest = offset;
+                for component in neighbour.iter_mut() {
+                    *component += (rest % 3) as i64 - 1;
+                    rest /= 3;
+                }
+                for candidate in grid.get(&neighbour).into_iter().flatten() {
+                    if self.same_vertex(*candidate as usize, index, tolerance) {
+                        found = Some(*candidate);
+                        break 'search;
+                    }
+                }
+            }
+            let target = found.unwrap_or_else(|| {
+                grid.entry(home).or_default().push(index as u32);
+                kept.push(index as u32);
+                index as u32
+            });
+            remap.push(target);
+        }
+
+        let mut compacted = vec![0_u32; self.vertices.len()];
+        for (new, old) in kept.iter().enumerate() {
+            compacted[*old as usize] = new as u32;
+        }
+        let indices: Vec<u32> = self.indices.chunks_exact(3)
+            .map(|triangle| triangle.iter().map(|index| compacted[remap[*index as usize] as usize]).collect::<Vec<u32>>())
+            .filter(|triangle| triangle[0] != triangle[1] && triangle[1] != triangle[2] && triangle[2] != triangle[0])
+            .flatten()
+            .collect();
+
+        let removed = self.vertices.len() - kept.len();
+        self.keep_vertices(&kept);
+        self.indices = indices;
+        removed
+    }
+
+    fn same_vertex(&self, a: usize, b: usize, tolerance: N) -> bool {
+        self.vertices[a].distance(&self.vertices[b]) <= tolerance
+            && self.normals.as_ref().map(|normals| normals[a].distance(&normals[b]) <= tolerance).unwrap_or(true)
+            && self.uv.get(a).zip(self.uv.get(b)).map(|(a, b)| a.distance(b) <= tolerance).unwrap_or(true)
+            && self.tangents.as_ref().map(|tangents| tangents[a].distance(&tangents[b]) <= tolerance).unwrap_or(true)
+    }
+
+    ///Every distinct position among the vertices, and for each vertex the index of its position, so vertices split along seams and hard edges can be told to be the same point.
+    pub(crate) fn shared_positions(&self) -> (Vec<Vector<L, N>>, Vec<u32>) {
+        let mut shared: HashMap<[u64; L], u32> = HashMap::new();
+        let mut positions = vec![];
+        let remap = self.vertices.iter().map(|vertex| {
+            //Adding zero turns negative zero positive, so they're the same point too.
+            *shared.entry(vertex.0.map(|component| (component.to_f64() + 0_f64).to_bits())).or_insert_with(|| {
+                positions.push(*vertex);
+                positions.len() as u32 - 1
+            })
+        }).collect();
+        (positions, remap)
+    }
+
+    ///Keeps only the listed vertices, in that order, without touching the indices.
+    pub(crate) fn keep_vertices(&mut self, kept: &[u32]) {
+        self.vertices = kept.iter().map(|i| self.vertices[*i as usize]).collect();
+        if let Some(normals) = &mut self.normals {
+            *normals = kept.iter().map(|i| normals[*i as usize]).collect();
+        }
+        if !self.uv.is_empty() {
+            self.uv = kept.iter().map(|i| self.uv.get(*i as usize).copied().unwrap_or(Vector2::ZERO)).collect();
+        }
+        if let Some(tangents) = &mut self.tangents {
+            *tangents = kept.iter().map(|i| tangents[*i as usize]).collect();
+        }
+    }
+
+    ///Reorders triangles so vertices get reused while still in the GPU's vertex cache, with Tom Forsyth's linear speed algorithm,
+    ///then reorders vertices by first use so they're fetched in order, dropping any no triangle uses.
+    ///<br>See: <a href="https://tomforsyth1000.github.io/papers/fast_vert_cache_opt.html">Linear-speed vertex cache optimisation</a>
+    pub fn optimize_indices(&mut self) {
+        let count = self.vertices.len();
+        let triangles: Vec<[u32; 3]> = self.indices.chunks_exact(3).map(|t| [t[0], t[1], t[2]]).collect();
+
+        let mut adjacency: Vec<Vec<usize>> = vec![vec![]; count];
+        for (t, triangle) in triangles.iter().enumerate() {
+            for vertex in triangle {
+                adjacency[*vertex as usize].push(t);
+            }
+        }
+        let mut remaining: Vec<usize> = adjacency.iter().map(|triangles| triangles.len()).collect();
+        let mut position: Vec<Option<usize>> = vec![None; count];
+        let mut scores: Vec<f64> = (0..count).map(|v| vertex_score(None, remaining[v])).collect();
+        let mut emitted = vec![false; triangles.len()];
+        let triangle_score = |triangle: &[u32; 3], scores: &[f64]| triangle.iter().map(|v| scores[*v as usize]).sum::<f64>();
+
+        let mut cache: Vec<u32> = vec![];
+        let mut order = Vec::with_capacity(triangles.len());
+        let mut best: Option<usize> = None;
+        let mut next_unemitted = 0;
+        while order.len() < triangles.len() {
+            //Without a candidate from the cache, start again from the first triangle left, rather than scoring them all,
+            //which would take quadratic time on meshes in many pieces.
+            let chosen = match best {
+                Some(best) => best,
+                None => {
+                    while emitted[next_unemitted] {
+                        next_unemitted += 1;
+                    }
+                    next_unemitted
+                }
+            };
+            emitted[chosen] = true;
+            order.push(triangles[chosen]);
+
+            for vertex in triangles[chosen] {
+                remaining[vertex as usize] -= 1;
+                cache.retain(|cached| *cached != vertex);
+                cache.insert(0, vertex);
+            }
+            let evicted: Vec<u32> = if cache.len() > VERTEX_CACHE_SIZE { cache.split_off(VERTEX_CACHE_SIZE) } else { vec![] };
+            for vertex in &evicted {
+                position[*vertex as usize] = None;
+                scores[*vertex as usize] = vertex_score(None, remaining[*vertex as usize]);
+            }
+            for (slot, vertex) in cache.iter().enumerate() {
+                position[*vertex as usize] = Some(slot);
+                scores[*vertex as usize] = vertex_score(Some(slot), remaining[*vertex as usize]);
+            }
+
+            best = cache.iter()
+                .flat_map(|vertex| adjacency[*vertex as usize].iter())
+                .filter(|t| !emitted[**t])
+                .max_by(|a, b| triangle_score(&triangles[**a], &scores).total_cmp(&triangle_score(&triangles[**b], &scores)))
+                .copied();
+        }
+
+        //Vertices numbered by first use.
+        let mut renumbered: Vec<Option<u32>> = vec![None; count];
+        let mut kept = vec![];
+        for vertex in order.iter().flatten() {
+            if renumbered[*vertex as usize].is_none() {
+                renumbered[*vertex as usize] = Some(kept.len() as u32);
+                kept.push(*vertex);
+            }
+        }
+        self.indices = order.iter().flatten().map(|vertex| renumbered[*vertex as usize].unwrap_or(0)).collect();
+        self.keep_vertices(&kept);
+    }
+
+    ///How many vertices a first in first out cache of a size misses per triangle drawn, from 0.5 at best for a large grid to 3 at worst.
+    pub fn average_cache_miss_ratio(&self, cache_size: usize) -> f64 {
+        if self.indices.len() < 3 {
+            return 0_f64;
+        }
+        let mut cache = std::collections::VecDeque::with_capacity(cache_size);
+        let mut misses = 0;
+        for index in &self.indices {
+            if !cache.contains(index) {
+                misses += 1;
+                cache.push_back(*index);
+                if cache.len() > cache_size {
+                    cache.pop_front();
+                }
+            }
+        }
+        misses as f64 / self.triangle_count() as f64
+    }
+}
+
+impl<N: Number> Geometry<3, N> {
+    ///The unit normal of every triangle, counterclockwise winding facing out.
+    pub fn face_normals(&self) -> Vec<Vector3<N>> {
+        self.indices.chunks_exact(3).map(|triangle| {
+            let [a, b, c] = [0, 1, 2].map(|k| self.vertices[triangle[k] as usize]);
+            let normal = (b - a).cross(&(c - a));
+            let magnitude = normal.magnitude();
+            if magnitude > N::ZERO { normal / magnitude } else { Vector3::ZERO }
+        }).collect()
+    }
+
+    ///Smooth normals shared by the triangles around each vertex, each weighted by the angle it has at the vertex
+    ///so how a surface happens to be split into triangles doesn't tilt them.
+    pub fn compute_normals(&mut self) {
+        let mut normals = vec![Vector3::ZERO; self.vertices.len()];
+        for (triangle, face) in self.indices.chunks_exact(3).zip(self.face_normals()) {
+            for k in 0..3 {
+                let corner = triangle[k] as usize;
+                normals[corner] += face * self.corner_angle(triangle, k);
+            }
+        }
+        self.normals = Some(normals.into_iter().map(|normal| {
+            let magnitude = normal.magnitude();
+            if magnitude > N::ZERO { normal / magnitude } else { Vector3::UP }
+        }).collect());
+    }
+
+    ///Gives every triangle its own vertices, with its face normal, for flat shading.
+    pub fn compute_flat_normals(&mut self) {
+        let faces = self.face_normals();
+        let corners = self.indices.clone();
+        self.normals = None;
+        self.keep_vertices(&corners);
+        self.normals = Some(faces.into_iter().flat_map(|face| [face; 3]).collect());
+        self.indices = (0..corners.len() as u32).collect();
+    }
+
+    ///Tangents following increasing u, the way MikkTSpace builds them: each triangle's direction of increasing u and v,
+    ///summed around every vertex weighted by its angle there, made perpendicular to the vertex normal, with the handedness from the v direction.
+    ///<br>Smooth normals are computed first if there aren't any. Without UVs every tangent is just some direction perpendicular to its normal.
+    ///<br>See: <a href="http://www.mikktspace.com/">MikkTSpace</a>
+    pub fn compute_tangents(&mut self) {
+        if self.normals.is_none() {
+            self.compute_normals();
+        }
+        let count = self.vertices.len();
+        let (mut along_u, mut along_v) = (vec![Vector3::ZERO; count], vec![Vector3::ZERO; count]);
+        if self.uv.len() == count {
+            for triangle in self.indices.chunks_exact(3) {
+                let [a, b, c] = [0, 1, 2].map(|k| triangle[k] as usize);
+                let (edge1, edge2) = (self.vertices[b] - self.vertices[a], self.vertices[c] - self.vertices[a]);
+                let (uv1, uv2) = (self.uv[b] - self.uv[a], self.uv[c] - self.uv[a]);
+                let determinant = uv1.x() * uv2.y() - uv2.x() * uv1.y();
+                if determinant.absolute() <= N::from_f64(1e-20) {
+                    continue;
+                }
+                let u = (edge1 * uv2.y() - edge2 * uv1.y()) / determinant;
+                let v = (edge2 * uv1.x() - edge1 * uv2.x()) / determinant;
+                for k in 0..3 {
+                    let weight = self.corner_angle(triangle, k);
+                    along_u[triangle[k] as usize] += u * weight;
+                    along_v[triangle[k] as usize] += v * weight;
+                }
+            }
+        }
+
+        let normals = self.normals.as_ref().expect("Normals were just computed");
+        self.tangents = Some((0..count).map(|i| tangent(normals[i], along_u[i], along_v[i])).collect());
+    }
+
+    ///The angle a triangle has at one of its corners.
+    fn corner_angle(&self, triangle: &[u32], corner: usize) -> N {
+        let at = self.vertices[triangle[corner] as usize];
+        let a = self.vertices[triangle[(corner + 1) % 3] as usize] - at;
+        let b = self.vertices[triangle[(corner + 2) % 3] as usize] - at;
+        a.cross(&b).magnitude().arc_tan2(a.dot(&b))
+    }
+}
+
+///Tom Forsyth's score of a vertex: higher the more recently it entered the cache, save for the last triangle's which would have to wait,
+///and the fewer triangles still use it, so stragglers get finished off.
+fn vertex_score(position: Option<usize>, remaining: usize) -> f64 {
+    if remaining == 0 {
+        return -1_f64;
+    }
+    let cache = match position {
+        None => 0_f64,
+        Some(position) if position < 3 => 0.75,
+        Some(position) => (1_f64 - (position - 3) as f64 / (VERTEX_CACHE_SIZE - 3) as f64).powf(1.5),
+    };
+    cache + 2_f64 * (remaining as f64).powf(-0.5)
+}
+
+///A tangent for a vertex: the direction of increasing u made perpendicular to the normal, and the handedness that turns the cross product of the normal and it towards increasing v.
+pub(crate) fn tangent<N: Number>(normal: Vector3<N>, along_u: Vector3<N>, along_v: Vector3<N>) -> Vector4<N> {
+    let mut tangent = along_u - normal * normal.dot(&along_u);
+    if tangent.magnitude_squared() <= N::from_f64(1e-24) {
+        //No direction along u here, so any direction perpendicular to the normal will do.
+        let axis = if normal.x().absolute() < N::from_f64(0.9) { Vector3::RIGHT } else { Vector3::UP };
+        tangent = normal.cross(&axis).cross(&normal);
+    }
+    let tangent = tangent.normalize();
+    let handedness = if normal.cross(&tangent).dot(&along_v) < N::ZERO { N::ZERO - N::ONE } else { N::ONE };
+    Vector4::new([tangent.x(), tangent.y(), tangent.z(), handedness])
+}
@@ -1,17 +1,27 @@
-use crate::linear_algebra::vector::types::{Vector2, Vector3F32};
+use crate::linear_algebra::vector::types::{Vector2, Vector3F32, Vector4};
 use crate::linear_algebra::vector::Vector;
 use crate::shared::traits::number::Number;
 
+pub mod imp;
 pub mod shape;
 pub mod triangle;
 pub mod curve;
 pub mod bezier;
+pub mod spline;
 pub mod uv_sphere;
+pub mod primitive;
+pub mod half_edge;
+pub mod subdivision;
+pub mod simplify;
+pub mod format;
 
 #[derive(Debug, Clone)]
 pub struct Geometry<const L: usize, N: Number> {
     pub vertices: Vec<Vector<L, N>>,
     pub normals: Option<Vec<Vector<L, N>>>,
     pub uv: Vec<Vector2<N>>,
+    ///Unit tangents pointing towards increasing u, with the handedness in w: the bitangent, towards increasing v, is the cross product of the normal and the tangent times w.
+    ///<br>See: <a href="https://registry.khronos.org/glTF/specs/2.0/glTF-2.0.html#meshes-overview">glTF tangents</a>
+    pub tangents: Option<Vec<Vector4<N>>>,
     pub indices: Vec<u32>,
-}
\ No newline at end of file
+}
@@ -0,0 +1,118 @@
+use crate::geometry::imp::tangent;
+use crate::geometry::Geometry;
+use crate::linear_algebra::vector::types::{Vector2, Vector3, Vector4};
+use crate::shared::traits::number::Number;
+
+///A point of the outline a surface of revolution is swept from, in the plane of its distance from the y axis and its height.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ProfilePoint<N: Number> {
+    pub radius: N,
+    pub y: N,
+    ///Unit normal as its distance from the axis and its height. Points further along the outline lie in the direction of its height and negated distance.
+    pub normal: (N, N),
+    pub v: N,
+}
+
+///Collects vertices and triangles of a primitive being built.
+#[derive(Debug, Default)]
+pub(crate) struct MeshBuilder<N: Number> {
+    vertices: Vec<Vector3<N>>,
+    normals: Vec<Vector3<N>>,
+    uv: Vec<Vector2<N>>,
+    tangents: Vec<Vector4<N>>,
+    indices: Vec<u32>,
+}
+
+impl<N: Number> MeshBuilder<N> {
+    pub fn new() -> Self {
+        Self { vertices: vec![], normals: vec![], uv: vec![], tangents: vec![], indices: vec![] }
+    }
+
+    pub fn vertex(&mut self, position: Vector3<N>, normal: Vector3<N>, uv: Vector2<N>, tangent: Vector4<N>) -> u32 {
+        self.vertices.push(position);
+        self.normals.push(normal);
+        self.uv.push(uv);
+        self.tangents.push(tangent);
+        self.vertices.len() as u32 - 1
+    }
+
+    pub fn triangle(&mut self, a: u32, b: u32, c: u32) {
+        self.indices.extend_from_slice(&[a, b, c]);
+    }
+
+    ///Sweeps an outline, listed from top to bottom, around the y axis.
+    ///<br>The seam's vertices are doubled so u runs from 0 to 1, and where the outline touches the axis only one triangle of each quad is kept.
+    pub fn revolve(&mut self, profile: &[ProfilePoint<N>], segments: usize) {
+        let segments = segments.max(3);
+        let start = self.vertices.len() as u32;
+        for point in profile {
+            for j in 0..=segments {
+                let u = N::from_f64(j as f64 / segments as f64);
+                //Both sides of the seam get exactly the same position.
+                let (sin, cos) = around(N::from_f64((j % segments) as f64 / segments as f64));
+                let (normal_radius, normal_y) = point.normal;
+                let normal = Vector3::new([normal_radius * sin, normal_y, normal_radius * cos]);
+                let along_u = Vector3::new([cos, N::ZERO, N::ZERO - sin]);
+                let along_v = Vector3::new([normal_y * sin, N::ZERO - normal_radius, normal_y * cos]);
+                self.vertex(
+                    Vector3::new([point.radius * sin, point.y, point.radius * cos]),
+                    normal,
+                    Vector2::new([u, point.v]),
+                    tangent(normal, along_u, along_v),
+                );
+            }
+        }
+
+        let stride = segments as u32 + 1;
+        for (i, pair) in profile.windows(2).enumerate() {
+            for j in 0..segments as u32 {
+                let a = start + i as u32 * stride + j;
+                let (b, c, d) = (a + stride, a + stride + 1, a + 1);
+                if pair[1].radius > N::ZERO {
+                    self.triangle(a, b, c);
+                }
+                if pair[0].radius > N::ZERO {
+                    self.triangle(a, c, d);
+                }
+            }
+        }
+    }
+
+    ///A disk at a height facing up or down, mapped onto the texture as seen from above.
+    pub fn disk(&mut self, radius: N, y: N, up: bool, segments: usize) {
+        let segments = segments.max(3);
+        let normal = if up { Vector3::UP } else { Vector3::new([N::ZERO, N::ZERO - N::ONE, N::ZERO]) };
+        let tangent = tangent(normal, Vector3::RIGHT, Vector3::FORWARD);
+        let half = N::ONE / N::TWO;
+        let center = self.vertex(Vector3::new([N::ZERO, y, N::ZERO]), normal, Vector2::new([half, half]), tangent);
+        for j in 0..segments {
+            let (sin, cos) = around(N::from_f64(j as f64 / segments as f64));
+            let uv = Vector2::new([half + sin * half, half + cos * half]);
+            self.vertex(Vector3::new([radius * sin, y, radius * cos]), normal, uv, tangent);
+        }
+        for j in 0..segments as u32 {
+            let (a, b) = (center + 1 + j, center + 1 + (j + 1) % segments as u32);
+            if up {
+                self.triangle(center, a, b);
+            } else {
+                self.triangle(center, b, a);
+            }
+        }
+    }
+
+    pub fn finish(self) -> Geometry<3, N> {
+        Geometry {
+            vertices: self.vertices,
+            normals: Some(self.normals),
+            uv: self.uv,
+            tangents: Some(self.tangents),
+            indices: self.indices,
+        }
+    }
+}
+
+///Sine and cosine of the angle around the y axis at u, which starts and ends at the back.
+pub(crate) fn around<N: Number>(u: N) -> (N, N) {
+    let angle = N::TWO * N::PI * u - N::PI;
+    (angle.sine(), angle.cosine())
+}
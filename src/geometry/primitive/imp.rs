@@ -0,0 +1,225 @@
+use std::collections::BTreeMap;
+
+use crate::geometry::imp::tangent;
+use crate::geometry::primitive::builder::{around, MeshBuilder, ProfilePoint};
+use crate::geometry::primitive::{Capsule, Cone, Cube, Cylinder, Disk, Icosphere, Plane, Torus};
+use crate::geometry::Geometry;
+use crate::linear_algebra::vector::types::{Vector2, Vector3};
+use crate::shared::traits::number::Number;
+
+///Triangles of an icosahedron, wound counterclockwise from outside.
+const ICOSAHEDRON: [[u32; 3]; 20] = [
+    [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+    [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+    [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+    [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+];
+
+impl<N: Number> Icosphere<N> {
+    pub fn to_geometry(self) -> Geometry<3, N> {
+        let t = (1_f64 + 5_f64.sqrt()) / 2_f64;
+        let mut positions: Vec<Vector3<N>> = [
+            [-1_f64, t, 0_f64], [1_f64, t, 0_f64], [-1_f64, -t, 0_f64], [1_f64, -t, 0_f64],
+            [0_f64, -1_f64, t], [0_f64, 1_f64, t], [0_f64, -1_f64, -t], [0_f64, 1_f64, -t],
+            [t, 0_f64, -1_f64], [t, 0_f64, 1_f64], [-t, 0_f64, -1_f64], [-t, 0_f64, 1_f64],
+        ].into_iter().map(|p| Vector3::new(p.map(N::from_f64)).normalize()).collect();
+        let mut triangles = ICOSAHEDRON.to_vec();
+
+        for _ in 0..self.subdivisions {
+            let mut midpoints = BTreeMap::new();
+            let mut midpoint = |a: u32, b: u32, positions: &mut Vec<Vector3<N>>| {
+                *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                    positions.push(((positions[a as usize] + positions[b as usize]) / N::TWO).normalize());
+                    positions.len() as u32 - 1
+                })
+            };
+            triangles = triangles.into_iter().flat_map(|[a, b, c]| {
+                let (ab, bc, ca) = (midpoint(a, b, &mut positions), midpoint(b, c, &mut positions), midpoint(c, a, &mut positions));
+                [[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+            }).collect();
+        }
+
+        //Vertices are shared until a triangle crosses the seam, or touches a pole where u is whatever suits the triangle.
+        let mut builder = MeshBuilder::new();
+        let mut shared = BTreeMap::new();
+        let half = N::ONE / N::TWO;
+        for triangle in triangles {
+            let mut u = triangle.map(|i| {
+                let p = positions[i as usize];
+                p.x().arc_tan2(p.z()) / (N::TWO * N::PI) + half
+            });
+            let (min, max) = (u[0].num_min(&u[1]).num_min(&u[2]), u[0].num_max(&u[1]).num_max(&u[2]));
+            if max - min > half {
+                u = u.map(|u| if u < half { u + N::ONE } else { u });
+            }
+            let poles = triangle.map(|i| {
+                let p = positions[i as usize];
+                p.x() * p.x() + p.z() * p.z() < N::from_f64(1e-12)
+            });
+            for k in 0..3 {
+                if poles[k] {
+                    u[k] = (u[(k + 1) % 3] + u[(k + 2) % 3]) / N::TWO;
+                }
+            }
+
+            let corners = [0, 1, 2].map(|k| {
+                let index = triangle[k];
+                *shared.entry((index, (u[k].to_f64() * 1e9).round() as i64)).or_insert_with(|| {
+                    let normal = positions[index as usize];
+                    let latitude = normal.y().clamp(N::ZERO - N::ONE, N::ONE);
+                    let v = half - latitude.arc_tan2((N::ONE - latitude * latitude).num_max(&N::ZERO).num_sqrt()) / N::PI;
+                    let (sin, cos) = around(u[k]);
+                    let along_u = Vector3::new([cos, N::ZERO, N::ZERO - sin]);
+                    let along_v = Vector3::new([latitude * sin, N::ZERO - (N::ONE - latitude * latitude).num_max(&N::ZERO).num_sqrt(), latitude * cos]);
+                    builder.vertex(normal * self.radius, normal, Vector2::new([u[k], v]), tangent(normal, along_u, along_v))
+                })
+            });
+            builder.triangle(corners[0], corners[1], corners[2]);
+        }
+        builder.finish()
+    }
+}
+
+impl<N: Number> Cube<N> {
+    pub fn to_geometry(self) -> Geometry<3, N> {
+        let mut builder = MeshBuilder::new();
+        let (one, zero) = (N::ONE, N::ZERO);
+        let minus = zero - one;
+        //Each face as its normal, then the directions to its right and up as seen from outside.
+        let faces = [
+            ([zero, zero, one], [one, zero, zero], [zero, one, zero]),
+            ([zero, zero, minus], [minus, zero, zero], [zero, one, zero]),
+            ([one, zero, zero], [zero, zero, minus], [zero, one, zero]),
+            ([minus, zero, zero], [zero, zero, one], [zero, one, zero]),
+            ([zero, one, zero], [one, zero, zero], [zero, zero, minus]),
+            ([zero, minus, zero], [one, zero, zero], [zero, zero, one]),
+        ];
+        let half = self.size / N::TWO;
+        for (normal, right, up) in faces {
+            let (normal, right, up) = (Vector3::new(normal), Vector3::new(right), Vector3::new(up));
+            let tangent = tangent(normal, right, Vector3::ZERO - up);
+            let corners = [(zero, zero), (one, zero), (one, one), (zero, one)].map(|(s, t)| {
+                let direction = normal + right * (N::TWO * s - one) + up * (N::TWO * t - one);
+                let position = Vector3::new([direction.x() * half.x(), direction.y() * half.y(), direction.z() * half.z()]);
+                builder.vertex(position, normal, Vector2::new([s, one - t]), tangent)
+            });
+            builder.triangle(corners[0], corners[1], corners[2]);
+            builder.triangle(corners[0], corners[2], corners[3]);
+        }
+        builder.finish()
+    }
+}
+
+impl<N: Number> Plane<N> {
+    pub fn to_geometry(self) -> Geometry<3, N> {
+        let mut builder = MeshBuilder::new();
+        let (segments_x, segments_z) = (self.segments_x.max(1), self.segments_z.max(1));
+        let tangent = tangent(Vector3::UP, Vector3::RIGHT, Vector3::FORWARD);
+        for i in 0..=segments_z {
+            let v = N::from_f64(i as f64 / segments_z as f64);
+            for j in 0..=segments_x {
+                let u = N::from_f64(j as f64 / segments_x as f64);
+                let position = Vector3::new([self.width * (u - N::ONE / N::TWO), N::ZERO, self.depth * (v - N::ONE / N::TWO)]);
+                builder.vertex(position, Vector3::UP, Vector2::new([u, v]), tangent);
+            }
+        }
+        let stride = segments_x as u32 + 1;
+        for i in 0..segments_z as u32 {
+            for j in 0..segments_x as u32 {
+                let a = i * stride + j;
+                let (b, c, d) = (a + stride, a + stride + 1, a + 1);
+                builder.triangle(a, b, c);
+                builder.triangle(a, c, d);
+            }
+        }
+        builder.finish()
+    }
+}
+
+impl<N: Number> Disk<N> {
+    pub fn to_geometry(self) -> Geometry<3, N> {
+        let mut builder = MeshBuilder::new();
+        builder.disk(self.radius, N::ZERO, true, self.segments);
+        builder.finish()
+    }
+}
+
+impl<N: Number> Cylinder<N> {
+    pub fn to_geometry(self) -> Geometry<3, N> {
+        let mut builder = MeshBuilder::new();
+        let rings = self.rings.max(1);
+        let half = self.height / N::TWO;
+        let profile: Vec<ProfilePoint<N>> = (0..=rings).map(|k| {
+            let v = N::from_f64(k as f64 / rings as f64);
+            ProfilePoint { radius: self.radius, y: half - self.height * v, normal: (N::ONE, N::ZERO), v }
+        }).collect();
+        builder.revolve(&profile, self.segments);
+        if self.capped {
+            builder.disk(self.radius, half, true, self.segments);
+            builder.disk(self.radius, N::ZERO - half, false, self.segments);
+        }
+        builder.finish()
+    }
+}
+
+impl<N: Number> Cone<N> {
+    pub fn to_geometry(self) -> Geometry<3, N> {
+        let mut builder = MeshBuilder::new();
+        let half = self.height / N::TWO;
+        let slant = (self.height * self.height + self.radius * self.radius).num_sqrt();
+        let normal = (self.height / slant, self.radius / slant);
+        builder.revolve(&[
+            ProfilePoint { radius: N::ZERO, y: half, normal, v: N::ZERO },
+            ProfilePoint { radius: self.radius, y: N::ZERO - half, normal, v: N::ONE },
+        ], self.segments);
+        if self.capped {
+            builder.disk(self.radius, N::ZERO - half, false, self.segments);
+        }
+        builder.finish()
+    }
+}
+
+impl<N: Number> Capsule<N> {
+    pub fn to_geometry(self) -> Geometry<3, N> {
+        let mut builder = MeshBuilder::new();
+        let rings = self.rings.max(1);
+        let half = (self.height / N::TWO - self.radius).num_max(&N::ZERO);
+        let quarter = N::PI / N::TWO;
+        //Distance along the outline from the top, for v.
+        let total = N::PI * self.radius + N::TWO * half;
+
+        let mut profile = vec![];
+        for k in 0..=rings {
+            let angle = quarter - quarter * N::from_f64(k as f64 / rings as f64);
+            let radius = if k == 0 { N::ZERO } else { self.radius * angle.cosine() };
+            let travelled = self.radius * (quarter - angle);
+            profile.push(ProfilePoint { radius, y: half + self.radius * angle.sine(), normal: (angle.cosine(), angle.sine()), v: travelled / total });
+        }
+        //Without a cylinder between them, the hemispheres share their equator.
+        let first = if half > N::ZERO { 0 } else { 1 };
+        for k in first..=rings {
+            let angle = N::ZERO - quarter * N::from_f64(k as f64 / rings as f64);
+            let radius = if k == rings { N::ZERO } else { self.radius * angle.cosine() };
+            let travelled = self.radius * quarter + N::TWO * half + self.radius * (N::ZERO - angle);
+            profile.push(ProfilePoint { radius, y: N::ZERO - half + self.radius * angle.sine(), normal: (angle.cosine(), angle.sine()), v: travelled / total });
+        }
+        builder.revolve(&profile, self.segments);
+        builder.finish()
+    }
+}
+
+impl<N: Number> Torus<N> {
+    pub fn to_geometry(self) -> Geometry<3, N> {
+        let mut builder = MeshBuilder::new();
+        let segments = self.minor_segments.max(3);
+        let profile: Vec<ProfilePoint<N>> = (0..=segments).map(|k| {
+            let v = N::from_f64(k as f64 / segments as f64);
+            //From the top of the tube, outwards and down, under and back up its inside.
+            let angle = N::PI / N::TWO - N::TWO * N::PI * v;
+            let (sin, cos) = (angle.sine(), angle.cosine());
+            ProfilePoint { radius: self.major_radius + self.minor_radius * cos, y: self.minor_radius * sin, normal: (cos, sin), v }
+        }).collect();
+        builder.revolve(&profile, self.major_segments);
+        builder.finish()
+    }
+}
@@ -0,0 +1,83 @@
+use crate::linear_algebra::vector::types::Vector3;
+use crate::shared::traits::number::Number;
+
+pub mod imp;
+pub mod builder;
+
+//Every primitive is centered on the origin with y up, has normals, UVs and tangents, and winds its triangles counterclockwise seen from outside.
+//Round ones wrap u once around the y axis, starting and ending at the back with their seam, and run v from 0 at the top to 1 at the bottom.
+
+///A sphere made by repeatedly splitting every triangle of an icosahedron into four, which spreads its vertices far more evenly than a [crate::geometry::uv_sphere::UVSphere].
+///<br>Every subdivision multiplies the triangles by four, from 20.
+#[derive(Debug, Clone, Copy)]
+pub struct Icosphere<N: Number> {
+    pub radius: N,
+    pub subdivisions: usize,
+}
+
+///A box with a separate quad for each face, so edges stay sharp, each mapped to the whole texture.
+#[derive(Debug, Clone, Copy)]
+pub struct Cube<N: Number> {
+    pub size: Vector3<N>,
+}
+
+///A flat grid in the xz plane facing up, with u along x and v along z.
+#[derive(Debug, Clone, Copy)]
+pub struct Plane<N: Number> {
+    pub width: N,
+    pub depth: N,
+    pub segments_x: usize,
+    pub segments_z: usize,
+}
+
+///A flat circle in the xz plane facing up, mapped onto the texture as seen from above.
+#[derive(Debug, Clone, Copy)]
+pub struct Disk<N: Number> {
+    pub radius: N,
+    pub segments: usize,
+}
+
+///A cylinder along y, optionally closed with a disk at either end.
+#[derive(Debug, Clone, Copy)]
+pub struct Cylinder<N: Number> {
+    pub radius: N,
+    pub height: N,
+    ///Around the y axis.
+    pub segments: usize,
+    ///Along the y axis.
+    pub rings: usize,
+    pub capped: bool,
+}
+
+///A cone along y with its base at the bottom and its tip at the top, optionally closed with a disk at the base.
+#[derive(Debug, Clone, Copy)]
+pub struct Cone<N: Number> {
+    pub radius: N,
+    pub height: N,
+    pub segments: usize,
+    pub capped: bool,
+}
+
+///A cylinder along y capped with hemispheres, shaped like a [crate::physics::collider::capsule_collider::CapsuleCollider] of the same radius and height.
+#[derive(Debug, Clone, Copy)]
+pub struct Capsule<N: Number> {
+    pub radius: N,
+    ///Total height, caps included.
+    pub height: N,
+    pub segments: usize,
+    ///Around each hemisphere, from its pole to its equator.
+    pub rings: usize,
+}
+
+///A ring around the y axis with a round cross section.
+#[derive(Debug, Clone, Copy)]
+pub struct Torus<N: Number> {
+    ///From the center to the middle of the tube.
+    pub major_radius: N,
+    ///Of the tube.
+    pub minor_radius: N,
+    ///Around the y axis.
+    pub major_segments: usize,
+    ///Around the tube.
+    pub minor_segments: usize,
+}
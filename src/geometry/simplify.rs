@@ -0,0 +1,336 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use crate::geometry::Geometry;
+use crate::linear_algebra::vector::types::Vector3;
+use crate::shared::traits::number::Number;
+
+///How much more moving off a boundary or UV seam costs than moving off the plane of a face, so outlines and seams keep their shape.
+const BORDER_WEIGHT: f64 = 10_f64;
+
+///Sums of squared distances to a set of planes, as a symmetric 4 by 4 matrix, along with the area of the faces they came from.
+#[derive(Debug, Clone, Copy, Default)]
+struct Quadric {
+    matrix: [f64; 10],
+    area: f64,
+}
+
+impl Quadric {
+    fn plane(normal: Vector3<f64>, point: Vector3<f64>, weight: f64) -> Self {
+        let [a, b, c] = normal.0;
+        let d = -normal.dot(&point);
+        Self { matrix: [a * a, a * b, a * c, a * d, b * b, b * c, b * d, c * c, c * d, d * d].map(|value| value * weight), area: 0_f64 }
+    }
+
+    fn add(&mut self, other: &Quadric) {
+        for (value, other) in self.matrix.iter_mut().zip(other.matrix) {
+            *value += other;
+        }
+        self.area += other.area;
+    }
+
+    ///The squared distance of a point to the planes, averaged over their faces' area.
+    fn error(&self, point: Vector3<f64>) -> f64 {
+        let [x, y, z] = point.0;
+        let q = &self.matrix;
+        let sum = q[0] * x * x + 2_f64 * q[1] * x * y + 2_f64 * q[2] * x * z + 2_f64 * q[3] * x
+            + q[4] * y * y + 2_f64 * q[5] * y * z + 2_f64 * q[6] * y
+            + q[7] * z * z + 2_f64 * q[8] * z
+            + q[9];
+        sum.max(0_f64) / self.area.max(f64::MIN_POSITIVE)
+    }
+}
+
+///Moving the vertex at one position onto the one at another, at a cost that's cheapest first.
+#[derive(Debug, Clone, Copy)]
+struct Collapse {
+    cost: f64,
+    from: u32,
+    to: u32,
+    versions: (u32, u32),
+}
+
+impl PartialEq for Collapse {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost
+    }
+}
+
+impl Eq for Collapse {}
+
+impl PartialOrd for Collapse {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Collapse {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EdgeKind {
+    Inner,
+    ///Along a boundary, with one face.
+    Boundary,
+    ///Along a UV seam or hard edge, with two faces whose vertices at its ends differ.
+    Seam,
+    ///Shared by more than two faces, so its ends never move.
+    Locked,
+}
+
+///Triangles with a corner at a position and at a vertex of the geometry, which carries the normal, UV and tangent.
+struct Simplifier {
+    positions: Vec<Vector3<f64>>,
+    triangles: Vec<[[u32; 2]; 3]>,
+    alive: Vec<bool>,
+    ///Triangles with a corner at each position, including some that have been removed since.
+    around: Vec<Vec<usize>>,
+    quadrics: Vec<Quadric>,
+    versions: Vec<u32>,
+    removed: Vec<bool>,
+}
+
+impl<N: Number> Geometry<3, N> {
+    ///Reduces the number of triangles by collapsing edges in the order that moves the surface the least,
+    ///measured by quadric error metrics, until at most the target number of triangles are left
+    ///or the next collapse would move a vertex further than the maximum error from the planes of the faces it started on.
+    ///<br>Vertices are only ever moved onto their neighbours, so the ones left keep their normals, UVs and tangents.
+    ///Boundaries and UV seams only collapse along themselves, and their corners and any non-manifold edges stay where they are.
+    ///<br>See: <a href="https://www.cs.cmu.edu/~garland/Papers/quadrics.pdf">Surface simplification using quadric error metrics</a>
+    pub fn simplify(&self, target_triangles: usize, max_error: Option<N>) -> Geometry<3, N> {
+        let mut geometry = self.clone();
+        geometry.weld(N::ZERO);
+        let (positions, remap) = geometry.shared_positions();
+
+        let triangles: Vec<[[u32; 2]; 3]> = geometry.indices.chunks_exact(3)
+            .map(|triangle| [0, 1, 2].map(|k| [remap[triangle[k] as usize], triangle[k]]))
+            .collect();
+        let mut simplifier = Simplifier::new(positions.iter().map(|p| Vector3::new(p.0.map(|c| c.to_f64()))).collect(), triangles);
+        let max_error = max_error.map(|error| error.to_f64() * error.to_f64());
+        simplifier.run(target_triangles, max_error);
+
+        geometry.indices = simplifier.triangles.iter().zip(&simplifier.alive)
+            .filter(|(_, alive)| **alive)
+            .flat_map(|(triangle, _)| triangle.map(|corner| corner[1]))
+            .collect();
+        let mut used = vec![false; geometry.vertices.len()];
+        geometry.indices.iter().for_each(|index| used[*index as usize] = true);
+        let kept: Vec<u32> = (0..geometry.vertices.len() as u32).filter(|index| used[*index as usize]).collect();
+        let mut compacted = vec![0_u32; geometry.vertices.len()];
+        kept.iter().enumerate().for_each(|(new, old)| compacted[*old as usize] = new as u32);
+        geometry.indices.iter_mut().for_each(|index| *index = compacted[*index as usize]);
+        geometry.keep_vertices(&kept);
+        geometry
+    }
+}
+
+impl Simplifier {
+    fn new(positions: Vec<Vector3<f64>>, triangles: Vec<[[u32; 2]; 3]>) -> Self {
+        let mut around = vec![vec![]; positions.len()];
+        let mut quadrics = vec![Quadric::default(); positions.len()];
+        for (t, triangle) in triangles.iter().enumerate() {
+            let [a, b, c] = triangle.map(|corner| positions[corner[0] as usize]);
+            let normal = (b - a).cross(&(c - a));
+            let area = normal.magnitude() / 2_f64;
+            let mut quadric = if area > 0_f64 { Quadric::plane(normal / (area * 2_f64), a, area) } else { Quadric::default() };
+            quadric.area = area;
+            for corner in triangle {
+                around[corner[0] as usize].push(t);
+                quadrics[corner[0] as usize].add(&quadric);
+            }
+        }
+
+        let alive = vec![true; triangles.len()];
+        let versions = vec![0; positions.len()];
+        let removed = vec![false; positions.len()];
+        let mut simplifier = Self { positions, triangles, alive, around, quadrics, versions, removed };
+
+        //Planes standing on boundaries and seams, so moving away from them along the surface costs too.
+        for t in 0..simplifier.triangles.len() {
+            let triangle = simplifier.triangles[t];
+            for k in 0..3 {
+                let (from, to) = (triangle[k][0], triangle[(k + 1) % 3][0]);
+                if simplifier.edge_kind(from, to) == EdgeKind::Inner {
+                    continue;
+                }
+                let [a, b, c] = triangle.map(|corner| simplifier.positions[corner[0] as usize]);
+                let normal = (b - a).cross(&(c - a));
+                let (start, end) = (simplifier.positions[from as usize], simplifier.positions[to as usize]);
+                let across = (end - start).cross(&normal);
+                if across.magnitude() <= 0_f64 || normal.magnitude() <= 0_f64 {
+                    continue;
+                }
+                let quadric = Quadric::plane(across.normalize(), start, BORDER_WEIGHT * (end - start).magnitude_squared());
+                simplifier.quadrics[from as usize].add(&quadric);
+                simplifier.quadrics[to as usize].add(&quadric);
+            }
+        }
+        simplifier
+    }
+
+    fn run(&mut self, target_triangles: usize, max_error: Option<f64>) {
+        let mut remaining = self.alive.len();
+        let mut queue = BinaryHeap::new();
+        for position in 0..self.positions.len() as u32 {
+            self.queue_edges(position, &mut queue);
+        }
+
+        while remaining > target_triangles {
+            let Some(collapse) = queue.pop() else {
+                break;
+            };
+            if self.removed[collapse.from as usize] || self.removed[collapse.to as usize]
+                || (self.versions[collapse.from as usize], self.versions[collapse.to as usize]) != collapse.versions {
+                continue;
+            }
+            if max_error.map(|max| collapse.cost > max).unwrap_or(false) {
+                break;
+            }
+            let Some(vertices) = self.check(collapse.from, collapse.to) else {
+                continue;
+            };
+            remaining -= self.collapse(collapse.from, collapse.to, &vertices);
+
+            let mut changed = self.neighbours(collapse.to);
+            changed.push(collapse.to);
+            for position in &changed {
+                self.versions[*position as usize] += 1;
+            }
+            for position in changed {
+                self.queue_edges(position, &mut queue);
+            }
+        }
+    }
+
+    fn queue_edges(&self, position: u32, queue: &mut BinaryHeap<Collapse>) {
+        for neighbour in self.neighbours(position) {
+            for (from, to) in [(position, neighbour), (neighbour, position)] {
+                let mut quadric = self.quadrics[from as usize];
+                quadric.add(&self.quadrics[to as usize]);
+                queue.push(Collapse {
+                    cost: quadric.error(self.positions[to as usize]),
+                    from,
+                    to,
+                    versions: (self.versions[from as usize], self.versions[to as usize]),
+                });
+            }
+        }
+    }
+
+    fn live_triangles(&self, position: u32) -> impl Iterator<Item = usize> + '_ {
+        self.around[position as usize].iter().copied().filter(|t| self.alive[*t])
+    }
+
+    fn corner(&self, triangle: usize, position: u32) -> Option<[u32; 2]> {
+        self.triangles[triangle].iter().find(|corner| corner[0] == position).copied()
+    }
+
+    fn neighbours(&self, position: u32) -> Vec<u32> {
+        let mut neighbours: Vec<u32> = self.live_triangles(position)
+            .flat_map(|t| self.triangles[t].map(|corner| corner[0]))
+            .filter(|neighbour| *neighbour != position)
+            .collect();
+        neighbours.sort_unstable();
+        neighbours.dedup();
+        neighbours
+    }
+
+    fn edge_kind(&self, a: u32, b: u32) -> EdgeKind {
+        let shared: Vec<usize> = self.live_triangles(a).filter(|t| self.corner(*t, b).is_some()).collect();
+        match shared.len() {
+            1 => EdgeKind::Boundary,
+            2 if self.corner(shared[0], a) == self.corner(shared[1], a) && self.corner(shared[0], b) == self.corner(shared[1], b) => EdgeKind::Inner,
+            2 => EdgeKind::Seam,
+            _ => EdgeKind::Locked,
+        }
+    }
+
+    ///Whether the vertex at one position can be moved onto another, and if so which vertex of the other each of its vertices becomes.
+    fn check(&self, from: u32, to: u32) -> Option<HashMap<u32, u32>> {
+        let shared: Vec<usize> = self.live_triangles(from).filter(|t| self.corner(*t, to).is_some()).collect();
+        if shared.is_empty() || shared.len() > 2 {
+            return None;
+        }
+
+        //Vertices on a boundary or seam can only slide along it, and corners of them don't move at all.
+        let ring = self.neighbours(from);
+        let borders: Vec<(u32, EdgeKind)> = ring.iter()
+            .map(|neighbour| (*neighbour, self.edge_kind(from, *neighbour)))
+            .filter(|(_, kind)| *kind != EdgeKind::Inner)
+            .collect();
+        if !borders.is_empty()
+            && (borders.len() != 2 || borders[0].1 != borders[1].1 || borders[0].1 == EdgeKind::Locked || !borders.iter().any(|(neighbour, _)| *neighbour == to)) {
+            return None;
+        }
+
+        let mut vertices = HashMap::new();
+        for t in &shared {
+            let (vertex, target) = (self.corner(*t, from)?[1], self.corner(*t, to)?[1]);
+            if *vertices.entry(vertex).or_insert(target) != target {
+                return None;
+            }
+        }
+
+        //Only the corners of the removed triangles may be neighbours of both, or the surface would pinch.
+        let other_ring = self.neighbours(to);
+        if ring.iter().filter(|neighbour| other_ring.contains(neighbour)).count() != shared.len() {
+            return None;
+        }
+
+        let target = self.positions[to as usize];
+        for t in self.live_triangles(from).filter(|t| !shared.contains(t)) {
+            let triangle = self.triangles[t];
+            if !vertices.contains_key(&self.corner(t, from)?[1]) {
+                return None;
+            }
+            let before = triangle.map(|corner| self.positions[corner[0] as usize]);
+            let after = triangle.map(|corner| if corner[0] == from { target } else { self.positions[corner[0] as usize] });
+            let normal_before = (before[1] - before[0]).cross(&(before[2] - before[0]));
+            let normal_after = (after[1] - after[0]).cross(&(after[2] - after[0]));
+            if normal_before.dot(&normal_after) <= 0_f64 {
+                return None;
+            }
+            //A triangle landing on another one, back to back.
+            let others: HashSet<u32> = triangle.iter().map(|corner| corner[0]).filter(|position| *position != from).collect();
+            if self.live_triangles(to).any(|other| triangle_positions(&self.triangles[other]).is_superset(&others)) {
+                return None;
+            }
+        }
+        Some(vertices)
+    }
+
+    ///Moves the vertex at one position onto another, returning how many triangles were removed.
+    fn collapse(&mut self, from: u32, to: u32, vertices: &HashMap<u32, u32>) -> usize {
+        let around = std::mem::take(&mut self.around[from as usize]);
+        let mut removed = 0;
+        for t in around {
+            if !self.alive[t] {
+                continue;
+            }
+            if self.corner(t, to).is_some() {
+                self.alive[t] = false;
+                removed += 1;
+                continue;
+            }
+            for corner in self.triangles[t].iter_mut() {
+                if corner[0] == from {
+                    *corner = [to, vertices[&corner[1]]];
+                }
+            }
+            self.around[to as usize].push(t);
+        }
+        let alive = &self.alive;
+        self.around[to as usize].retain(|t| alive[*t]);
+        let quadric = self.quadrics[from as usize];
+        self.quadrics[to as usize].add(&quadric);
+        self.removed[from as usize] = true;
+        removed
+    }
+}
+
+fn triangle_positions(triangle: &[[u32; 2]; 3]) -> HashSet<u32> {
+    triangle.iter().map(|corner| corner[0]).collect()
+}
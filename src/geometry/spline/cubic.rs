@@ -0,0 +1,223 @@
+use crate::geometry::bezier::Bezier;
+use crate::geometry::curve::Curve;
+use crate::geometry::spline::{CubicSpline, Parameterization};
+use crate::linear_algebra::vector::types::Vector2;
+use crate::linear_algebra::vector::Vector;
+use crate::shared::traits::number::Number;
+
+impl Parameterization {
+    ///Knots for points, starting at zero.
+    pub fn knots<const L: usize, N: Number>(&self, points: &[Vector<L, N>]) -> Vec<N> {
+        let mut knots = Vec::with_capacity(points.len());
+        let mut t = N::ZERO;
+        knots.push(t);
+        for pair in points.windows(2) {
+            let distance = pair[0].distance(&pair[1]);
+            t += match self {
+                Parameterization::Uniform => N::ONE,
+                Parameterization::Centripetal => distance.num_sqrt(),
+                Parameterization::Chordal => distance,
+            };
+            knots.push(t);
+        }
+        knots
+    }
+}
+
+impl<const L: usize, N: Number> CubicSpline<L, N> {
+    ///A Hermite spline passing through each point in the direction and at the speed of its tangent, with the points one apart in t.
+    pub fn hermite(points: Vec<Vector<L, N>>, tangents: Vec<Vector<L, N>>) -> Self {
+        let knots = Parameterization::Uniform.knots(&points);
+        Self::hermite_with_knots(points, tangents, knots)
+    }
+
+    ///A Hermite spline with the value of t at each point.
+    pub fn hermite_with_knots(points: Vec<Vector<L, N>>, tangents: Vec<Vector<L, N>>, knots: Vec<N>) -> Self {
+        assert!(points.len() >= 2, "A cubic spline needs at least two points");
+        assert_eq!(points.len(), tangents.len(), "A Hermite spline needs a tangent for every point");
+        assert_eq!(points.len(), knots.len(), "A cubic spline needs a knot for every point");
+        assert!(knots.windows(2).all(|pair| pair[0] < pair[1]), "Cubic spline knots have to be increasing, so neighbouring points can't be the same");
+        Self { points, tangents, knots }
+    }
+
+    ///A Catmull–Rom spline, whose tangent at each point follows the points on either side.
+    ///<br>The tangents are those of the Barry–Goldman construction for the knots, which reduce to half the difference between the neighbours for uniform ones.
+    ///The ends continue in a straight line towards the points next to them.
+    pub fn catmull_rom(points: Vec<Vector<L, N>>, parameterization: Parameterization) -> Self {
+        assert!(points.len() >= 2, "A cubic spline needs at least two points");
+        let knots = parameterization.knots(&points);
+        let last = points.len() - 1;
+        let tangents = (0..points.len()).map(|i| {
+            if i == 0 || i == last {
+                let (a, b) = if i == 0 { (0, 1) } else { (last - 1, last) };
+                return (points[b] - points[a]) / (knots[b] - knots[a]);
+            }
+            let (before, after) = (knots[i] - knots[i - 1], knots[i + 1] - knots[i]);
+            (points[i] - points[i - 1]) / before
+                - (points[i + 1] - points[i - 1]) / (before + after)
+                + (points[i + 1] - points[i]) / after
+        }).collect();
+        Self::hermite_with_knots(points, tangents, knots)
+    }
+
+    ///A natural cubic spline, continuous in curvature through every point and without any at its ends.
+    pub fn natural(points: Vec<Vector<L, N>>, parameterization: Parameterization) -> Self {
+        assert!(points.len() >= 2, "A cubic spline needs at least two points");
+        let knots = parameterization.knots(&points);
+        let tangents = solve_tangents(&points, &knots, None);
+        Self::hermite_with_knots(points, tangents, knots)
+    }
+
+    ///A clamped cubic spline, continuous in curvature through every point and leaving and arriving with the provided tangents.
+    pub fn clamped(points: Vec<Vector<L, N>>, start: Vector<L, N>, end: Vector<L, N>, parameterization: Parameterization) -> Self {
+        assert!(points.len() >= 2, "A cubic spline needs at least two points");
+        let knots = parameterization.knots(&points);
+        let tangents = solve_tangents(&points, &knots, Some((start, end)));
+        Self::hermite_with_knots(points, tangents, knots)
+    }
+
+    pub fn points(&self) -> &[Vector<L, N>] {
+        &self.points
+    }
+
+    pub fn tangents(&self) -> &[Vector<L, N>] {
+        &self.tangents
+    }
+
+    pub fn knots(&self) -> &[N] {
+        &self.knots
+    }
+
+    ///The range of t, from the first knot to the last.
+    pub fn domain(&self) -> (N, N) {
+        (self.knots[0], self.knots[self.knots.len() - 1])
+    }
+
+    pub fn segments(&self) -> usize {
+        self.points.len() - 1
+    }
+
+    ///The point on the spline at t, clamped to its domain.
+    pub fn interpolate(&self, t: N) -> Vector<L, N> {
+        let (i, s, h) = self.locate(t);
+        let (s2, s3) = (s * s, s * s * s);
+        let three = N::from_f64(3_f64);
+        let h00 = N::TWO * s3 - three * s2 + N::ONE;
+        let h10 = s3 - N::TWO * s2 + s;
+        let h01 = three * s2 - N::TWO * s3;
+        let h11 = s3 - s2;
+        self.points[i] * h00 + self.tangents[i] * (h10 * h) + self.points[i + 1] * h01 + self.tangents[i + 1] * (h11 * h)
+    }
+
+    ///The derivative with respect to t at t.
+    pub fn derivative_at(&self, t: N) -> Vector<L, N> {
+        let (i, s, h) = self.locate(t);
+        let (three, six) = (N::from_f64(3_f64), N::from_f64(6_f64));
+        let s2 = s * s;
+        let h00 = six * s2 - six * s;
+        let h10 = three * s2 - N::from_f64(4_f64) * s + N::ONE;
+        let h01 = six * s - six * s2;
+        let h11 = three * s2 - N::TWO * s;
+        (self.points[i] * h00 + self.points[i + 1] * h01) / h + self.tangents[i] * h10 + self.tangents[i + 1] * h11
+    }
+
+    ///The second derivative with respect to t at t, which within a segment changes linearly.
+    pub fn second_derivative_at(&self, t: N) -> Vector<L, N> {
+        let (i, s, h) = self.locate(t);
+        let (six, twelve) = (N::from_f64(6_f64), N::from_f64(12_f64));
+        let h00 = twelve * s - six;
+        let h10 = six * s - N::from_f64(4_f64);
+        let h01 = six - twelve * s;
+        let h11 = six * s - N::TWO;
+        (self.points[i] * h00 + self.points[i + 1] * h01) / (h * h) + (self.tangents[i] * h10 + self.tangents[i + 1] * h11) / h
+    }
+
+    ///Every segment as a cubic Bézier curve from one point to the next, with t from 0 to 1 over it.
+    ///<br>The inner control points sit a third of the way along each tangent, scaled by the segment's length in t.
+    pub fn to_beziers(&self) -> Vec<Bezier<L, N>> {
+        let three = N::from_f64(3_f64);
+        (0..self.segments()).map(|i| {
+            let h = self.knots[i + 1] - self.knots[i];
+            Bezier::new(vec![
+                self.points[i],
+                self.points[i] + self.tangents[i] * (h / three),
+                self.points[i + 1] - self.tangents[i + 1] * (h / three),
+                self.points[i + 1],
+            ])
+        }).collect()
+    }
+
+    ///The points of the spline evenly spaced in t over its domain, including both ends.
+    pub fn sample(&self, count: usize) -> Vec<Vector<L, N>> {
+        let (start, end) = self.domain();
+        match count {
+            0 => vec![],
+            1 => vec![self.points[0]],
+            _ => (0..count).map(|i| self.interpolate(start + (end - start) * N::from_f64(i as f64 / (count - 1) as f64))).collect(),
+        }
+    }
+
+    ///The segment holding t, how far along it t is from 0 to 1, and its length in t.
+    fn locate(&self, t: N) -> (usize, N, N) {
+        let (start, end) = self.domain();
+        let t = t.clamp(start, end);
+        let i = self.knots.partition_point(|knot| *knot <= t).max(1).min(self.segments()) - 1;
+        let h = self.knots[i + 1] - self.knots[i];
+        (i, (t - self.knots[i]) / h, h)
+    }
+}
+
+impl<N: Number> CubicSpline<2, N> {
+    ///Every segment as a [Curve::Cubic].
+    pub fn to_curves(&self) -> Vec<Curve<N>> {
+        self.to_beziers().into_iter().map(|bezier| {
+            let points: &[Vector2<N>] = bezier.points();
+            Curve::Cubic { p0: points[0], p1: points[1], p2: points[2], p3: points[3] }
+        }).collect()
+    }
+}
+
+///Tangents that make the second derivative continuous at every inner point, from the tridiagonal system
+///h_i m_(i-1) + 2 (h_(i-1) + h_i) m_i + h_(i-1) m_(i+1) = 3 (h_i (P_i - P_(i-1)) / h_(i-1) + h_(i-1) (P_(i+1) - P_i) / h_i), solved with the Thomas algorithm.
+///<br>Natural ends have 2 m_0 + m_1 = 3 (P_1 - P_0) / h_0, and likewise at the end, while clamped ones have their tangent fixed.
+fn solve_tangents<const L: usize, N: Number>(points: &[Vector<L, N>], knots: &[N], clamped: Option<(Vector<L, N>, Vector<L, N>)>) -> Vec<Vector<L, N>> {
+    let n = points.len();
+    let three = N::from_f64(3_f64);
+    let h: Vec<N> = knots.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    let slope = |i: usize| (points[i + 1] - points[i]) / h[i];
+
+    //Each row is below * m_(i-1) + diagonal * m_i + above * m_(i+1) = right.
+    let mut below = vec![N::ZERO; n];
+    let mut diagonal = vec![N::ONE; n];
+    let mut above = vec![N::ZERO; n];
+    let mut right = vec![Vector::ZERO; n];
+    match clamped {
+        Some((start, end)) => {
+            right[0] = start;
+            right[n - 1] = end;
+        }
+        None => {
+            (diagonal[0], above[0], right[0]) = (N::TWO, N::ONE, slope(0) * three);
+            (below[n - 1], diagonal[n - 1], right[n - 1]) = (N::ONE, N::TWO, slope(n - 2) * three);
+        }
+    }
+    for i in 1..n - 1 {
+        below[i] = h[i];
+        diagonal[i] = N::TWO * (h[i - 1] + h[i]);
+        above[i] = h[i - 1];
+        right[i] = (slope(i - 1) * h[i] + slope(i) * h[i - 1]) * three;
+    }
+
+    for i in 1..n {
+        let factor = below[i] / diagonal[i - 1];
+        diagonal[i] -= factor * above[i - 1];
+        let previous = right[i - 1];
+        right[i] -= previous * factor;
+    }
+    let mut tangents = vec![Vector::ZERO; n];
+    tangents[n - 1] = right[n - 1] / diagonal[n - 1];
+    for i in (0..n - 1).rev() {
+        tangents[i] = (right[i] - tangents[i + 1] * above[i]) / diagonal[i];
+    }
+    tangents
+}
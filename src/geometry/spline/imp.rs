@@ -0,0 +1,251 @@
+use crate::algebra::binomial;
+use crate::geometry::spline::BSpline;
+use crate::linear_algebra::vector::Vector;
+use crate::shared::traits::number::Number;
+
+impl<const L: usize, N: Number> BSpline<L, N> {
+    ///A B-spline with its own knots, which have to be non-decreasing and number the control points plus the degree plus one.
+    pub fn new(degree: usize, knots: Vec<N>, points: Vec<Vector<L, N>>) -> Self {
+        assert!(points.len() > degree, "A B-spline of degree {degree} needs at least {} control points", degree + 1);
+        assert_eq!(knots.len(), points.len() + degree + 1, "A B-spline needs as many knots as control points plus its degree plus one");
+        assert!(knots.windows(2).all(|pair| pair[0] <= pair[1]), "B-spline knots have to be non-decreasing");
+        Self { degree, knots, points, weights: None }
+    }
+
+    ///A B-spline with evenly spaced knots, 0, 1, 2... It doesn't reach its first and last control points.
+    pub fn uniform(degree: usize, points: Vec<Vector<L, N>>) -> Self {
+        let knots = (0..points.len() + degree + 1).map(|i| N::from_f64(i as f64)).collect();
+        Self::new(degree, knots, points)
+    }
+
+    ///A B-spline with evenly spaced knots between 0 and 1, except the ends which are repeated so the curve starts and ends on its first and last control points.
+    ///<br>With as many control points as the degree plus one, this is a Bézier curve.
+    pub fn clamped(degree: usize, points: Vec<Vector<L, N>>) -> Self {
+        assert!(points.len() > degree, "A B-spline of degree {degree} needs at least {} control points", degree + 1);
+        let spans = points.len() - degree;
+        let knots = (0..points.len() + degree + 1)
+            .map(|i| N::from_f64((i.saturating_sub(degree).min(spans)) as f64 / spans as f64))
+            .collect();
+        Self::new(degree, knots, points)
+    }
+
+    ///A NURBS curve, a B-spline with a positive weight for every control point.
+    pub fn nurbs(degree: usize, knots: Vec<N>, points: Vec<Vector<L, N>>, weights: Vec<N>) -> Self {
+        Self::new(degree, knots, points).with_weights(weights)
+    }
+
+    pub fn with_weights(mut self, weights: Vec<N>) -> Self {
+        assert_eq!(weights.len(), self.points.len(), "A NURBS curve needs a weight for every control point");
+        self.weights = Some(weights);
+        self
+    }
+
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    pub fn knots(&self) -> &[N] {
+        &self.knots
+    }
+
+    pub fn points(&self) -> &[Vector<L, N>] {
+        &self.points
+    }
+
+    pub fn weights(&self) -> Option<&[N]> {
+        self.weights.as_deref()
+    }
+
+    pub fn is_rational(&self) -> bool {
+        self.weights.is_some()
+    }
+
+    ///The range of t the curve is defined over, from the knot after the first degree ones to the one before the last degree ones.
+    pub fn domain(&self) -> (N, N) {
+        (self.knots[self.degree], self.knots[self.points.len()])
+    }
+
+    ///The point on the curve at t, clamped to its domain.
+    pub fn interpolate(&self, t: N) -> Vector<L, N> {
+        let (point, weight) = evaluate(self.degree, &self.knots, &self.homogeneous(), t);
+        point / weight
+    }
+
+    ///The point at t followed by its first, second and further derivatives up to an order.
+    ///<br>The derivatives of the weighted points and of the weights come from derived B-splines,
+    ///combined as C⁽ᵏ⁾ = (A⁽ᵏ⁾ - Σ C(k, i) w⁽ⁱ⁾ C⁽ᵏ⁻ⁱ⁾) / w, which reduces to A⁽ᵏ⁾ for a plain B-spline.
+    pub fn derivatives(&self, t: N, order: usize) -> Vec<Vector<L, N>> {
+        let mut spline = (self.degree, self.knots.clone(), self.homogeneous());
+        let mut homogeneous = Vec::with_capacity(order + 1);
+        for _ in 0..=order {
+            homogeneous.push(evaluate(spline.0, &spline.1, &spline.2, t));
+            spline = derive(spline.0, &spline.1, &spline.2);
+        }
+
+        let weight = homogeneous[0].1;
+        let mut derivatives: Vec<Vector<L, N>> = Vec::with_capacity(order + 1);
+        for k in 0..=order {
+            let mut value = homogeneous[k].0;
+            for i in 1..=k {
+                value -= derivatives[k - i] * (N::from_f64(binomial(k, i)) * homogeneous[i].1);
+            }
+            derivatives.push(value / weight);
+        }
+        derivatives
+    }
+
+    ///The first derivative at t, the direction and speed the curve moves in there.
+    pub fn derivative_at(&self, t: N) -> Vector<L, N> {
+        self.derivatives(t, 1)[1]
+    }
+
+    ///The derivative of a plain B-spline as a B-spline one degree lower, none for a NURBS curve whose derivative isn't one.
+    pub fn derivative(&self) -> Option<Self> {
+        if self.is_rational() || self.degree == 0 {
+            return None;
+        }
+        let (degree, knots, homogeneous) = derive(self.degree, &self.knots, &self.homogeneous());
+        Some(Self {
+            degree,
+            knots,
+            points: homogeneous.into_iter().map(|(point, _)| point).collect(),
+            weights: None,
+        })
+    }
+
+    ///How many times a knot value repeats.
+    pub fn multiplicity(&self, t: N) -> usize {
+        self.knots.iter().filter(|knot| **knot == t).count()
+    }
+
+    ///Inserts a knot at t a number of times with Boehm's algorithm, adding a control point each time without changing the curve's shape.
+    ///<br>Stops once the knot repeats as many times as the degree, where the curve passes through a control point.
+    pub fn insert_knot(&mut self, t: N, times: usize) {
+        let (start, end) = self.domain();
+        assert!(t >= start && t <= end, "Knots can only be inserted inside the curve's domain");
+        for _ in 0..times {
+            if self.multiplicity(t) >= self.degree {
+                break;
+            }
+            let homogeneous = self.homogeneous();
+            let p = self.degree;
+            let span = find_span(p, &self.knots, homogeneous.len(), t);
+
+            let mut inserted = Vec::with_capacity(homogeneous.len() + 1);
+            inserted.extend_from_slice(&homogeneous[..=span - p]);
+            for i in span - p + 1..=span {
+                let range = self.knots[i + p] - self.knots[i];
+                let alpha = if range > N::ZERO { (t - self.knots[i]) / range } else { N::ZERO };
+                let (previous, current) = (homogeneous[i - 1], homogeneous[i]);
+                inserted.push((previous.0 * (N::ONE - alpha) + current.0 * alpha, previous.1 * (N::ONE - alpha) + current.1 * alpha));
+            }
+            inserted.extend_from_slice(&homogeneous[span..]);
+
+            self.knots.insert(span + 1, t);
+            self.set_homogeneous(inserted);
+        }
+    }
+
+    ///The curve's points evenly spaced in t over its domain, including both ends.
+    pub fn sample(&self, count: usize) -> Vec<Vector<L, N>> {
+        let (start, end) = self.domain();
+        match count {
+            0 => vec![],
+            1 => vec![self.interpolate(start)],
+            _ => (0..count).map(|i| self.interpolate(start + (end - start) * N::from_f64(i as f64 / (count - 1) as f64))).collect(),
+        }
+    }
+
+    ///Control points multiplied by their weights, paired with the weights.
+    pub(crate) fn homogeneous(&self) -> Vec<(Vector<L, N>, N)> {
+        match &self.weights {
+            None => self.points.iter().map(|point| (*point, N::ONE)).collect(),
+            Some(weights) => self.points.iter().zip(weights).map(|(point, weight)| (*point * *weight, *weight)).collect(),
+        }
+    }
+
+    fn set_homogeneous(&mut self, homogeneous: Vec<(Vector<L, N>, N)>) {
+        self.points = homogeneous.iter().map(|(point, weight)| *point / *weight).collect();
+        if self.weights.is_some() {
+            self.weights = Some(homogeneous.iter().map(|(_, weight)| *weight).collect());
+        }
+    }
+}
+
+///The index of the knot span holding t, the last i with knots[i] <= t clamped to the domain.
+pub(crate) fn find_span<N: Number>(degree: usize, knots: &[N], count: usize, t: N) -> usize {
+    if t >= knots[count] {
+        //The domain's end belongs to the last span that isn't empty.
+        let mut span = count - 1;
+        while span > degree && knots[span] >= knots[count] {
+            span -= 1;
+        }
+        return span;
+    }
+    if t <= knots[degree] {
+        let mut span = degree;
+        while span + 1 < count && knots[span + 1] <= t {
+            span += 1;
+        }
+        return span;
+    }
+    let (mut low, mut high) = (degree, count);
+    while high - low > 1 {
+        let middle = (low + high) / 2;
+        if t < knots[middle] {
+            high = middle;
+        } else {
+            low = middle;
+        }
+    }
+    low
+}
+
+///The degree plus one basis functions that aren't zero in a span, at t, with the Cox–de Boor recursion.
+pub(crate) fn basis<N: Number>(degree: usize, knots: &[N], span: usize, t: N) -> Vec<N> {
+    let mut values = vec![N::ZERO; degree + 1];
+    let mut left = vec![N::ZERO; degree + 1];
+    let mut right = vec![N::ZERO; degree + 1];
+    values[0] = N::ONE;
+    for j in 1..=degree {
+        left[j] = t - knots[span + 1 - j];
+        right[j] = knots[span + j] - t;
+        let mut saved = N::ZERO;
+        for r in 0..j {
+            let denominator = right[r + 1] + left[j - r];
+            let temp = if denominator != N::ZERO { values[r] / denominator } else { N::ZERO };
+            values[r] = saved + right[r + 1] * temp;
+            saved = left[j - r] * temp;
+        }
+        values[j] = saved;
+    }
+    values
+}
+
+pub(crate) fn evaluate<const L: usize, N: Number>(degree: usize, knots: &[N], points: &[(Vector<L, N>, N)], t: N) -> (Vector<L, N>, N) {
+    let (start, end) = (knots[degree], knots[points.len()]);
+    let t = t.clamp(start, end);
+    let span = find_span(degree, knots, points.len(), t);
+    basis(degree, knots, span, t).iter().enumerate().fold((Vector::ZERO, N::ZERO), |(point, weight), (i, value)| {
+        let (control, control_weight) = points[span - degree + i];
+        (point + control * *value, weight + control_weight * *value)
+    })
+}
+
+///The derivative of a B-spline, one degree lower on the same knots without the first and last,
+///with control points Q_i = p (P_(i+1) - P_i) / (u_(i+p+1) - u_(i+1)).
+fn derive<const L: usize, N: Number>(degree: usize, knots: &[N], points: &[(Vector<L, N>, N)]) -> (usize, Vec<N>, Vec<(Vector<L, N>, N)>) {
+    if degree == 0 {
+        return (0, knots.to_vec(), points.iter().map(|_| (Vector::ZERO, N::ZERO)).collect());
+    }
+    let p = N::from_f64(degree as f64);
+    let derived = (0..points.len() - 1).map(|i| {
+        let range = knots[i + degree + 1] - knots[i + 1];
+        if range <= N::ZERO {
+            return (Vector::ZERO, N::ZERO);
+        }
+        let scale = p / range;
+        ((points[i + 1].0 - points[i].0) * scale, (points[i + 1].1 - points[i].1) * scale)
+    }).collect();
+    (degree - 1, knots[1..knots.len() - 1].to_vec(), derived)
+}
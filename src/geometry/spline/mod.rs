@@ -0,0 +1,60 @@
+use crate::linear_algebra::vector::types::Vector3;
+use crate::linear_algebra::vector::Vector;
+use crate::shared::traits::number::Number;
+
+pub mod imp;
+pub mod surface;
+pub mod cubic;
+
+///A B-spline curve: pieces of polynomial curves of a degree joined at its knots, each shaped by only the degree plus one nearest control points.
+///<br>With weights it's a NURBS curve, where each control point pulls the curve towards itself in proportion to its weight, which allows exact conic sections.
+///<br>Points are evaluated with the Cox–de Boor recursion for the basis functions.
+///<br>See: <a href="https://en.wikipedia.org/wiki/B-spline">B-spline</a> and <a href="https://en.wikipedia.org/wiki/Non-uniform_rational_B-spline">NURBS</a>
+#[derive(Debug, Clone, PartialEq)]
+pub struct BSpline<const L: usize, N: Number> {
+    pub(crate) degree: usize,
+    ///Non-decreasing, as many as the control points plus the degree plus one.
+    pub(crate) knots: Vec<N>,
+    pub(crate) points: Vec<Vector<L, N>>,
+    ///A weight for every control point of a NURBS curve, none for a plain B-spline where they are all one.
+    pub(crate) weights: Option<Vec<N>>,
+}
+
+///A tensor product B-spline or NURBS surface: a B-spline across u of B-splines across v.
+///<br>Control points are laid out in rows of the same u, each with a point for every v.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BSplineSurface<N: Number> {
+    pub(crate) degree_u: usize,
+    pub(crate) degree_v: usize,
+    pub(crate) knots_u: Vec<N>,
+    pub(crate) knots_v: Vec<N>,
+    ///How many control points each row has along v.
+    pub(crate) columns: usize,
+    pub(crate) points: Vec<Vector3<N>>,
+    pub(crate) weights: Option<Vec<N>>,
+}
+
+///How far apart consecutive control points of an interpolating spline are in t, from the distance between them raised to a power.
+///<br>See: <a href="https://en.wikipedia.org/wiki/Centripetal_Catmull%E2%80%93Rom_spline">Centripetal Catmull–Rom spline</a>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parameterization {
+    ///One apart, regardless of distance. Overshoots and can loop where points bunch up.
+    Uniform,
+    ///The square root of their distance apart. Never loops or forms cusps within a segment.
+    Centripetal,
+    ///Their distance apart, which spreads speed most evenly but swings widest around sharp turns.
+    Chordal,
+}
+
+///A piecewise cubic curve that passes through every control point, described by its tangent at each of them.
+///<br>Built as a Hermite spline with explicit tangents, a Catmull–Rom spline whose tangents come from the neighbouring points,
+///or a natural or clamped cubic spline whose tangents make the curve's curvature continuous.
+///<br>See: <a href="https://en.wikipedia.org/wiki/Cubic_Hermite_spline">Cubic Hermite spline</a> and <a href="https://en.wikipedia.org/wiki/Spline_interpolation">Spline interpolation</a>
+#[derive(Debug, Clone, PartialEq)]
+pub struct CubicSpline<const L: usize, N: Number> {
+    pub(crate) points: Vec<Vector<L, N>>,
+    ///Derivative with respect to t at each point.
+    pub(crate) tangents: Vec<Vector<L, N>>,
+    ///Value of t at each point, increasing.
+    pub(crate) knots: Vec<N>,
+}
@@ -0,0 +1,207 @@
+use crate::geometry::imp::tangent;
+use crate::geometry::spline::imp::{basis, find_span};
+use crate::geometry::spline::{BSpline, BSplineSurface};
+use crate::geometry::Geometry;
+use crate::linear_algebra::vector::types::{Vector2, Vector3};
+use crate::shared::traits::number::Number;
+
+impl<N: Number> BSplineSurface<N> {
+    ///A surface with its own knots along both directions, from rows of control points that each have as many points.
+    pub fn new(degree_u: usize, degree_v: usize, knots_u: Vec<N>, knots_v: Vec<N>, rows: Vec<Vec<Vector3<N>>>) -> Self {
+        let columns = rows.first().map(|row| row.len()).unwrap_or(0);
+        assert!(rows.iter().all(|row| row.len() == columns), "Every row of a B-spline surface needs as many control points");
+        assert!(rows.len() > degree_u && columns > degree_v, "A B-spline surface needs more control points than its degree in both directions");
+        assert_eq!(knots_u.len(), rows.len() + degree_u + 1, "A B-spline surface needs as many knots along u as rows plus its degree plus one");
+        assert_eq!(knots_v.len(), columns + degree_v + 1, "A B-spline surface needs as many knots along v as columns plus its degree plus one");
+        Self {
+            degree_u,
+            degree_v,
+            knots_u,
+            knots_v,
+            columns,
+            points: rows.into_iter().flatten().collect(),
+            weights: None,
+        }
+    }
+
+    ///A surface whose knots are evenly spaced between 0 and 1 and repeated at the ends, so its corners are its corner control points.
+    pub fn clamped(degree_u: usize, degree_v: usize, rows: Vec<Vec<Vector3<N>>>) -> Self {
+        let columns = rows.first().map(|row| row.len()).unwrap_or(0);
+        let knots_u = BSpline::<1, N>::clamped(degree_u, vec![Default::default(); rows.len()]).knots;
+        let knots_v = BSpline::<1, N>::clamped(degree_v, vec![Default::default(); columns]).knots;
+        Self::new(degree_u, degree_v, knots_u, knots_v, rows)
+    }
+
+    ///Turns this into a NURBS surface with a weight for every control point, laid out like them.
+    pub fn with_weights(mut self, weights: Vec<Vec<N>>) -> Self {
+        let weights: Vec<N> = weights.into_iter().flatten().collect();
+        assert_eq!(weights.len(), self.points.len(), "A NURBS surface needs a weight for every control point");
+        self.weights = Some(weights);
+        self
+    }
+
+    pub fn degrees(&self) -> (usize, usize) {
+        (self.degree_u, self.degree_v)
+    }
+
+    pub fn knots_u(&self) -> &[N] {
+        &self.knots_u
+    }
+
+    pub fn knots_v(&self) -> &[N] {
+        &self.knots_v
+    }
+
+    pub fn rows(&self) -> usize {
+        self.points.len() / self.columns
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn point(&self, row: usize, column: usize) -> &Vector3<N> {
+        &self.points[row * self.columns + column]
+    }
+
+    pub fn weight(&self, row: usize, column: usize) -> N {
+        self.weights.as_ref().map(|weights| weights[row * self.columns + column]).unwrap_or(N::ONE)
+    }
+
+    ///The ranges of u and v the surface is defined over.
+    pub fn domain(&self) -> ((N, N), (N, N)) {
+        (
+            (self.knots_u[self.degree_u], self.knots_u[self.rows()]),
+            (self.knots_v[self.degree_v], self.knots_v[self.columns]),
+        )
+    }
+
+    ///The point on the surface at u and v, clamped to its domain.
+    pub fn evaluate(&self, u: N, v: N) -> Vector3<N> {
+        let ((u_start, u_end), (v_start, v_end)) = self.domain();
+        let (u, v) = (u.clamp(u_start, u_end), v.clamp(v_start, v_end));
+        let span_u = find_span(self.degree_u, &self.knots_u, self.rows(), u);
+        let span_v = find_span(self.degree_v, &self.knots_v, self.columns, v);
+        let basis_u = basis(self.degree_u, &self.knots_u, span_u, u);
+        let basis_v = basis(self.degree_v, &self.knots_v, span_v, v);
+
+        let (mut point, mut weight) = (Vector3::ZERO, N::ZERO);
+        for (i, value_u) in basis_u.iter().enumerate() {
+            for (j, value_v) in basis_v.iter().enumerate() {
+                let (row, column) = (span_u - self.degree_u + i, span_v - self.degree_v + j);
+                let scale = *value_u * *value_v * self.weight(row, column);
+                point += *self.point(row, column) * scale;
+                weight += scale;
+            }
+        }
+        point / weight
+    }
+
+    ///The curve along u at a fixed v.
+    pub fn isocurve_u(&self, v: N) -> BSpline<3, N> {
+        let ((_, _), (v_start, v_end)) = self.domain();
+        let v = v.clamp(v_start, v_end);
+        let span = find_span(self.degree_v, &self.knots_v, self.columns, v);
+        let basis = basis(self.degree_v, &self.knots_v, span, v);
+        let rows = (0..self.rows()).map(|row| {
+            basis.iter().enumerate().fold((Vector3::ZERO, N::ZERO), |(point, weight), (j, value)| {
+                let column = span - self.degree_v + j;
+                let scale = *value * self.weight(row, column);
+                (point + *self.point(row, column) * scale, weight + scale)
+            })
+        }).collect();
+        self.isocurve(self.degree_u, self.knots_u.clone(), rows)
+    }
+
+    ///The curve along v at a fixed u.
+    pub fn isocurve_v(&self, u: N) -> BSpline<3, N> {
+        let ((u_start, u_end), (_, _)) = self.domain();
+        let u = u.clamp(u_start, u_end);
+        let span = find_span(self.degree_u, &self.knots_u, self.rows(), u);
+        let basis = basis(self.degree_u, &self.knots_u, span, u);
+        let columns = (0..self.columns).map(|column| {
+            basis.iter().enumerate().fold((Vector3::ZERO, N::ZERO), |(point, weight), (i, value)| {
+                let row = span - self.degree_u + i;
+                let scale = *value * self.weight(row, column);
+                (point + *self.point(row, column) * scale, weight + scale)
+            })
+        }).collect();
+        self.isocurve(self.degree_v, self.knots_v.clone(), columns)
+    }
+
+    fn isocurve(&self, degree: usize, knots: Vec<N>, homogeneous: Vec<(Vector3<N>, N)>) -> BSpline<3, N> {
+        let points = homogeneous.iter().map(|(point, weight)| *point / *weight).collect();
+        let curve = BSpline::new(degree, knots, points);
+        match self.weights {
+            Some(_) => curve.with_weights(homogeneous.iter().map(|(_, weight)| *weight).collect()),
+            None => curve,
+        }
+    }
+
+    ///The derivatives of the surface along u and along v at a point.
+    pub fn partial_derivatives(&self, u: N, v: N) -> (Vector3<N>, Vector3<N>) {
+        (self.isocurve_u(v).derivative_at(u), self.isocurve_v(u).derivative_at(v))
+    }
+
+    ///The unit normal of the surface at u and v, the cross product of the derivatives along u and v.
+    ///<br>Where that vanishes, like at the pole of a sphere, the normal is taken a little way towards the middle of the domain instead.
+    pub fn normal(&self, u: N, v: N) -> Vector3<N> {
+        let (du, dv) = self.partial_derivatives(u, v);
+        let normal = du.cross(&dv);
+        let magnitude = normal.magnitude();
+        let ((u_start, u_end), (v_start, v_end)) = self.domain();
+        let scale = (u_end - u_start).num_max(&(v_end - v_start));
+        if magnitude > N::from_f64(1e-12) * scale * scale {
+            return normal / magnitude;
+        }
+
+        let nudge = N::from_f64(1e-6);
+        let (middle_u, middle_v) = ((u_start + u_end) / N::TWO, (v_start + v_end) / N::TWO);
+        let (du, dv) = self.partial_derivatives(u + (middle_u - u) * nudge, v + (middle_v - v) * nudge);
+        du.cross(&dv).normalize()
+    }
+
+    ///Tessellates the surface into a grid of triangles with a number of segments along u and along v, with normals, tangents along u and UVs from 0 to 1 over the domain.
+    ///<br>Triangles wind counterclockwise around the normal.
+    pub fn to_geometry(&self, segments_u: usize, segments_v: usize) -> Geometry<3, N> {
+        let (segments_u, segments_v) = (segments_u.max(1), segments_v.max(1));
+        let ((u_start, u_end), (v_start, v_end)) = self.domain();
+
+        let mut vertices = Vec::with_capacity((segments_u + 1) * (segments_v + 1));
+        let mut normals = Vec::with_capacity(vertices.capacity());
+        let mut uv = Vec::with_capacity(vertices.capacity());
+        let mut tangents = Vec::with_capacity(vertices.capacity());
+        for i in 0..=segments_u {
+            let s = N::from_f64(i as f64 / segments_u as f64);
+            let u = u_start + (u_end - u_start) * s;
+            for j in 0..=segments_v {
+                let t = N::from_f64(j as f64 / segments_v as f64);
+                let v = v_start + (v_end - v_start) * t;
+                let normal = self.normal(u, v);
+                let (along_u, along_v) = self.partial_derivatives(u, v);
+                vertices.push(self.evaluate(u, v));
+                normals.push(normal);
+                tangents.push(tangent(normal, along_u, along_v));
+                uv.push(Vector2::new([s, t]));
+            }
+        }
+
+        let mut indices = Vec::with_capacity(segments_u * segments_v * 6);
+        let stride = segments_v as u32 + 1;
+        for i in 0..segments_u as u32 {
+            for j in 0..segments_v as u32 {
+                let a = i * stride + j;
+                let (b, c, d) = (a + stride, a + stride + 1, a + 1);
+                indices.extend_from_slice(&[a, b, c, a, c, d]);
+            }
+        }
+
+        Geometry {
+            vertices,
+            normals: Some(normals),
+            uv,
+            tangents: Some(tangents),
+            indices,
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+
+use crate::geometry::half_edge::{HalfEdgeError, HalfEdgeMesh, VertexId};
+use crate::geometry::Geometry;
+use crate::linear_algebra::vector::types::{Vector2, Vector3};
+use crate::shared::traits::number::Number;
+
+///Faces whose corners each refer to a position, shared across UV seams so the surface is smoothed as one piece,
+///and to a UV of their own, which is only ever interpolated along the face so seams stay where they are.
+///<br>Without UVs the second index is just the position.
+struct Corners<N: Number> {
+    positions: Vec<Vector3<N>>,
+    uv: Vec<Vector2<N>>,
+    faces: Vec<Vec<[u32; 2]>>,
+}
+
+impl<N: Number> Geometry<3, N> {
+    ///Loop subdivision, splitting every triangle into four a number of times and smoothing the surface towards a limit that's curved everywhere but at extraordinary vertices.
+    ///<br>Boundaries are smoothed as curves of their own, so open meshes keep their outline. UVs are interpolated linearly, so seams stay put.
+    ///Normals are computed smooth, and tangents too if the geometry had them.
+    ///<br>Fails if the triangles aren't a manifold once vertices at the same position are joined.
+    ///<br>See: <a href="https://www.microsoft.com/en-us/research/wp-content/uploads/2016/02/thesis-10.pdf">Smooth subdivision surfaces based on triangles</a>
+    pub fn subdivide_loop(&self, levels: usize) -> Result<Geometry<3, N>, HalfEdgeError> {
+        let mut corners = Corners::new(self);
+        for _ in 0..levels {
+            corners = corners.loop_step()?;
+        }
+        Ok(corners.to_geometry(self.tangents.is_some()))
+    }
+
+    ///Catmull–Clark subdivision, splitting every face into a quad per corner a number of times and smoothing the surface.
+    ///<br>The triangles are subdivided as they are, so the first level turns each into three quads, and the last level's quads are split into triangles again.
+    ///Boundaries, UVs, normals and tangents are handled like in [Geometry::subdivide_loop].
+    ///<br>See: <a href="https://en.wikipedia.org/wiki/Catmull%E2%80%93Clark_subdivision_surface">Catmull–Clark subdivision surface</a>
+    pub fn subdivide_catmull_clark(&self, levels: usize) -> Result<Geometry<3, N>, HalfEdgeError> {
+        let mut corners = Corners::new(self);
+        for _ in 0..levels {
+            corners = corners.catmull_clark_step()?;
+        }
+        Ok(corners.to_geometry(self.tangents.is_some()))
+    }
+}
+
+impl<N: Number> Corners<N> {
+    fn new(geometry: &Geometry<3, N>) -> Self {
+        let (positions, remap) = geometry.shared_positions();
+        let textured = !geometry.vertices.is_empty() && geometry.uv.len() == geometry.vertices.len();
+        let faces = geometry.indices.chunks_exact(3).map(|triangle| {
+            triangle.iter().map(|index| [remap[*index as usize], if textured { *index } else { remap[*index as usize] }]).collect()
+        }).collect();
+        Self { positions, uv: if textured { geometry.uv.clone() } else { vec![] }, faces }
+    }
+
+    fn topology(&self) -> Result<HalfEdgeMesh<N>, HalfEdgeError> {
+        let faces: Vec<Vec<u32>> = self.faces.iter().map(|face| face.iter().map(|corner| corner[0]).collect()).collect();
+        let mesh = HalfEdgeMesh::from_polygons(self.positions.clone(), &faces)?;
+        mesh.validate()?;
+        Ok(mesh)
+    }
+
+    ///Where a vertex goes if it's on a boundary: three quarters of the way to itself from the middle of its two neighbours along the boundary.
+    fn boundary_position(mesh: &HalfEdgeMesh<N>, vertex: VertexId) -> Vector3<N> {
+        let ring = mesh.one_ring(vertex);
+        let (first, last) = (mesh.position(ring[0]), mesh.position(ring[ring.len() - 1]));
+        mesh.position(vertex) * N::from_f64(0.75) + (first + last) * N::from_f64(0.125)
+    }
+
+    fn loop_step(&self) -> Result<Self, HalfEdgeError> {
+        let mesh = self.topology()?;
+        let mut positions: Vec<Vector3<N>> = mesh.vertices().map(|vertex| {
+            let ring = mesh.one_ring(vertex);
+            let position = mesh.position(vertex);
+            if ring.is_empty() {
+                return position;
+            }
+            if mesh.is_boundary_vertex(vertex) {
+                return Self::boundary_position(&mesh, vertex);
+            }
+            //Warren's weights.
+            let count = ring.len() as f64;
+            let beta = N::from_f64(if ring.len() == 3 { 3_f64 / 16_f64 } else { 3_f64 / (8_f64 * count) });
+            let sum = ring.iter().fold(Vector3::ZERO, |sum, neighbour| sum + mesh.position(*neighbour));
+            position * (N::ONE - beta * N::from_f64(count)) + sum * beta
+        }).collect();
+
+        let mut edge_points = HashMap::new();
+        for h in mesh.edges() {
+            let (a, b) = (mesh.origin(h), mesh.destination(h));
+            let ends = mesh.position(a) + mesh.position(b);
+            let point = if mesh.is_boundary_edge(h) {
+                ends / N::TWO
+            } else {
+                let across = mesh.position(mesh.origin(mesh.previous(h))) + mesh.position(mesh.origin(mesh.previous(mesh.twin(h))));
+                ends * N::from_f64(0.375) + across * N::from_f64(0.125)
+            };
+            edge_points.insert(edge_key(a.index() as u32, b.index() as u32), positions.len() as u32);
+            positions.push(point);
+        }
+
+        let mut subdivided = Self { positions, uv: self.uv.clone(), faces: vec![] };
+        let mut edge_uv = HashMap::new();
+        for face in &self.faces {
+            let [a, b, c] = [0, 1, 2].map(|k| face[k]);
+            let [ab, bc, ca] = [(a, b), (b, c), (c, a)].map(|(from, to)| {
+                let position = edge_points[&edge_key(from[0], to[0])];
+                subdivided.edge_corner(position, from[1], to[1], &mut edge_uv)
+            });
+            subdivided.faces.extend([vec![a, ab, ca], vec![ab, b, bc], vec![ca, bc, c], vec![ab, bc, ca]]);
+        }
+        Ok(subdivided)
+    }
+
+    fn catmull_clark_step(&self) -> Result<Self, HalfEdgeError> {
+        let mesh = self.topology()?;
+        let face_points: Vec<Vector3<N>> = mesh.faces().map(|face| mesh.face_centroid(face)).collect();
+        let mut positions: Vec<Vector3<N>> = mesh.vertices().map(|vertex| {
+            let ring = mesh.one_ring(vertex);
+            let position = mesh.position(vertex);
+            if ring.is_empty() {
+                return position;
+            }
+            if mesh.is_boundary_vertex(vertex) {
+                return Self::boundary_position(&mesh, vertex);
+            }
+            let count = N::from_f64(ring.len() as f64);
+            let faces = mesh.vertex_faces(vertex);
+            let average_face = faces.iter().fold(Vector3::ZERO, |sum, face| sum + face_points[face.index()]) / N::from_f64(faces.len() as f64);
+            let average_edge = ring.iter().fold(Vector3::ZERO, |sum, neighbour| sum + (position + mesh.position(*neighbour)) / N::TWO) / count;
+            (average_face + average_edge * N::TWO + position * (count - N::from_f64(3.0))) / count
+        }).collect();
+
+        let first_face_point = positions.len() as u32;
+        positions.extend_from_slice(&face_points);
+
+        let mut edge_points = HashMap::new();
+        for h in mesh.edges() {
+            let (a, b) = (mesh.origin(h), mesh.destination(h));
+            let ends = mesh.position(a) + mesh.position(b);
+            let point = match (mesh.face(h), mesh.face(mesh.twin(h))) {
+                (Some(left), Some(right)) => (ends + face_points[left.index()] + face_points[right.index()]) / N::from_f64(4.0),
+                _ => ends / N::TWO,
+            };
+            edge_points.insert(edge_key(a.index() as u32, b.index() as u32), positions.len() as u32);
+            positions.push(point);
+        }
+
+        let mut subdivided = Self { positions, uv: self.uv.clone(), faces: vec![] };
+        let mut edge_uv = HashMap::new();
+        for (f, face) in self.faces.iter().enumerate() {
+            let center_position = first_face_point + f as u32;
+            let center = if self.uv.is_empty() {
+                [center_position, center_position]
+            } else {
+                let average = face.iter().fold(Vector2::ZERO, |sum, corner| sum + self.uv[corner[1] as usize]) / N::from_f64(face.len() as f64);
+                subdivided.uv.push(average);
+                [center_position, subdivided.uv.len() as u32 - 1]
+            };
+            let edges: Vec<[u32; 2]> = (0..face.len()).map(|i| {
+                let (from, to) = (face[i], face[(i + 1) % face.len()]);
+                subdivided.edge_corner(edge_points[&edge_key(from[0], to[0])], from[1], to[1], &mut edge_uv)
+            }).collect();
+            for i in 0..face.len() {
+                subdivided.faces.push(vec![face[i], edges[i], center, edges[(i + face.len() - 1) % face.len()]]);
+            }
+        }
+        Ok(subdivided)
+    }
+
+    ///The corner at the new vertex of an edge, with the UV halfway between the UVs of its ends on this side of the edge.
+    fn edge_corner(&mut self, position: u32, from: u32, to: u32, edge_uv: &mut HashMap<(u32, u32), u32>) -> [u32; 2] {
+        if self.uv.is_empty() {
+            return [position, position];
+        }
+        let uv = &mut self.uv;
+        let index = *edge_uv.entry(edge_key(from, to)).or_insert_with(|| {
+            uv.push((uv[from as usize] + uv[to as usize]) / N::TWO);
+            uv.len() as u32 - 1
+        });
+        [position, index]
+    }
+
+    fn to_geometry(&self, tangents: bool) -> Geometry<3, N> {
+        let mut lookup: HashMap<[u32; 2], u32> = HashMap::new();
+        let mut corners = vec![];
+        let mut indices = vec![];
+        let mut shared_indices = vec![];
+        for face in &self.faces {
+            for i in 1..face.len() - 1 {
+                for corner in [face[0], face[i], face[i + 1]] {
+                    indices.push(*lookup.entry(corner).or_insert_with(|| {
+                        corners.push(corner);
+                        corners.len() as u32 - 1
+                    }));
+                    shared_indices.push(corner[0]);
+                }
+            }
+        }
+
+        //Normals are computed on the shared positions so they're smooth across seams.
+        let mut shared = Geometry { vertices: self.positions.clone(), normals: None, uv: vec![], tangents: None, indices: shared_indices };
+        shared.compute_normals();
+        let shared_normals = shared.normals.unwrap_or_default();
+
+        let mut geometry = Geometry {
+            vertices: corners.iter().map(|corner| self.positions[corner[0] as usize]).collect(),
+            normals: Some(corners.iter().map(|corner| shared_normals[corner[0] as usize]).collect()),
+            uv: if self.uv.is_empty() { vec![] } else { corners.iter().map(|corner| self.uv[corner[1] as usize]).collect() },
+            tangents: None,
+            indices,
+        };
+        if tangents {
+            geometry.compute_tangents();
+        }
+        geometry
+    }
+}
+
+fn edge_key(a: u32, b: u32) -> (u32, u32) {
+    (a.min(b), a.max(b))
+}
@@ -21,20 +21,50 @@ mod tests {
 
     use crate::algebra::summation;
     use crate::color::Color;
+    use crate::geometry::Geometry;
+    use crate::geometry::bezier::Bezier;
     use crate::geometry::curve::Curve;
+    use crate::geometry::format::{MeshFormatError, PlyEncoding, StlEncoding};
+    use crate::geometry::format::obj::read_obj;
+    use crate::geometry::half_edge::{HalfEdgeError, HalfEdgeMesh};
     use crate::geometry::shape::Shape;
+    use crate::geometry::spline::{BSpline, BSplineSurface, CubicSpline, Parameterization};
     use crate::geometry::triangle::triangle2d::Triangle2D;
     use crate::geometry::uv_sphere::UVSphere;
     use crate::linear_algebra::euler_angles::EulerAngles;
     use crate::linear_algebra::euler_angles::principle_euler_angles::PrincipleEulerAngles;
     use crate::linear_algebra::matrix::Matrix;
+    use crate::linear_algebra::quaternion::Quaternion;
     use crate::linear_algebra::matrix::types::Mat4F32;
     use crate::linear_algebra::vec3;
-    use crate::linear_algebra::vector::types::{Vector2F32, Vector3, Vector3F32};
-    use crate::physics::collider::Collider;
+    use crate::linear_algebra::vector::types::{Vector2F32, Vector2F64, Vector3, Vector3F32, Vector3F64};
+    use crate::physics::broad_phase::BroadPhaseType;
+    use crate::physics::collider::bounds::Bounds;
+    use crate::physics::collider::box_collider::BoxCollider;
+    use crate::physics::collider::capsule_collider::CapsuleCollider;
+    use crate::physics::collider::convex_collider::ConvexHullCollider;
+    use crate::physics::collider::convex_hull::ConvexHull;
+    use crate::physics::collider::{Collider, ALL_LAYERS, DEFAULT_LAYER};
+    use crate::physics::collider_handle::ColliderHandle;
+    use crate::physics::collision_event::CollisionEvent;
     use crate::physics::collider::sphere_collider::SphereCollider;
+    use crate::physics::collider::support::Support;
+    use crate::physics::gjk;
+    use crate::physics::joint::Joint;
+    use crate::physics::material::PhysicsMaterial;
+    use crate::physics::pose::Pose;
     use crate::physics::rigidbody::Rigidbody;
+    use crate::physics::rigidbody::flags::RigidbodyFlags;
     use crate::physics::rigidbody_handle::RigidbodyHandle;
+    use crate::physics::replay::{Recorder, Replay, ReplayInput};
+    use crate::physics::particle::{Emitter, ParticleSystem, VelocityDistribution};
+    use crate::physics::force_field::{Drag, ForceField, Gravity, PointAttractor, Turbulence, Vortex};
+    use crate::physics::soft_body::{MassSpring, SoftBody};
+    use crate::physics::gravitation::Gravitation;
+    use crate::physics::character_controller::{CharacterController, CharacterSettings};
+    use crate::physics::vehicle::{Vehicle, WheelSettings};
+    use crate::physics::world::snapshot::SnapshotError;
+    use crate::shared::endian::Endian;
     use crate::physics::world::World;
     use crate::shared::angle::Angle::Degrees;
     use crate::shared::traits::lerp::Lerp;
@@ -55,17 +85,501 @@ mod tests {
         let sphere = UVSphere {
             radius: 5.0,
             latitudes: 10.0,
-            longitudes: 10.0,
+            longitudes: 12.0,
         };
+        let geometry = sphere.to_geometry();
+
+        //Every ring has its own vertices, the seam doubled, and the poles only get one triangle per quad.
+        assert_eq!(geometry.vertices.len(), 11 * 13);
+        assert_eq!(geometry.indices.len(), 12 * (2 * 10 - 2) * 3);
+        assert!(geometry.indices.iter().all(|i| (*i as usize) < geometry.vertices.len()));
+        for (vertex, normal) in geometry.vertices.iter().zip(geometry.normals.as_ref().unwrap()) {
+            assert!((vertex.magnitude() - 5_f64).abs() < 1e-12 && vertex.distance(&(*normal * 5_f64)) < 1e-12);
+        }
+        //Points spread all the way around, not just along a diagonal.
+        assert!(geometry.vertices.iter().any(|v| v.x() > 4.9) && geometry.vertices.iter().any(|v| v.z() < -4.9));
+
+        let small = UVSphere { radius: 1_f64, latitudes: 1_f64, longitudes: 1_f64 }.to_geometry();
+        assert_eq!(small.vertices.len(), 3 * 4);
+    }
+
+    #[test]
+    fn test_primitives() {
+        use crate::geometry::primitive::{Capsule, Cone, Cube, Cylinder, Disk, Icosphere, Plane, Torus};
+        use std::f64::consts::PI;
+
+        //Checks what every primitive promises, and returns its volume and area.
+        let check = |name: &str, geometry: &Geometry<3, f64>| -> (f64, f64) {
+            let normals = geometry.normals.as_ref().unwrap();
+            let tangents = geometry.tangents.as_ref().unwrap();
+            assert_eq!(normals.len(), geometry.vertices.len());
+            assert_eq!(tangents.len(), geometry.vertices.len());
+            assert_eq!(geometry.uv.len(), geometry.vertices.len());
+            assert_eq!(geometry.indices.len() % 3, 0);
+            assert!(geometry.indices.iter().all(|i| (*i as usize) < geometry.vertices.len()), "{name}");
+            for (normal, tangent) in normals.iter().zip(tangents) {
+                let direction = Vector3F64::new([tangent.x(), tangent.y(), tangent.z()]);
+                assert!((normal.magnitude() - 1_f64).abs() < 1e-9 && (direction.magnitude() - 1_f64).abs() < 1e-9, "{name}");
+                assert!(normal.dot(&direction).abs() < 1e-9 && tangent[3].abs() == 1_f64, "{name}");
+            }
+
+            let (mut volume, mut area) = (0_f64, 0_f64);
+            for triangle in geometry.indices.chunks(3) {
+                let [a, b, c] = [0, 1, 2].map(|k| triangle[k] as usize);
+                let (pa, pb, pc) = (geometry.vertices[a], geometry.vertices[b], geometry.vertices[c]);
+                let face = (pb - pa).cross(&(pc - pa));
+                volume += pa.dot(&pb.cross(&pc)) / 6_f64;
+                area += face.magnitude() / 2_f64;
+                if face.magnitude() < 1e-12 {
+                    continue;
+                }
+
+                //Wound counterclockwise around the normals, with tangents along increasing u and bitangents along increasing v.
+                let average = normals[a] + normals[b] + normals[c];
+                assert!(face.dot(&average) > 0_f64, "{name} {:?}", triangle);
+                let (e1, e2) = (pb - pa, pc - pa);
+                let (du1, dv1) = (geometry.uv[b].x() - geometry.uv[a].x(), geometry.uv[b].y() - geometry.uv[a].y());
+                let (du2, dv2) = (geometry.uv[c].x() - geometry.uv[a].x(), geometry.uv[c].y() - geometry.uv[a].y());
+                let determinant = du1 * dv2 - du2 * dv1;
+                if determinant.abs() < 1e-12 {
+                    continue;
+                }
+                let along_u = (e1 * dv2 - e2 * dv1) / determinant;
+                let along_v = (e2 * du1 - e1 * du2) / determinant;
+                let tangent = tangents[a];
+                let direction = Vector3F64::new([tangent.x(), tangent.y(), tangent.z()]);
+                assert!(direction.dot(&along_u) > 0_f64, "{name} {:?}", triangle);
+                assert!(normals[a].cross(&direction).dot(&along_v) * tangent[3] > 0_f64, "{name} {:?}", triangle);
+            }
+            (volume, area)
+        };
+        let near = |value: f64, expected: f64, tolerance: f64| (value - expected).abs() <= tolerance * expected.abs();
+
+        let (volume, area) = check("uv sphere", &UVSphere { radius: 2_f64, latitudes: 64_f64, longitudes: 128_f64 }.to_geometry());
+        assert!(near(volume, 4_f64 / 3_f64 * PI * 8_f64, 0.01) && near(area, 4_f64 * PI * 4_f64, 0.01));
+
+        let icosphere = Icosphere { radius: 2_f64, subdivisions: 4 }.to_geometry();
+        assert!(icosphere.indices.len() == 20 * 4_usize.pow(4) * 3);
+        assert!(icosphere.vertices.iter().all(|v| (v.magnitude() - 2_f64).abs() < 1e-12));
+        assert!(icosphere.uv.iter().all(|uv| uv.x() >= 0_f64 && uv.x() <= 1.5 && uv.y() >= 0_f64 && uv.y() <= 1_f64));
+        let (volume, _) = check("icosphere", &icosphere);
+        assert!(near(volume, 4_f64 / 3_f64 * PI * 8_f64, 0.01));
+
+        let (volume, area) = check("cube", &Cube { size: Vector3F64::new([1_f64, 2_f64, 3_f64]) }.to_geometry());
+        assert!(near(volume, 6_f64, 1e-12) && near(area, 22_f64, 1e-12));
+
+        let plane = Plane { width: 4_f64, depth: 2_f64, segments_x: 4, segments_z: 3 }.to_geometry();
+        assert_eq!(plane.vertices.len(), 5 * 4);
+        let (_, area) = check("plane", &plane);
+        assert!(near(area, 8_f64, 1e-12));
+
+        let (_, area) = check("disk", &Disk { radius: 1_f64, segments: 256 }.to_geometry());
+        assert!(near(area, PI, 0.001));
+
+        let (volume, area) = check("cylinder", &Cylinder { radius: 1_f64, height: 3_f64, segments: 256, rings: 3, capped: true }.to_geometry());
+        assert!(near(volume, PI * 3_f64, 0.001) && near(area, 2_f64 * PI * 3_f64 + 2_f64 * PI, 0.001));
+
+        let (volume, _) = check("cone", &Cone { radius: 1_f64, height: 3_f64, segments: 256, capped: true }.to_geometry());
+        assert!(near(volume, PI, 0.001));
+
+        //Both sides of a revolved seam sit at exactly the same position, so the surface closes.
+        let cylinder = Cylinder { radius: 1_f64, height: 3_f64, segments: 7, rings: 1, capped: false }.to_geometry();
+        let seam = |u: f64| cylinder.vertices.iter().zip(&cylinder.uv).filter(|(_, uv)| uv.x() == u).map(|(vertex, _)| *vertex).collect::<Vec<Vector3F64>>();
+        assert_eq!(seam(0_f64).len(), 2);
+        assert_eq!(seam(0_f64), seam(1_f64));
+
+        let (volume, _) = check("capsule", &Capsule { radius: 0.5, height: 3_f64, segments: 128, rings: 32 }.to_geometry());
+        assert!(near(volume, PI * 0.25 * (2_f64 + 4_f64 / 3_f64 * 0.5), 0.01));
+        //Too short for a cylinder, the hemispheres meet without a band of flat triangles between them.
+        let sphere = Capsule { radius: 0.5, height: 0.8, segments: 64, rings: 16 }.to_geometry();
+        let (volume, _) = check("spherical capsule", &sphere);
+        assert!(near(volume, 4_f64 / 3_f64 * PI * 0.125, 0.01));
+        assert!(sphere.face_normals().iter().all(|normal| normal.magnitude() > 0.5));
+
+        let (volume, area) = check("torus", &Torus { major_radius: 2_f64, minor_radius: 0.5, major_segments: 128, minor_segments: 64 }.to_geometry());
+        assert!(near(volume, 2_f64 * PI * PI * 2_f64 * 0.25, 0.01) && near(area, 4_f64 * PI * PI * 2_f64 * 0.5, 0.01));
+    }
+
+    #[test]
+    fn test_geometry_processing() {
+        use crate::geometry::primitive::{Cube, Plane};
+
+        //Smooth normals and tangents come out close to the exact ones a sphere is built with, away from its seam and poles.
+        let exact = UVSphere { radius: 1_f64, latitudes: 32_f64, longitudes: 64_f64 }.to_geometry();
+        let mut sphere = exact.clone();
+        sphere.normals = None;
+        sphere.tangents = None;
+        sphere.compute_tangents();
+        let (normals, tangents) = (sphere.normals.as_ref().unwrap(), sphere.tangents.as_ref().unwrap());
+        for i in 0..sphere.vertices.len() {
+            let uv = sphere.uv[i];
+            if uv.x() < 0.01 || uv.x() > 0.99 || uv.y() < 0.01 || uv.y() > 0.99 {
+                continue;
+            }
+            let (expected, tangent) = (exact.tangents.as_ref().unwrap()[i], tangents[i]);
+            assert!(normals[i].dot(&exact.normals.as_ref().unwrap()[i]) > 0.999);
+            assert!(expected.x() * tangent.x() + expected.y() * tangent.y() + expected.z() * tangent.z() > 0.999);
+            assert_eq!(expected[3], tangent[3]);
+        }
+
+        //Welding keeps the UV seam apart, but closes it once only positions are left, after which normals are smooth across it.
+        assert_eq!(sphere.clone().weld(1e-9), 0);
+        sphere.uv.clear();
+        sphere.normals = None;
+        sphere.tangents = None;
+        let triangles = sphere.triangle_count();
+        sphere.weld(1e-9);
+        assert_eq!(sphere.vertices.len(), 31 * 64 + 2);
+        assert_eq!(sphere.triangle_count(), triangles);
+        sphere.compute_normals();
+        assert!(sphere.vertices.iter().zip(sphere.normals.as_ref().unwrap()).all(|(vertex, normal)| vertex.dot(normal) > 0.999));
+
+        //Flat normals split every vertex, and welding them back gives the plane it started as.
+        let plane = Plane { width: 2_f64, depth: 2_f64, segments_x: 3, segments_z: 3 }.to_geometry();
+        let mut flat = plane.clone();
+        flat.compute_flat_normals();
+        assert_eq!(flat.vertices.len(), 3 * 3 * 2 * 3);
+        assert!(flat.normals.as_ref().unwrap().iter().all(|normal| *normal == Vector3F64::UP));
+        assert_eq!(flat.weld(1e-9), 3 * 3 * 2 * 3 - 16);
+        let corners = |g: &Geometry<3, f64>| g.indices.iter().map(|i| g.vertices[*i as usize]).collect::<Vec<Vector3F64>>();
+        assert_eq!(corners(&flat), corners(&plane));
+
+        //Reordering a scrambled grid for the vertex cache keeps every triangle and drops the misses.
+        let mut grid = Plane { width: 1_f64, depth: 1_f64, segments_x: 48, segments_z: 48 }.to_geometry();
+        let mut triangles: Vec<[u32; 3]> = grid.indices.chunks(3).map(|t| [t[0], t[1], t[2]]).collect();
+        let count = triangles.len();
+        for i in 0..count {
+            triangles.swap(i, (i * 7919 + 13) % count);
+        }
+        grid.indices = triangles.into_iter().flatten().collect();
+        let before = grid.average_cache_miss_ratio(32);
+        let positions: Vec<Vector3F64> = grid.indices.iter().map(|i| grid.vertices[*i as usize]).collect();
+        grid.optimize_indices();
+        let after = grid.average_cache_miss_ratio(32);
+        assert!(before > 2_f64 && after < 0.8, "{before} {after}");
+        assert_eq!(grid.triangle_count(), count);
+        let mut reordered: Vec<Vec<[u64; 3]>> = grid.indices.chunks(3).map(|t| t.iter().map(|i| grid.vertices[*i as usize].0.map(f64::to_bits)).collect()).collect();
+        let mut original: Vec<Vec<[u64; 3]>> = positions.chunks(3).map(|t| t.iter().map(|v| v.0.map(f64::to_bits)).collect()).collect();
+        reordered.sort();
+        original.sort();
+        assert_eq!(reordered, original);
+        //Vertices are numbered in the order they're first used.
+        let mut next = 0;
+        for index in &grid.indices {
+            assert!(*index <= next);
+            next = next.max(*index + 1);
+        }
+        //Triangles sharing no vertices take linear time and keep their order.
+        let mut unwelded = Plane { width: 1_f64, depth: 1_f64, segments_x: 200, segments_z: 200 }.to_geometry();
+        unwelded.compute_flat_normals();
+        unwelded.optimize_indices();
+        assert_eq!(unwelded.triangle_count(), 200 * 200 * 2);
+        assert!(unwelded.indices.iter().enumerate().all(|(i, index)| *index == i as u32));
+
+        //Bounds hold every vertex, and the sphere is close to the smallest.
+        let cube = Cube { size: Vector3F64::new([1_f64, 2_f64, 3_f64]) }.to_geometry();
+        let (min, max) = cube.bounds();
+        assert_eq!((min, max), (Vector3F64::new([-0.5, -1_f64, -1.5]), Vector3F64::new([0.5, 1_f64, 1.5])));
+        let (center, radius) = cube.bounding_sphere();
+        let smallest = (0.25 + 1_f64 + 2.25_f64).sqrt();
+        assert!(cube.vertices.iter().all(|v| v.distance(&center) <= radius + 1e-12));
+        assert!(radius >= smallest - 1e-12 && radius < smallest * 1.05);
+
+        //Merging offsets indices, and only keeps normals every part has.
+        let merged = Geometry::merge(&[cube.clone(), plane.clone()]);
+        assert_eq!(merged.vertices.len(), cube.vertices.len() + plane.vertices.len());
+        assert_eq!(merged.indices[cube.indices.len()], plane.indices[0] + cube.vertices.len() as u32);
+        assert!(merged.normals.is_some() && merged.tangents.is_some());
+        let mut bare = plane.clone();
+        bare.normals = None;
+        bare.uv.clear();
+        let merged = Geometry::merge(&[cube.clone(), bare]);
+        assert!(merged.normals.is_none() && merged.uv.len() == merged.vertices.len());
+    }
 
-        let mut rgb = image::DynamicImage::new(100, 100, ColorType::Rgb8);
+    #[test]
+    fn test_half_edge_mesh() {
+        use crate::geometry::primitive::{Cube, Icosphere, Plane};
+        let euler = |mesh: &HalfEdgeMesh<f64>| mesh.vertex_count() as i64 - mesh.edge_count() as i64 + mesh.face_count() as i64;
+
+        //A cube's split vertices join into a closed mesh.
+        let mut cube = HalfEdgeMesh::from_geometry(&Cube { size: Vector3F64::ONE }.to_geometry()).unwrap();
+        assert_eq!((cube.vertex_count(), cube.edge_count(), cube.face_count()), (8, 18, 12));
+        assert!(cube.is_manifold() && cube.boundary_loops().is_empty());
+        assert_eq!(cube.vertices().map(|v| cube.valence(v)).sum::<usize>(), 36);
+        for vertex in cube.vertices() {
+            assert_eq!(cube.one_ring(vertex).len(), cube.vertex_faces(vertex).len());
+            assert!(cube.outgoing(vertex).iter().all(|h| cube.origin(*h) == vertex));
+        }
+        for face in cube.faces() {
+            assert!(cube.face_normal(face).dot(&cube.face_centroid(face)) > 0_f64);
+        }
+        let geometry = cube.to_geometry();
+        assert_eq!((geometry.vertices.len(), geometry.triangle_count()), (8, 12));
+
+        //Flips and splits keep it closed.
+        let edge = cube.edges().find(|h| cube.face_normal(cube.face(*h).unwrap()) == cube.face_normal(cube.face(cube.twin(*h)).unwrap())).unwrap();
+        let (a, b) = (cube.origin(edge), cube.destination(edge));
+        cube.flip_edge(edge).unwrap();
+        assert!(cube.is_manifold() && !cube.one_ring(a).contains(&b) && euler(&cube) == 2);
+        let edge = cube.edges().next().unwrap();
+        let middle = (cube.position(cube.origin(edge)) + cube.position(cube.destination(edge))) / 2_f64;
+        let vertex = cube.split_edge(edge, 0.5);
+        assert_eq!(cube.position(vertex), middle);
+        assert_eq!((cube.vertex_count(), cube.edge_count(), cube.face_count()), (9, 21, 14));
+        assert!(cube.is_manifold() && cube.valence(vertex) == 4);
+
+        //Collapses remove a vertex, three edges and two faces, inside and along a boundary.
+        let mut sphere = HalfEdgeMesh::from_geometry(&Icosphere { radius: 1_f64, subdivisions: 1 }.to_geometry()).unwrap();
+        assert_eq!((sphere.vertex_count(), sphere.face_count()), (42, 80));
+        let edge = sphere.edges().next().unwrap();
+        sphere.collapse_edge(edge, Vector3F64::UP).unwrap();
+        assert_eq!((sphere.vertex_count(), sphere.edge_count(), sphere.face_count()), (41, 117, 78));
+        assert!(sphere.is_manifold() && euler(&sphere) == 2);
+
+        let mut plane = HalfEdgeMesh::from_geometry(&Plane { width: 2_f64, depth: 2_f64, segments_x: 2, segments_z: 2 }.to_geometry()).unwrap();
+        let center = plane.vertices().find(|v| !plane.is_boundary_vertex(*v)).unwrap();
+        assert_eq!(plane.valence(center), 6);
+        assert_eq!(plane.boundary_loops().iter().map(|l| l.len()).collect::<Vec<usize>>(), vec![8]);
+        //An edge across the inside between two boundary vertices would pinch the boundary.
+        let bridge = plane.edges().find(|h| !plane.is_boundary_edge(*h) && plane.is_boundary_vertex(plane.origin(*h)) && plane.is_boundary_vertex(plane.destination(*h))).unwrap();
+        assert!(matches!(plane.collapse_edge(bridge, Vector3F64::ZERO), Err(HalfEdgeError::InvalidEdit(_))));
+        let boundary = plane.half_edges().find(|h| plane.face(*h).is_none()).unwrap();
+        plane.collapse_edge(boundary, plane.position(plane.origin(boundary))).unwrap();
+        assert!(plane.is_manifold() && plane.boundary_loops()[0].len() == 7 && plane.face_count() == 7);
+
+        let positions = vec![Vector3F64::ZERO, Vector3F64::RIGHT, Vector3F64::UP, Vector3F64::FORWARD];
+        let mut tetrahedron = HalfEdgeMesh::from_polygons(positions.clone(), &[vec![0, 2, 1], vec![0, 1, 3], vec![0, 3, 2], vec![1, 2, 3]]).unwrap();
+        assert!(tetrahedron.is_manifold());
+        let edge = tetrahedron.edges().next().unwrap();
+        assert!(tetrahedron.collapse_edge(edge, Vector3F64::ZERO).is_err());
+        assert!(tetrahedron.flip_edge(edge).is_err());
+
+        //Extruding a quad makes an open box whose sides face out.
+        let mut quad = HalfEdgeMesh::from_polygons(
+            vec![Vector3F64::ZERO, Vector3F64::new([1_f64, 0_f64, 0_f64]), Vector3F64::new([1_f64, 1_f64, 0_f64]), Vector3F64::new([0_f64, 1_f64, 0_f64])],
+            &[vec![0, 1, 2, 3]],
+        ).unwrap();
+        let top = quad.faces().next().unwrap();
+        let sides = quad.extrude_face(top, Vector3F64::new([0_f64, 0_f64, 1_f64]));
+        assert!(quad.is_manifold() && sides.len() == 4);
+        assert_eq!((quad.vertex_count(), quad.edge_count(), quad.face_count()), (8, 12, 5));
+        assert_eq!(quad.face_centroid(top), Vector3F64::new([0.5, 0.5, 1_f64]));
+        for side in sides {
+            let outwards = quad.face_centroid(side) - Vector3F64::new([0.5, 0.5, 0.5]);
+            assert!(quad.face_normal(side).dot(&outwards) > 0_f64);
+        }
+        assert_eq!(quad.boundary_loops()[0].len(), 4);
+
+        //Faces touching at a single vertex aren't a manifold, and neither are three faces on one edge.
+        let mut pinched = positions.clone();
+        pinched.extend_from_slice(&[Vector3F64::new([-1_f64, 0_f64, 0_f64]), Vector3F64::new([0_f64, -1_f64, 0_f64])]);
+        let bowtie = HalfEdgeMesh::from_polygons(pinched.clone(), &[vec![0, 1, 2], vec![0, 4, 5]]).unwrap();
+        assert_eq!(bowtie.validate(), Err(HalfEdgeError::NonManifoldVertex(bowtie.vertices().next().unwrap())));
+        assert!(matches!(HalfEdgeMesh::from_polygons(pinched, &[vec![0, 1, 2], vec![1, 0, 3], vec![0, 1, 4]]), Err(HalfEdgeError::NonManifoldEdge(0, 1))));
+
+        //Negative zero is the same position as zero, so triangles split there still share their edge.
+        let split = Geometry {
+            vertices: vec![Vector3F64::ZERO, Vector3F64::RIGHT, Vector3F64::UP, Vector3F64::new([-0_f64, -0_f64, -0_f64]), Vector3F64::new([0_f64, -1_f64, 0_f64])],
+            normals: None,
+            uv: vec![],
+            tangents: None,
+            indices: vec![0, 1, 2, 1, 3, 4],
+        };
+        let joined = HalfEdgeMesh::from_geometry(&split).unwrap();
+        assert_eq!((joined.vertex_count(), joined.edge_count()), (4, 5));
+        assert_eq!(joined.boundary_loops()[0].len(), 4);
+    }
 
-        for v in sphere.to_geometry().vertices {
-            rgb.put_pixel(50 + v.x() as u32, 50 + v.y() as u32, Rgba([255; 4]))
+    #[test]
+    fn test_subdivision_and_simplification() {
+        use crate::geometry::primitive::{Cube, Icosphere, Plane};
+        let closed = |geometry: &Geometry<3, f64>| {
+            let mesh = HalfEdgeMesh::from_geometry(geometry).unwrap();
+            mesh.is_manifold() && mesh.boundary_loops().is_empty()
+        };
+        let area = |geometry: &Geometry<3, f64>| geometry.indices.chunks(3).map(|t| {
+            let [a, b, c] = [0, 1, 2].map(|k| geometry.vertices[t[k] as usize]);
+            (b - a).cross(&(c - a)).magnitude() / 2_f64
+        }).sum::<f64>();
+        let volume = |geometry: &Geometry<3, f64>| geometry.indices.chunks(3).map(|t| {
+            let [a, b, c] = [0, 1, 2].map(|k| geometry.vertices[t[k] as usize]);
+            a.dot(&b.cross(&c)) / 6_f64
+        }).sum::<f64>();
+
+        //Loop subdivision shrinks an icosahedron towards a rounder shape, without cracks.
+        let icosahedron = Icosphere { radius: 1_f64, subdivisions: 0 }.to_geometry();
+        let smooth = icosahedron.subdivide_loop(3).unwrap();
+        assert_eq!(smooth.triangle_count(), 20 * 64);
+        assert!(closed(&smooth));
+        let radii: Vec<f64> = smooth.vertices.iter().map(|v| v.magnitude()).collect();
+        let (smallest, largest) = (radii.iter().cloned().fold(f64::MAX, f64::min), radii.iter().cloned().fold(0_f64, f64::max));
+        assert!(smallest > 0.6 && largest < 1_f64 && largest - smallest < 0.05, "{smallest} {largest}");
+        assert!(smooth.normals.as_ref().unwrap().iter().zip(&smooth.vertices).all(|(n, v)| n.dot(&v.normalize()) > 0.99));
+        assert_eq!(smooth.tangents.as_ref().unwrap().len(), smooth.vertices.len());
+
+        //The sphere's UV seam stays split, with UVs on either side, while its positions stay joined.
+        let sphere = UVSphere { radius: 1_f64, latitudes: 8_f64, longitudes: 16_f64 }.to_geometry();
+        let smooth = sphere.subdivide_loop(1).unwrap();
+        assert!(closed(&smooth));
+        let (positions, _) = smooth.shared_positions();
+        assert!(smooth.vertices.len() > positions.len());
+        assert!(smooth.uv.iter().all(|uv| uv.x() >= 0_f64 && uv.x() <= 1_f64) && smooth.uv.iter().any(|uv| uv.x() == 1_f64));
+
+        //A flat plane stays flat, with its boundary smoothed as a curve of its own.
+        let plane = Plane { width: 2_f64, depth: 2_f64, segments_x: 2, segments_z: 2 }.to_geometry();
+        let smooth = plane.subdivide_loop(1).unwrap();
+        assert_eq!((smooth.vertices.len(), smooth.triangle_count()), (25, 32));
+        assert!(smooth.vertices.iter().all(|v| v.y() == 0_f64 && v.x().abs() <= 1_f64 && v.z().abs() <= 1_f64));
+        let smooth = plane.subdivide_catmull_clark(2).unwrap();
+        assert!(smooth.vertices.iter().all(|v| v.y() == 0_f64));
+        assert_eq!(HalfEdgeMesh::from_geometry(&smooth).unwrap().boundary_loops().len(), 1);
+
+        //Catmull–Clark turns every triangle into three quads, then every quad into four, staying inside the cube.
+        let cube = Cube { size: Vector3F64::ONE }.to_geometry();
+        let smooth = cube.subdivide_catmull_clark(2).unwrap();
+        assert_eq!(smooth.triangle_count(), 12 * 3 * 4 * 2);
+        assert!(closed(&smooth) && smooth.vertices.iter().all(|v| v.0.iter().all(|c| c.abs() < 0.5)));
+        assert!(volume(&smooth) > 0_f64);
+
+        //Decimation keeps the sphere closed and its UV seam split, with vertices taken from the original.
+        let sphere = UVSphere { radius: 1_f64, latitudes: 32_f64, longitudes: 64_f64 }.to_geometry();
+        let simple = sphere.simplify(500, None);
+        assert!(simple.triangle_count() <= 500 && simple.triangle_count() > 400, "{}", simple.triangle_count());
+        assert!(closed(&simple));
+        assert!((volume(&simple) - volume(&sphere)).abs() < 0.08 * volume(&sphere));
+        let (positions, _) = simple.shared_positions();
+        assert!(simple.vertices.len() > positions.len());
+        for (vertex, uv) in simple.vertices.iter().zip(&simple.uv) {
+            assert!(sphere.vertices.iter().zip(&sphere.uv).any(|(v, u)| v == vertex && u == uv));
         }
 
-        rgb.save("output.png").unwrap();
-        //rgb.put_pixel(50 + )
+        //A flat plane loses its inner vertices without its outline moving, while a cube's hard corners keep it as it is.
+        let plane = Plane { width: 2_f64, depth: 2_f64, segments_x: 16, segments_z: 16 }.to_geometry();
+        let simple = plane.simplify(0, Some(1e-9));
+        assert!(simple.triangle_count() < 20, "{}", simple.triangle_count());
+        assert_eq!(simple.bounds(), plane.bounds());
+        assert!((area(&simple) - 4_f64).abs() < 1e-12);
+        assert_eq!(cube.simplify(0, None).triangle_count(), 12);
+    }
+
+    #[test]
+    fn test_mesh_formats() {
+        use crate::geometry::primitive::{Cube, Icosphere};
+        use crate::shared::endian::Endian;
+        let same = |a: &Geometry<3, f64>, b: &Geometry<3, f64>| {
+            assert_eq!(a.vertices, b.vertices);
+            assert_eq!(a.normals, b.normals);
+            assert_eq!(a.uv.len(), b.uv.len());
+            assert!(a.uv.iter().zip(&b.uv).all(|(a, b)| a.distance(b) < 1e-15));
+            assert_eq!(a.indices, b.indices);
+        };
+
+        //OBJ keeps everything but tangents, numbering vertices in the order faces use them.
+        let corners = |g: &Geometry<3, f64>| g.indices.iter().map(|i| {
+            let i = *i as usize;
+            (g.vertices[i], g.normals.as_ref().unwrap()[i], g.uv[i].x(), (g.uv[i].y() * 1e12).round())
+        }).collect::<Vec<(Vector3F64, Vector3F64, f64, f64)>>();
+        let cube = Cube { size: Vector3F64::new([1_f64, 2_f64, 3_f64]) }.to_geometry();
+        same(&Geometry::from_obj(&cube.to_obj()).unwrap(), &cube);
+        let sphere = UVSphere { radius: 1.5, latitudes: 8_f64, longitudes: 12_f64 }.to_geometry();
+        assert_eq!(corners(&Geometry::from_obj(&sphere.to_obj()).unwrap()), corners(&sphere));
+
+        let text = "# two objects\nv 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nvn 0 0 1\nvt 0 0\nvt 1 1\n\
+            o quad\nf 1//1 2//1 3//1 4//1\ng triangle\nusemtl red\nf -4/-2 -3/-1 -2/-1\n";
+        let objects = read_obj::<f64>(text).unwrap();
+        assert_eq!(objects.iter().map(|o| o.name.as_str()).collect::<Vec<&str>>(), vec!["quad", "triangle"]);
+        assert_eq!(objects[0].geometry.indices, vec![0, 1, 2, 0, 2, 3]);
+        assert!(objects[0].geometry.normals.is_some() && objects[0].geometry.uv.is_empty());
+        assert!(objects[1].geometry.normals.is_none() && objects[1].geometry.uv[0] == Vector2F64::new([0_f64, 1_f64]));
+        assert_eq!(read_obj::<f64>("v 0 0 0\nv 1 0 0\nf 1 2 3\n").unwrap_err(), MeshFormatError::Parse { line: 3, message: "position 3 doesn't exist, there are 2".to_string() });
+        assert!(matches!(Geometry::<3, f64>::from_obj("v 0 zero 0\n"), Err(MeshFormatError::Parse { line: 1, .. })));
+
+        //STL only has positions and flat normals, in 32 bits when binary.
+        let icosphere = Icosphere { radius: 1_f64, subdivisions: 1 }.to_geometry();
+        for encoding in [StlEncoding::Ascii, StlEncoding::Binary] {
+            let read = Geometry::<3, f64>::from_stl(&icosphere.to_stl(encoding)).unwrap();
+            assert_eq!(read.triangle_count(), icosphere.triangle_count());
+            assert_eq!(read.vertices.len(), icosphere.triangle_count() * 3);
+            for (triangle, normal) in read.indices.chunks(3).zip(read.face_normals()) {
+                assert!(read.normals.as_ref().unwrap()[triangle[0] as usize].distance(&normal) < 1e-6);
+                assert!(triangle.iter().all(|i| (read.vertices[*i as usize].magnitude() - 1_f64).abs() < 1e-6));
+            }
+        }
+        let flat = Geometry::<3, f64>::from_stl(&cube.to_stl(StlEncoding::Binary)).unwrap();
+        assert_eq!((flat.vertices.len(), flat.triangle_count()), (24, 12));
+        let broken = "solid broken\n  facet normal 0 0 1\n    outer loop\n      vertex 1 2\n";
+        assert!(matches!(Geometry::<3, f64>::from_stl(broken.as_bytes()), Err(MeshFormatError::Parse { line: 4, .. })));
+        let binary = cube.to_stl(StlEncoding::Binary);
+        assert!(Geometry::<3, f64>::from_stl(&binary[..binary.len() - 10]).is_err());
+
+        //PLY keeps everything but tangents in every encoding.
+        for encoding in [PlyEncoding::Ascii, PlyEncoding::Binary(Endian::Little), PlyEncoding::Binary(Endian::Big)] {
+            same(&Geometry::from_ply(&sphere.to_ply(encoding)).unwrap(), &sphere);
+        }
+        let text = "ply\nformat ascii 1.0\ncomment a quad\nelement vertex 4\nproperty float x\nproperty float y\nproperty float z\nproperty uchar red\n\
+            element face 1\nproperty list uchar int vertex_index\nelement edge 1\nproperty int vertex1\nproperty int vertex2\nend_header\n\
+            0 0 0 255\n1 0 0 255\n1 1 0 255\n0 1 0 255\n4 0 1 2 3\n0 1\n";
+        let quad = Geometry::<3, f64>::from_ply(text.as_bytes()).unwrap();
+        assert_eq!((quad.vertices.len(), quad.indices.clone()), (4, vec![0, 1, 2, 0, 2, 3]));
+        assert!(quad.normals.is_none() && quad.uv.is_empty());
+        let missing = text.replace("4 0 1 2 3", "3 0 1 7");
+        assert!(matches!(Geometry::<3, f64>::from_ply(missing.as_bytes()), Err(MeshFormatError::Parse { line: 19, .. })));
+        //Indices and counts that aren't whole numbers are errors, rather than rounded down to some vertex.
+        for broken in ["3 0 1 nan", "3 0 1.5 2", "3 0 1 inf", "2.5 0 1 2", "-1 0 1 2", "nan 0 1 2"] {
+            let broken = text.replace("4 0 1 2 3", broken);
+            assert!(matches!(Geometry::<3, f64>::from_ply(broken.as_bytes()), Err(MeshFormatError::Parse { line: 19, .. })), "{broken}");
+        }
+        let unknown = text.replace("property uchar red", "property colour red");
+        assert!(matches!(Geometry::<3, f64>::from_ply(unknown.as_bytes()), Err(MeshFormatError::Parse { line: 8, .. })));
+    }
+
+    #[test]
+    fn test_gltf_export() {
+        use crate::shared::binary::ByteReader;
+        let sphere = UVSphere { radius: 1.5, latitudes: 8_f64, longitudes: 12_f64 }.to_geometry();
+        let count = sphere.vertices.len();
+        let file = sphere.to_gltf("sphere.bin").unwrap();
+        assert_eq!(file.buffer.len(), count * (12 + 12 + 16 + 8) + sphere.indices.len() * 4);
+        assert!(file.json.contains(r#""buffers":[{"byteLength":"#) && file.json.contains(r#""uri":"sphere.bin""#));
+        assert!(file.json.contains(r#""attributes":{"POSITION":0,"NORMAL":1,"TANGENT":2,"TEXCOORD_0":3},"indices":4,"mode":4"#));
+        let (min, max) = sphere.bounds();
+        let bounds = format!(r#""min":[{},{},{}],"max":[{},{},{}]"#, min.x() as f32, min.y() as f32, min.z() as f32, max.x() as f32, max.y() as f32, max.z() as f32);
+        assert!(file.json.contains(&bounds));
+
+        //The buffer is little endian and starts with the positions.
+        let mut reader = ByteReader::new(&file.buffer, Endian::Little);
+        for vertex in &sphere.vertices {
+            let read: Vector3F32 = reader.vector().unwrap();
+            assert_eq!(read.0, vertex.0.map(|c| c as f32));
+        }
+
+        //A binary file holds the same JSON, padded with spaces, and the same buffer.
+        let glb = sphere.to_glb().unwrap();
+        let mut reader = ByteReader::new(&glb, Endian::Little);
+        assert_eq!(reader.raw(4).unwrap(), b"glTF");
+        assert_eq!((reader.number::<u32>().unwrap(), reader.number::<u32>().unwrap() as usize), (2, glb.len()));
+        let length = reader.number::<u32>().unwrap() as usize;
+        assert_eq!((length % 4, reader.raw(4).unwrap()), (0, &b"JSON"[..]));
+        let json = std::str::from_utf8(reader.raw(length).unwrap()).unwrap();
+        assert_eq!(json.trim_end(), file.json.replace(r#","uri":"sphere.bin""#, ""));
+        assert_eq!(reader.number::<u32>().unwrap() as usize, file.buffer.len());
+        assert_eq!(reader.raw(4).unwrap(), b"BIN\0");
+        assert_eq!(reader.raw(file.buffer.len()).unwrap(), &file.buffer[..]);
+        assert_eq!(reader.remaining(), 0);
+
+        let bare = Geometry::<3, f64> { vertices: sphere.vertices.clone(), normals: None, uv: vec![], tangents: None, indices: sphere.indices.clone() };
+        assert!(bare.to_gltf("bare.bin").unwrap().json.contains(r#""attributes":{"POSITION":0},"indices":1"#));
+
+        //Nothing to draw, or positions without bounds, can't be written.
+        let empty = Geometry::<3, f64> { vertices: vec![], normals: None, uv: vec![], tangents: None, indices: vec![] };
+        assert!(matches!(empty.to_gltf("empty.bin"), Err(MeshFormatError::Invalid(_))));
+        assert!(matches!(empty.to_glb(), Err(MeshFormatError::Invalid(_))));
+        let mut broken = bare.clone();
+        broken.vertices[3] = Vector3F64::new([0_f64, f64::NAN, 0_f64]);
+        assert_eq!(broken.to_glb(), Err(MeshFormatError::Invalid("vertex 3 isn't at a finite position".to_string())));
+        broken.vertices[3] = Vector3F64::new([1e300, 0_f64, 0_f64]);
+        assert!(broken.to_gltf("broken.bin").is_err());
     }
 
     #[test]
@@ -131,11 +645,11 @@ mod tests {
     }
 
     #[test]
-    fn test_inverse_curve() {
-        let p0 = Vector2F32::new([0_f32; 2]);
-        let p1 = Vector2F32::new([0_f32, 100_f32]);
-        let p2 = Vector2F32::new([100_f32, 0.0]);
-        let p3 = Vector2F32::new([100_f32; 2]);
+    fn test_curve_arc_length() {
+        let p0 = Vector2F64::new([0_f64; 2]);
+        let p1 = Vector2F64::new([0_f64, 100_f64]);
+        let p2 = Vector2F64::new([100_f64, 0.0]);
+        let p3 = Vector2F64::new([100_f64; 2]);
 
         let curve = Curve::Cubic {
             p0,
@@ -144,45 +658,233 @@ mod tests {
             p3,
         };
 
+        //Finding t back from a point on the curve.
         let p = curve.interpolate(0.5);
+        let (t, closest, distance) = curve.closest_point(p);
+        assert!((t - 0.5).abs() < 1e-9 && closest.distance(&p) < 1e-9 && distance < 1e-9, "{t} {:?}", closest);
+
+        //And from a point off it, which is then along the curve's normal from the closest point.
+        let bezier = curve.to_bezier();
+        let tangent = bezier.derivative_at(0.3).normalize();
+        let off = bezier.interpolate(0.3) + Vector2F64::new([-tangent.y(), tangent.x()]) * 2_f64;
+        let (t, _, distance) = curve.closest_point(off);
+        assert!((t - 0.3).abs() < 1e-9 && (distance - 2_f64).abs() < 1e-9, "{t} {distance}");
+        let (t, closest, _) = curve.closest_point(Vector2F64::new([-50_f64, -50_f64]));
+        assert!(t == 0_f64 && closest.distance(&p0) < 1e-12);
+
+        let line = Curve::Linear { p0, p1: p3 };
+        assert!((line.arc_length() - 100_f64 * 2_f64.sqrt()).abs() < 1e-9);
+        let arc = Bezier::rational(
+            vec![Vector2F64::new([1_f64, 0_f64]), Vector2F64::new([1_f64, 1_f64]), Vector2F64::new([0_f64, 1_f64])],
+            vec![1_f64, 0.5_f64.sqrt(), 1_f64],
+        );
+        assert!((arc.arc_length() - std::f64::consts::FRAC_PI_2).abs() < 1e-10, "{}", arc.arc_length());
+        let quarter = arc.point_at_distance(std::f64::consts::FRAC_PI_4);
+        assert!((quarter.x() - quarter.y()).abs() < 1e-9, "{:?}", quarter);
+
+        //Sampling in t bunches points up, sampling by distance spaces them evenly.
+        let length = curve.arc_length();
+        let spacings = |points: &Vec<Vector2F64>| points.windows(2).map(|pair| pair[0].distance(&pair[1])).collect::<Vec<_>>();
+        let uneven = spacings(&bezier.sample(41));
+        let even = spacings(&curve.sample_evenly(41));
+        let spread = |spacings: &Vec<f64>| spacings.iter().cloned().fold(f64::MIN, f64::max) - spacings.iter().cloned().fold(f64::MAX, f64::min);
+        assert!(spread(&uneven) > 0.5 * length / 40_f64);
+        //Neighbours are measured straight across rather than along the curve, which differs slightly where it bends.
+        assert!(spread(&even) < 0.01 * length / 40_f64, "{}", spread(&even));
+        assert!((bezier.length_at(bezier.parameter_at_distance(0.3 * length)) - 0.3 * length).abs() < 1e-9);
+
+        //Every point of the curve stays within the tolerance of the polyline, which needs fewer points where the curve is straight.
+        let tolerance = 0.05;
+        let polyline = curve.flatten(tolerance);
+        assert_eq!(polyline[0], p0);
+        assert_eq!(*polyline.last().unwrap(), p3);
+        for point in bezier.sample(2001) {
+            let nearest = polyline.windows(2).map(|pair| {
+                let along = pair[1] - pair[0];
+                let t = ((point - pair[0]).dot(&along) / along.magnitude_squared()).clamp(0_f64, 1_f64);
+                point.distance(&(pair[0] + along * t))
+            }).fold(f64::MAX, f64::min);
+            assert!(nearest <= tolerance, "{nearest}");
+        }
+        assert_eq!(line.flatten(tolerance).len(), 2);
+        assert!(polyline.len() < 100, "{}", polyline.len());
+
+        //Single precision only integrates as finely as it can resolve, landing close to the double precision length.
+        let single = Bezier::new(bezier.points().iter().map(|p| Vector2F32::new(p.0.map(|c| c as f32))).collect());
+        assert!((single.arc_length() as f64 - length).abs() < 1e-5 * length, "{} {length}", single.arc_length());
+        for (a, b) in single.sample_evenly(20).into_iter().zip(bezier.sample_evenly(20)) {
+            assert!((a.x() as f64 - b.x()).hypot(a.y() as f64 - b.y()) < 1e-4 * length);
+        }
+    }
 
-        let p0x = p0.x();
-        let p1x = p1.x();
-        let p2x = p2.x();
-        let p3x = p3.x();
-        let px = p.x();
-
-        let a = p3x - 3_f32 * p2x + 3_f32 * p1x - p0x;
-        let b = 3_f32 * p2x - 6_f32 * p1x + 3_f32 * p0x;
-        let c = 3_f32 * p1x - 3_f32 * p0x;
-        let d = p0x - px;
-
-
-        let p = -b / (3_f32 * a);
-        let q = (p * p * p) + (b * c - 3_f32 * a * d) / (6_f32 * (a * a));
-        let r = c / (3_f32 * a);
-
-        println!("{:?}", (a, b, c, d));
-
-        println!("{:?}", (p, q, r));
-
-        //q + [q2 + (r-p2)3]1/2
-
-        let q2 = q * q;
-        let r_p2_3 = (r - p * p) * (r - p * p) * (r - p * p);
+    #[test]
+    fn test_b_splines() {
+        let points = vec![
+            Vector2F64::new([0_f64, 0_f64]),
+            Vector2F64::new([1_f64, 3_f64]),
+            Vector2F64::new([3_f64, -1_f64]),
+            Vector2F64::new([4_f64, 2_f64]),
+        ];
+
+        //Clamped with only as many points as the degree plus one, it's a Bézier curve.
+        let bezier = Bezier::new(points.clone());
+        let spline = BSpline::clamped(3, points.clone());
+        for i in 0..=10 {
+            let t = i as f64 / 10_f64;
+            assert!(spline.interpolate(t).distance(&bezier.interpolate(t)) < 1e-12);
+            assert!(spline.derivative_at(t).distance(&bezier.derivative_at(t)) < 1e-9);
+        }
 
-        println!("{:?}", r_p2_3);
+        //A uniform cubic starts at a blend of its first three points.
+        let uniform = BSpline::uniform(3, points.clone());
+        assert_eq!(uniform.domain(), (3_f64, 4_f64));
+        let start = (points[0] + points[1] * 4_f64 + points[2]) / 6_f64;
+        assert!(uniform.interpolate(3_f64).distance(&start) < 1e-12);
+
+        //Non-uniform, with more points than one Bézier curve. Derivatives against central differences, and the derivative as a curve.
+        let spline = BSpline::new(3, vec![0_f64, 0_f64, 0_f64, 0_f64, 0.2, 0.7, 1_f64, 1_f64, 1_f64, 1_f64], vec![
+            Vector3F64::new([0_f64, 0_f64, 0_f64]),
+            Vector3F64::new([1_f64, 2_f64, 0_f64]),
+            Vector3F64::new([2_f64, -1_f64, 1_f64]),
+            Vector3F64::new([3_f64, 3_f64, 2_f64]),
+            Vector3F64::new([5_f64, 0_f64, 1_f64]),
+            Vector3F64::new([6_f64, 2_f64, -1_f64]),
+        ]);
+        let derivative = spline.derivative().unwrap();
+        let h = 1e-5;
+        for t in [0.1, 0.35, 0.5, 0.85] {
+            let derivatives = spline.derivatives(t, 2);
+            let first = (spline.interpolate(t + h) - spline.interpolate(t - h)) / (2_f64 * h);
+            let second = (spline.interpolate(t + h) - spline.interpolate(t) * 2_f64 + spline.interpolate(t - h)) / (h * h);
+            assert!(derivatives[1].distance(&first) < 1e-6 && derivatives[2].distance(&second) < 1e-3, "{:?} {:?}", derivatives, (first, second));
+            assert!(derivative.interpolate(t).distance(&derivatives[1]) < 1e-9);
+        }
 
-        let inner = (
-            q2 + r_p2_3
-        ).powf(0.5);
+        //A NURBS circle, exact everywhere, and still once knots are inserted.
+        let weight = 0.5_f64.sqrt();
+        let mut circle = BSpline::nurbs(
+            2,
+            vec![0_f64, 0_f64, 0_f64, 0.25, 0.25, 0.5, 0.5, 0.75, 0.75, 1_f64, 1_f64, 1_f64],
+            vec![[1_f64, 0_f64], [1_f64, 1_f64], [0_f64, 1_f64], [-1_f64, 1_f64], [-1_f64, 0_f64], [-1_f64, -1_f64], [0_f64, -1_f64], [1_f64, -1_f64], [1_f64, 0_f64]]
+                .into_iter().map(Vector2F64::new).collect(),
+            vec![1_f64, weight, 1_f64, weight, 1_f64, weight, 1_f64, weight, 1_f64],
+        );
+        let before: Vec<Vector2F64> = circle.sample(50);
+        circle.insert_knot(0.4, 2);
+        circle.insert_knot(0.125, 1);
+        //Already repeated as many times as the degree, so this adds nothing.
+        circle.insert_knot(0.25, 3);
+        assert_eq!(circle.points().len(), 9 + 2 + 1);
+        assert_eq!((circle.multiplicity(0.4), circle.multiplicity(0.25)), (2, 2));
+        for (i, point) in circle.sample(50).into_iter().enumerate() {
+            let t = i as f64 / 49_f64;
+            assert!((point.magnitude() - 1_f64).abs() < 1e-12 && point.distance(&before[i]) < 1e-12);
+            assert!(circle.derivative_at(t).dot(&point).abs() < 1e-9);
+        }
+        let derivatives = circle.derivatives(0.6, 2);
+        let second = (circle.interpolate(0.6 + h) - circle.interpolate(0.6) * 2_f64 + circle.interpolate(0.6 - h)) / (h * h);
+        assert!(derivatives[2].distance(&second) < 1e-3 * second.magnitude());
+
+        //A flat patch, and a NURBS cylinder swept from the circle.
+        let patch = BSplineSurface::clamped(1, 1, vec![
+            vec![Vector3F64::new([0_f64, 0_f64, 0_f64]), Vector3F64::new([0_f64, 1_f64, 0_f64])],
+            vec![Vector3F64::new([2_f64, 0_f64, 0_f64]), Vector3F64::new([2_f64, 1_f64, 0_f64])],
+        ]);
+        assert!(patch.evaluate(0.5, 0.5).distance(&Vector3F64::new([1_f64, 0.5, 0_f64])) < 1e-12);
+        assert!(patch.normal(0.3, 0.9).distance(&Vector3F64::FORWARD) < 1e-12);
+
+        let rows = circle.points().iter().map(|p| vec![Vector3F64::new([p.x(), p.y(), 0_f64]), Vector3F64::new([p.x(), p.y(), 2_f64])]).collect();
+        let cylinder = BSplineSurface::new(2, 1, circle.knots().to_vec(), vec![0_f64, 0_f64, 1_f64, 1_f64], rows)
+            .with_weights(circle.weights().unwrap().iter().map(|w| vec![*w, *w]).collect());
+        let geometry = cylinder.to_geometry(32, 4);
+        assert_eq!(geometry.vertices.len(), 33 * 5);
+        assert_eq!(geometry.indices.len(), 32 * 4 * 6);
+        for (vertex, normal) in geometry.vertices.iter().zip(geometry.normals.as_ref().unwrap()) {
+            let radial = Vector3F64::new([vertex.x(), vertex.y(), 0_f64]);
+            assert!((radial.magnitude() - 1_f64).abs() < 1e-12 && vertex.z() >= 0_f64 && vertex.z() <= 2_f64);
+            assert!(normal.dot(&radial) > 1_f64 - 1e-9, "{:?} {:?}", vertex, normal);
+        }
+        assert!(geometry.uv.iter().all(|uv| uv.x() >= 0_f64 && uv.x() <= 1_f64 && uv.y() >= 0_f64 && uv.y() <= 1_f64));
 
-        println!("inner: {inner}");
+        //Triangles wind counterclockwise seen from outside.
+        for triangle in geometry.indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| geometry.vertices[triangle[i] as usize]);
+            let face = (b - a).cross(&(c - a));
+            assert!(face.dot(&Vector3F64::new([a.x() + b.x() + c.x(), a.y() + b.y() + c.y(), 0_f64])) > 0_f64);
+        }
+    }
 
-        let a = (q + inner).powf(0.33);
-        let b = (q - inner).powf(0.33) + p;
+    #[test]
+    fn test_interpolating_splines() {
+        let points: Vec<Vector3F64> = [[0_f64, 0_f64, 0_f64], [1_f64, 2_f64, 0_f64], [1.2, 2.1, 0.5], [4_f64, 0_f64, 1_f64], [5_f64, 3_f64, -1_f64]]
+            .into_iter().map(Vector3F64::new).collect();
+        let h = 1e-6;
+
+        for parameterization in [Parameterization::Uniform, Parameterization::Centripetal, Parameterization::Chordal] {
+            let splines = [
+                CubicSpline::catmull_rom(points.clone(), parameterization),
+                CubicSpline::natural(points.clone(), parameterization),
+                CubicSpline::clamped(points.clone(), Vector3F64::RIGHT, Vector3F64::UP, parameterization),
+            ];
+            for spline in &splines {
+                //Every spline goes through its points, smoothly.
+                for (point, knot) in points.iter().zip(spline.knots()) {
+                    assert!(spline.interpolate(*knot).distance(point) < 1e-12);
+                }
+                for knot in &spline.knots()[1..points.len() - 1] {
+                    let before = (spline.interpolate(*knot) - spline.interpolate(knot - h)) / h;
+                    let after = (spline.interpolate(knot + h) - spline.interpolate(*knot)) / h;
+                    assert!(before.distance(&after) < 1e-4 * before.magnitude().max(1_f64));
+                }
+                let t = (spline.knots()[1] + spline.knots()[2]) / 2_f64;
+                let numeric = (spline.interpolate(t + h) - spline.interpolate(t - h)) / (2_f64 * h);
+                assert!(spline.derivative_at(t).distance(&numeric) < 1e-6);
+
+                //The Bézier segments trace the same curve.
+                for (i, bezier) in spline.to_beziers().iter().enumerate() {
+                    let (start, end) = (spline.knots()[i], spline.knots()[i + 1]);
+                    for s in [0_f64, 0.3, 0.7, 1_f64] {
+                        assert!(bezier.interpolate(s).distance(&spline.interpolate(start + (end - start) * s)) < 1e-12);
+                    }
+                }
+            }
+
+            //Natural and clamped splines are continuous in curvature, natural ones have none at the ends, clamped ones keep their tangents.
+            let (natural, clamped) = (&splines[1], &splines[2]);
+            for spline in [natural, clamped] {
+                for knot in &spline.knots()[1..points.len() - 1] {
+                    let (before, after) = (spline.second_derivative_at(knot - 1e-12), spline.second_derivative_at(knot + 1e-12));
+                    assert!(before.distance(&after) < 1e-6 * before.magnitude().max(1_f64), "{:?} {:?} {:?}", parameterization, before, after);
+                }
+            }
+            let (start, end) = natural.domain();
+            assert!(natural.second_derivative_at(start).magnitude() < 1e-9 && natural.second_derivative_at(end).magnitude() < 1e-9);
+            assert!(clamped.derivative_at(start).distance(&Vector3F64::RIGHT) < 1e-12 && clamped.derivative_at(end).distance(&Vector3F64::UP) < 1e-12);
+        }
 
-        println!("{:?}", (a, b));
+        //Uniform Catmull–Rom tangents are half the difference between the neighbours.
+        let catmull_rom = CubicSpline::catmull_rom(points.clone(), Parameterization::Uniform);
+        assert!(catmull_rom.tangents()[2].distance(&((points[3] - points[1]) / 2_f64)) < 1e-12);
+        //Between the two close points, uniform knots swing the curve wide where centripetal ones keep it tight.
+        let centripetal = CubicSpline::catmull_rom(points.clone(), Parameterization::Centripetal);
+        let (wide, tight) = (catmull_rom.to_beziers()[1].arc_length(), centripetal.to_beziers()[1].arc_length());
+        assert!(wide > 1.2 * tight && tight < 1.1 * points[1].distance(&points[2]), "{wide} {tight}");
+
+        //A natural spline through points on a line is that line.
+        let line: Vec<Vector3F64> = (0..5).map(|i| Vector3F64::new([i as f64, 2_f64 * i as f64, 0_f64])).collect();
+        let natural = CubicSpline::natural(line, Parameterization::Chordal);
+        assert!(natural.interpolate(3.3).distance(&(Vector3F64::new([1_f64, 2_f64, 0_f64]) * (3.3 / 5_f64.sqrt()))) < 1e-12);
+
+        //Hermite splines in 2D, converted to curves.
+        let hermite = CubicSpline::hermite(
+            vec![Vector2F64::new([0_f64, 0_f64]), Vector2F64::new([3_f64, 0_f64]), Vector2F64::new([3_f64, 3_f64])],
+            vec![Vector2F64::new([3_f64, 0_f64]), Vector2F64::new([0_f64, 3_f64]), Vector2F64::new([-3_f64, 0_f64])],
+        );
+        assert!(hermite.derivative_at(1_f64).distance(&Vector2F64::new([0_f64, 3_f64])) < 1e-12);
+        let curves = hermite.to_curves();
+        assert_eq!(curves.len(), 2);
+        assert!(curves[1].interpolate(0.4).distance(&hermite.interpolate(1.4)) < 1e-12);
+        assert!(matches!(curves[0], Curve::Cubic { p1, .. } if p1.distance(&Vector2F64::new([1_f64, 0_f64])) < 1e-12));
     }
 
     #[test]
@@ -216,6 +918,90 @@ mod tests {
         img.save("graph.png").unwrap();
     }
 
+    #[test]
+    fn test_bezier() {
+        let close = |a: Vector2F64, b: Vector2F64, tolerance: f64| a.distance(&b) < tolerance;
+
+        let linear = Curve::Linear { p0: Vector2F64::new([0_f64, 2_f64]), p1: Vector2F64::new([4_f64, 6_f64]) };
+        assert!(close(linear.interpolate(0.25), Vector2F64::new([1_f64, 3_f64]), 1e-12), "{:?}", linear.interpolate(0.25));
+
+        let cubic = Curve::Cubic {
+            p0: Vector2F64::new([0_f64, 0_f64]),
+            p1: Vector2F64::new([0_f64, 50_f64]),
+            p2: Vector2F64::new([99_f64, 50_f64]),
+            p3: Vector2F64::new([99_f64, 99_f64]),
+        };
+        let bezier = cubic.to_bezier();
+        for i in 1..10 {
+            let t = i as f64 / 10_f64;
+            assert!(close(bezier.interpolate(t), cubic.interpolate(t), 1e-9));
+
+            //The derivative, both as a curve and at a point, against a central difference.
+            let h = 1e-6;
+            let numeric = (bezier.interpolate(t + h) - bezier.interpolate(t - h)) / (2_f64 * h);
+            assert!(close(bezier.derivative().interpolate(t), numeric, 1e-5));
+            assert!(close(bezier.derivative_at(t), numeric, 1e-5));
+        }
+
+        //A degree 5 curve in 3D, raised twice and lowered back to itself.
+        let curve = Bezier::new(vec![
+            Vector3F64::new([0_f64, 0_f64, 0_f64]),
+            Vector3F64::new([1_f64, 3_f64, -1_f64]),
+            Vector3F64::new([2_f64, -2_f64, 4_f64]),
+            Vector3F64::new([4_f64, 5_f64, 1_f64]),
+            Vector3F64::new([5_f64, -1_f64, -3_f64]),
+            Vector3F64::new([7_f64, 2_f64, 2_f64]),
+        ]);
+        let elevated = curve.elevate().elevate();
+        assert_eq!(elevated.degree(), 7);
+        let reduced = elevated.reduce().unwrap().reduce().unwrap();
+        for (a, b) in curve.points().iter().zip(reduced.points()) {
+            assert!(a.distance(b) < 1e-9, "{:?} {:?}", a, b);
+        }
+        for i in 0..=20 {
+            let t = i as f64 / 20_f64;
+            assert!(curve.interpolate(t).distance(&elevated.interpolate(t)) < 1e-9);
+        }
+
+        //Both halves of a split trace the original.
+        let (before, after) = curve.split(0.3);
+        assert!(before.last().distance(&curve.interpolate(0.3)) < 1e-12);
+        for i in 0..=10 {
+            let s = i as f64 / 10_f64;
+            assert!(before.interpolate(s).distance(&curve.interpolate(0.3 * s)) < 1e-9);
+            assert!(after.interpolate(s).distance(&curve.interpolate(0.3 + 0.7 * s)) < 1e-9);
+        }
+        assert!(curve.segment(0.2, 0.6).interpolate(0.5).distance(&curve.interpolate(0.4)) < 1e-9);
+
+        //Bounds hug the curve, unlike the control points' box.
+        let (min, max) = curve.bounds();
+        let samples = curve.sample(100_001);
+        let (sampled_min, sampled_max) = samples.iter().fold((samples[0], samples[0]), |(min, max), p| (min.min(p), max.max(p)));
+        assert!(min.distance(&sampled_min) < 1e-6 && max.distance(&sampled_max) < 1e-6, "{:?} {:?} {:?} {:?}", min, max, sampled_min, sampled_max);
+        assert!(curve.hull_bounds().1.y() > max.y() + 1_f64);
+
+        //A rational quadratic with a middle weight of cos 45° is an exact quarter circle.
+        let arc = Bezier::rational(
+            vec![Vector2F64::new([1_f64, 0_f64]), Vector2F64::new([1_f64, 1_f64]), Vector2F64::new([0_f64, 1_f64])],
+            vec![1_f64, 0.5_f64.sqrt(), 1_f64],
+        );
+        let derivative = arc.derivative();
+        for i in 0..=20 {
+            let t = i as f64 / 20_f64;
+            let point = arc.interpolate(t);
+            assert!((point.magnitude() - 1_f64).abs() < 1e-12, "{}", point.magnitude());
+
+            //Moving along a circle, the derivative is perpendicular to the radius.
+            assert!(arc.derivative_at(t).dot(&point).abs() < 1e-9);
+            assert!(close(derivative.interpolate(t), arc.derivative_at(t), 1e-9));
+        }
+        let (first, second) = arc.split(0.5);
+        assert!(first.is_rational() && (first.interpolate(0.5).magnitude() - 1_f64).abs() < 1e-12);
+        assert!((second.elevate().interpolate(0.3).magnitude() - 1_f64).abs() < 1e-12);
+        let (min, max) = arc.bounds();
+        assert!(close(min, Vector2F64::new([0_f64, 0_f64]), 1e-9) && close(max, Vector2F64::new([1_f64, 1_f64]), 1e-9));
+    }
+
     #[test]
     fn test_world() {
         let mut world = World::default();
@@ -227,7 +1013,7 @@ mod tests {
             world.update();
         }
 
-        println!("{:?}", handle.rigidbody);
+        println!("{:?}", world.rigidbody(&handle).unwrap());
     }
 
     #[test]
@@ -314,19 +1100,13 @@ mod tests {
         }
 
         let mut sphere_collider = SphereCollider::new(5_f64);
-        sphere_collider.set_handle(RigidbodyHandle {
-            id: 0,
-            rigidbody: Default::default(),
-        });
+        sphere_collider.set_rigidbody(Rigidbody::default());
 
         let mut sphere_collider_2 = SphereCollider::new(5_f64);
-        sphere_collider_2.set_handle(RigidbodyHandle {
-            id: 0,
-            rigidbody: {
-                let r = Rigidbody::default();
-                r.set_position(vec3(7.0, 0.0, 0.0));
-                r
-            },
+        sphere_collider_2.set_rigidbody({
+            let r = Rigidbody::default();
+            r.set_position(vec3(7.0, 0.0, 0.0));
+            r
         });
 
         let col = sphere_collider.check_collision(&sphere_collider_2);
@@ -359,4 +1139,1072 @@ mod tests {
         r.mul_add_row(1, 0, 4_f32);
         println!("{}", r);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_sphere_contact_manifold() {
+        let a = Rigidbody::default();
+        let b = Rigidbody::default();
+        a.set_position(vec3(10.0, 0.0, 0.0));
+        b.set_position(vec3(13.0, 0.0, 0.0));
+
+        let mut sphere_a = SphereCollider::new(2_f64);
+        sphere_a.set_rigidbody(a);
+        let mut sphere_b = SphereCollider::new(2_f64);
+        sphere_b.set_rigidbody(b);
+
+        let manifold = sphere_a.contact_manifold(&sphere_b).unwrap();
+        assert_eq!(manifold.len(), 1);
+        assert!((manifold.normal.x() - 1.0).abs() < 1e-9);
+        assert!((manifold.penetration() - 1.0).abs() < 1e-9);
+        assert!((manifold.points[0].position.x() - 11.5).abs() < 1e-9);
+
+        let flipped = sphere_b.contact_manifold(&sphere_a).unwrap();
+        assert!((flipped.normal.x() + 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_box_contact_manifold() {
+        let ground = Rigidbody::default();
+        let crate_body = Rigidbody::default();
+        crate_body.set_position(vec3(0.0, 0.95, 0.0));
+        crate_body.set_rotation(Quaternion::from_axis_angle(Vector3F64::UP, Degrees(30.0)));
+
+        let mut ground_box = BoxCollider::new(vec3(10.0, 1.0, 10.0));
+        ground_box.set_rigidbody(ground);
+        let mut crate_box = BoxCollider::new(vec3(1.0, 1.0, 1.0));
+        crate_box.set_rigidbody(crate_body);
+
+        let manifold = ground_box.contact_manifold(&crate_box).unwrap();
+        assert_eq!(manifold.len(), 4);
+        assert!((manifold.normal.y() - 1.0).abs() < 1e-9);
+        for point in &manifold.points {
+            assert!((point.depth - 0.05).abs() < 1e-9);
+            assert!((point.position.y() - 0.475).abs() < 1e-9);
+        }
+
+        let mut sphere = SphereCollider::new(0.5);
+        sphere.set_rigidbody(Rigidbody::default());
+        sphere.rigidbody().unwrap().set_position(vec3(2.0, 0.9, 0.0));
+        let manifold = sphere.contact_manifold(&ground_box).unwrap();
+        assert!((manifold.normal.y() + 1.0).abs() < 1e-9);
+        assert!((manifold.penetration() - 0.1).abs() < 1e-9);
+
+        crate_box.rigidbody().unwrap().set_position(vec3(0.0, 1.5, 0.0));
+        assert!(ground_box.contact_manifold(&crate_box).is_none());
+    }
+
+    #[test]
+    fn test_world_box_stack() {
+        let mut world = World::default();
+
+        let ground = world.create_rigidbody();
+        world.rigidbody_mut(&ground).unwrap().set_flags(0);
+        world.attach_collider(&ground, BoxCollider::new(vec3(20.0, 1.0, 20.0)));
+
+        let boxes = (0..3).map(|i| {
+            let handle = world.create_rigidbody();
+            world.rigidbody_mut(&handle).unwrap().set_position(vec3(0.0, 1.0 + i as f64 * 1.01, 0.0));
+            world.attach_collider(&handle, BoxCollider::new(vec3(1.0, 1.0, 1.0)));
+            handle
+        }).collect::<Vec<RigidbodyHandle>>();
+
+        for _ in 0..240 {
+            world.step(1.0 / 60.0);
+        }
+
+        for (i, handle) in boxes.iter().enumerate() {
+            let position = world.rigidbody(&handle).unwrap().position();
+            assert!((position.y() - (1.0 + i as f64)).abs() < 0.05, "box {i} at {position}");
+            assert!(position.x().abs() < 0.05 && position.z().abs() < 0.05);
+            assert!(world.rigidbody(&handle).unwrap().velocity().magnitude() < 0.05);
+        }
+    }
+
+    #[test]
+    fn test_world_restitution_and_friction() {
+        let mut world = World::default();
+
+        let ground = world.create_rigidbody();
+        world.rigidbody_mut(&ground).unwrap().set_flags(0);
+        let ground_collider = world.attach_collider(&ground, BoxCollider::new(vec3(100.0, 1.0, 100.0)));
+        world.set_material(&ground_collider, PhysicsMaterial::new(0.5, 1.0));
+
+        let ball = world.create_rigidbody();
+        world.rigidbody_mut(&ball).unwrap().set_position(vec3(0.0, 5.5, 0.0));
+        world.attach_collider(&ball, SphereCollider::new(0.5));
+
+        let mut peak_after_bounce = 0_f64;
+        let mut bounced = false;
+        for _ in 0..300 {
+            world.step(1.0 / 120.0);
+            let rigidbody = world.rigidbody(&ball).unwrap();
+            bounced |= rigidbody.velocity().y() > 0.0;
+            if bounced {
+                peak_after_bounce = peak_after_bounce.max(rigidbody.position().y());
+            }
+        }
+        assert!(bounced);
+        assert!(peak_after_bounce > 4.5, "peak {peak_after_bounce}");
+
+        let slider = world.create_rigidbody();
+        world.rigidbody_mut(&slider).unwrap().set_position(vec3(20.0, 1.0, 0.0));
+        world.rigidbody_mut(&slider).unwrap().set_velocity(vec3(5.0, 0.0, 0.0));
+        world.attach_collider(&slider, BoxCollider::new(vec3(1.0, 1.0, 1.0)));
+
+        for _ in 0..180 {
+            world.step(1.0 / 60.0);
+        }
+        //μ = √(0.5 * 0.6), so the box stops after v² / 2μg ≈ 2.3 units.
+        let position = world.rigidbody(&slider).unwrap().position();
+        assert!(world.rigidbody(&slider).unwrap().velocity().magnitude() < 0.05);
+        assert!(position.x() > 21.5 && position.x() < 23.5, "slid to {position}");
+    }
+
+    #[test]
+    fn test_broad_phase_agreement() {
+        let mut seed = 12345_u64;
+        let mut random = move || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 11) as f64 / (1_u64 << 53) as f64
+        };
+
+        let boxes = (0..300).map(|_| Bounds {
+            center: vec3(random() * 20.0, random() * 20.0, random() * 20.0),
+            extents: vec3(random() + 0.1, random() + 0.1, random() + 0.1),
+        }).collect::<Vec<Bounds>>();
+
+        let types = [BroadPhaseType::BruteForce, BroadPhaseType::SweepAndPrune, BroadPhaseType::DynamicTree];
+        let mut broad_phases = types.map(|t| t.create());
+        for broad_phase in &mut broad_phases {
+            for (id, bounds) in boxes.iter().enumerate() {
+                broad_phase.insert(id as u32, *bounds);
+            }
+        }
+
+        for frame in 0..3 {
+            let query = Bounds { center: vec3(10.0, 10.0, 10.0), extents: vec3(3.0, 3.0, 3.0) };
+            let expected = broad_phases[0].overlapping_pairs();
+            let expected_query = broad_phases[0].query(&query);
+            assert!(!expected.is_empty() && !expected_query.is_empty());
+            for broad_phase in &mut broad_phases[1..] {
+                assert_eq!(broad_phase.overlapping_pairs(), expected);
+                assert_eq!(broad_phase.query(&query), expected_query);
+            }
+
+            for broad_phase in &mut broad_phases {
+                broad_phase.remove(frame);
+                for (id, bounds) in boxes.iter().enumerate().skip(frame as usize + 1) {
+                    let mut moved = *bounds;
+                    moved.center += vec3(1.0, 0.0, -1.0) * (frame as f64 + 1.0) * (id % 3) as f64;
+                    broad_phase.update(id as u32, moved);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_gjk_epa() {
+        let mut corners = vec![];
+        for i in 0..8 {
+            corners.push(vec3(
+                if i & 1 == 0 { -1.0 } else { 1.0 },
+                if i & 2 == 0 { -1.0 } else { 1.0 },
+                if i & 4 == 0 { -1.0 } else { 1.0 },
+            ));
+        }
+        //Points inside and on the faces don't end up in the hull.
+        corners.push(vec3(0.0, 0.0, 0.0));
+        corners.push(vec3(0.5, 1.0, 0.0));
+        let hull = ConvexHull::new(&corners).unwrap();
+        assert_eq!(hull.vertices().len(), 8);
+        assert_eq!(hull.faces().len(), 12);
+        assert!((hull.volume() - 8.0).abs() < 1e-9);
+        assert!((hull.inertia(12.0) - Collider::inertia(&BoxCollider::new(vec3(2.0, 2.0, 2.0)), 12.0)).magnitude() < 1e-9);
+
+        let sphere = SphereCollider::new(0.5);
+        let at = |x: f64, y: f64| Pose::from_position(vec3(x, y, 0.0));
+        assert!((gjk::distance(&hull, &at(0.0, 0.0), &hull, &at(3.0, 0.0)) - 1.0).abs() < 1e-9);
+        assert!((gjk::distance(&hull, &at(0.0, 0.0), &sphere, &at(0.0, 2.0)) - 0.5).abs() < 1e-9);
+        assert!(!gjk::intersects(&hull, &at(0.0, 0.0), &hull, &at(2.5, 2.5)));
+
+        let manifold = gjk::contact(&hull, &at(0.0, 0.0), &hull, &at(1.5, 0.2)).unwrap();
+        assert!((manifold.normal - vec3(1.0, 0.0, 0.0)).magnitude() < 1e-6);
+        assert!((manifold.penetration() - 0.5).abs() < 1e-6);
+        assert_eq!(manifold.len(), 4);
+
+        let manifold = gjk::contact(&sphere, &at(0.0, 1.3), &hull, &at(0.0, 0.0)).unwrap();
+        assert!((manifold.normal - vec3(0.0, -1.0, 0.0)).magnitude() < 1e-6);
+        assert!((manifold.penetration() - 0.2).abs() < 1e-6);
+
+        //Cores that just touch still overlap by the margins.
+        let manifold = gjk::contact(&sphere, &at(0.0, 1.0), &hull, &at(0.0, 0.0)).unwrap();
+        assert!((manifold.normal - vec3(0.0, -1.0, 0.0)).magnitude() < 1e-6);
+        assert!((manifold.penetration() - 0.5).abs() < 1e-6);
+        let capsule = CapsuleCollider::new(0.25, 2.0);
+        let lying = Pose::new(vec3(0.0, 0.75, 0.0), Quaternion::from_axis_angle(Vector3F64::FORWARD, Degrees(90.0)));
+        let manifold = gjk::contact(&capsule, &at(0.0, 0.0), &capsule, &lying).unwrap();
+        assert!((manifold.penetration() - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_world_convex_hull() {
+        let mut world = World::default();
+
+        let ground = world.create_rigidbody();
+        world.rigidbody_mut(&ground).unwrap().set_flags(0);
+        world.attach_collider(&ground, BoxCollider::new(vec3(20.0, 1.0, 20.0)));
+
+        //A square pyramid, base down.
+        let geometry = Geometry::<3, f32> {
+            vertices: vec![
+                vec3(-0.5, -0.25, -0.5), vec3(0.5, -0.25, -0.5), vec3(0.5, -0.25, 0.5), vec3(-0.5, -0.25, 0.5),
+                vec3(0.0, 0.75, 0.0),
+            ],
+            normals: None,
+            uv: vec![],
+            tangents: None,
+            indices: vec![],
+        };
+        let hull = ConvexHull::from_geometry(&geometry).unwrap();
+        assert!((hull.centroid() - vec3(0.0, 0.0, 0.0)).magnitude() < 1e-6);
+
+        let pyramid = world.create_rigidbody();
+        world.rigidbody_mut(&pyramid).unwrap().set_position(vec3(0.0, 1.5, 0.0));
+        world.attach_collider(&pyramid, ConvexHullCollider::new(hull));
+
+        let ball = world.create_rigidbody();
+        world.rigidbody_mut(&ball).unwrap().set_position(vec3(0.05, 3.0, 0.0));
+        world.attach_collider(&ball, SphereCollider::new(0.25));
+
+        for _ in 0..300 {
+            world.step(1.0 / 60.0);
+        }
+
+        let position = world.rigidbody(&pyramid).unwrap().position();
+        assert!((position.y() - 0.75).abs() < 0.05, "pyramid at {position}");
+        assert!(world.rigidbody(&pyramid).unwrap().velocity().magnitude() < 0.05);
+        assert!((world.rigidbody(&pyramid).unwrap().rotation() * Vector3F64::UP - Vector3F64::UP).magnitude() < 0.05);
+        //The ball rolls off the pyramid's slope and comes to rest beside it.
+        assert!(world.rigidbody(&ball).unwrap().position().y() < 1.0);
+    }
+
+    #[test]
+    fn test_world_queries() {
+        let mut world = World::default();
+
+        let ground = world.create_rigidbody();
+        world.rigidbody_mut(&ground).unwrap().set_flags(0);
+        let ground_collider = world.attach_collider(&ground, BoxCollider::new(vec3(20.0, 1.0, 20.0)));
+
+        let ball = world.create_rigidbody();
+        world.rigidbody_mut(&ball).unwrap().set_position(vec3(0.0, 2.0, 0.0));
+        let ball_collider = world.attach_collider(&ball, SphereCollider::new(0.5));
+        world.set_layers(&ball_collider, 2);
+
+        let cube = world.create_rigidbody();
+        world.rigidbody_mut(&cube).unwrap().set_position(vec3(3.0, 1.0, 0.0));
+        let corners = (0..8).map(|i| vec3(
+            if i & 1 == 0 { -0.5 } else { 0.5 },
+            if i & 2 == 0 { -0.5 } else { 0.5 },
+            if i & 4 == 0 { -0.5 } else { 0.5 },
+        )).collect::<Vec<Vector3F64>>();
+        let cube_collider = world.attach_collider(&cube, ConvexHullCollider::new(ConvexHull::new(&corners).unwrap()));
+
+        let down = vec3(0.0, -1.0, 0.0);
+        let hit = world.raycast(vec3(0.0, 10.0, 0.0), down, f64::INFINITY, ALL_LAYERS).unwrap();
+        assert_eq!(hit.collider, ball_collider);
+        assert!((hit.distance - 7.5).abs() < 1e-9);
+        assert!((hit.normal - Vector3F64::UP).magnitude() < 1e-9);
+
+        //Layer 2 is filtered out, so the ray goes on to the ground.
+        let hit = world.raycast(vec3(0.0, 10.0, 0.0), down, 100.0, DEFAULT_LAYER).unwrap();
+        assert_eq!(hit.collider, ground_collider);
+        assert!((hit.point - vec3(0.0, 0.5, 0.0)).magnitude() < 1e-9);
+        assert!(world.raycast(vec3(0.0, 10.0, 0.0), down, 5.0, ALL_LAYERS).is_none());
+
+        let hits = world.raycast_all(vec3(0.0, 10.0, 0.0), down, 100.0, ALL_LAYERS);
+        assert_eq!(hits.iter().map(|hit| hit.collider).collect::<Vec<ColliderHandle>>(), vec![ball_collider, ground_collider]);
+
+        let hit = world.raycast(vec3(10.0, 1.0, 0.0), vec3(-1.0, 0.0, 0.0), 100.0, ALL_LAYERS).unwrap();
+        assert_eq!(hit.collider, cube_collider);
+        assert!((hit.distance - 6.5).abs() < 1e-6);
+        assert!((hit.normal - vec3(1.0, 0.0, 0.0)).magnitude() < 1e-6);
+
+        let hit = world.sphere_cast(vec3(0.0, 10.0, 0.0), 0.5, down, 100.0, ALL_LAYERS).unwrap();
+        assert_eq!(hit.collider, ball_collider);
+        assert!((hit.distance - 7.0).abs() < 1e-6);
+        assert!((hit.point - vec3(0.0, 2.5, 0.0)).magnitude() < 1e-4);
+
+        let hit = world.box_cast(vec3(3.0, 10.0, 0.0), vec3(0.5, 0.5, 0.5), Quaternion::identity(), down, 100.0, ALL_LAYERS).unwrap();
+        assert_eq!(hit.collider, cube_collider);
+        assert!((hit.distance - 8.0).abs() < 1e-6);
+
+        assert_eq!(world.overlap_sphere(vec3(0.0, 1.2, 0.0), 0.5, ALL_LAYERS), vec![ball_collider]);
+        assert_eq!(world.overlap_box(vec3(3.0, 1.0, 0.0), vec3(0.6, 0.6, 0.6), Quaternion::identity(), ALL_LAYERS), vec![ground_collider, cube_collider]);
+        assert!(world.overlap_sphere(vec3(0.0, 1.2, 0.0), 0.5, DEFAULT_LAYER).is_empty());
+    }
+
+    #[test]
+    fn test_world_joints() {
+        let mut world = World::default();
+        let body = |world: &mut World, position: Vector3F64, flags: u8| {
+            let handle = world.create_rigidbody();
+            world.rigidbody_mut(&handle).unwrap().set_position(position);
+            world.rigidbody_mut(&handle).unwrap().set_flags(flags);
+            world.attach_collider(&handle, BoxCollider::new(vec3(0.2, 0.2, 0.2)));
+            handle
+        };
+
+        //Pendulum on a rod attached to the world.
+        let pendulum = body(&mut world, vec3(2.0, 5.0, 0.0), RigidbodyFlags::DEFAULT);
+        world.add_joint(Joint::distance(vec3(2.0, 5.0, 0.0), vec3(0.0, 5.0, 0.0)), &pendulum, None);
+
+        //Motorised hinge limited to a quarter turn, without gravity.
+        let door = body(&mut world, vec3(10.0, 0.0, 0.0), RigidbodyFlags::UseKinematics as u8);
+        let hinge = world.add_joint(
+            Joint::hinge(vec3(10.0, 0.0, 0.0), Vector3F64::UP).with_limits(0.0, std::f64::consts::FRAC_PI_2).with_motor(1.0, 100.0),
+            &door,
+            None,
+        );
+
+        //A slider that lets its body drop by 1 along y at most.
+        let slider = body(&mut world, vec3(20.0, 0.0, 0.0), RigidbodyFlags::DEFAULT);
+        world.add_joint(Joint::slider(vec3(20.0, 0.0, 0.0), Vector3F64::UP).with_limits(-1.0, 0.0), &slider, None);
+
+        //Two bodies welded together, hanging from a ball and socket joint.
+        let top = body(&mut world, vec3(30.0, 0.0, 0.0), RigidbodyFlags::DEFAULT);
+        let bottom = body(&mut world, vec3(30.0, -1.0, 0.0), RigidbodyFlags::DEFAULT);
+        world.add_joint(Joint::ball_socket(vec3(30.0, 0.0, 0.0)), &top, None);
+        world.add_joint(Joint::fixed(vec3(30.0, -0.5, 0.0)), &top, Some(&bottom));
+
+        //A weight on a damped spring.
+        let weight = body(&mut world, vec3(40.0, -1.0, 0.0), RigidbodyFlags::DEFAULT);
+        world.add_joint(Joint::spring(vec3(40.0, -1.0, 0.0), vec3(40.0, 0.0, 0.0), 100.0, 5.0), &weight, None);
+
+        let mut lowest = f64::MAX;
+        for _ in 0..300 {
+            world.step(1.0 / 60.0);
+            let position = world.rigidbody(&pendulum).unwrap().position();
+            assert!((position.distance(&vec3(0.0, 5.0, 0.0)) - 2.0).abs() < 0.05, "pendulum at {position}");
+            lowest = lowest.min(position.y());
+        }
+        assert!(lowest < 3.1);
+
+        //The motor turned the door until the limit stopped it.
+        let forward = world.rigidbody(&door).unwrap().rotation() * Vector3F64::RIGHT;
+        assert!((forward - vec3(0.0, 0.0, -1.0)).magnitude() < 0.05, "door facing {forward}");
+        assert!((world.rigidbody(&door).unwrap().position() - vec3(10.0, 0.0, 0.0)).magnitude() < 0.01);
+
+        assert!((world.rigidbody(&slider).unwrap().position() - vec3(20.0, -1.0, 0.0)).magnitude() < 0.02);
+        assert!((world.rigidbody(&slider).unwrap().rotation() * Vector3F64::RIGHT - Vector3F64::RIGHT).magnitude() < 0.01);
+
+        assert!((world.rigidbody(&top).unwrap().position() - vec3(30.0, 0.0, 0.0)).magnitude() < 0.02);
+        assert!((world.rigidbody(&bottom).unwrap().position() - vec3(30.0, -1.0, 0.0)).magnitude() < 0.02);
+
+        let stretch = 9.81 / 100.0;
+        assert!((world.rigidbody(&weight).unwrap().position().y() - (-1.0 - stretch)).abs() < 0.01);
+
+        //Turning the motor around swings the door back.
+        if let Some(joint) = world.joint_mut(&hinge) {
+            *joint = joint.with_motor(-1.0, 100.0);
+        }
+        for _ in 0..150 {
+            world.step(1.0 / 60.0);
+        }
+        assert!((world.rigidbody(&door).unwrap().rotation() * Vector3F64::RIGHT - Vector3F64::RIGHT).magnitude() < 0.05);
+    }
+
+    #[test]
+    #[should_panic(expected = "joining a removed rigidbody")]
+    fn test_joint_on_removed_rigidbody() {
+        let mut world = World::default();
+        let a = world.create_rigidbody();
+        let removed = world.create_rigidbody();
+        world.remove_rigidbody(&removed);
+        //Takes the removed body's slot, which the stale handle mustn't reach.
+        world.create_rigidbody();
+        world.add_joint(Joint::ball_socket(Vector3F64::ZERO), &a, Some(&removed));
+    }
+
+    #[test]
+    fn test_world_events() {
+        let mut world = World::default();
+
+        let ground = world.create_rigidbody();
+        world.rigidbody_mut(&ground).unwrap().set_flags(0);
+        let ground_collider = world.attach_collider(&ground, BoxCollider::new(vec3(20.0, 1.0, 20.0)));
+        world.set_collision_mask(&ground_collider, DEFAULT_LAYER);
+
+        let zone = world.create_rigidbody();
+        world.rigidbody_mut(&zone).unwrap().set_flags(0);
+        world.rigidbody_mut(&zone).unwrap().set_position(vec3(0.0, 3.0, 0.0));
+        let zone_collider = world.attach_collider(&zone, BoxCollider::new(vec3(1.0, 0.5, 1.0)));
+        world.set_trigger(&zone_collider, true);
+
+        let ball = world.create_rigidbody();
+        world.rigidbody_mut(&ball).unwrap().set_position(vec3(0.0, 5.0, 0.0));
+        let ball_collider = world.attach_collider(&ball, SphereCollider::new(0.25));
+
+        //On a layer the ground doesn't collide with.
+        let ghost = world.create_rigidbody();
+        world.rigidbody_mut(&ghost).unwrap().set_position(vec3(5.0, 2.0, 0.0));
+        let ghost_collider = world.attach_collider(&ghost, SphereCollider::new(0.25));
+        world.set_layers(&ghost_collider, 2);
+
+        let mut events = vec![];
+        for _ in 0..120 {
+            world.step(1.0 / 60.0);
+            events.extend(world.drain_events().into_iter().map(|event| match event {
+                CollisionEvent::CollisionEnter { a, b, .. } => ("enter", a, b),
+                CollisionEvent::CollisionStay { a, b, .. } => ("stay", a, b),
+                CollisionEvent::CollisionExit { a, b } => ("exit", a, b),
+                CollisionEvent::TriggerEnter { trigger, other } => ("trigger enter", trigger, other),
+                CollisionEvent::TriggerExit { trigger, other } => ("trigger exit", trigger, other),
+            }));
+        }
+
+        let kinds = events.iter().map(|(kind, _, _)| *kind).collect::<Vec<&str>>();
+        let first = |kind: &str| kinds.iter().position(|k| *k == kind).unwrap();
+        assert_eq!(kinds.iter().filter(|k| **k == "trigger enter").count(), 1);
+        assert_eq!(kinds.iter().filter(|k| **k == "trigger exit").count(), 1);
+        assert_eq!(kinds.iter().filter(|k| **k == "enter").count(), 1);
+        assert!(first("trigger enter") < first("trigger exit") && first("trigger exit") < first("enter"));
+        assert_eq!(events[first("trigger enter")], ("trigger enter", zone_collider, ball_collider));
+        assert_eq!(events[first("enter")], ("enter", ground_collider, ball_collider));
+        assert_eq!(kinds.last(), Some(&"stay"));
+
+        //The trigger let the ball through, the ground didn't, and the ghost fell through the ground.
+        assert!((world.rigidbody(&ball).unwrap().position().y() - 0.75).abs() < 0.05);
+        assert!(world.rigidbody(&ghost).unwrap().position().y() < -5.0);
+    }
+
+    #[test]
+    fn test_world_colliders_follow_rigidbodies() {
+        let mut world = World::default();
+        world.set_gravity(Vector3F64::ZERO);
+
+        let ground = world.create_rigidbody();
+        world.rigidbody_mut(&ground).unwrap().set_flags(0);
+        let ground_collider = world.attach_collider(&ground, BoxCollider::new(vec3(4.0, 1.0, 4.0)));
+
+        let ball = world.create_rigidbody();
+        world.rigidbody_mut(&ball).unwrap().set_position(vec3(0.0, 0.7, 0.0));
+        let ball_collider = world.attach_collider(&ball, SphereCollider::new(0.25));
+
+        //Fetched back from the world, the colliders are where their rigidbodies are and still touch.
+        let contact = |world: &World| world.collider(&ball_collider).unwrap().contact_manifold(&world.collider(&ground_collider).unwrap());
+        assert!(contact(&world).is_some());
+        assert_eq!(world.collider(&ball_collider).unwrap().pose().position, vec3(0.0, 0.7, 0.0));
+        assert!(world.collider(&ball_collider).unwrap().collider().downcast_ref::<SphereCollider>().is_some());
+
+        //They keep following their rigidbodies, moved by steps or by hand.
+        world.rigidbody_mut(&ball).unwrap().set_velocity(vec3(0.0, 6.0, 0.0));
+        world.step(1.0 / 60.0);
+        assert!(contact(&world).is_none());
+        assert!((world.collider(&ball_collider).unwrap().pose().position - vec3(0.0, 0.8, 0.0)).magnitude() < 1e-9);
+
+        world.rigidbody_mut(&ground).unwrap().set_position(vec3(0.0, 1.2, 0.0));
+        assert_eq!(world.collider(&ground_collider).unwrap().bounds().center, vec3(0.0, 1.2, 0.0));
+        assert!(contact(&world).is_some());
+    }
+
+    #[test]
+    fn test_kinematic_body_through_trigger() {
+        let mut world = World::default();
+
+        let zone = world.create_rigidbody();
+        world.rigidbody_mut(&zone).unwrap().set_flags(0);
+        let zone_collider = world.attach_collider(&zone, BoxCollider::new(vec3(1.0, 1.0, 1.0)));
+        world.set_trigger(&zone_collider, true);
+
+        //Neither body can be pushed, so only the trigger makes them worth checking.
+        let platform = world.create_rigidbody();
+        world.rigidbody_mut(&platform).unwrap().set_flags(0);
+        let platform_collider = world.attach_collider(&platform, BoxCollider::new(vec3(0.5, 0.5, 0.5)));
+
+        let mut events = vec![];
+        for i in 0..=60 {
+            world.rigidbody_mut(&platform).unwrap().set_position(vec3(-3.0 + 0.1 * i as f64, 0.0, 0.0));
+            world.step(1.0 / 60.0);
+            events.extend(world.drain_events().into_iter().map(|event| (i, event)));
+        }
+
+        assert_eq!(events.len(), 2, "{:?}", events);
+        assert!(matches!(events[0], (22..=24, CollisionEvent::TriggerEnter { trigger, other }) if trigger == zone_collider && other == platform_collider));
+        assert!(matches!(events[1], (37..=39, CollisionEvent::TriggerExit { trigger, other }) if trigger == zone_collider && other == platform_collider));
+    }
+
+    #[test]
+    fn test_world_sleeping_and_removal() {
+        let mut world = World::default();
+
+        let ground = world.create_rigidbody();
+        world.rigidbody_mut(&ground).unwrap().set_flags(0);
+        world.attach_collider(&ground, BoxCollider::new(vec3(20.0, 1.0, 20.0)));
+
+        let stack = (0..3).map(|i| {
+            let handle = world.create_rigidbody();
+            world.rigidbody_mut(&handle).unwrap().set_position(vec3(0.0, 1.0 + i as f64, 0.0));
+            world.attach_collider(&handle, BoxCollider::new(vec3(1.0, 1.0, 1.0)));
+            handle
+        }).collect::<Vec<RigidbodyHandle>>();
+
+        let loner = world.create_rigidbody();
+        world.rigidbody_mut(&loner).unwrap().set_position(vec3(5.0, 1.0, 0.0));
+        world.attach_collider(&loner, BoxCollider::new(vec3(1.0, 1.0, 1.0)));
+
+        world.step(1.0 / 60.0);
+        let islands = world.islands().iter().map(|island| island.iter().map(|h| h.id()).collect()).collect::<Vec<Vec<u32>>>();
+        assert_eq!(islands, vec![vec![stack[0].id(), stack[1].id(), stack[2].id()], vec![loner.id()]]);
+
+        for _ in 0..120 {
+            world.step(1.0 / 60.0);
+        }
+        assert!(stack.iter().chain([&loner]).all(|handle| world.is_sleeping(handle)));
+        assert!(!world.is_sleeping(&ground));
+
+        //Giving a sleeping body a velocity wakes it, but not the unconnected stack.
+        world.rigidbody_mut(&loner).unwrap().set_velocity(vec3(0.0, 3.0, 0.0));
+        world.step(1.0 / 60.0);
+        assert!(!world.is_sleeping(&loner));
+        assert!(world.is_sleeping(&stack[2]));
+
+        //Pulling out the bottom box wakes the rest of the stack, which falls into its place.
+        assert!(world.remove_rigidbody(&stack[0]));
+        assert!(!world.contains(&stack[0]));
+        assert!(!world.remove_rigidbody(&stack[0]));
+        assert!(!world.is_sleeping(&stack[1]) && !world.is_sleeping(&stack[2]));
+        for _ in 0..180 {
+            world.step(1.0 / 60.0);
+        }
+        assert!((world.rigidbody(&stack[1]).unwrap().position().y() - 1.0).abs() < 0.05);
+        assert!((world.rigidbody(&stack[2]).unwrap().position().y() - 2.0).abs() < 0.05);
+
+        //The removed id is reused with a new generation, so the old handle stays stale.
+        let reused = world.create_rigidbody();
+        assert_eq!(reused.id(), stack[0].id());
+        assert_eq!(reused.generation(), stack[0].generation() + 1);
+        assert!(world.contains(&reused) && !world.contains(&stack[0]));
+    }
+
+    #[test]
+    fn test_body_store_parallel_integration() {
+        //Enough bodies for every thread to get a chunk, spinning and falling without colliders.
+        let build = |threads: usize| {
+            let mut world = World::default();
+            world.solver_settings_mut().threads = threads;
+            for i in 0..5000 {
+                let handle = world.create_rigidbody();
+                let mut rigidbody = world.rigidbody_mut(&handle).unwrap();
+                rigidbody.set_position(vec3(i as f64, 0.0, 0.0));
+                rigidbody.set_angular_velocity(vec3(0.0, (i % 7) as f64, 0.0));
+                if i % 3 == 0 {
+                    rigidbody.set_flags(RigidbodyFlags::UseKinematics as u8);
+                }
+            }
+            world
+        };
+        let (mut serial, mut parallel) = (build(1), build(4));
+        for _ in 0..30 {
+            serial.step(1.0 / 60.0);
+            parallel.step(1.0 / 60.0);
+        }
+
+        assert_eq!(serial.bodies().positions(), parallel.bodies().positions());
+        let (a, b) = (serial.bodies().rotations(), parallel.bodies().rotations());
+        assert!(a.iter().zip(b).all(|(a, b)| a.xyz() == b.xyz() && a.w() == b.w()));
+        //Half a second of free fall, apart from the bodies not using gravity.
+        let positions = parallel.bodies().positions();
+        assert!((positions[1].y() + 0.5 * 9.81 * 0.25).abs() < 0.1);
+        assert_eq!(positions[3].y(), 0.0);
+    }
+
+    #[test]
+    fn test_world_snapshot_and_replay() {
+        let mut world = World::default();
+        let ground = world.create_rigidbody();
+        world.rigidbody_mut(&ground).unwrap().set_flags(0);
+        world.attach_collider(&ground, BoxCollider::new(vec3(20.0, 1.0, 20.0)));
+
+        let bodies = (0..4).map(|i| {
+            let handle = world.create_rigidbody();
+            world.rigidbody_mut(&handle).unwrap().set_position(vec3(i as f64 * 0.3, 1.5 + i as f64 * 1.2, 0.0));
+            handle
+        }).collect::<Vec<RigidbodyHandle>>();
+        world.attach_collider(&bodies[0], BoxCollider::new(vec3(1.0, 1.0, 1.0)));
+        world.attach_collider(&bodies[1], SphereCollider::new(0.5));
+        let hull = ConvexHull::new(&[vec3(-0.5, 0.0, -0.5), vec3(0.5, 0.0, -0.5), vec3(0.0, 0.0, 0.5), vec3(0.0, 0.8, 0.0)]).unwrap();
+        world.attach_collider(&bodies[2], ConvexHullCollider::new(hull));
+        world.attach_collider(&bodies[3], SphereCollider::new(0.4));
+        world.add_joint(Joint::distance(vec3(0.3, 3.9, 0.0), vec3(0.9, 5.1, 0.0)), &bodies[2], Some(&bodies[3]));
+        for _ in 0..30 {
+            world.step(1.0 / 60.0);
+        }
+
+        //A restored world, even from the other byte order, carries on exactly like the original.
+        let mut restored = World::restore(&world.snapshot(Endian::Big).unwrap()).unwrap();
+        assert_eq!(restored.steps(), 30);
+        assert_eq!(world.snapshot(Endian::Little).unwrap(), restored.snapshot(Endian::Little).unwrap());
+        for _ in 0..60 {
+            world.step(1.0 / 60.0);
+            restored.step(1.0 / 60.0);
+        }
+        assert_eq!(world.bodies().positions(), restored.bodies().positions());
+        assert_eq!(world.bodies().velocities(), restored.bodies().velocities());
+
+        //Replaying the recorded inputs ends in the same state as the recorded run.
+        let mut recorder = Recorder::start(&world).unwrap();
+        recorder.apply(&mut world, ReplayInput::SetVelocity(bodies[1], vec3(3.0, 4.0, 0.0)));
+        for step in 0..90 {
+            if step == 45 {
+                recorder.apply(&mut world, ReplayInput::SetGravity(vec3(0.0, -3.0, 0.0)));
+            }
+            recorder.step(&mut world, 1.0 / 60.0);
+        }
+        let bytes = recorder.finish().to_bytes(Endian::Little);
+        let replay = Replay::from_bytes(&bytes).unwrap();
+        assert_eq!(replay.steps(), 90);
+        let replayed = replay.play().unwrap();
+        assert_eq!(replayed.steps(), world.steps());
+        assert_eq!(world.bodies().positions(), replayed.bodies().positions());
+        assert_eq!(replay.play_steps(45).unwrap().steps(), world.steps() - 45);
+
+        //Driving a vehicle is recorded along with everything else.
+        let mut driving = World::default();
+        let floor = driving.create_rigidbody();
+        driving.rigidbody_mut(&floor).unwrap().set_flags(0);
+        driving.attach_collider(&floor, BoxCollider::new(vec3(200.0, 1.0, 200.0)));
+        driving.rigidbody_mut(&floor).unwrap().set_position(vec3(0.0, -0.5, 0.0));
+        let chassis = driving.create_rigidbody();
+        driving.rigidbody_mut(&chassis).unwrap().set_mass(1000.0);
+        driving.rigidbody_mut(&chassis).unwrap().set_position(vec3(0.0, 0.8, 0.0));
+        driving.attach_collider(&chassis, BoxCollider::new(vec3(1.8, 0.5, 4.0)));
+        let mut vehicle = Vehicle::new(chassis);
+        for (x, z) in [(-0.8, 1.4), (0.8, 1.4), (-0.8, -1.4), (0.8, -1.4)] {
+            let wheel = WheelSettings::new(vec3(x, -0.1, z), 0.35);
+            vehicle = vehicle.with_wheel(if z > 0.0 { wheel.steered() } else { wheel.driven() });
+        }
+        let car = driving.add_vehicle(vehicle);
+        let mut recorder = Recorder::start(&driving).unwrap();
+        for step in 0..150 {
+            match step {
+                30 => recorder.apply(&mut driving, ReplayInput::SetThrottle(car, 1.0)),
+                80 => recorder.apply(&mut driving, ReplayInput::SetSteering(car, 0.5)),
+                120 => {
+                    recorder.apply(&mut driving, ReplayInput::SetThrottle(car, 0.0));
+                    recorder.apply(&mut driving, ReplayInput::SetBrake(car, 1.0));
+                }
+                _ => {}
+            }
+            recorder.step(&mut driving, 1.0 / 60.0);
+        }
+        let replay = Replay::from_bytes(&recorder.finish().to_bytes(Endian::Big)).unwrap();
+        let replayed = replay.play().unwrap();
+        assert_eq!(driving.bodies().positions(), replayed.bodies().positions());
+        let (driven, played) = (driving.vehicle(&car).unwrap(), replayed.vehicle(&car).unwrap());
+        assert_eq!((played.throttle(), played.steering(), played.brake()), (0.0, 0.5, 1.0));
+        assert_eq!((driven.throttle(), driven.steering(), driven.brake()), (played.throttle(), played.steering(), played.brake()));
+        let moved = driving.rigidbody(&chassis).unwrap().position();
+        assert!(moved.z().abs() > 1.0 && moved.x().abs() > 0.1, "{:?}", moved);
+
+        assert_eq!(World::restore(b"nope").err(), Some(SnapshotError::NotASnapshot));
+        let snapshot = world.snapshot(Endian::Little).unwrap();
+        assert!(matches!(World::restore(&snapshot[..snapshot.len() - 3]), Err(SnapshotError::UnexpectedEnd(_))));
+    }
+
+    #[test]
+    fn test_particles_and_force_fields() {
+        //A steady rate with a fixed lifetime settles at rate × lifetime particles.
+        let mut fountain = ParticleSystem::new(Emitter::new(Vector3F64::ZERO, 100.0).with_lifetime(0.5, 0.5), 7)
+            .with_field(Gravity::from_world(&World::default()));
+        for _ in 0..120 {
+            fountain.step(1.0 / 60.0);
+        }
+        assert!((fountain.len() as i64 - 50).abs() <= 2, "{} alive", fountain.len());
+        assert!(fountain.ages().iter().all(|age| *age < 0.5));
+
+        //Cone velocities stay within the cone.
+        let cone = VelocityDistribution::Cone { direction: Vector3F64::UP, angle: 0.3, speed: (2.0, 3.0) };
+        let mut sprinkler = ParticleSystem::new(Emitter::new(Vector3F64::ZERO, 0.0).with_velocity(cone).with_max_particles(200), 3);
+        sprinkler.burst(500);
+        assert_eq!(sprinkler.len(), 200);
+        for velocity in sprinkler.velocities() {
+            let speed = velocity.magnitude();
+            assert!((2.0..=3.0).contains(&speed));
+            assert!(velocity.y() / speed >= 0.3_f64.cos() - 1e-9);
+        }
+
+        //A single particle under each field on its own.
+        let single = |position: Vector3F64, velocity: Vector3F64, field: Box<dyn Fn(ParticleSystem) -> ParticleSystem>| {
+            let emitter = Emitter::new(position, 0.0).with_lifetime(100.0, 100.0).with_velocity(VelocityDistribution::Constant(velocity));
+            let mut system = field(ParticleSystem::new(emitter, 1));
+            system.burst(1);
+            for _ in 0..60 {
+                system.step(1.0 / 60.0);
+            }
+            (system.positions()[0], system.velocities()[0])
+        };
+
+        let (position, _) = single(Vector3F64::ZERO, vec3(1.0, 0.0, 0.0), Box::new(|s| s.with_field(Gravity::new(vec3(0.0, -9.81, 0.0)))));
+        assert!((position.x() - 1.0).abs() < 1e-9);
+        assert!((position.y() + 0.5 * 9.81).abs() < 0.1);
+
+        let (_, velocity) = single(Vector3F64::ZERO, vec3(4.0, 0.0, 0.0), Box::new(|s| s.with_field(Drag::new(1.0))));
+        assert!((velocity.x() - 4.0 * (-1.0_f64).exp()).abs() < 0.05);
+
+        let (position, _) = single(vec3(5.0, 0.0, 0.0), Vector3F64::ZERO, Box::new(|s| s.with_field(PointAttractor::new(Vector3F64::ZERO, 50.0))));
+        assert!(position.x() < 4.5 && position.x() > 0.0);
+
+        let (_, velocity) = single(vec3(1.0, 0.0, 0.0), Vector3F64::ZERO, Box::new(|s| s.with_field(Vortex::new(Vector3F64::ZERO, Vector3F64::UP, 2.0, 5.0))));
+        assert!(velocity.z() < -0.5 && velocity.y() == 0.0);
+
+        //Turbulence is bounded and the same for the same seed.
+        let turbulence = Turbulence::new(3.0, 0.7, 11);
+        let samples = (0..50).map(|i| turbulence.acceleration(vec3(i as f64 * 0.37, 1.3, -0.2 * i as f64), Vector3F64::ZERO, 0.5)).collect::<Vec<Vector3F64>>();
+        assert!(samples.iter().all(|a| a.magnitude() <= 3.0 * 3_f64.sqrt()));
+        assert!(samples.windows(2).any(|w| w[0] != w[1]));
+        assert_eq!(samples[3], Turbulence::new(3.0, 0.7, 11).acceleration(vec3(3.0 * 0.37, 1.3, -0.6), Vector3F64::ZERO, 0.5));
+    }
+
+    #[test]
+    fn test_soft_bodies() {
+        //A square of cloth in the xz plane, n by n vertices a tenth apart.
+        let cloth = |n: u32, height: f64| {
+            let mut geometry = Geometry::<3, f64> { vertices: vec![], normals: None, uv: vec![], tangents: None, indices: vec![] };
+            for i in 0..n {
+                for j in 0..n {
+                    geometry.vertices.push(vec3(i as f64 * 0.1 - 0.05 * (n - 1) as f64, height, j as f64 * 0.1 - 0.05 * (n - 1) as f64));
+                }
+            }
+            for i in 0..n - 1 {
+                for j in 0..n - 1 {
+                    let k = i * n + j;
+                    geometry.indices.extend([k, k + 1, k + n, k + 1, k + n + 1, k + n]);
+                }
+            }
+            geometry
+        };
+        let max_stretch = |positions: &[Vector3F64], geometry: &Geometry<3, f64>| {
+            geometry.indices.chunks(3).flat_map(|t| [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])])
+                .map(|(a, b)| positions[a as usize].distance(&positions[b as usize]) / geometry.vertices[a as usize].distance(&geometry.vertices[b as usize]))
+                .fold(0.0, f64::max)
+        };
+
+        //Cloth hanging from two corners.
+        let geometry = cloth(10, 2.0);
+        let mut hanging = SoftBody::from_geometry(&geometry, 1.0).with_distance_constraints(0.0).with_bending_constraints(1e-3);
+        hanging.pin(0);
+        hanging.pin(9);
+        let mut lowest = f64::MAX;
+        for _ in 0..120 {
+            hanging.step(1.0 / 60.0, &[]);
+            lowest = hanging.positions().iter().map(|p| p.y()).fold(lowest, f64::min);
+        }
+        assert_eq!(hanging.positions()[0], geometry.vertices[0]);
+        assert_eq!(hanging.positions()[9], geometry.vertices[9]);
+        assert!(lowest < 1.2 && lowest > 2.0 - 0.9 * 1.05, "{lowest}");
+        assert!(max_stretch(hanging.positions(), &geometry) < 1.05);
+
+        //Cloth draped over a sphere stays outside it.
+        let mut sphere = SphereCollider::new(0.5);
+        sphere.set_rigidbody(Rigidbody::default());
+        let geometry = cloth(15, 0.7);
+        let mut draped = SoftBody::from_geometry(&geometry, 1.0).with_distance_constraints(0.0).with_bending_constraints(1e-2);
+        for _ in 0..90 {
+            draped.step(1.0 / 60.0, &[&sphere]);
+        }
+        assert!(draped.positions().iter().all(|p| p.magnitude() > 0.5));
+        assert!((draped.positions()[7 * 15 + 7].y() - 0.51).abs() < 0.02);
+        assert!(draped.positions().iter().any(|p| p.y() < 0.3));
+
+        //A rope falling onto a box in a world comes to rest on top of it without losing length.
+        let mut world = World::default();
+        let ground = world.create_rigidbody();
+        world.rigidbody_mut(&ground).unwrap().set_flags(0);
+        world.attach_collider(&ground, BoxCollider::new(vec3(4.0, 1.0, 4.0)));
+        world.step(1.0 / 60.0);
+        let mut rope = SoftBody::rope(vec3(-1.0, 1.5, 0.0), vec3(1.0, 1.5, 0.0), 20, 0.5, 1e-4);
+        for _ in 0..120 {
+            rope.step_in_world(1.0 / 60.0, &world);
+        }
+        assert!(rope.positions().iter().all(|p| (p.y() - 0.51).abs() < 0.02), "{:?}", rope.positions());
+        let length = rope.positions().windows(2).map(|w| w[0].distance(&w[1])).sum::<f64>();
+        assert!((length - 2.0).abs() < 0.02);
+
+        //An octahedron inflated to twice its volume without gravity.
+        let octahedron = Geometry::<3, f64> {
+            vertices: vec![vec3(1.0, 0.0, 0.0), vec3(-1.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0), vec3(0.0, -1.0, 0.0), vec3(0.0, 0.0, 1.0), vec3(0.0, 0.0, -1.0)],
+            normals: None,
+            uv: vec![],
+            tangents: None,
+            indices: vec![0, 2, 4, 2, 1, 4, 1, 3, 4, 3, 0, 4, 2, 0, 5, 1, 2, 5, 3, 1, 5, 0, 3, 5],
+        };
+        let mut balloon = SoftBody::from_geometry(&octahedron, 1.0).with_gravity(Vector3F64::ZERO).with_volume_constraint(2.0, 0.0);
+        assert!((balloon.volume() - 4.0 / 3.0).abs() < 1e-9);
+        for _ in 0..60 {
+            balloon.step(1.0 / 60.0, &[]);
+        }
+        assert!((balloon.volume() - 8.0 / 3.0).abs() < 0.01);
+
+        //The explicit mass-spring cloth hangs too, and its energy settles.
+        let geometry = cloth(10, 2.0);
+        let mut springs = MassSpring::from_geometry(&geometry, 1.0, 2000.0, 2.0).with_bending_springs(50.0, 0.5).with_substeps(40);
+        springs.pin(0);
+        springs.pin(9);
+        let mut lowest = f64::MAX;
+        for _ in 0..120 {
+            springs.step(1.0 / 60.0, &[]);
+            lowest = springs.positions().iter().map(|p| p.y()).fold(lowest, f64::min);
+        }
+        assert_eq!(springs.positions()[0], geometry.vertices[0]);
+        assert!(lowest < 1.2, "{lowest}");
+        assert!(springs.positions().iter().all(|p| p.magnitude().is_finite()));
+        assert!(max_stretch(springs.positions(), &geometry) < 1.3);
+    }
+
+    #[test]
+    fn test_n_body_gravitation() {
+        //A light planet around a heavy sun with G = 1, started at periapsis in the center of mass frame.
+        let orbit = |periapsis: f64, eccentricity: f64, gravitation: Gravitation| {
+            let (sun_mass, planet_mass) = (1.0, 1e-3);
+            let mu = sun_mass + planet_mass;
+            let mut world = World::default();
+            world.set_gravity(Vector3F64::ZERO);
+            world.set_gravitation(Some(gravitation));
+            world.sleep_settings_mut().enabled = false;
+
+            let speed = (mu * (1.0 + eccentricity) / periapsis).sqrt();
+            let mut body = |mass: f64, position: Vector3F64, velocity: Vector3F64| {
+                let handle = world.create_rigidbody();
+                let mut rigidbody = world.rigidbody_mut(&handle).unwrap();
+                rigidbody.set_mass(mass);
+                rigidbody.set_position(position);
+                rigidbody.set_velocity(velocity);
+                handle
+            };
+            let sun = body(sun_mass, vec3(-periapsis * planet_mass / mu, 0.0, 0.0), vec3(0.0, 0.0, -speed * planet_mass / mu));
+            let planet = body(planet_mass, vec3(periapsis * sun_mass / mu, 0.0, 0.0), vec3(0.0, 0.0, speed * sun_mass / mu));
+            (world, sun, planet)
+        };
+        let energy = |world: &World| {
+            world.bodies().handles().iter()
+                .map(|h| world.rigidbody(h).unwrap())
+                .map(|b| 0.5 * b.mass() * b.velocity().magnitude_squared())
+                .sum::<f64>() + world.gravitational_energy()
+        };
+        let separation = |world: &World, sun: &RigidbodyHandle, planet: &RigidbodyHandle| {
+            world.rigidbody(planet).unwrap().position() - world.rigidbody(sun).unwrap().position()
+        };
+
+        //Circular: the radius holds and the planet is back where it started after one period.
+        for gravitation in [Gravitation::new(1.0), Gravitation::new(1.0).with_barnes_hut(0.5)] {
+            let (mut world, sun, planet) = orbit(1.0, 0.0, gravitation);
+            let start = energy(&world);
+            let period = 2.0 * std::f64::consts::PI / 1.001_f64.sqrt();
+            let delta = period / 6000.0;
+            for _ in 0..6000 {
+                world.step(delta);
+                assert!((separation(&world, &sun, &planet).magnitude() - 1.0).abs() < 1e-3);
+            }
+            let end = separation(&world, &sun, &planet);
+            assert!((end - vec3(1.0, 0.0, 0.0)).magnitude() < 1e-2, "{end}");
+            assert!(((energy(&world) - start) / start).abs() < 1e-4);
+        }
+
+        //Eccentric: with a = 1 and e = 0.5 the planet swings out to 1.5 and is back at 0.5 after the same period.
+        let (mut world, sun, planet) = orbit(0.5, 0.5, Gravitation::new(1.0));
+        let period = 2.0 * std::f64::consts::PI / 1.001_f64.sqrt();
+        let delta = period / 20000.0;
+        let mut apoapsis = 0.0_f64;
+        for _ in 0..20000 {
+            world.step(delta);
+            apoapsis = apoapsis.max(separation(&world, &sun, &planet).magnitude());
+        }
+        assert!((apoapsis - 1.5).abs() < 1e-3, "{apoapsis}");
+        assert!((separation(&world, &sun, &planet) - vec3(0.5, 0.0, 0.0)).magnitude() < 1e-2);
+
+        //Barnes–Hut agrees with the direct sum, exactly when every cell is opened.
+        let mut random = 88172645463325252_u64;
+        let mut next = || {
+            random ^= random << 13;
+            random ^= random >> 7;
+            random ^= random << 17;
+            (random % 10000) as f64 / 10000.0
+        };
+        let positions = (0..300).map(|_| vec3(next() * 10.0, next() * 10.0, next() * 10.0)).collect::<Vec<Vector3F64>>();
+        let masses = (0..300).map(|_| next() + 0.1).collect::<Vec<f64>>();
+        let direct = Gravitation::new(1.0).with_softening(0.01).accelerations(&positions, &masses);
+        let exact = Gravitation::new(1.0).with_softening(0.01).with_barnes_hut(0.0).accelerations(&positions, &masses);
+        let approximate = Gravitation::new(1.0).with_softening(0.01).with_barnes_hut(0.5).accelerations(&positions, &masses);
+        let mean = direct.iter().map(|a| a.magnitude()).sum::<f64>() / direct.len() as f64;
+        let error = (0..positions.len()).map(|i| (direct[i] - approximate[i]).magnitude()).sum::<f64>() / direct.len() as f64;
+        for i in 0..positions.len() {
+            assert!((direct[i] - exact[i]).magnitude() < 1e-9 * direct[i].magnitude());
+        }
+        assert!(error < 0.01 * mean, "{error} {mean}");
+
+        //Snapshots keep gravitation.
+        let restored = World::restore(&world.snapshot(Endian::Little).unwrap()).unwrap();
+        assert_eq!(restored.gravitation(), Some(&Gravitation::new(1.0)));
+    }
+
+    #[test]
+    fn test_character_controller() {
+        //Flat ground with its top at y = 0, plus whatever the scenario adds.
+        let level = |obstacles: Vec<(Vector3F64, Vector3F64, Quaternion<f64>)>| {
+            let mut world = World::default();
+            for (size, position, rotation) in [(vec3(100.0, 1.0, 100.0), vec3(0.0, -0.5, 0.0), Quaternion::identity())].into_iter().chain(obstacles) {
+                let handle = world.create_rigidbody();
+                let mut body = world.rigidbody_mut(&handle).unwrap();
+                body.set_flags(0);
+                body.set_position(position);
+                body.set_rotation(rotation);
+                world.attach_collider(&handle, BoxCollider::new(size));
+            }
+            world.step(1.0 / 60.0);
+            let character = CharacterController::new(&mut world, vec3(0.0, 1.2, 0.0), CharacterSettings::default());
+            (world, character)
+        };
+        let walk = |world: &mut World, character: &mut CharacterController, step: Vector3F64, times: usize| {
+            for _ in 0..times {
+                character.move_by(world, step - vec3(0.0, 0.05, 0.0));
+            }
+        };
+
+        //Falls onto the ground, and is stopped by a wall while sliding along it.
+        let (mut world, mut character) = level(vec![(vec3(1.0, 4.0, 20.0), vec3(3.5, 2.0, 0.0), Quaternion::identity())]);
+        walk(&mut world, &mut character, Vector3F64::ZERO, 10);
+        assert!(character.is_grounded());
+        assert!((character.position().y() - 1.01).abs() < 1e-3, "{}", character.position());
+        walk(&mut world, &mut character, vec3(0.1, 0.0, 0.05), 40);
+        let position = character.position();
+        assert!((position.x() - 2.49).abs() < 2e-3 && position.z() > 1.5, "{position}");
+        assert!(character.collision_flags().sides && character.collision_flags().below);
+
+        //Steps onto a low ledge but not a high one.
+        let (mut world, mut character) = level(vec![
+            (vec3(4.0, 0.2, 4.0), vec3(3.0, 0.1, 0.0), Quaternion::identity()),
+            (vec3(4.0, 0.6, 4.0), vec3(3.0, 0.3, 10.0), Quaternion::identity()),
+        ]);
+        walk(&mut world, &mut character, vec3(0.1, 0.0, 0.0), 30);
+        assert!((character.position().y() - 1.21).abs() < 1e-3 && character.position().x() > 2.5, "{}", character.position());
+        character.teleport(&mut world, vec3(0.0, 1.01, 10.0));
+        walk(&mut world, &mut character, vec3(0.1, 0.0, 0.0), 30);
+        assert!((character.position().y() - 1.01).abs() < 1e-3 && character.position().x() < 0.5, "{}", character.position());
+
+        //Walks up a gentle ramp but not a steep one, whose foot is at x = 2.
+        let ramp = |degrees: f64, z: f64| {
+            let rotation = Quaternion::from_axis_angle(Vector3F64::FORWARD, Degrees(degrees));
+            let normal = rotation * Vector3F64::UP;
+            (vec3(20.0, 1.0, 4.0), vec3(2.0, 0.0, z) + rotation * vec3(10.0, 0.0, 0.0) - normal * 0.5, rotation)
+        };
+        let (mut world, mut character) = level(vec![ramp(30.0, 0.0), ramp(60.0, 10.0)]);
+        walk(&mut world, &mut character, vec3(0.1, 0.0, 0.0), 40);
+        assert!(character.is_grounded() && character.position().y() > 1.5, "{}", character.position());
+        character.teleport(&mut world, vec3(0.0, 1.01, 10.0));
+        walk(&mut world, &mut character, vec3(0.1, 0.0, 0.0), 40);
+        assert!(character.position().y() < 1.3 && character.position().x() < 2.5, "{}", character.position());
+
+        //Rides a platform moved by hand.
+        let (mut world, mut character) = level(vec![(vec3(3.0, 0.5, 3.0), vec3(0.0, 0.25, 0.0), Quaternion::identity())]);
+        let platform = RigidbodyHandle { id: 1, generation: 0 };
+        walk(&mut world, &mut character, Vector3F64::ZERO, 5);
+        assert!((character.position().y() - 1.51).abs() < 1e-3);
+        for i in 1..=20 {
+            world.rigidbody_mut(&platform).unwrap().set_position(vec3(i as f64 * 0.05, 0.25 + i as f64 * 0.01, 0.0));
+            character.move_by(&mut world, vec3(0.0, -0.05, 0.0));
+        }
+        assert!((character.position() - vec3(1.0, 1.71, 0.0)).magnitude() < 1e-3, "{}", character.position());
+
+        //Dynamic rigidbodies bump into the capsule.
+        let crate_body = world.create_rigidbody();
+        world.rigidbody_mut(&crate_body).unwrap().set_position(vec3(1.0, 3.5, 0.0));
+        world.attach_collider(&crate_body, SphereCollider::new(0.3));
+        for _ in 0..60 {
+            world.step(1.0 / 60.0);
+        }
+        assert!(world.rigidbody(&crate_body).unwrap().position().y() > 2.5);
+    }
+
+    #[test]
+    fn test_raycast_vehicle() {
+        let car = |anti_roll: f64| {
+            let mut world = World::default();
+            let ground = world.create_rigidbody();
+            world.rigidbody_mut(&ground).unwrap().set_flags(0);
+            world.attach_collider(&ground, BoxCollider::new(vec3(400.0, 1.0, 400.0)));
+            world.rigidbody_mut(&ground).unwrap().set_position(vec3(0.0, -0.5, 0.0));
+
+            let chassis = world.create_rigidbody();
+            world.rigidbody_mut(&chassis).unwrap().set_mass(1000.0);
+            world.rigidbody_mut(&chassis).unwrap().set_position(vec3(0.0, 0.8, 0.0));
+            world.attach_collider(&chassis, BoxCollider::new(vec3(1.8, 0.5, 4.0)));
+
+            let mut vehicle = Vehicle::new(chassis);
+            for (x, z) in [(-0.8, 1.4), (0.8, 1.4), (-0.8, -1.4), (0.8, -1.4)] {
+                let wheel = WheelSettings::new(vec3(x, -0.1, z), 0.35);
+                vehicle = vehicle.with_wheel(if z > 0.0 { wheel.steered() } else { wheel.driven() });
+            }
+            if anti_roll > 0.0 {
+                vehicle = vehicle.with_anti_roll_bar(0, 1, anti_roll).with_anti_roll_bar(2, 3, anti_roll);
+            }
+            let handle = world.add_vehicle(vehicle);
+            (world, chassis, handle)
+        };
+        let run = |world: &mut World, seconds: f64| {
+            for _ in 0..(seconds * 60.0) as usize {
+                world.step(1.0 / 60.0);
+            }
+        };
+
+        //Settles on its suspension with the weight spread over the wheels.
+        let (mut world, chassis, handle) = car(0.0);
+        run(&mut world, 3.0);
+        let body = world.rigidbody(&chassis).unwrap();
+        assert!(body.velocity().magnitude() < 0.01);
+        let loads = world.vehicle(&handle).unwrap().wheels().iter().map(|w| w.load()).collect::<Vec<f64>>();
+        assert!(((loads.iter().sum::<f64>() - 9810.0) / 9810.0).abs() < 0.01, "{loads:?}");
+        assert!(loads.iter().all(|load| (load - 2452.5).abs() < 250.0), "{loads:?}");
+        let height = body.position().y();
+        assert!((height - (0.1 + 0.3 + 0.35 - 0.07)).abs() < 0.02, "{height}");
+
+        //Throttle drives it forward in a straight line.
+        world.vehicle_mut(&handle).unwrap().set_throttle(1.0);
+        run(&mut world, 3.0);
+        let body = world.rigidbody(&chassis).unwrap();
+        assert!(body.velocity().z() > 10.0 && body.position().x().abs() < 0.05, "{}", body.velocity());
+        assert!(world.vehicle(&handle).unwrap().wheels()[2].spin() > 25.0);
+
+        //Snapshots carry the vehicle along.
+        let mut restored = World::restore(&world.snapshot(Endian::Little).unwrap()).unwrap();
+        run(&mut world, 0.5);
+        run(&mut restored, 0.5);
+        assert_eq!(world.rigidbody(&chassis).unwrap().position(), restored.rigidbody(&chassis).unwrap().position());
+
+        //Brakes bring it to a stop.
+        world.vehicle_mut(&handle).unwrap().set_throttle(0.0);
+        world.vehicle_mut(&handle).unwrap().set_brake(1.0);
+        run(&mut world, 5.0);
+        assert!(world.rigidbody(&chassis).unwrap().velocity().magnitude() < 0.05);
+
+        //Steering right turns right, and anti-roll bars keep the chassis flatter doing so.
+        let roll = |anti_roll: f64| {
+            let (mut world, chassis, handle) = car(anti_roll);
+            run(&mut world, 1.0);
+            world.vehicle_mut(&handle).unwrap().set_throttle(0.6);
+            run(&mut world, 3.0);
+            world.vehicle_mut(&handle).unwrap().set_steering(0.5);
+            let mut roll = 0.0_f64;
+            for _ in 0..120 {
+                world.step(1.0 / 60.0);
+                let right = world.rigidbody(&chassis).unwrap().rotation() * Vector3F64::RIGHT;
+                roll = roll.max(right.y().abs());
+            }
+            let body = world.rigidbody(&chassis).unwrap();
+            assert!(body.position().x() > 2.0 && (body.rotation() * Vector3F64::FORWARD).x() > 0.3, "{}", body.position());
+            roll
+        };
+        let (soft, stiff) = (roll(0.0), roll(30000.0));
+        assert!(stiff < soft * 0.8, "{soft} {stiff}");
+    }
+}
@@ -10,6 +10,26 @@ use crate::shared::traits::number::Number;
 pub mod operations;
 
 impl<N: Number> Quaternion<N> {
+    ///The identity quaternion, representing no rotation.
+    pub fn identity() -> Self {
+        Self {
+            xyz: Vector3::ZERO,
+            w: N::ONE,
+        }
+    }
+
+    ///Creates a unit quaternion rotating by an angle around an axis. The axis is expected to be normalized.
+    ///<br>q = cos(θ/2) + sin(θ/2)(xi + yj + zk)
+    ///<br>See <a href="https://en.wikipedia.org/wiki/Quaternions_and_spatial_rotation">Quaternions and spatial rotation</a>
+    pub fn from_axis_angle(axis: Vector3<N>, angle: Angle<N>) -> Self {
+        let half = angle.to_radians().take() / N::TWO;
+        let (c, s) = half.cos_sin();
+        Self {
+            xyz: axis * s,
+            w: c,
+        }
+    }
+
     pub fn to_euler_angle<E: EulerAngles<N>>(self) -> E {
         let (mut roll, mut pitch, mut yaw) = (N::ZERO, N::ZERO, N::ZERO);
 
@@ -62,6 +82,14 @@ impl<N: Number> Quaternion<N> {
         [self.w, self.xyz[0], self.xyz[1], self.xyz[2]]
     }
 
+    ///The inverse of [Quaternion::wxyz], building a quaternion from its components as they are.
+    pub fn from_wxyz(wxyz: [N; 4]) -> Self {
+        Self {
+            xyz: Vector3::new([wxyz[1], wxyz[2], wxyz[3]]),
+            w: wxyz[0],
+        }
+    }
+
     pub fn components(&self) -> (N, N, N, N) {
         let wxyz = self.wxyz();
         (wxyz[0], wxyz[1], wxyz[2], wxyz[3])
@@ -94,6 +122,16 @@ impl<N: Number> Quaternion<N> {
         conjugate / (*self * conjugate)
     }
 
+    ///Advances this rotation by an angular velocity (in radians per second) over a time step, returning a normalized quaternion.
+    ///<br>q` = q + (Δt / 2)ωq
+    pub fn integrate(&self, angular_velocity: Vector3<N>, delta: N) -> Self where N: Neg<Output=N> {
+        let spin = Self {
+            xyz: angular_velocity,
+            w: N::ZERO,
+        } * *self * (delta / N::TWO);
+        (*self + spin).norm()
+    }
+
     pub fn slerp(&self, b: &Self, t: N) -> Quaternion<N> where N: Neg<Output=N> {
         let (one, two) = (*self, *b);
         (two * one.inverse()) * t * one
@@ -1,5 +1,5 @@
 use std::fmt::Display;
-use std::ops::{Add, AddAssign, Sub};
+use std::ops::{Add, AddAssign, MulAssign, Sub, SubAssign};
 
 use crate::linear_algebra::vector::Vector;
 use crate::shared::traits::lerp::Lerp;
@@ -27,6 +27,12 @@ impl <const L: usize, N: Number> Vector<L, N> {
         self.0.iter().map(|n| n.num_pow(N::TWO)).sum::<N>().num_sqrt()
     }
 
+    ///Calculates the squared magnitude of this vector, avoiding the square root.
+    ///<br>Example: x^2 + y^2 + ...
+    pub fn magnitude_squared(&self) -> N {
+        self.dot(self)
+    }
+
     ///Sums the components of the vector.
     pub fn sum(&self) -> N {
         self.0.iter().map(|s| *s).sum()
@@ -110,6 +116,18 @@ impl <const L: usize, N: Number> AddAssign<Vector<L, N>> for Vector<L, N> {
     }
 }
 
+impl <const L: usize, N: Number> SubAssign<Vector<L, N>> for Vector<L, N> {
+    fn sub_assign(&mut self, rhs: Vector<L, N>) {
+        *self = *self - rhs;
+    }
+}
+
+impl <const L: usize, N: Number> MulAssign<N> for Vector<L, N> {
+    fn mul_assign(&mut self, rhs: N) {
+        *self = *self * rhs;
+    }
+}
+
 impl <const L: usize, N: Number> Add<Self> for Vector<L, N> {
     type Output = Self;
 
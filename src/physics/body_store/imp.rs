@@ -0,0 +1,313 @@
+use std::fmt::{Debug, Formatter};
+use crate::linear_algebra::quaternion::Quaternion;
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::body_store::{BodyStore, RigidbodyMut, RigidbodyRef, MIN_BODIES_PER_THREAD};
+use crate::physics::pose::Pose;
+use crate::physics::rigidbody::flags::RigidbodyFlags;
+use crate::physics::rigidbody::Rigidbody;
+use crate::physics::rigidbody_handle::RigidbodyHandle;
+
+impl BodyStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///Copies a rigidbody's current state into the store, reusing the slot of a removed rigidbody if there is one.
+    pub fn insert(&mut self, rigidbody: &Rigidbody) -> RigidbodyHandle {
+        let index = match self.free.pop() {
+            Some(id) => id as usize,
+            None => {
+                self.positions.push(Vector3F64::ZERO);
+                self.rotations.push(Quaternion::identity());
+                self.velocities.push(Vector3F64::ZERO);
+                self.angular_velocities.push(Vector3F64::ZERO);
+                self.masses.push(0_f64);
+                self.inertias.push(Vector3F64::ZERO);
+                self.flags.push(0);
+                self.inverse_masses.push(0_f64);
+                self.generations.push(0);
+                self.alive.push(false);
+                self.rest_times.push(0_f64);
+                self.sleeping.push(None);
+                self.disturbed.push(false);
+                self.positions.len() - 1
+            }
+        };
+
+        self.positions[index] = rigidbody.position();
+        self.rotations[index] = rigidbody.rotation();
+        self.velocities[index] = rigidbody.velocity();
+        self.angular_velocities[index] = rigidbody.angular_velocity();
+        self.masses[index] = rigidbody.mass();
+        self.inertias[index] = rigidbody.inertia();
+        self.flags[index] = rigidbody.flags();
+        self.alive[index] = true;
+        self.rest_times[index] = 0_f64;
+        self.sleeping[index] = None;
+        self.disturbed[index] = false;
+        self.refresh(index);
+        self.handle(index)
+    }
+
+    ///Frees a rigidbody's slot, bumping its generation so existing handles to it go stale.
+    ///<br>Returns false if the handle is already stale.
+    pub fn remove(&mut self, handle: &RigidbodyHandle) -> bool {
+        if !self.contains(handle) {
+            return false;
+        }
+        let index = handle.id as usize;
+        self.alive[index] = false;
+        self.sleeping[index] = None;
+        self.velocities[index] = Vector3F64::ZERO;
+        self.angular_velocities[index] = Vector3F64::ZERO;
+        self.generations[index] += 1;
+        self.free.push(handle.id);
+        self.refresh(index);
+        true
+    }
+
+    ///Whether the handle refers to a rigidbody in this store, false once it has been removed.
+    pub fn contains(&self, handle: &RigidbodyHandle) -> bool {
+        let index = handle.id as usize;
+        index < self.len() && self.alive[index] && self.generations[index] == handle.generation
+    }
+
+    pub fn get(&self, handle: &RigidbodyHandle) -> Option<RigidbodyRef<'_>> {
+        self.contains(handle).then_some(RigidbodyRef { store: self, index: handle.id as usize })
+    }
+
+    pub fn get_mut(&mut self, handle: &RigidbodyHandle) -> Option<RigidbodyMut<'_>> {
+        self.contains(handle).then_some(RigidbodyMut { store: self, index: handle.id as usize })
+    }
+
+    ///How many slots the store has, including those of removed rigidbodies.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    ///Handles to every rigidbody in the store, in id order.
+    pub fn handles(&self) -> Vec<RigidbodyHandle> {
+        (0..self.len()).filter(|i| self.alive[*i]).map(|i| self.handle(i)).collect()
+    }
+
+    ///Positions of every slot, indexed by rigidbody id.
+    pub fn positions(&self) -> &[Vector3F64] {
+        &self.positions
+    }
+
+    pub fn rotations(&self) -> &[Quaternion<f64>] {
+        &self.rotations
+    }
+
+    pub fn velocities(&self) -> &[Vector3F64] {
+        &self.velocities
+    }
+
+    pub fn angular_velocities(&self) -> &[Vector3F64] {
+        &self.angular_velocities
+    }
+
+    ///Applies gravity to every awake dynamic rigidbody using it, spread over up to the provided number of threads.
+    pub fn integrate_velocities(&mut self, gravity: Vector3F64, delta: f64, threads: usize) {
+        let chunk = chunk_size(self.len(), threads);
+        if chunk >= self.len() {
+            integrate_velocities(&mut self.velocities, &self.flags, &self.inverse_masses, gravity * delta);
+            return;
+        }
+
+        std::thread::scope(|scope| {
+            let columns = self.velocities.chunks_mut(chunk)
+                .zip(self.flags.chunks(chunk))
+                .zip(self.inverse_masses.chunks(chunk));
+            for ((velocities, flags), inverse_masses) in columns {
+                scope.spawn(move || integrate_velocities(velocities, flags, inverse_masses, gravity * delta));
+            }
+        });
+    }
+
+    ///Moves every awake dynamic rigidbody along its velocities, spread over up to the provided number of threads.
+    pub fn integrate_positions(&mut self, delta: f64, threads: usize) {
+        let chunk = chunk_size(self.len(), threads);
+        if chunk >= self.len() {
+            integrate_positions(&mut self.positions, &mut self.rotations, &self.velocities, &self.angular_velocities, &self.inverse_masses, delta);
+            return;
+        }
+
+        std::thread::scope(|scope| {
+            let columns = self.positions.chunks_mut(chunk)
+                .zip(self.rotations.chunks_mut(chunk))
+                .zip(self.velocities.chunks(chunk))
+                .zip(self.angular_velocities.chunks(chunk))
+                .zip(self.inverse_masses.chunks(chunk));
+            for ((((positions, rotations), velocities), angular_velocities), inverse_masses) in columns {
+                scope.spawn(move || integrate_positions(positions, rotations, velocities, angular_velocities, inverse_masses, delta));
+            }
+        });
+    }
+
+    pub(crate) fn handle(&self, index: usize) -> RigidbodyHandle {
+        RigidbodyHandle {
+            id: index as u32,
+            generation: self.generations[index],
+        }
+    }
+
+    pub(crate) fn pose(&self, index: usize) -> Pose {
+        Pose::new(self.positions[index], self.rotations[index])
+    }
+
+    ///Whether the rigidbody is integrated and resolved when awake. Rigidbodies without [RigidbodyFlags::UseKinematics] or without mass are treated as immovable.
+    pub(crate) fn is_dynamic(&self, index: usize) -> bool {
+        self.alive[index] && self.flags[index] & RigidbodyFlags::UseKinematics > 0 && self.masses[index] > 0_f64
+    }
+
+    pub(crate) fn set_sleeping(&mut self, index: usize, island: Option<u32>) {
+        self.sleeping[index] = island;
+        self.rest_times[index] = 0_f64;
+        if island.is_some() {
+            self.velocities[index] = Vector3F64::ZERO;
+            self.angular_velocities[index] = Vector3F64::ZERO;
+        }
+        self.refresh(index);
+    }
+
+    ///Recomputes the cached inverse mass after the mass, flags or sleep state changed.
+    pub(crate) fn refresh(&mut self, index: usize) {
+        self.inverse_masses[index] = if self.is_dynamic(index) && self.sleeping[index].is_none() {
+            1_f64 / self.masses[index]
+        } else {
+            0_f64
+        };
+    }
+}
+
+///How many bodies each thread integrates, which covers every body when it isn't worth splitting them.
+fn chunk_size(len: usize, threads: usize) -> usize {
+    len.div_ceil(threads.max(1)).max(MIN_BODIES_PER_THREAD)
+}
+
+fn integrate_velocities(velocities: &mut [Vector3F64], flags: &[u8], inverse_masses: &[f64], change: Vector3F64) {
+    for ((velocity, flags), inverse_mass) in velocities.iter_mut().zip(flags).zip(inverse_masses) {
+        if *inverse_mass > 0_f64 && *flags & RigidbodyFlags::UseGravity > 0 {
+            *velocity += change;
+        }
+    }
+}
+
+fn integrate_positions(positions: &mut [Vector3F64], rotations: &mut [Quaternion<f64>], velocities: &[Vector3F64], angular_velocities: &[Vector3F64], inverse_masses: &[f64], delta: f64) {
+    for i in 0..positions.len() {
+        if inverse_masses[i] > 0_f64 {
+            positions[i] += velocities[i] * delta;
+            rotations[i] = rotations[i].integrate(angular_velocities[i], delta);
+        }
+    }
+}
+
+impl RigidbodyRef<'_> {
+    pub fn handle(&self) -> RigidbodyHandle {
+        self.store.handle(self.index)
+    }
+
+    pub fn position(&self) -> Vector3F64 {
+        self.store.positions[self.index]
+    }
+
+    pub fn rotation(&self) -> Quaternion<f64> {
+        self.store.rotations[self.index]
+    }
+
+    ///The world space position and rotation of this rigidbody.
+    pub fn pose(&self) -> Pose {
+        self.store.pose(self.index)
+    }
+
+    pub fn velocity(&self) -> Vector3F64 {
+        self.store.velocities[self.index]
+    }
+
+    pub fn angular_velocity(&self) -> Vector3F64 {
+        self.store.angular_velocities[self.index]
+    }
+
+    pub fn mass(&self) -> f64 {
+        self.store.masses[self.index]
+    }
+
+    ///The principal moments of inertia, in the rigidbody's local space.
+    pub fn inertia(&self) -> Vector3F64 {
+        self.store.inertias[self.index]
+    }
+
+    pub fn flags(&self) -> u8 {
+        self.store.flags[self.index]
+    }
+
+    ///Whether the world integrates and resolves collisions for this rigidbody while it's awake.
+    pub fn is_dynamic(&self) -> bool {
+        self.store.is_dynamic(self.index)
+    }
+
+    pub fn is_sleeping(&self) -> bool {
+        self.store.sleeping[self.index].is_some()
+    }
+}
+
+impl Debug for RigidbodyRef<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Rigidbody")
+            .field("position", &self.position())
+            .field("rotation", &self.rotation())
+            .field("mass", &self.mass())
+            .field("velocity", &self.velocity())
+            .field("angular_velocity", &self.angular_velocity())
+            .field("inertia", &self.inertia())
+            .field("flags", &self.flags())
+            .finish()
+    }
+}
+
+impl RigidbodyMut<'_> {
+    ///Read access to the same rigidbody.
+    pub fn read(&self) -> RigidbodyRef<'_> {
+        RigidbodyRef { store: self.store, index: self.index }
+    }
+
+    pub fn set_position(&mut self, position: Vector3F64) {
+        self.store.positions[self.index] = position;
+        self.store.disturbed[self.index] = true;
+    }
+
+    pub fn set_rotation(&mut self, rotation: Quaternion<f64>) {
+        self.store.rotations[self.index] = rotation;
+        self.store.disturbed[self.index] = true;
+    }
+
+    pub fn set_velocity(&mut self, velocity: Vector3F64) {
+        self.store.velocities[self.index] = velocity;
+        self.store.disturbed[self.index] = true;
+    }
+
+    pub fn set_angular_velocity(&mut self, angular_velocity: Vector3F64) {
+        self.store.angular_velocities[self.index] = angular_velocity;
+        self.store.disturbed[self.index] = true;
+    }
+
+    pub fn set_mass(&mut self, mass: f64) {
+        self.store.masses[self.index] = mass;
+        self.store.refresh(self.index);
+    }
+
+    ///Sets the principal moments of inertia, in the rigidbody's local space.
+    pub fn set_inertia(&mut self, inertia: Vector3F64) {
+        self.store.inertias[self.index] = inertia;
+    }
+
+    pub fn set_flags(&mut self, flags: u8) {
+        self.store.flags[self.index] = flags;
+        self.store.refresh(self.index);
+    }
+}
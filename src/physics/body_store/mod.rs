@@ -0,0 +1,49 @@
+use crate::linear_algebra::quaternion::Quaternion;
+use crate::linear_algebra::vector::types::Vector3F64;
+
+pub mod imp;
+
+///Bodies per thread below which integration stays on the calling thread, as spawning would cost more than it saves.
+pub const MIN_BODIES_PER_THREAD: usize = 1024;
+
+///The state of every rigidbody in a [crate::physics::world::World], laid out as a structure of arrays indexed by rigidbody id.
+///<br>Keeping each field in its own column lets the integrator run over plain slices without locking, and split them across threads.
+///<br>Slots of removed rigidbodies stay in place with an inverse mass of 0 until their id is reused.
+#[derive(Debug, Clone, Default)]
+pub struct BodyStore {
+    pub(crate) positions: Vec<Vector3F64>,
+    pub(crate) rotations: Vec<Quaternion<f64>>,
+    pub(crate) velocities: Vec<Vector3F64>,
+    pub(crate) angular_velocities: Vec<Vector3F64>,
+    pub(crate) masses: Vec<f64>,
+    ///Principal moments of inertia in each rigidbody's local space.
+    pub(crate) inertias: Vec<Vector3F64>,
+    pub(crate) flags: Vec<u8>,
+    ///1 / mass for bodies the integrator moves, 0 for static, massless, sleeping and removed ones.
+    pub(crate) inverse_masses: Vec<f64>,
+    ///Current generation of every slot, bumped when the slot is freed.
+    pub(crate) generations: Vec<u32>,
+    pub(crate) alive: Vec<bool>,
+    ///Ids of removed rigidbodies, reused before new slots are made.
+    pub(crate) free: Vec<u32>,
+    ///How long each rigidbody has been resting for.
+    pub(crate) rest_times: Vec<f64>,
+    ///The island a rigidbody fell asleep with, which wakes up as a whole. None while awake.
+    pub(crate) sleeping: Vec<Option<u32>>,
+    ///Set when a rigidbody is moved or pushed by hand, waking it up on the next step.
+    pub(crate) disturbed: Vec<bool>,
+}
+
+///Read access to one rigidbody in a [BodyStore].
+#[derive(Clone, Copy)]
+pub struct RigidbodyRef<'a> {
+    pub(crate) store: &'a BodyStore,
+    pub(crate) index: usize,
+}
+
+///Write access to one rigidbody in a [BodyStore].
+///<br>Changing the pose or velocity of a sleeping rigidbody wakes it up on the next step.
+pub struct RigidbodyMut<'a> {
+    pub(crate) store: &'a mut BodyStore,
+    pub(crate) index: usize,
+}
@@ -0,0 +1,44 @@
+use std::collections::BTreeMap;
+use crate::physics::broad_phase::BroadPhase;
+use crate::physics::collider::bounds::Bounds;
+
+///Tests every pair of proxies against each other, O(n²).
+#[derive(Debug, Clone, Default)]
+pub struct BruteForce {
+    proxies: BTreeMap<u32, Bounds>,
+}
+
+impl BroadPhase for BruteForce {
+    fn insert(&mut self, id: u32, bounds: Bounds) {
+        self.proxies.insert(id, bounds);
+    }
+
+    fn remove(&mut self, id: u32) {
+        self.proxies.remove(&id);
+    }
+
+    fn update(&mut self, id: u32, bounds: Bounds) {
+        self.proxies.insert(id, bounds);
+    }
+
+    fn len(&self) -> usize {
+        self.proxies.len()
+    }
+
+    fn overlapping_pairs(&mut self) -> Vec<(u32, u32)> {
+        let proxies = self.proxies.iter().collect::<Vec<(&u32, &Bounds)>>();
+        let mut pairs = vec![];
+        for i in 0..proxies.len() {
+            for j in (i + 1)..proxies.len() {
+                if proxies[i].1.intersects(proxies[j].1) {
+                    pairs.push((*proxies[i].0, *proxies[j].0));
+                }
+            }
+        }
+        pairs
+    }
+
+    fn query(&self, bounds: &Bounds) -> Vec<u32> {
+        self.proxies.iter().filter(|(_, b)| b.intersects(bounds)).map(|(id, _)| *id).collect()
+    }
+}
@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+use crate::physics::broad_phase::BroadPhase;
+use crate::physics::collider::bounds::Bounds;
+
+const NULL: usize = usize::MAX;
+
+///How much leaf bounds are enlarged by, so small movements don't require the leaf to be reinserted.
+pub const DEFAULT_MARGIN: f64 = 0.1;
+
+#[derive(Debug, Clone)]
+struct TreeNode {
+    ///Enlarged bounds for leaves, the union of the children for internal nodes.
+    bounds: Bounds,
+    ///The proxy's actual bounds, only meaningful for leaves.
+    tight: Bounds,
+    parent: usize,
+    children: [usize; 2],
+    ///Leaves have a height of 0, free nodes a height of -1.
+    height: i32,
+    id: u32,
+}
+
+impl TreeNode {
+    fn is_leaf(&self) -> bool {
+        self.children[0] == NULL
+    }
+}
+
+///A dynamic bounding volume hierarchy, kept balanced with tree rotations.
+///<br>Leaves are inserted next to the sibling that grows the tree's surface area the least.
+///<br>See <a href="https://en.wikipedia.org/wiki/Bounding_volume_hierarchy">Bounding volume hierarchy</a>
+#[derive(Debug, Clone)]
+pub struct DynamicTree {
+    nodes: Vec<TreeNode>,
+    free: Vec<usize>,
+    root: usize,
+    ///Leaf node of every proxy.
+    leaves: HashMap<u32, usize>,
+    margin: f64,
+}
+
+impl DynamicTree {
+    pub fn new(margin: f64) -> Self {
+        Self {
+            nodes: vec![],
+            free: vec![],
+            root: NULL,
+            leaves: HashMap::new(),
+            margin,
+        }
+    }
+
+    ///The height of the tree, 0 for a single leaf. A balanced tree of n leaves has a height close to log2(n).
+    pub fn height(&self) -> i32 {
+        if self.root == NULL {
+            return 0;
+        }
+        self.nodes[self.root].height
+    }
+
+    fn allocate(&mut self, bounds: Bounds, id: u32) -> usize {
+        let node = TreeNode {
+            bounds,
+            tight: bounds,
+            parent: NULL,
+            children: [NULL; 2],
+            height: 0,
+            id,
+        };
+        match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn release(&mut self, index: usize) {
+        self.nodes[index].height = -1;
+        self.free.push(index);
+    }
+
+    fn insert_leaf(&mut self, leaf: usize) {
+        if self.root == NULL {
+            self.root = leaf;
+            self.nodes[leaf].parent = NULL;
+            return;
+        }
+
+        //Walk down towards the cheapest sibling.
+        let leaf_bounds = self.nodes[leaf].bounds;
+        let mut index = self.root;
+        while !self.nodes[index].is_leaf() {
+            let node = &self.nodes[index];
+            let area = node.bounds.surface_area();
+            let combined_area = node.bounds.union(&leaf_bounds).surface_area();
+
+            let cost = 2_f64 * combined_area;
+            let inheritance = 2_f64 * (combined_area - area);
+
+            let child_cost = |child: usize| {
+                let child = &self.nodes[child];
+                let union = child.bounds.union(&leaf_bounds).surface_area();
+                if child.is_leaf() {
+                    union + inheritance
+                } else {
+                    union - child.bounds.surface_area() + inheritance
+                }
+            };
+            let [c1, c2] = node.children;
+            let (cost1, cost2) = (child_cost(c1), child_cost(c2));
+
+            if cost < cost1 && cost < cost2 {
+                break;
+            }
+            index = if cost1 < cost2 { c1 } else { c2 };
+        }
+
+        let sibling = index;
+        let old_parent = self.nodes[sibling].parent;
+        let new_parent = self.allocate(leaf_bounds.union(&self.nodes[sibling].bounds), u32::MAX);
+        self.nodes[new_parent].parent = old_parent;
+        self.nodes[new_parent].height = self.nodes[sibling].height + 1;
+        self.nodes[new_parent].children = [sibling, leaf];
+        self.nodes[sibling].parent = new_parent;
+        self.nodes[leaf].parent = new_parent;
+
+        if old_parent == NULL {
+            self.root = new_parent;
+        } else {
+            self.replace_child(old_parent, sibling, new_parent);
+        }
+
+        self.refit(self.nodes[leaf].parent);
+    }
+
+    fn remove_leaf(&mut self, leaf: usize) {
+        if leaf == self.root {
+            self.root = NULL;
+            return;
+        }
+
+        let parent = self.nodes[leaf].parent;
+        let grand_parent = self.nodes[parent].parent;
+        let [c1, c2] = self.nodes[parent].children;
+        let sibling = if c1 == leaf { c2 } else { c1 };
+
+        if grand_parent == NULL {
+            self.root = sibling;
+            self.nodes[sibling].parent = NULL;
+            self.release(parent);
+        } else {
+            self.replace_child(grand_parent, parent, sibling);
+            self.nodes[sibling].parent = grand_parent;
+            self.release(parent);
+            self.refit(grand_parent);
+        }
+    }
+
+    fn replace_child(&mut self, parent: usize, old: usize, new: usize) {
+        let children = &mut self.nodes[parent].children;
+        if children[0] == old {
+            children[0] = new;
+        } else {
+            children[1] = new;
+        }
+    }
+
+    ///Walks from index up to the root, rebalancing and recomputing bounds and heights.
+    fn refit(&mut self, mut index: usize) {
+        while index != NULL {
+            index = self.balance(index);
+            let [c1, c2] = self.nodes[index].children;
+            self.nodes[index].height = 1 + self.nodes[c1].height.max(self.nodes[c2].height);
+            self.nodes[index].bounds = self.nodes[c1].bounds.union(&self.nodes[c2].bounds);
+            index = self.nodes[index].parent;
+        }
+    }
+
+    ///Performs a left or right rotation if node a is imbalanced, returning the new root of the subtree.
+    fn balance(&mut self, a: usize) -> usize {
+        if self.nodes[a].is_leaf() || self.nodes[a].height < 2 {
+            return a;
+        }
+
+        let [b, c] = self.nodes[a].children;
+        let balance = self.nodes[c].height - self.nodes[b].height;
+
+        if balance > 1 {
+            self.rotate_up(a, c, 1, b);
+            return c;
+        }
+        if balance < -1 {
+            self.rotate_up(a, b, 0, c);
+            return b;
+        }
+        a
+    }
+
+    ///Moves child (at slot side of a) up to take a's place. other is a's remaining child.
+    fn rotate_up(&mut self, a: usize, child: usize, side: usize, other: usize) {
+        let [f, g] = self.nodes[child].children;
+
+        let a_parent = self.nodes[a].parent;
+        self.nodes[child].children[0] = a;
+        self.nodes[child].parent = a_parent;
+        self.nodes[a].parent = child;
+
+        if a_parent == NULL {
+            self.root = child;
+        } else {
+            self.replace_child(a_parent, a, child);
+        }
+
+        //The taller grandchild stays with child, the shorter one moves to a.
+        let (keep, give) = if self.nodes[f].height > self.nodes[g].height { (f, g) } else { (g, f) };
+        self.nodes[child].children[1] = keep;
+        self.nodes[a].children[side] = give;
+        self.nodes[give].parent = a;
+
+        self.nodes[a].bounds = self.nodes[other].bounds.union(&self.nodes[give].bounds);
+        self.nodes[child].bounds = self.nodes[a].bounds.union(&self.nodes[keep].bounds);
+        self.nodes[a].height = 1 + self.nodes[other].height.max(self.nodes[give].height);
+        self.nodes[child].height = 1 + self.nodes[a].height.max(self.nodes[keep].height);
+    }
+
+    ///Visits every leaf whose tight bounds overlap the provided bounds. The stack is reused between queries to avoid allocating.
+    fn query_nodes<F: FnMut(u32)>(&self, bounds: &Bounds, stack: &mut Vec<usize>, mut visit: F) {
+        if self.root == NULL {
+            return;
+        }
+        stack.clear();
+        stack.push(self.root);
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !node.bounds.intersects(bounds) {
+                continue;
+            }
+            if node.is_leaf() {
+                if node.tight.intersects(bounds) {
+                    visit(node.id);
+                }
+            } else {
+                stack.extend(node.children);
+            }
+        }
+    }
+}
+
+impl Default for DynamicTree {
+    fn default() -> Self {
+        Self::new(DEFAULT_MARGIN)
+    }
+}
+
+impl BroadPhase for DynamicTree {
+    fn insert(&mut self, id: u32, bounds: Bounds) {
+        self.remove(id);
+        let leaf = self.allocate(bounds.expanded(self.margin), id);
+        self.nodes[leaf].tight = bounds;
+        self.insert_leaf(leaf);
+        self.leaves.insert(id, leaf);
+    }
+
+    fn remove(&mut self, id: u32) {
+        if let Some(leaf) = self.leaves.remove(&id) {
+            self.remove_leaf(leaf);
+            self.release(leaf);
+        }
+    }
+
+    fn update(&mut self, id: u32, bounds: Bounds) {
+        let Some(&leaf) = self.leaves.get(&id) else { return };
+        self.nodes[leaf].tight = bounds;
+
+        if self.nodes[leaf].bounds.contains(&bounds) {
+            return;
+        }
+
+        self.remove_leaf(leaf);
+        self.nodes[leaf].bounds = bounds.expanded(self.margin);
+        self.insert_leaf(leaf);
+    }
+
+    fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    fn overlapping_pairs(&mut self) -> Vec<(u32, u32)> {
+        let mut pairs = vec![];
+        let mut stack = vec![];
+        for (id, leaf) in &self.leaves {
+            self.query_nodes(&self.nodes[*leaf].tight, &mut stack, |other| {
+                if other > *id {
+                    pairs.push((*id, other));
+                }
+            });
+        }
+        pairs.sort();
+        pairs
+    }
+
+    fn query(&self, bounds: &Bounds) -> Vec<u32> {
+        let mut ids = vec![];
+        self.query_nodes(bounds, &mut vec![], |id| ids.push(id));
+        ids.sort();
+        ids
+    }
+}
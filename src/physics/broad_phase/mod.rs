@@ -0,0 +1,40 @@
+use crate::physics::collider::bounds::Bounds;
+
+pub mod brute_force;
+pub mod sweep_and_prune;
+pub mod dynamic_tree;
+
+///Selects the broad phase a [crate::physics::world::World] uses to find potentially touching colliders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BroadPhaseType {
+    ///Tests every pair of proxies. Only sensible for a handful of colliders.
+    BruteForce,
+    ///Sorts proxies along one axis and only tests proxies whose intervals overlap. Works best when most bodies move little between steps.
+    SweepAndPrune,
+    ///A bounding volume hierarchy with enlarged leaf bounds, so proxies only get reinserted when they leave their enlarged box.
+    DynamicTree,
+}
+
+///Tracks the bounds of colliders (proxies) by id and reports which of them overlap.
+pub trait BroadPhase {
+    fn insert(&mut self, id: u32, bounds: Bounds);
+    fn remove(&mut self, id: u32);
+    fn update(&mut self, id: u32, bounds: Bounds);
+    fn len(&self) -> usize;
+
+    ///Every pair of proxies whose bounds overlap, with the smaller id first and sorted so the result is deterministic.
+    fn overlapping_pairs(&mut self) -> Vec<(u32, u32)>;
+
+    ///The ids of every proxy whose bounds overlap the provided bounds, in ascending order.
+    fn query(&self, bounds: &Bounds) -> Vec<u32>;
+}
+
+impl BroadPhaseType {
+    pub fn create(&self) -> Box<dyn BroadPhase> {
+        match self {
+            BroadPhaseType::BruteForce => Box::new(brute_force::BruteForce::default()),
+            BroadPhaseType::SweepAndPrune => Box::new(sweep_and_prune::SweepAndPrune::default()),
+            BroadPhaseType::DynamicTree => Box::new(dynamic_tree::DynamicTree::default()),
+        }
+    }
+}
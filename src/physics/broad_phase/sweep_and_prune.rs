@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use crate::physics::broad_phase::BroadPhase;
+use crate::physics::collider::bounds::Bounds;
+
+///Sweep and prune over the axis along which the proxies are most spread out.
+///<br>Proxies stay sorted between calls and are re-sorted with an adaptive sort, which is close to linear when bodies move coherently.
+///<br>See <a href="https://en.wikipedia.org/wiki/Sweep_and_prune">Sweep and prune</a>
+#[derive(Debug, Clone, Default)]
+pub struct SweepAndPrune {
+    proxies: HashMap<u32, Bounds>,
+    order: Vec<u32>,
+    axis: usize,
+}
+
+impl SweepAndPrune {
+    ///Picks the axis with the largest variance of proxy centers.
+    fn choose_axis(&self) -> usize {
+        let n = self.proxies.len().max(1) as f64;
+        let mut sum = [0_f64; 3];
+        let mut sum_squared = [0_f64; 3];
+        for bounds in self.proxies.values() {
+            for i in 0..3 {
+                sum[i] += bounds.center[i];
+                sum_squared[i] += bounds.center[i] * bounds.center[i];
+            }
+        }
+        let variance = |i: usize| sum_squared[i] / n - (sum[i] / n) * (sum[i] / n);
+        (0..3).max_by(|a, b| variance(*a).total_cmp(&variance(*b))).unwrap()
+    }
+
+}
+
+impl BroadPhase for SweepAndPrune {
+    fn insert(&mut self, id: u32, bounds: Bounds) {
+        if self.proxies.insert(id, bounds).is_none() {
+            self.order.push(id);
+        }
+    }
+
+    fn remove(&mut self, id: u32) {
+        if self.proxies.remove(&id).is_some() {
+            self.order.retain(|o| *o != id);
+        }
+    }
+
+    fn update(&mut self, id: u32, bounds: Bounds) {
+        if let Some(proxy) = self.proxies.get_mut(&id) {
+            *proxy = bounds;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.proxies.len()
+    }
+
+    fn overlapping_pairs(&mut self) -> Vec<(u32, u32)> {
+        self.axis = self.choose_axis();
+        let axis = self.axis;
+
+        //The order is kept from the previous call, so this is an adaptive sort over mostly sorted data.
+        let mut sorted = self.order.iter().map(|id| (*id, self.proxies[id])).collect::<Vec<(u32, Bounds)>>();
+        sorted.sort_by(|(_, a), (_, b)| (a.center[axis] - a.extents[axis]).total_cmp(&(b.center[axis] - b.extents[axis])));
+        self.order = sorted.iter().map(|(id, _)| *id).collect();
+
+        let mut pairs = vec![];
+        for i in 0..sorted.len() {
+            let (a_id, a) = &sorted[i];
+            let a_max = a.center[axis] + a.extents[axis];
+
+            for (b_id, b) in &sorted[i + 1..] {
+                if b.center[axis] - b.extents[axis] > a_max {
+                    break;
+                }
+                if a.intersects(b) {
+                    pairs.push((*a_id.min(b_id), *a_id.max(b_id)));
+                }
+            }
+        }
+        pairs.sort();
+        pairs
+    }
+
+    fn query(&self, bounds: &Bounds) -> Vec<u32> {
+        let mut ids = self.proxies.iter().filter(|(_, b)| b.intersects(bounds)).map(|(id, _)| *id).collect::<Vec<u32>>();
+        ids.sort();
+        ids
+    }
+}
@@ -0,0 +1,265 @@
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::character_controller::{CharacterController, CharacterSettings, CollisionFlags, Ground};
+use crate::physics::collider::capsule_collider::CapsuleCollider;
+use crate::physics::collider::{narrow_phase, Collider, ALL_LAYERS};
+use crate::physics::collider_handle::ColliderHandle;
+use crate::physics::gjk;
+use crate::physics::gjk::CastHit;
+use crate::physics::pose::Pose;
+use crate::physics::rigidbody_handle::RigidbodyHandle;
+use crate::physics::world::World;
+
+///Passes pushing the capsule out of whatever it overlaps before moving.
+const DEPENETRATION_ITERATIONS: usize = 4;
+
+impl Default for CharacterSettings {
+    fn default() -> Self {
+        Self {
+            radius: 0.5,
+            height: 2_f64,
+            max_slope: 45_f64.to_radians(),
+            step_offset: 0.3,
+            skin_width: 0.01,
+            snap_distance: 0.3,
+            max_slides: 4,
+            collision_mask: ALL_LAYERS,
+        }
+    }
+}
+
+impl CharacterController {
+    ///Adds the character's capsule to the world, centered on the provided position.
+    pub fn new(world: &mut World, position: Vector3F64, settings: CharacterSettings) -> Self {
+        let rigidbody = world.create_rigidbody();
+        if let Some(mut body) = world.rigidbody_mut(&rigidbody) {
+            body.set_flags(0);
+            body.set_position(position);
+        }
+        let collider = world.attach_collider(&rigidbody, CapsuleCollider::new(settings.radius, settings.height));
+        world.sync_broad_phase();
+        Self {
+            settings,
+            rigidbody,
+            collider,
+            position,
+            ground: None,
+            flags: CollisionFlags::default(),
+        }
+    }
+
+    pub fn settings(&self) -> &CharacterSettings {
+        &self.settings
+    }
+
+    pub fn settings_mut(&mut self) -> &mut CharacterSettings {
+        &mut self.settings
+    }
+
+    pub fn rigidbody(&self) -> RigidbodyHandle {
+        self.rigidbody
+    }
+
+    pub fn collider(&self) -> ColliderHandle {
+        self.collider
+    }
+
+    ///Center of the capsule.
+    pub fn position(&self) -> Vector3F64 {
+        self.position
+    }
+
+    ///Where the bottom of the capsule touches down.
+    pub fn feet(&self) -> Vector3F64 {
+        self.position - Vector3F64::UP * (self.settings.height / 2_f64).max(self.settings.radius)
+    }
+
+    pub fn is_grounded(&self) -> bool {
+        self.ground.is_some()
+    }
+
+    pub fn ground(&self) -> Option<&Ground> {
+        self.ground.as_ref()
+    }
+
+    ///What the last move ran into.
+    pub fn collision_flags(&self) -> CollisionFlags {
+        self.flags
+    }
+
+    ///Puts the character somewhere else without sweeping it there, leaving the ground behind.
+    pub fn teleport(&mut self, world: &mut World, position: Vector3F64) {
+        self.position = position;
+        self.ground = None;
+        if let Some(mut body) = world.rigidbody_mut(&self.rigidbody) {
+            body.set_position(position);
+        }
+        world.sync_broad_phase();
+    }
+
+    ///Moves the character by a displacement, usually its velocity times the time step, sliding along anything in the way.
+    ///<br>The character rides along with the ground it stands on if that moved since the last call, climbs steps up to the step offset and slopes up to the max slope,
+    ///and sticks to the ground when walking down them unless the displacement points up.
+    pub fn move_by(&mut self, world: &mut World, displacement: Vector3F64) -> CollisionFlags {
+        world.sync_broad_phase();
+        let mut position = self.position;
+
+        if let Some(ground) = &self.ground {
+            if let Some(pose) = world.collider_pose(&ground.collider) {
+                position = pose.transform_point(ground.pose.inverse_transform_point(position));
+            }
+        }
+        position = self.depenetrate(world, position);
+
+        let vertical = displacement.dot(&Vector3F64::UP);
+        let horizontal = displacement - Vector3F64::UP * vertical;
+        let step = if self.ground.is_some() && horizontal.magnitude_squared() > 0_f64 { self.settings.step_offset } else { 0_f64 };
+
+        let mut result = self.attempt(world, position, horizontal, vertical, step);
+        //Stepping up onto something too steep to stand on isn't a step.
+        if step > 0_f64 && result.2.is_none() && result.3 {
+            result = self.attempt(world, position, horizontal, vertical, 0_f64);
+        }
+        let (position, flags, ground, _) = result;
+
+        self.position = position;
+        self.flags = flags;
+        self.ground = ground;
+        if let Some(mut body) = world.rigidbody_mut(&self.rigidbody) {
+            body.set_position(position);
+        }
+        world.sync_broad_phase();
+        flags
+    }
+
+    ///Moves up by the step and any upward displacement, across, then back down.
+    ///<br>Returns where the capsule ended up, what it touched, the ground it landed on and whether it landed on something too steep.
+    fn attempt(&self, world: &World, start: Vector3F64, horizontal: Vector3F64, vertical: f64, step: f64) -> (Vector3F64, CollisionFlags, Option<Ground>, bool) {
+        let mut flags = CollisionFlags::default();
+        let rise = step + vertical.max(0_f64);
+        let raised = self.slide(world, start, Vector3F64::UP * rise, false, &mut flags);
+        let stepped = (raised - start).dot(&Vector3F64::UP).min(step);
+        let across = self.slide(world, raised, horizontal, true, &mut flags);
+
+        let snap = if self.ground.is_some() && vertical <= 0_f64 { self.settings.snap_distance } else { 0_f64 };
+        let fall = stepped + (-vertical).max(0_f64);
+        let Some((collider, hit)) = self.cast(world, across, -Vector3F64::UP, fall + snap + self.settings.skin_width) else {
+            return (across - Vector3F64::UP * fall, flags, None, false);
+        };
+
+        if self.walkable(hit.normal) {
+            flags.below = true;
+            let landed = across - Vector3F64::UP * (hit.distance - self.settings.skin_width).max(0_f64);
+            let ground = world.collider_pose(&collider).map(|pose| Ground {
+                collider,
+                point: hit.point,
+                normal: hit.normal,
+                pose,
+            });
+            return (landed, flags, ground, false);
+        }
+
+        //Too steep to stand on, so slide down it instead, without snapping.
+        let slid = self.slide(world, across, -Vector3F64::UP * fall, false, &mut flags);
+        (slid, flags, None, true)
+    }
+
+    ///Moves along a displacement until something is hit, then along the surfaces hit for whatever is left of it.
+    ///<br>Horizontal moves treat surfaces too steep to stand on as vertical walls, so they can't be climbed by running into them.
+    fn slide(&self, world: &World, start: Vector3F64, displacement: Vector3F64, horizontal: bool, flags: &mut CollisionFlags) -> Vector3F64 {
+        let mut position = start;
+        let mut remaining = displacement;
+        let mut previous: Option<Vector3F64> = None;
+
+        for _ in 0..self.settings.max_slides.max(1) {
+            let distance = remaining.magnitude();
+            if distance < 1e-9 {
+                break;
+            }
+            let direction = remaining / distance;
+            let Some((_, hit)) = self.cast(world, position, direction, distance + self.settings.skin_width) else {
+                position += remaining;
+                break;
+            };
+
+            let travel = (hit.distance - self.settings.skin_width).clamp(0_f64, distance);
+            position += direction * travel;
+            remaining = direction * (distance - travel);
+            self.classify(hit.normal, flags);
+
+            let mut normal = hit.normal;
+            if horizontal && !self.walkable(normal) {
+                let flat = normal - Vector3F64::UP * normal.dot(&Vector3F64::UP);
+                if flat.magnitude_squared() > 1e-12 {
+                    normal = flat.normalize();
+                }
+            }
+            remaining -= normal * remaining.dot(&normal);
+            //Wedged between two surfaces, so only the crease between them is left to move along.
+            if let Some(previous) = previous {
+                if remaining.dot(&previous) < 0_f64 {
+                    let crease = previous.cross(&normal);
+                    remaining = if crease.magnitude_squared() > 1e-12 {
+                        let crease = crease.normalize();
+                        crease * remaining.dot(&crease)
+                    } else {
+                        Vector3F64::ZERO
+                    };
+                }
+            }
+            previous = Some(normal);
+        }
+        position
+    }
+
+    ///The nearest collider the capsule hits when swept from a position, leaving out the character itself and triggers.
+    fn cast(&self, world: &World, from: Vector3F64, direction: Vector3F64, distance: f64) -> Option<(ColliderHandle, CastHit)> {
+        let capsule = CapsuleCollider::new(self.settings.radius, self.settings.height);
+        let pose = Pose::from_position(from);
+        let start = capsule.compute_bounds(&pose);
+        let mut end = start;
+        end.center += direction * distance;
+
+        let support = capsule.support_map()?;
+        world.obstacles(start.union(&end), self.settings.collision_mask).into_iter()
+            .filter(|(handle, _, _)| *handle != self.collider)
+            .filter_map(|(handle, collider, collider_pose)| {
+                gjk::shape_cast(support, &pose, collider.support_map()?, &collider_pose, direction, distance).map(|hit| (handle, hit))
+            })
+            .min_by(|a, b| a.1.distance.total_cmp(&b.1.distance).then(a.0.id.cmp(&b.0.id)))
+    }
+
+    ///Pushes the capsule out of everything it overlaps, deepest first.
+    fn depenetrate(&self, world: &World, start: Vector3F64) -> Vector3F64 {
+        let capsule = CapsuleCollider::new(self.settings.radius, self.settings.height);
+        let mut position = start;
+        for _ in 0..DEPENETRATION_ITERATIONS {
+            let pose = Pose::from_position(position);
+            let deepest = world.obstacles(capsule.compute_bounds(&pose), self.settings.collision_mask).into_iter()
+                .filter(|(handle, _, _)| *handle != self.collider)
+                .filter_map(|(_, collider, collider_pose)| narrow_phase::contact_manifold_at(&capsule, &pose, collider, &collider_pose))
+                .max_by(|a, b| a.penetration().total_cmp(&b.penetration()));
+            let Some(manifold) = deepest else { break };
+            if manifold.penetration() <= 0_f64 {
+                break;
+            }
+            //The normal points from the capsule into the collider.
+            position -= manifold.normal * (manifold.penetration() + self.settings.skin_width);
+        }
+        position
+    }
+
+    fn walkable(&self, normal: Vector3F64) -> bool {
+        normal.dot(&Vector3F64::UP) >= self.settings.max_slope.cos() - 1e-9
+    }
+
+    fn classify(&self, normal: Vector3F64, flags: &mut CollisionFlags) {
+        let up = normal.dot(&Vector3F64::UP);
+        if self.walkable(normal) {
+            flags.below = true;
+        } else if up <= -self.settings.max_slope.cos() {
+            flags.above = true;
+        } else {
+            flags.sides = true;
+        }
+    }
+}
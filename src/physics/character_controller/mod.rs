@@ -0,0 +1,57 @@
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::collider_handle::ColliderHandle;
+use crate::physics::pose::Pose;
+use crate::physics::rigidbody_handle::RigidbodyHandle;
+
+pub mod imp;
+
+///The shape and movement limits of a [CharacterController].
+#[derive(Debug, Clone, Copy)]
+pub struct CharacterSettings {
+    pub radius: f64,
+    ///Total height of the capsule, caps included.
+    pub height: f64,
+    ///Steepest slope in radians the character can stand on and walk up. Steeper ones are treated as walls.
+    pub max_slope: f64,
+    ///Tallest ledge the character steps up onto without jumping.
+    pub step_offset: f64,
+    ///Gap kept between the capsule and everything it touches, so casts don't start out touching.
+    pub skin_width: f64,
+    ///How far the character is pulled down onto the ground when walking off a slope or down a step, instead of floating off.
+    pub snap_distance: f64,
+    ///Surfaces hit per move before giving up on the rest of it.
+    pub max_slides: usize,
+    ///Layers of colliders the character collides with.
+    pub collision_mask: u32,
+}
+
+///What a [CharacterController] touched during its last move.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CollisionFlags {
+    pub below: bool,
+    pub sides: bool,
+    pub above: bool,
+}
+
+///The walkable surface a [CharacterController] stands on.
+#[derive(Debug, Clone, Copy)]
+pub struct Ground {
+    pub collider: ColliderHandle,
+    pub point: Vector3F64,
+    pub normal: Vector3F64,
+    ///Pose of the ground's rigidbody as of the last move, for carrying the character along when it moves.
+    pub(crate) pose: Pose,
+}
+
+///A kinematic capsule moved by hand through a [crate::physics::world::World], sliding along whatever it runs into.
+///<br>The capsule is a rigidbody without [crate::physics::rigidbody::flags::RigidbodyFlags::UseKinematics], so the world never moves it but dynamic rigidbodies still bump into it.
+///Every move sweeps the capsule with shape casts: up by the step offset, along the displacement, then back down onto the ground.
+pub struct CharacterController {
+    pub(crate) settings: CharacterSettings,
+    pub(crate) rigidbody: RigidbodyHandle,
+    pub(crate) collider: ColliderHandle,
+    ///Center of the capsule.
+    pub(crate) position: Vector3F64,
+    pub(crate) ground: Option<Ground>,
+    pub(crate) flags: CollisionFlags,
+}
@@ -1,7 +1,64 @@
 use crate::linear_algebra::vector::types::Vector3F64;
 
+///An axis aligned bounding box, described by its center and half size along each axis.
 #[derive(Debug, Clone, Copy)]
 pub struct Bounds {
     pub center: Vector3F64,
     pub extents: Vector3F64
-}
\ No newline at end of file
+}
+
+impl Bounds {
+    pub fn from_min_max(min: Vector3F64, max: Vector3F64) -> Self {
+        Self {
+            center: (min + max) / 2_f64,
+            extents: (max - min) / 2_f64,
+        }
+    }
+
+    pub fn min(&self) -> Vector3F64 {
+        self.center - self.extents
+    }
+
+    pub fn max(&self) -> Vector3F64 {
+        self.center + self.extents
+    }
+
+    pub fn size(&self) -> Vector3F64 {
+        self.extents * 2_f64
+    }
+
+    ///Whether these bounds overlap another's. Touching bounds count as overlapping.
+    pub fn intersects(&self, other: &Bounds) -> bool {
+        (0..3).all(|i| (self.center[i] - other.center[i]).abs() <= self.extents[i] + other.extents[i])
+    }
+
+    ///Whether other lies entirely inside these bounds.
+    pub fn contains(&self, other: &Bounds) -> bool {
+        let (min, max) = (self.min(), self.max());
+        let (other_min, other_max) = (other.min(), other.max());
+        (0..3).all(|i| min[i] <= other_min[i] && max[i] >= other_max[i])
+    }
+
+    pub fn contains_point(&self, point: &Vector3F64) -> bool {
+        (0..3).all(|i| (point[i] - self.center[i]).abs() <= self.extents[i])
+    }
+
+    ///The smallest bounds containing both these bounds and another.
+    pub fn union(&self, other: &Bounds) -> Bounds {
+        Bounds::from_min_max(self.min().min(&other.min()), self.max().max(&other.max()))
+    }
+
+    ///Grows the bounds by margin on every side.
+    pub fn expanded(&self, margin: f64) -> Bounds {
+        Bounds {
+            center: self.center,
+            extents: self.extents + margin,
+        }
+    }
+
+    ///The surface area of the box, used as the cost heuristic when building bounding volume hierarchies.
+    pub fn surface_area(&self) -> f64 {
+        let (x, y, z) = self.size().xyz();
+        2_f64 * (x * y + y * z + z * x)
+    }
+}
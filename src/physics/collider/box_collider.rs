@@ -1,39 +1,125 @@
 use crate::linear_algebra::vector::types::Vector3F64;
 use crate::physics::collider::bounds::Bounds;
 use crate::physics::collider::Collider;
-use crate::physics::rigidbody_handle::RigidbodyHandle;
+use crate::physics::collider::narrow_phase;
+use crate::physics::collider::support::Support;
+use crate::physics::contact::ContactManifold;
+use crate::physics::pose::Pose;
+use crate::physics::rigidbody::Rigidbody;
 
 pub struct BoxCollider {
     size: Vector3F64,
     center: Vector3F64,
 
     _bounds: Bounds,
-    rigidbody_handle: Option<RigidbodyHandle>
+    rigidbody: Option<Rigidbody>
 }
 
 impl BoxCollider {
+    pub fn new(size: Vector3F64) -> Self {
+        Self {
+            size,
+            center: Vector3F64::ZERO,
+            _bounds: Bounds {
+                center: Vector3F64::ZERO,
+                extents: size / 2_f64,
+            },
+            rigidbody: None,
+        }
+    }
+
+    ///Offsets the box from its rigidbody's position, in the rigidbody's local space.
+    pub fn with_center(mut self, center: Vector3F64) -> Self {
+        self.center = center;
+        self
+    }
+
+    pub fn size(&self) -> Vector3F64 {
+        self.size
+    }
+
+    pub fn center(&self) -> Vector3F64 {
+        self.center
+    }
 
+    pub fn half_extents(&self) -> Vector3F64 {
+        self.size / 2_f64
+    }
+
+    ///The world space pose of the box itself, which is the rigidbody pose offset by [BoxCollider::center].
+    pub fn box_pose(&self, body: &Pose) -> Pose {
+        Pose::new(body.transform_point(self.center), body.rotation)
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        self._bounds
+    }
 }
 
 impl Collider for BoxCollider {
     fn update(&mut self) {
-        if let Some(rigid) = self.rigidbody() {
-            self._bounds = Bounds {
-                center: rigid.rigidbody.position() + self.center,
-                extents: self.size / 2_f64,
-            };
+        if let Some(pose) = self.pose() {
+            self._bounds = self.compute_bounds(&pose);
         }
     }
 
     fn check_collision(&self, b: &dyn Collider) -> bool {
-        false
+        self.contact_manifold(b).is_some()
+    }
+
+    fn contact_manifold(&self, b: &dyn Collider) -> Option<ContactManifold> {
+        narrow_phase::contact_manifold(self, b)
+    }
+
+    fn rigidbody(&self) -> Option<&Rigidbody> {
+        self.rigidbody.as_ref()
+    }
+
+    fn set_rigidbody(&mut self, rigidbody: Rigidbody) {
+        self.rigidbody = Some(rigidbody);
+    }
+
+    fn compute_bounds(&self, pose: &Pose) -> Bounds {
+        let pose = self.box_pose(pose);
+        let half = self.half_extents();
+        let axes = pose.axes();
+
+        let mut extents = Vector3F64::ZERO;
+        for i in 0..3 {
+            for j in 0..3 {
+                extents[i] += (axes[j][i] * half[j]).abs();
+            }
+        }
+
+        Bounds {
+            center: pose.position,
+            extents,
+        }
+    }
+
+    ///Solid cuboid: I = m(h^2 + d^2) / 12, ignoring any center offset.
+    fn inertia(&self, mass: f64) -> Vector3F64 {
+        let (x, y, z) = self.size.xyz();
+        let (x2, y2, z2) = (x * x, y * y, z * z);
+        Vector3F64::new([y2 + z2, x2 + z2, x2 + y2]) * (mass / 12_f64)
+    }
+
+    fn support_map(&self) -> Option<&dyn Support> {
+        Some(self)
     }
+}
 
-    fn point_of_contact(&self, b: &dyn Collider) -> Option<Vector3F64> {
-        None
+impl Support for BoxCollider {
+    fn support(&self, direction: &Vector3F64) -> Vector3F64 {
+        let half = self.half_extents();
+        let mut point = self.center;
+        for i in 0..3 {
+            point[i] += if direction[i] >= 0_f64 { half[i] } else { -half[i] };
+        }
+        point
     }
 
-    fn rigidbody(&self) -> Option<&RigidbodyHandle> {
-        self.rigidbody_handle.as_ref()
+    fn inertia(&self, mass: f64) -> Vector3F64 {
+        Collider::inertia(self, mass)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,110 @@
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::collider::bounds::Bounds;
+use crate::physics::collider::Collider;
+use crate::physics::collider::narrow_phase;
+use crate::physics::collider::support::Support;
+use crate::physics::contact::ContactManifold;
+use crate::physics::pose::Pose;
+use crate::physics::rigidbody::Rigidbody;
+
+///A cylinder capped with half spheres, standing along the rigidbody's local y axis.
+pub struct CapsuleCollider {
+    radius: f64,
+    height: f64,
+    rigidbody: Option<Rigidbody>,
+}
+
+impl CapsuleCollider {
+    ///A capsule of the provided total height, caps included. Heights below twice the radius give a sphere.
+    pub fn new(radius: f64, height: f64) -> Self {
+        Self {
+            radius,
+            height,
+            rigidbody: None,
+        }
+    }
+
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    pub fn height(&self) -> f64 {
+        self.height
+    }
+
+    ///Half the length of the segment between the centers of the caps.
+    pub fn half_segment(&self) -> f64 {
+        (self.height / 2_f64 - self.radius).max(0_f64)
+    }
+}
+
+impl Collider for CapsuleCollider {
+    fn update(&mut self) {
+
+    }
+
+    fn check_collision(&self, b: &dyn Collider) -> bool {
+        self.contact_manifold(b).is_some()
+    }
+
+    fn contact_manifold(&self, b: &dyn Collider) -> Option<ContactManifold> {
+        narrow_phase::contact_manifold(self, b)
+    }
+
+    fn rigidbody(&self) -> Option<&Rigidbody> {
+        self.rigidbody.as_ref()
+    }
+
+    fn set_rigidbody(&mut self, rigidbody: Rigidbody) {
+        self.rigidbody = Some(rigidbody);
+    }
+
+    fn compute_bounds(&self, pose: &Pose) -> Bounds {
+        let axis = pose.transform_vector(Vector3F64::UP);
+        let mut extents = Vector3F64::ONE * self.radius;
+        for i in 0..3 {
+            extents[i] += axis[i].abs() * self.half_segment();
+        }
+        Bounds {
+            center: pose.position,
+            extents,
+        }
+    }
+
+    ///A cylinder of the segment's length plus a sphere made of the two caps, each weighted by its volume.
+    fn inertia(&self, mass: f64) -> Vector3F64 {
+        let (r, h) = (self.radius, self.half_segment() * 2_f64);
+        let cylinder_volume = std::f64::consts::PI * r * r * h;
+        let sphere_volume = 4_f64 / 3_f64 * std::f64::consts::PI * r * r * r;
+        let cylinder = mass * cylinder_volume / (cylinder_volume + sphere_volume);
+        let sphere = mass - cylinder;
+
+        let along = cylinder * r * r / 2_f64 + sphere * 0.4 * r * r;
+        //The caps sit off center, which the parallel axis theorem accounts for.
+        let across = cylinder * (3_f64 * r * r + h * h) / 12_f64 + sphere * (0.4 * r * r + h * h / 4_f64 + 3_f64 * h * r / 8_f64);
+        Vector3F64::new([across, along, across])
+    }
+
+    fn support_map(&self) -> Option<&dyn Support> {
+        Some(self)
+    }
+}
+
+///A segment inflated by the radius.
+impl Support for CapsuleCollider {
+    fn support(&self, direction: &Vector3F64) -> Vector3F64 {
+        if direction.y() < 0_f64 {
+            Vector3F64::new([0_f64, -self.half_segment(), 0_f64])
+        } else {
+            Vector3F64::new([0_f64, self.half_segment(), 0_f64])
+        }
+    }
+
+    fn margin(&self) -> f64 {
+        self.radius
+    }
+
+    fn inertia(&self, mass: f64) -> Vector3F64 {
+        Collider::inertia(self, mass)
+    }
+}
@@ -0,0 +1,74 @@
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::collider::bounds::Bounds;
+use crate::physics::collider::convex_hull::ConvexHull;
+use crate::physics::collider::Collider;
+use crate::physics::collider::narrow_phase;
+use crate::physics::collider::support::Support;
+use crate::physics::contact::ContactManifold;
+use crate::physics::gjk;
+use crate::physics::pose::Pose;
+use crate::physics::rigidbody::Rigidbody;
+
+///A collider for any convex shape implementing [Support], collided using GJK and EPA.
+pub struct ConvexCollider<S: Support + 'static> {
+    shape: S,
+    rigidbody: Option<Rigidbody>,
+}
+
+pub type ConvexHullCollider = ConvexCollider<ConvexHull>;
+
+impl<S: Support + 'static> ConvexCollider<S> {
+    pub fn new(shape: S) -> Self {
+        Self {
+            shape,
+            rigidbody: None,
+        }
+    }
+
+    pub fn shape(&self) -> &S {
+        &self.shape
+    }
+}
+
+impl<S: Support + 'static> Collider for ConvexCollider<S> {
+    fn update(&mut self) {
+
+    }
+
+    fn check_collision(&self, b: &dyn Collider) -> bool {
+        self.contact_manifold(b).is_some()
+    }
+
+    fn contact_manifold(&self, b: &dyn Collider) -> Option<ContactManifold> {
+        narrow_phase::contact_manifold(self, b)
+    }
+
+    fn rigidbody(&self) -> Option<&Rigidbody> {
+        self.rigidbody.as_ref()
+    }
+
+    fn set_rigidbody(&mut self, rigidbody: Rigidbody) {
+        self.rigidbody = Some(rigidbody);
+    }
+
+    fn inertia(&self, mass: f64) -> Vector3F64 {
+        self.shape.inertia(mass)
+    }
+
+    ///Found from the shape's world space support points along each axis.
+    fn compute_bounds(&self, pose: &Pose) -> Bounds {
+        let mut min = Vector3F64::ZERO;
+        let mut max = Vector3F64::ZERO;
+        for i in 0..3 {
+            let mut axis = Vector3F64::ZERO;
+            axis[i] = 1_f64;
+            max[i] = gjk::world_support(&self.shape, pose, axis, true)[i];
+            min[i] = gjk::world_support(&self.shape, pose, -axis, true)[i];
+        }
+        Bounds::from_min_max(min, max)
+    }
+
+    fn support_map(&self) -> Option<&dyn Support> {
+        Some(&self.shape)
+    }
+}
@@ -0,0 +1,212 @@
+use crate::geometry::Geometry;
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::collider::support::Support;
+use crate::shared::traits::number::Number;
+
+///The smallest convex polyhedron containing a set of points, stored as vertices and outward facing triangles.
+///<br>Built with an incremental algorithm: starting from a tetrahedron, every point outside the current hull replaces the faces it can see.
+///<br>See <a href="https://en.wikipedia.org/wiki/Convex_hull_algorithms">Convex hull algorithms</a>
+#[derive(Debug, Clone)]
+pub struct ConvexHull {
+    vertices: Vec<Vector3F64>,
+    faces: Vec<[usize; 3]>,
+    volume: f64,
+    centroid: Vector3F64,
+}
+
+impl ConvexHull {
+    ///Computes the hull of a set of points, in the local space of the rigidbody it will be attached to.
+    ///<br>Returns None if there are less than four points or they are all coplanar.
+    pub fn new(points: &[Vector3F64]) -> Option<Self> {
+        let scale = points.iter().fold(1_f64, |scale, p| scale.max(p.x().abs()).max(p.y().abs()).max(p.z().abs()));
+        let epsilon = 1e-9 * scale;
+
+        let initial = initial_tetrahedron(points, epsilon)?;
+        let interior = initial.iter().fold(Vector3F64::ZERO, |sum, i| sum + points[*i]) / 4_f64;
+        let [a, b, c, d] = initial;
+        let mut faces = [[a, b, c], [a, d, b], [a, c, d], [b, d, c]].iter()
+            .map(|face| orient(points, *face, interior))
+            .collect::<Vec<[usize; 3]>>();
+
+        for (index, point) in points.iter().enumerate() {
+            if initial.contains(&index) {
+                continue;
+            }
+
+            let mut horizon: Vec<(usize, usize)> = vec![];
+            faces.retain(|face| {
+                if plane_distance(points, face, *point) <= epsilon {
+                    return true;
+                }
+                for k in 0..3 {
+                    let edge = (face[k], face[(k + 1) % 3]);
+                    match horizon.iter().position(|e| *e == (edge.1, edge.0)) {
+                        Some(shared) => {
+                            horizon.swap_remove(shared);
+                        }
+                        None => horizon.push(edge),
+                    }
+                }
+                false
+            });
+            faces.extend(horizon.into_iter().map(|(i, j)| [i, j, index]));
+        }
+
+        //Only keep the points the hull's faces actually use.
+        let mut remap = vec![usize::MAX; points.len()];
+        let mut vertices = vec![];
+        for face in &mut faces {
+            for i in face.iter_mut() {
+                if remap[*i] == usize::MAX {
+                    remap[*i] = vertices.len();
+                    vertices.push(points[*i]);
+                }
+                *i = remap[*i];
+            }
+        }
+
+        Some(Self::from_parts(vertices, faces))
+    }
+
+    ///Rebuilds a hull from vertices and faces that are already convex and outward wound, such as those of another hull.
+    pub(crate) fn from_parts(vertices: Vec<Vector3F64>, faces: Vec<[usize; 3]>) -> Self {
+        let (volume, centroid) = mass_properties(&vertices, &faces);
+        Self {
+            vertices,
+            faces,
+            volume,
+            centroid,
+        }
+    }
+
+    ///Computes the hull of a mesh's vertices, ignoring its indices.
+    pub fn from_geometry<N: Number>(geometry: &Geometry<3, N>) -> Option<Self> {
+        let points = geometry.vertices.iter()
+            .map(|v| Vector3F64::new([v[0].to_f64(), v[1].to_f64(), v[2].to_f64()]))
+            .collect::<Vec<Vector3F64>>();
+        Self::new(&points)
+    }
+
+    pub fn vertices(&self) -> &Vec<Vector3F64> {
+        &self.vertices
+    }
+
+    ///Triangles indexing into [ConvexHull::vertices], wound counter-clockwise when seen from outside.
+    pub fn faces(&self) -> &Vec<[usize; 3]> {
+        &self.faces
+    }
+
+    pub fn volume(&self) -> f64 {
+        self.volume
+    }
+
+    ///The center of mass assuming uniform density.
+    pub fn centroid(&self) -> Vector3F64 {
+        self.centroid
+    }
+
+    ///The outward unit normal of a face.
+    pub fn face_normal(&self, face: usize) -> Vector3F64 {
+        let [a, b, c] = self.faces[face].map(|i| self.vertices[i]);
+        (b - a).cross(&(c - a)).normalize()
+    }
+}
+
+impl Support for ConvexHull {
+    fn support(&self, direction: &Vector3F64) -> Vector3F64 {
+        let mut best = self.vertices[0];
+        let mut best_dot = best.dot(direction);
+        for vertex in &self.vertices[1..] {
+            let dot = vertex.dot(direction);
+            if dot > best_dot {
+                best = *vertex;
+                best_dot = dot;
+            }
+        }
+        best
+    }
+
+    ///Exact principal moments of the solid polyhedron about its centroid, found by summing the covariance of the tetrahedra fanning out from it.
+    ///<br>Products of inertia are ignored, like the rest of the engine's diagonal inertia tensors.
+    fn inertia(&self, mass: f64) -> Vector3F64 {
+        if self.volume <= 0_f64 {
+            return Vector3F64::ZERO;
+        }
+
+        let mut covariance = Vector3F64::ZERO;
+        for face in &self.faces {
+            let [a, b, c] = face.map(|i| self.vertices[i] - self.centroid);
+            let determinant = a.dot(&b.cross(&c));
+            for i in 0..3 {
+                let sum = a[i] + b[i] + c[i];
+                covariance[i] += determinant / 120_f64 * (a[i] * a[i] + b[i] * b[i] + c[i] * c[i] + sum * sum);
+            }
+        }
+
+        let density = mass / self.volume;
+        let trace = covariance.sum();
+        Vector3F64::new([trace - covariance[0], trace - covariance[1], trace - covariance[2]]) * density
+    }
+}
+
+fn plane_distance(points: &[Vector3F64], face: &[usize; 3], point: Vector3F64) -> f64 {
+    let [a, b, c] = face.map(|i| points[i]);
+    let normal = (b - a).cross(&(c - a)).normalize();
+    normal.dot(&(point - a))
+}
+
+///Winds a face counter-clockwise when seen from outside, given a point inside the hull.
+fn orient(points: &[Vector3F64], face: [usize; 3], interior: Vector3F64) -> [usize; 3] {
+    if plane_distance(points, &face, interior) > 0_f64 {
+        [face[0], face[2], face[1]]
+    } else {
+        face
+    }
+}
+
+///Four extreme points spanning a tetrahedron with volume.
+fn initial_tetrahedron(points: &[Vector3F64], epsilon: f64) -> Option<[usize; 4]> {
+    if points.len() < 4 {
+        return None;
+    }
+    let farthest = |distance: &dyn Fn(Vector3F64) -> f64| {
+        (0..points.len()).max_by(|i, j| distance(points[*i]).total_cmp(&distance(points[*j])))
+    };
+
+    let a = farthest(&|p| -p.x())?;
+    let b = farthest(&|p| p.distance(&points[a]))?;
+    let direction = (points[b] - points[a]).normalize();
+    if points[a].distance(&points[b]) <= epsilon {
+        return None;
+    }
+
+    let c = farthest(&|p| direction.cross(&(p - points[a])).magnitude())?;
+    let normal = (points[b] - points[a]).cross(&(points[c] - points[a]));
+    if normal.magnitude() <= epsilon {
+        return None;
+    }
+    let normal = normal.normalize();
+
+    let d = farthest(&|p| normal.dot(&(p - points[a])).abs())?;
+    if normal.dot(&(points[d] - points[a])).abs() <= epsilon {
+        return None;
+    }
+    Some([a, b, c, d])
+}
+
+///Volume and centroid of a closed, outward wound triangle mesh.
+fn mass_properties(vertices: &[Vector3F64], faces: &[[usize; 3]]) -> (f64, Vector3F64) {
+    let reference = vertices.iter().fold(Vector3F64::ZERO, |sum, v| sum + *v) / vertices.len() as f64;
+    let mut volume = 0_f64;
+    let mut weighted = Vector3F64::ZERO;
+    for face in faces {
+        let [a, b, c] = face.map(|i| vertices[i] - reference);
+        let tetrahedron = a.dot(&b.cross(&c)) / 6_f64;
+        volume += tetrahedron;
+        weighted += (a + b + c) * (tetrahedron / 4_f64);
+    }
+    if volume <= 0_f64 {
+        return (0_f64, reference);
+    }
+    (volume, reference + weighted / volume)
+}
@@ -1,16 +1,50 @@
 use downcast_rs::{Downcast, impl_downcast};
 use crate::linear_algebra::vector::types::Vector3F64;
-use crate::physics::rigidbody_handle::RigidbodyHandle;
+use crate::physics::collider::bounds::Bounds;
+use crate::physics::collider::support::Support;
+use crate::physics::contact::ContactManifold;
+use crate::physics::pose::Pose;
+use crate::physics::rigidbody::Rigidbody;
 
 pub mod sphere_collider;
+pub mod capsule_collider;
 pub mod box_collider;
 pub mod bounds;
+pub mod narrow_phase;
+pub mod support;
+pub mod convex_hull;
+pub mod convex_collider;
+pub mod ray_cast;
+
+///The layer colliders are on unless told otherwise.
+pub const DEFAULT_LAYER: u32 = 1;
+///A layer mask matching every layer.
+pub const ALL_LAYERS: u32 = u32::MAX;
 
 impl_downcast!(Collider);
 
 pub trait Collider: Downcast {
     fn update(&mut self);
     fn check_collision(&self, b: &dyn Collider) -> bool;
-    fn point_of_contact(&self, b: &dyn Collider) -> Option<Vector3F64>;
-    fn rigidbody(&self) -> Option<&RigidbodyHandle>;
-}
\ No newline at end of file
+    ///Computes the contact manifold between this collider (A) and another (B), if they are touching.
+    fn contact_manifold(&self, b: &dyn Collider) -> Option<ContactManifold>;
+    ///The standalone rigidbody this collider follows when used on its own. Colliders attached to a [crate::physics::world::World] are placed by the world instead.
+    fn rigidbody(&self) -> Option<&Rigidbody>;
+    fn set_rigidbody(&mut self, rigidbody: Rigidbody);
+
+    ///The principal moments of inertia of this shape for a given mass, about the rigidbody's origin.
+    fn inertia(&self, mass: f64) -> Vector3F64;
+
+    ///The world space bounding box of this collider when its rigidbody is at the provided pose.
+    fn compute_bounds(&self, pose: &Pose) -> Bounds;
+
+    ///The world space pose of the rigidbody this collider is attached to.
+    fn pose(&self) -> Option<Pose> {
+        self.rigidbody().map(|rigidbody| rigidbody.pose())
+    }
+
+    ///The shape's support function, letting GJK and EPA collide it with any other collider that has one.
+    fn support_map(&self) -> Option<&dyn Support> {
+        None
+    }
+}
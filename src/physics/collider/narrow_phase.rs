@@ -0,0 +1,360 @@
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::collider::box_collider::BoxCollider;
+use crate::physics::collider::Collider;
+use crate::physics::collider::sphere_collider::SphereCollider;
+use crate::physics::contact::{ContactManifold, ContactPoint, FeatureId};
+use crate::physics::gjk;
+use crate::physics::pose::Pose;
+
+///Below this length a direction is treated as degenerate.
+const EPSILON: f64 = 1e-9;
+
+///Computes the contact manifold between two colliders using the poses of their attached rigidbodies.
+///<br>Returns None if either collider has no rigidbody, if the colliders are apart, or if the pair of shapes is not supported.
+///Pairs without a dedicated routine fall back to GJK and EPA when both colliders have a support map.
+pub fn contact_manifold(a: &dyn Collider, b: &dyn Collider) -> Option<ContactManifold> {
+    let a_pose = a.pose()?;
+    let b_pose = b.pose()?;
+    contact_manifold_at(a, &a_pose, b, &b_pose)
+}
+
+///Computes the contact manifold between two colliders placed at explicit rigidbody poses.
+pub fn contact_manifold_at(a: &dyn Collider, a_pose: &Pose, b: &dyn Collider, b_pose: &Pose) -> Option<ContactManifold> {
+    if let Some(a_sphere) = a.downcast_ref::<SphereCollider>() {
+        if let Some(b_sphere) = b.downcast_ref::<SphereCollider>() {
+            return sphere_sphere(a_pose.position, a_sphere.radius(), b_pose.position, b_sphere.radius());
+        }
+        if let Some(b_box) = b.downcast_ref::<BoxCollider>() {
+            return sphere_box(a_pose.position, a_sphere.radius(), &b_box.box_pose(b_pose), b_box.half_extents());
+        }
+    }
+
+    if let Some(a_box) = a.downcast_ref::<BoxCollider>() {
+        if let Some(b_sphere) = b.downcast_ref::<SphereCollider>() {
+            return sphere_box(b_pose.position, b_sphere.radius(), &a_box.box_pose(a_pose), a_box.half_extents())
+                .map(ContactManifold::flip);
+        }
+        if let Some(b_box) = b.downcast_ref::<BoxCollider>() {
+            return box_box(&a_box.box_pose(a_pose), a_box.half_extents(), &b_box.box_pose(b_pose), b_box.half_extents());
+        }
+    }
+
+    //Any other pair of convex shapes goes through GJK and EPA.
+    if let (Some(a_support), Some(b_support)) = (a.support_map(), b.support_map()) {
+        return gjk::contact(a_support, a_pose, b_support, b_pose);
+    }
+
+    None
+}
+
+///Contact between two spheres. The manifold always holds a single point.
+pub fn sphere_sphere(a_center: Vector3F64, a_radius: f64, b_center: Vector3F64, b_radius: f64) -> Option<ContactManifold> {
+    let offset = b_center - a_center;
+    let distance = offset.magnitude();
+    let depth = a_radius + b_radius - distance;
+
+    if depth < 0_f64 {
+        return None;
+    }
+
+    let normal = if distance > EPSILON {
+        offset / distance
+    } else {
+        Vector3F64::UP
+    };
+
+    let position = a_center + normal * (a_radius - depth / 2_f64);
+    Some(ContactManifold::single(normal, ContactPoint::new(position, depth, FeatureId(0))))
+}
+
+///Contact between a sphere (A) and an oriented box (B). The manifold always holds a single point.
+///<br>The feature id records which face, edge or vertex region of the box the sphere touches.
+pub fn sphere_box(center: Vector3F64, radius: f64, box_pose: &Pose, half_extents: Vector3F64) -> Option<ContactManifold> {
+    let local = box_pose.inverse_transform_point(center);
+
+    let mut closest = local;
+    let mut region = 0_u32;
+    for i in 0..3 {
+        let code = if local[i] < -half_extents[i] {
+            closest[i] = -half_extents[i];
+            0
+        } else if local[i] > half_extents[i] {
+            closest[i] = half_extents[i];
+            2
+        } else {
+            1
+        };
+        region = region * 3 + code;
+    }
+
+    let offset = closest - local;
+    let distance = offset.magnitude();
+
+    let (local_normal, depth) = if distance > EPSILON {
+        (offset / distance, radius - distance)
+    } else {
+        //The sphere's center is inside the box, push it out through the nearest face.
+        let mut axis = 0;
+        let mut face_distance = f64::MAX;
+        for i in 0..3 {
+            let d = half_extents[i] - local[i].abs();
+            if d < face_distance {
+                face_distance = d;
+                axis = i;
+            }
+        }
+        let mut normal = Vector3F64::ZERO;
+        normal[axis] = if local[axis] < 0_f64 { 1_f64 } else { -1_f64 };
+        region = 27 + axis as u32 * 2 + (local[axis] >= 0_f64) as u32;
+        (normal, radius + face_distance)
+    };
+
+    if depth < 0_f64 {
+        return None;
+    }
+
+    let normal = box_pose.transform_vector(local_normal);
+    let position = center + normal * (radius - depth / 2_f64);
+    Some(ContactManifold::single(normal, ContactPoint::new(position, depth, FeatureId(region))))
+}
+
+#[derive(Debug, Clone, Copy)]
+enum SeparatingAxis {
+    FaceA(usize),
+    FaceB(usize),
+    Edge(usize, usize),
+}
+
+///Contact between two oriented boxes using the separating axis theorem.
+///<br>Face contacts clip the incident face against the reference face's side planes, producing up to four points.
+///Edge contacts produce a single point between the closest points of the two edges.
+///<br>See <a href="https://en.wikipedia.org/wiki/Hyperplane_separation_theorem">Hyperplane separation theorem</a>
+pub fn box_box(a: &Pose, a_half: Vector3F64, b: &Pose, b_half: Vector3F64) -> Option<ContactManifold> {
+    let a_axes = a.axes();
+    let b_axes = b.axes();
+    let offset = b.position - a.position;
+
+    let mut abs_r = [[0_f64; 3]; 3];
+    for i in 0..3 {
+        for j in 0..3 {
+            abs_r[i][j] = a_axes[i].dot(&b_axes[j]).abs() + EPSILON;
+        }
+    }
+
+    let mut face_a = (f64::MAX, 0, Vector3F64::ZERO);
+    for i in 0..3 {
+        let ra = a_half[i];
+        let rb = (0..3).map(|j| b_half[j] * abs_r[i][j]).sum::<f64>();
+        let distance = offset.dot(&a_axes[i]);
+        let penetration = ra + rb - distance.abs();
+        if penetration < 0_f64 {
+            return None;
+        }
+        if penetration < face_a.0 {
+            let normal = if distance < 0_f64 { -a_axes[i] } else { a_axes[i] };
+            face_a = (penetration, i, normal);
+        }
+    }
+
+    let mut face_b = (f64::MAX, 0, Vector3F64::ZERO);
+    for j in 0..3 {
+        let ra = (0..3).map(|i| a_half[i] * abs_r[i][j]).sum::<f64>();
+        let rb = b_half[j];
+        let distance = offset.dot(&b_axes[j]);
+        let penetration = ra + rb - distance.abs();
+        if penetration < 0_f64 {
+            return None;
+        }
+        if penetration < face_b.0 {
+            let normal = if distance < 0_f64 { -b_axes[j] } else { b_axes[j] };
+            face_b = (penetration, j, normal);
+        }
+    }
+
+    let mut edge = (f64::MAX, (0, 0), Vector3F64::ZERO);
+    for i in 0..3 {
+        for j in 0..3 {
+            let axis = a_axes[i].cross(&b_axes[j]);
+            let length = axis.magnitude();
+            if length < 1e-6 {
+                continue;
+            }
+            let axis = axis / length;
+            let ra = (0..3).map(|k| a_half[k] * a_axes[k].dot(&axis).abs()).sum::<f64>();
+            let rb = (0..3).map(|k| b_half[k] * b_axes[k].dot(&axis).abs()).sum::<f64>();
+            let distance = offset.dot(&axis);
+            let penetration = ra + rb - distance.abs();
+            if penetration < 0_f64 {
+                return None;
+            }
+            if penetration < edge.0 {
+                let normal = if distance < 0_f64 { -axis } else { axis };
+                edge = (penetration, (i, j), normal);
+            }
+        }
+    }
+
+    //Prefer faces over edges, and A over B, unless the alternative is clearly better. This stops the chosen feature from flickering between steps.
+    let (mut penetration, mut axis, mut normal) = (face_a.0, SeparatingAxis::FaceA(face_a.1), face_a.2);
+    if face_b.0 < 0.95 * penetration - 1e-4 {
+        (penetration, axis, normal) = (face_b.0, SeparatingAxis::FaceB(face_b.1), face_b.2);
+    }
+    if edge.0 < 0.95 * penetration - 1e-4 {
+        (penetration, axis, normal) = (edge.0, SeparatingAxis::Edge(edge.1.0, edge.1.1), edge.2);
+    }
+
+    match axis {
+        SeparatingAxis::FaceA(i) => {
+            let points = clip_box_faces(a, &a_axes, a_half, i, normal, b, &b_axes, b_half, 0);
+            finish_manifold(normal, points)
+        }
+        SeparatingAxis::FaceB(j) => {
+            let points = clip_box_faces(b, &b_axes, b_half, j, -normal, a, &a_axes, a_half, 1);
+            finish_manifold(normal, points)
+        }
+        SeparatingAxis::Edge(i, j) => {
+            let point = edge_contact(a, &a_axes, a_half, i, b, &b_axes, b_half, j, normal, penetration);
+            Some(ContactManifold::single(normal, point))
+        }
+    }
+}
+
+fn finish_manifold(normal: Vector3F64, points: Vec<ContactPoint>) -> Option<ContactManifold> {
+    if points.is_empty() {
+        return None;
+    }
+    let mut manifold = ContactManifold { normal, points };
+    manifold.reduce();
+    Some(manifold)
+}
+
+///Clips the incident box's most anti-parallel face against the side planes of the reference face.
+///<br>reference_normal points out of the reference box's face towards the incident box.
+fn clip_box_faces(
+    reference: &Pose,
+    reference_axes: &[Vector3F64; 3],
+    reference_half: Vector3F64,
+    reference_axis: usize,
+    reference_normal: Vector3F64,
+    incident: &Pose,
+    incident_axes: &[Vector3F64; 3],
+    incident_half: Vector3F64,
+    reference_is_b: u32,
+) -> Vec<ContactPoint> {
+    //The incident face is the one whose normal is most opposed to the reference normal.
+    let mut incident_axis = 0;
+    let mut best = f64::MIN;
+    for k in 0..3 {
+        let d = incident_axes[k].dot(&reference_normal).abs();
+        if d > best {
+            best = d;
+            incident_axis = k;
+        }
+    }
+    let incident_sign = if incident_axes[incident_axis].dot(&reference_normal) > 0_f64 { -1_f64 } else { 1_f64 };
+    let incident_center = incident.position + incident_axes[incident_axis] * (incident_sign * incident_half[incident_axis]);
+
+    let (u1, u2) = ((incident_axis + 1) % 3, (incident_axis + 2) % 3);
+    let e1 = incident_axes[u1] * incident_half[u1];
+    let e2 = incident_axes[u2] * incident_half[u2];
+
+    let mut polygon = vec![
+        (incident_center + e1 + e2, 0_u32),
+        (incident_center - e1 + e2, 1),
+        (incident_center - e1 - e2, 2),
+        (incident_center + e1 - e2, 3),
+    ];
+
+    let (s1, s2) = ((reference_axis + 1) % 3, (reference_axis + 2) % 3);
+    let planes = [
+        (reference_axes[s1], reference_half[s1]),
+        (-reference_axes[s1], reference_half[s1]),
+        (reference_axes[s2], reference_half[s2]),
+        (-reference_axes[s2], reference_half[s2]),
+    ];
+
+    for (plane, (plane_normal, half)) in planes.iter().enumerate() {
+        let offset = plane_normal.dot(&reference.position) + half;
+        polygon = clip_polygon(&polygon, *plane_normal, offset, plane as u32);
+        if polygon.is_empty() {
+            return vec![];
+        }
+    }
+
+    let reference_face = reference.position + reference_normal * reference_half[reference_axis];
+    let reference_sign = (reference_normal.dot(&reference_axes[reference_axis]) > 0_f64) as u32;
+    let reference_face_id = reference_axis as u32 * 2 + reference_sign;
+    let incident_face_id = incident_axis as u32 * 2 + (incident_sign > 0_f64) as u32;
+
+    polygon.into_iter().filter_map(|(point, vertex)| {
+        let separation = (point - reference_face).dot(&reference_normal);
+        if separation > 0_f64 {
+            return None;
+        }
+        let feature = FeatureId(reference_is_b << 15 | reference_face_id << 12 | incident_face_id << 9 | vertex);
+        Some(ContactPoint::new(point - reference_normal * (separation / 2_f64), -separation, feature))
+    }).collect()
+}
+
+///Sutherland-Hodgman clipping of a polygon against the half space `normal · p <= offset`.
+///<br>New vertices get an id derived from the clipping plane and the vertex they were clipped from.
+fn clip_polygon(polygon: &[(Vector3F64, u32)], normal: Vector3F64, offset: f64, plane: u32) -> Vec<(Vector3F64, u32)> {
+    let mut out = vec![];
+    for i in 0..polygon.len() {
+        let (p, p_id) = polygon[i];
+        let (q, _) = polygon[(i + 1) % polygon.len()];
+        let dp = normal.dot(&p) - offset;
+        let dq = normal.dot(&q) - offset;
+
+        if dp <= 0_f64 {
+            out.push((p, p_id));
+        }
+        if (dp <= 0_f64) != (dq <= 0_f64) {
+            let t = dp / (dp - dq);
+            out.push((p + (q - p) * t, (plane + 1) << 4 | (p_id & 0xF)));
+        }
+    }
+    out
+}
+
+fn edge_contact(
+    a: &Pose,
+    a_axes: &[Vector3F64; 3],
+    a_half: Vector3F64,
+    i: usize,
+    b: &Pose,
+    b_axes: &[Vector3F64; 3],
+    b_half: Vector3F64,
+    j: usize,
+    normal: Vector3F64,
+    penetration: f64,
+) -> ContactPoint {
+    //The edge of A furthest along the normal, and the edge of B furthest against it.
+    let mut a_point = a.position;
+    let mut b_point = b.position;
+    for k in 0..3 {
+        if k != i {
+            a_point += a_axes[k] * (a_half[k] * normal.dot(&a_axes[k]).signum());
+        }
+        if k != j {
+            b_point -= b_axes[k] * (b_half[k] * normal.dot(&b_axes[k]).signum());
+        }
+    }
+
+    let (da, db) = (a_axes[i], b_axes[j]);
+    let r = a_point - b_point;
+    let d = da.dot(&db);
+    let (c, f) = (da.dot(&r), db.dot(&r));
+    let denominator = 1_f64 - d * d;
+
+    let (s, t) = if denominator > EPSILON {
+        ((d * f - c) / denominator, (f - d * c) / denominator)
+    } else {
+        (0_f64, 0_f64)
+    };
+    let s = s.clamp(-a_half[i], a_half[i]);
+    let t = t.clamp(-b_half[j], b_half[j]);
+
+    let position = ((a_point + da * s) + (b_point + db * t)) / 2_f64;
+    ContactPoint::new(position, penetration, FeatureId(1 << 16 | (i * 3 + j) as u32))
+}
@@ -0,0 +1,87 @@
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::collider::box_collider::BoxCollider;
+use crate::physics::collider::Collider;
+use crate::physics::collider::sphere_collider::SphereCollider;
+use crate::physics::gjk;
+use crate::physics::gjk::CastHit;
+use crate::physics::pose::Pose;
+
+///Casts a ray against a collider whose rigidbody is at the provided pose.
+///<br>Spheres and boxes are solved analytically, other shapes with a support map through GJK.
+///A ray starting inside the shape hits it at a distance of 0, with the normal facing back along the ray.
+pub fn raycast(collider: &dyn Collider, pose: &Pose, origin: Vector3F64, direction: Vector3F64, max_distance: f64) -> Option<CastHit> {
+    let direction = direction.normalize();
+    if let Some(sphere) = collider.downcast_ref::<SphereCollider>() {
+        return ray_sphere(pose.position, sphere.radius(), origin, direction, max_distance);
+    }
+    if let Some(cuboid) = collider.downcast_ref::<BoxCollider>() {
+        return ray_box(&cuboid.box_pose(pose), cuboid.half_extents(), origin, direction, max_distance);
+    }
+    gjk::raycast(collider.support_map()?, pose, origin, direction, max_distance)
+}
+
+///Ray against sphere, solving |origin + t * direction - center| = radius for the smallest t.
+pub fn ray_sphere(center: Vector3F64, radius: f64, origin: Vector3F64, direction: Vector3F64, max_distance: f64) -> Option<CastHit> {
+    let offset = origin - center;
+    let c = offset.magnitude_squared() - radius * radius;
+    if c <= 0_f64 {
+        return Some(CastHit { distance: 0_f64, point: origin, normal: -direction });
+    }
+
+    let b = offset.dot(&direction);
+    let discriminant = b * b - c;
+    if b > 0_f64 || discriminant < 0_f64 {
+        return None;
+    }
+
+    let distance = -b - discriminant.sqrt();
+    if distance > max_distance {
+        return None;
+    }
+    let point = origin + direction * distance;
+    Some(CastHit { distance, point, normal: (point - center).normalize() })
+}
+
+///Ray against an oriented box using the slab method in the box's local space.
+///<br>See <a href="https://en.wikipedia.org/wiki/Slab_method">Slab method</a>
+pub fn ray_box(box_pose: &Pose, half_extents: Vector3F64, origin: Vector3F64, direction: Vector3F64, max_distance: f64) -> Option<CastHit> {
+    let local_origin = box_pose.inverse_transform_point(origin);
+    let local_direction = box_pose.inverse_transform_vector(direction);
+
+    let mut enter = f64::NEG_INFINITY;
+    let mut exit = f64::INFINITY;
+    let mut axis = 0;
+    for i in 0..3 {
+        if local_direction[i].abs() < 1e-12 {
+            if local_origin[i].abs() > half_extents[i] {
+                return None;
+            }
+            continue;
+        }
+        let inverse = 1_f64 / local_direction[i];
+        let (mut near, mut far) = ((-half_extents[i] - local_origin[i]) * inverse, (half_extents[i] - local_origin[i]) * inverse);
+        if near > far {
+            std::mem::swap(&mut near, &mut far);
+        }
+        if near > enter {
+            enter = near;
+            axis = i;
+        }
+        exit = exit.min(far);
+    }
+
+    if enter > exit || exit < 0_f64 || enter > max_distance {
+        return None;
+    }
+    if enter <= 0_f64 {
+        return Some(CastHit { distance: 0_f64, point: origin, normal: -direction });
+    }
+
+    let mut normal = Vector3F64::ZERO;
+    normal[axis] = -local_direction[axis].signum();
+    Some(CastHit {
+        distance: enter,
+        point: origin + direction * enter,
+        normal: box_pose.transform_vector(normal),
+    })
+}
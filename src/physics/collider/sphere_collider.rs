@@ -1,22 +1,27 @@
 use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::collider::bounds::Bounds;
 use crate::physics::collider::Collider;
-use crate::physics::rigidbody_handle::RigidbodyHandle;
+use crate::physics::collider::narrow_phase;
+use crate::physics::collider::support::Support;
+use crate::physics::contact::ContactManifold;
+use crate::physics::pose::Pose;
+use crate::physics::rigidbody::Rigidbody;
 
 pub struct SphereCollider {
     radius: f64,
-    rigidbody_handle: Option<RigidbodyHandle>,
+    rigidbody: Option<Rigidbody>,
 }
 
 impl SphereCollider {
     pub fn new(radius: f64) -> Self {
         Self {
             radius,
-            rigidbody_handle: None,
+            rigidbody: None,
         }
     }
 
-    pub fn set_handle(&mut self, rigidbody_handle: RigidbodyHandle) {
-        self.rigidbody_handle = Some(rigidbody_handle);
+    pub fn radius(&self) -> f64 {
+        self.radius
     }
 }
 
@@ -26,34 +31,50 @@ impl Collider for SphereCollider {
     }
 
     fn check_collision(&self, b: &dyn Collider) -> bool {
-        if let Some(a_rigid) = self.rigidbody() {
-            if let Some(b_rigid) = b.rigidbody() {
-                if let Some(b_sphere) = b.downcast_ref::<SphereCollider>() {
-                    let d = a_rigid.rigidbody.position().distance(&b_rigid.rigidbody.position());
-                    return (self.radius + b_sphere.radius) >= d;
-                }
-            }
-        }
-        false
+        self.contact_manifold(b).is_some()
+    }
+
+    fn contact_manifold(&self, b: &dyn Collider) -> Option<ContactManifold> {
+        narrow_phase::contact_manifold(self, b)
+    }
+
+    fn rigidbody(&self) -> Option<&Rigidbody> {
+        self.rigidbody.as_ref()
     }
 
-    fn point_of_contact(&self, b: &dyn Collider) -> Option<Vector3F64> {
-        if let Some(a_rigid) = self.rigidbody() {
-            if let Some(b_rigid) = b.rigidbody() {
-                if let Some(b_sphere) = b.downcast_ref::<SphereCollider>() {
-                    let c = b_rigid.rigidbody.position() - a_rigid.rigidbody.position();
-                    let d = c.magnitude();
-                    if (self.radius + b_sphere.radius) >= d {
-                        return Some(c.normalize() * self.radius);
-                    }
-                }
-            }
+    fn set_rigidbody(&mut self, rigidbody: Rigidbody) {
+        self.rigidbody = Some(rigidbody);
+    }
+
+    fn compute_bounds(&self, pose: &Pose) -> Bounds {
+        Bounds {
+            center: pose.position,
+            extents: Vector3F64::ONE * self.radius,
         }
-        None
     }
 
-    fn rigidbody(&self) -> Option<&RigidbodyHandle> {
-        self.rigidbody_handle.as_ref()
+    ///Solid sphere: I = 2/5 mr^2
+    fn inertia(&self, mass: f64) -> Vector3F64 {
+        Vector3F64::ONE * (0.4 * mass * self.radius * self.radius)
+    }
+
+    fn support_map(&self) -> Option<&dyn Support> {
+        Some(self)
+    }
+}
+
+///A point inflated by the radius.
+impl Support for SphereCollider {
+    fn support(&self, _direction: &Vector3F64) -> Vector3F64 {
+        Vector3F64::ZERO
+    }
+
+    fn margin(&self) -> f64 {
+        self.radius
+    }
+
+    fn inertia(&self, mass: f64) -> Vector3F64 {
+        Collider::inertia(self, mass)
     }
 }
 
@@ -61,7 +82,7 @@ impl Default for SphereCollider {
     fn default() -> Self {
         Self {
             radius: 1.0,
-            rigidbody_handle: None,
+            rigidbody: None,
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,28 @@
+use crate::linear_algebra::vector::types::Vector3F64;
+
+///A convex shape described by its support function, which is all GJK and EPA need to collide it with any other such shape.
+///<br>Shapes may be split into a core and a margin: the full shape is the core inflated by a sphere of radius [Support::margin].
+///Spheres are a point with a margin and capsules a segment with one, which keeps their curved surfaces exact.
+///<br>See <a href="https://en.wikipedia.org/wiki/Support_function">Support function</a>
+pub trait Support {
+    ///The point of the shape's core furthest along a direction, in the rigidbody's local space.
+    fn support(&self, direction: &Vector3F64) -> Vector3F64;
+
+    ///Radius the core is inflated by.
+    fn margin(&self) -> f64 {
+        0_f64
+    }
+
+    ///The principal moments of inertia for a given mass.
+    ///<br>Defaults to those of the shape's local bounding box.
+    fn inertia(&self, mass: f64) -> Vector3F64 {
+        let mut size = Vector3F64::ZERO;
+        for i in 0..3 {
+            let mut axis = Vector3F64::ZERO;
+            axis[i] = 1_f64;
+            size[i] = self.support(&axis)[i] - self.support(&-axis)[i] + 2_f64 * self.margin();
+        }
+        let (x2, y2, z2) = (size.x() * size.x(), size.y() * size.y(), size.z() * size.z());
+        Vector3F64::new([y2 + z2, x2 + z2, x2 + y2]) * (mass / 12_f64)
+    }
+}
@@ -0,0 +1,34 @@
+use crate::physics::collider_handle::ColliderHandle;
+use crate::physics::contact::ContactManifold;
+
+///Something that happened between two colliders during a [crate::physics::world::World] step.
+#[derive(Debug, Clone)]
+pub enum CollisionEvent {
+    ///Two colliders started touching. The manifold's normal points from a to b.
+    CollisionEnter {
+        a: ColliderHandle,
+        b: ColliderHandle,
+        manifold: ContactManifold,
+    },
+    ///Two colliders that were touching last step still are.
+    CollisionStay {
+        a: ColliderHandle,
+        b: ColliderHandle,
+        manifold: ContactManifold,
+    },
+    ///Two colliders stopped touching.
+    CollisionExit {
+        a: ColliderHandle,
+        b: ColliderHandle,
+    },
+    ///A collider started overlapping a trigger.
+    TriggerEnter {
+        trigger: ColliderHandle,
+        other: ColliderHandle,
+    },
+    ///A collider stopped overlapping a trigger.
+    TriggerExit {
+        trigger: ColliderHandle,
+        other: ColliderHandle,
+    },
+}
@@ -0,0 +1,109 @@
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::contact::{ContactManifold, ContactPoint, FeatureId, MAX_MANIFOLD_POINTS};
+
+impl ContactPoint {
+    pub fn new(position: Vector3F64, depth: f64, feature: FeatureId) -> Self {
+        Self {
+            position,
+            depth,
+            feature,
+        }
+    }
+}
+
+impl ContactManifold {
+    pub fn new(normal: Vector3F64) -> Self {
+        Self {
+            normal,
+            points: vec![],
+        }
+    }
+
+    ///Creates a manifold holding a single contact point.
+    pub fn single(normal: Vector3F64, point: ContactPoint) -> Self {
+        Self {
+            normal,
+            points: vec![point],
+        }
+    }
+
+    pub fn push(&mut self, point: ContactPoint) {
+        self.points.push(point);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    ///The deepest penetration of any point in this manifold.
+    pub fn penetration(&self) -> f64 {
+        self.points.iter().map(|p| p.depth).fold(0_f64, f64::max)
+    }
+
+    ///Swaps the roles of A and B, reversing the normal.
+    pub fn flip(mut self) -> Self {
+        self.normal = -self.normal;
+        self
+    }
+
+    ///Reduces the manifold down to at most [MAX_MANIFOLD_POINTS] points.
+    ///<br>The deepest point is always kept, the rest are picked to cover the largest area of the contact region, which keeps stacking stable.
+    pub fn reduce(&mut self) {
+        if self.points.len() <= MAX_MANIFOLD_POINTS {
+            return;
+        }
+
+        let points = std::mem::take(&mut self.points);
+        let normal = self.normal;
+
+        let deepest = (0..points.len())
+            .max_by(|a, b| points[*a].depth.total_cmp(&points[*b].depth))
+            .unwrap();
+        let a = points[deepest].position;
+
+        let farthest = (0..points.len())
+            .max_by(|i, j| {
+                let di = points[*i].position.distance(&a);
+                let dj = points[*j].position.distance(&a);
+                di.total_cmp(&dj)
+            })
+            .unwrap();
+        let b = points[farthest].position;
+
+        let signed_area = |p: Vector3F64, q: Vector3F64, r: Vector3F64| (q - p).cross(&(r - p)).dot(&normal);
+
+        let third = (0..points.len())
+            .max_by(|i, j| {
+                let ai = signed_area(a, b, points[*i].position).abs();
+                let aj = signed_area(a, b, points[*j].position).abs();
+                ai.total_cmp(&aj)
+            })
+            .unwrap();
+        let c = points[third].position;
+
+        let winding = signed_area(a, b, c).signum();
+        let fourth = (0..points.len())
+            .filter(|i| *i != deepest && *i != farthest && *i != third)
+            .map(|i| {
+                let p = points[i].position;
+                let outside = (winding * signed_area(a, b, p))
+                    .min(winding * signed_area(b, c, p))
+                    .min(winding * signed_area(c, a, p));
+                (i, outside)
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .filter(|(_, outside)| *outside < 0_f64)
+            .map(|(i, _)| i);
+
+        let mut kept = vec![deepest, farthest, third];
+        kept.extend(fourth);
+        kept.sort();
+        kept.dedup();
+
+        self.points = kept.into_iter().map(|i| points[i]).collect();
+    }
+}
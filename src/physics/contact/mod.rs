@@ -0,0 +1,29 @@
+use crate::linear_algebra::vector::types::Vector3F64;
+
+pub mod imp;
+
+///The maximum number of points kept in a single contact manifold.
+pub const MAX_MANIFOLD_POINTS: usize = 4;
+
+///Identifies the pair of features (faces, edges or vertices) that produced a contact point.
+///<br>Feature ids are stable between steps as long as the same features stay in contact, allowing a solver to warm-start from last step's impulses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct FeatureId(pub u32);
+
+///A single world space point of contact between two colliders.
+#[derive(Debug, Clone, Copy)]
+pub struct ContactPoint {
+    ///The world space position of the contact, halfway between the two touching surfaces.
+    pub position: Vector3F64,
+    ///How far the two surfaces overlap at this point along the manifold normal.
+    pub depth: f64,
+    pub feature: FeatureId,
+}
+
+///The contact region between two colliders A and B.
+///<br>The normal points from A towards B, so separating the colliders means moving B along the normal and A against it.
+#[derive(Debug, Clone)]
+pub struct ContactManifold {
+    pub normal: Vector3F64,
+    pub points: Vec<ContactPoint>,
+}
@@ -0,0 +1,160 @@
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::force_field::{Drag, ForceField, Gravity, PointAttractor, Turbulence, Vortex};
+use crate::physics::world::World;
+
+impl Gravity {
+    pub fn new(acceleration: Vector3F64) -> Self {
+        Self { acceleration }
+    }
+
+    ///The gravity the world applies to its rigidbodies, so particles fall alongside them.
+    pub fn from_world(world: &World) -> Self {
+        Self::new(world.gravity())
+    }
+}
+
+impl ForceField for Gravity {
+    fn acceleration(&self, _position: Vector3F64, _velocity: Vector3F64, _time: f64) -> Vector3F64 {
+        self.acceleration
+    }
+
+    fn accumulate(&self, _positions: &[Vector3F64], _velocities: &[Vector3F64], _time: f64, accelerations: &mut [Vector3F64]) {
+        for acceleration in accelerations {
+            *acceleration += self.acceleration;
+        }
+    }
+}
+
+impl PointAttractor {
+    ///An attractor reaching infinitely far.
+    pub fn new(center: Vector3F64, strength: f64) -> Self {
+        Self {
+            center,
+            strength,
+            radius: f64::INFINITY,
+            softening: 0.01,
+        }
+    }
+
+    pub fn with_radius(mut self, radius: f64) -> Self {
+        self.radius = radius;
+        self
+    }
+}
+
+impl ForceField for PointAttractor {
+    ///a = s d̂ / (|d|² + ε)
+    fn acceleration(&self, position: Vector3F64, _velocity: Vector3F64, _time: f64) -> Vector3F64 {
+        let offset = self.center - position;
+        let distance_squared = offset.magnitude_squared();
+        if distance_squared > self.radius * self.radius || distance_squared == 0_f64 {
+            return Vector3F64::ZERO;
+        }
+        offset / distance_squared.sqrt() * (self.strength / (distance_squared + self.softening))
+    }
+}
+
+impl Drag {
+    pub fn new(linear: f64) -> Self {
+        Self {
+            linear,
+            quadratic: 0_f64,
+        }
+    }
+
+    pub fn with_quadratic(mut self, quadratic: f64) -> Self {
+        self.quadratic = quadratic;
+        self
+    }
+}
+
+impl ForceField for Drag {
+    ///a = -(k₁ + k₂|v|) v
+    fn acceleration(&self, _position: Vector3F64, velocity: Vector3F64, _time: f64) -> Vector3F64 {
+        velocity * -(self.linear + self.quadratic * velocity.magnitude())
+    }
+}
+
+impl Vortex {
+    pub fn new(center: Vector3F64, axis: Vector3F64, strength: f64, radius: f64) -> Self {
+        assert!(axis.magnitude_squared() > 0_f64, "a vortex needs a nonzero axis to swirl around");
+        Self {
+            center,
+            axis: axis.normalize(),
+            strength,
+            radius,
+            inward: 0_f64,
+        }
+    }
+
+    pub fn with_inward(mut self, inward: f64) -> Self {
+        self.inward = inward;
+        self
+    }
+}
+
+impl ForceField for Vortex {
+    fn acceleration(&self, position: Vector3F64, _velocity: Vector3F64, _time: f64) -> Vector3F64 {
+        let offset = position - self.center;
+        let radial = offset - self.axis * offset.dot(&self.axis);
+        let distance = radial.magnitude();
+        if distance >= self.radius || distance == 0_f64 {
+            return Vector3F64::ZERO;
+        }
+        let falloff = 1_f64 - distance / self.radius;
+        let outward = radial / distance;
+        (self.axis.cross(&outward) * self.strength - outward * self.inward) * falloff
+    }
+}
+
+impl Turbulence {
+    pub fn new(strength: f64, frequency: f64, seed: u32) -> Self {
+        Self {
+            strength,
+            frequency,
+            speed: 1_f64,
+            seed,
+        }
+    }
+}
+
+impl ForceField for Turbulence {
+    ///Each component is its own value noise, sampled at a position drifting through the noise over time.
+    fn acceleration(&self, position: Vector3F64, _velocity: Vector3F64, time: f64) -> Vector3F64 {
+        let sample = position * self.frequency + Vector3F64::ONE * (time * self.speed);
+        let mut acceleration = Vector3F64::ZERO;
+        for i in 0..3 {
+            acceleration[i] = value_noise(sample, self.seed.wrapping_add(i as u32 * 0x9E37)) * self.strength;
+        }
+        acceleration
+    }
+}
+
+///Smoothly interpolated random values at the integer lattice, between -1 and 1.
+///<br>See <a href="https://en.wikipedia.org/wiki/Value_noise">Value noise</a>
+fn value_noise(p: Vector3F64, seed: u32) -> f64 {
+    let base = [p[0].floor(), p[1].floor(), p[2].floor()];
+    let t = [0, 1, 2].map(|i| {
+        let f = p[i] - base[i];
+        f * f * (3_f64 - 2_f64 * f)
+    });
+    let corner = |dx: i64, dy: i64, dz: i64| lattice(base[0] as i64 + dx, base[1] as i64 + dy, base[2] as i64 + dz, seed);
+    let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+
+    let x00 = lerp(corner(0, 0, 0), corner(1, 0, 0), t[0]);
+    let x10 = lerp(corner(0, 1, 0), corner(1, 1, 0), t[0]);
+    let x01 = lerp(corner(0, 0, 1), corner(1, 0, 1), t[0]);
+    let x11 = lerp(corner(0, 1, 1), corner(1, 1, 1), t[0]);
+    lerp(lerp(x00, x10, t[1]), lerp(x01, x11, t[1]), t[2])
+}
+
+fn lattice(x: i64, y: i64, z: i64, seed: u32) -> f64 {
+    let mut h = (x as u64).wrapping_mul(0x8DA6B343)
+        ^ (y as u64).wrapping_mul(0xD8163841)
+        ^ (z as u64).wrapping_mul(0xCB1AB31F)
+        ^ (seed as u64).wrapping_mul(0x9E3779B97F4A7C15);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xFF51AFD7ED558CCD);
+    h ^= h >> 33;
+    (h >> 11) as f64 / (1_u64 << 52) as f64 - 1_f64
+}
@@ -0,0 +1,65 @@
+use crate::linear_algebra::vector::types::Vector3F64;
+
+pub mod imp;
+
+///Accelerates particles depending on where they are and how fast they move.
+///<br>Fields are applied in bulk: every field adds its acceleration to a whole column at once, so any number of them can be stacked.
+pub trait ForceField {
+    ///The acceleration of a particle at a position moving at a velocity, time seconds into the simulation.
+    fn acceleration(&self, position: Vector3F64, velocity: Vector3F64, time: f64) -> Vector3F64;
+
+    ///Adds this field's acceleration of every particle to the accumulated accelerations.
+    fn accumulate(&self, positions: &[Vector3F64], velocities: &[Vector3F64], time: f64, accelerations: &mut [Vector3F64]) {
+        for ((acceleration, position), velocity) in accelerations.iter_mut().zip(positions).zip(velocities) {
+            *acceleration += self.acceleration(*position, *velocity, time);
+        }
+    }
+}
+
+///The same acceleration everywhere.
+#[derive(Debug, Clone, Copy)]
+pub struct Gravity {
+    pub acceleration: Vector3F64,
+}
+
+///Pulls particles towards a point with an inverse square falloff, or pushes them away when the strength is negative.
+#[derive(Debug, Clone, Copy)]
+pub struct PointAttractor {
+    pub center: Vector3F64,
+    ///Acceleration at a distance of 1.
+    pub strength: f64,
+    ///Particles further away than this are unaffected.
+    pub radius: f64,
+    ///Added to the squared distance so particles passing through the center don't get flung away.
+    pub softening: f64,
+}
+
+///Slows particles down in proportion to their speed, and optionally to its square.
+#[derive(Debug, Clone, Copy)]
+pub struct Drag {
+    pub linear: f64,
+    pub quadratic: f64,
+}
+
+///Swirls particles around an axis through a point, fading out linearly towards the radius.
+#[derive(Debug, Clone, Copy)]
+pub struct Vortex {
+    pub center: Vector3F64,
+    pub axis: Vector3F64,
+    ///Tangential acceleration on the axis, positive spinning counter-clockwise around it.
+    pub strength: f64,
+    pub radius: f64,
+    ///Acceleration pulling particles in towards the axis, keeping them in orbit.
+    pub inward: f64,
+}
+
+///Smoothly varying pseudo random acceleration, drifting over time.
+#[derive(Debug, Clone, Copy)]
+pub struct Turbulence {
+    pub strength: f64,
+    ///How many times the noise changes per unit of distance.
+    pub frequency: f64,
+    ///How fast the noise changes over time.
+    pub speed: f64,
+    pub seed: u32,
+}
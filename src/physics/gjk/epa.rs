@@ -0,0 +1,163 @@
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::collider::support::Support;
+use crate::physics::gjk::{minkowski_support, SupportPoint};
+use crate::physics::pose::Pose;
+
+const MAX_ITERATIONS: usize = 64;
+const TOLERANCE: f64 = 1e-6;
+
+///How deep two shapes overlap, found by EPA.
+#[derive(Debug, Clone, Copy)]
+pub struct Penetration {
+    ///Unit normal pointing from A to B.
+    pub normal: Vector3F64,
+    pub depth: f64,
+    ///The deepest point of A inside B, in world space.
+    pub point_a: Vector3F64,
+    ///The deepest point of B inside A, in world space.
+    pub point_b: Vector3F64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Face {
+    indices: [usize; 3],
+    normal: Vector3F64,
+    distance: f64,
+}
+
+///Runs the Expanding Polytope Algorithm on the margin inflated shapes, starting from a GJK simplex that encloses the origin.
+///<br>The polytope is grown towards the boundary of the Minkowski difference until the face closest to the origin stops moving,
+///that face's normal and distance being the minimum translation that separates the shapes.
+///<br>See <a href="https://dyn4j.org/2010/05/epa-expanding-polytope-algorithm/">EPA (Expanding Polytope Algorithm)</a>
+pub fn epa(a: &dyn Support, a_pose: &Pose, b: &dyn Support, b_pose: &Pose, simplex: Vec<SupportPoint>) -> Option<Penetration> {
+    let support = |direction: Vector3F64| minkowski_support(a, a_pose, b, b_pose, direction, true);
+    let mut vertices = complete_simplex(simplex, &support)?;
+
+    let interior = vertices.iter().fold(Vector3F64::ZERO, |sum, v| sum + v.point) / 4_f64;
+    let mut faces = [[0, 1, 2], [0, 3, 1], [0, 2, 3], [1, 3, 2]].iter()
+        .filter_map(|indices| make_face(&vertices, *indices, interior))
+        .collect::<Vec<Face>>();
+    if faces.len() < 4 {
+        return None;
+    }
+
+    let mut closest = faces[0];
+    for _ in 0..MAX_ITERATIONS {
+        closest = *faces.iter().min_by(|x, y| x.distance.total_cmp(&y.distance))?;
+
+        let w = support(closest.normal);
+        if w.point.dot(&closest.normal) - closest.distance < TOLERANCE {
+            break;
+        }
+
+        //Faces the new point can see are removed, leaving a hole bounded by the horizon edges.
+        let mut horizon: Vec<(usize, usize)> = vec![];
+        faces.retain(|face| {
+            if face.normal.dot(&(w.point - vertices[face.indices[0]].point)) <= 0_f64 {
+                return true;
+            }
+            for k in 0..3 {
+                let edge = (face.indices[k], face.indices[(k + 1) % 3]);
+                match horizon.iter().position(|e| *e == (edge.1, edge.0)) {
+                    Some(shared) => {
+                        horizon.swap_remove(shared);
+                    }
+                    None => horizon.push(edge),
+                }
+            }
+            false
+        });
+
+        vertices.push(w);
+        let index = vertices.len() - 1;
+        faces.extend(horizon.iter().filter_map(|(i, j)| make_face(&vertices, [*i, *j, index], interior)));
+        if faces.is_empty() {
+            break;
+        }
+    }
+
+    let [i, j, k] = closest.indices;
+    let weights = barycentric(closest.normal * closest.distance, vertices[i].point, vertices[j].point, vertices[k].point);
+    Some(Penetration {
+        normal: closest.normal,
+        depth: closest.distance,
+        point_a: vertices[i].a * weights[0] + vertices[j].a * weights[1] + vertices[k].a * weights[2],
+        point_b: vertices[i].b * weights[0] + vertices[j].b * weights[1] + vertices[k].b * weights[2],
+    })
+}
+
+///GJK may stop with fewer than four points when the origin lies on the simplex, so search for more until there's a tetrahedron with volume.
+fn complete_simplex<F: Fn(Vector3F64) -> SupportPoint>(mut simplex: Vec<SupportPoint>, support: &F) -> Option<Vec<SupportPoint>> {
+    let axes = [Vector3F64::RIGHT, Vector3F64::UP, Vector3F64::FORWARD];
+    let is_new = |simplex: &[SupportPoint], candidate: &SupportPoint| {
+        match simplex.len() {
+            1 => candidate.point.distance(&simplex[0].point) > TOLERANCE,
+            2 => {
+                let direction = (simplex[1].point - simplex[0].point).normalize();
+                direction.cross(&(candidate.point - simplex[0].point)).magnitude() > TOLERANCE
+            }
+            _ => {
+                let normal = (simplex[1].point - simplex[0].point).cross(&(simplex[2].point - simplex[0].point)).normalize();
+                normal.dot(&(candidate.point - simplex[0].point)).abs() > TOLERANCE
+            }
+        }
+    };
+
+    while simplex.len() < 4 {
+        let directions = match simplex.len() {
+            1 | 2 => axes.iter().flat_map(|axis| [*axis, -*axis]).collect::<Vec<Vector3F64>>(),
+            _ => {
+                let normal = (simplex[1].point - simplex[0].point).cross(&(simplex[2].point - simplex[0].point));
+                vec![normal, -normal]
+            }
+        };
+        let directions = if simplex.len() == 2 {
+            //Directions perpendicular to the segment.
+            let segment = simplex[1].point - simplex[0].point;
+            directions.iter().map(|d| segment.cross(d)).filter(|d| d.magnitude_squared() > TOLERANCE).collect()
+        } else {
+            directions
+        };
+
+        let candidate = directions.into_iter()
+            .map(support)
+            .find(|candidate| is_new(&simplex, candidate))?;
+        simplex.push(candidate);
+    }
+    Some(simplex)
+}
+
+///A face with its normal pointing away from a point known to be inside the polytope.
+fn make_face(vertices: &[SupportPoint], indices: [usize; 3], interior: Vector3F64) -> Option<Face> {
+    let [a, b, c] = indices.map(|i| vertices[i].point);
+    let normal = (b - a).cross(&(c - a));
+    let length = normal.magnitude();
+    if length < 1e-12 {
+        return None;
+    }
+    let mut normal = normal / length;
+    let mut indices = indices;
+    if normal.dot(&(a - interior)) < 0_f64 {
+        normal = -normal;
+        indices.swap(1, 2);
+    }
+    Some(Face {
+        indices,
+        normal,
+        distance: normal.dot(&a).max(0_f64),
+    })
+}
+
+///Barycentric coordinates of a point projected onto the plane of a triangle.
+fn barycentric(p: Vector3F64, a: Vector3F64, b: Vector3F64, c: Vector3F64) -> [f64; 3] {
+    let (v0, v1, v2) = (b - a, c - a, p - a);
+    let (d00, d01, d11) = (v0.dot(&v0), v0.dot(&v1), v1.dot(&v1));
+    let (d20, d21) = (v2.dot(&v0), v2.dot(&v1));
+    let denominator = d00 * d11 - d01 * d01;
+    if denominator.abs() < 1e-12 {
+        return [1_f64, 0_f64, 0_f64];
+    }
+    let v = (d11 * d20 - d01 * d21) / denominator;
+    let w = (d00 * d21 - d01 * d20) / denominator;
+    [1_f64 - v - w, v, w]
+}
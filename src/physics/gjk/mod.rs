@@ -0,0 +1,396 @@
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::collider::support::Support;
+use crate::physics::contact::{ContactManifold, ContactPoint, FeatureId};
+use crate::physics::pose::Pose;
+use crate::physics::solver::contact_constraint::tangent_basis;
+
+pub mod epa;
+
+const MAX_ITERATIONS: usize = 64;
+const EPSILON: f64 = 1e-10;
+///How many tilted normals are tried when filling out a manifold.
+const PERTURBATIONS: usize = 4;
+///Length of the tilt added to the unit normal, roughly its angle in radians.
+const PERTURBATION: f64 = 0.1;
+///How far outside the other shape a perturbed point may lie and still count as touching.
+const LATERAL_TOLERANCE: f64 = 1e-3;
+///How close a cast has to get to a shape to count as touching it.
+const CAST_TOLERANCE: f64 = 1e-7;
+///Perturbed points closer than this to an existing one are dropped.
+const MERGE_DISTANCE: f64 = 1e-3;
+
+///A vertex of the Minkowski difference A - B, along with the points of A and B it came from.
+#[derive(Debug, Clone, Copy)]
+pub struct SupportPoint {
+    pub point: Vector3F64,
+    pub a: Vector3F64,
+    pub b: Vector3F64,
+}
+
+#[derive(Debug, Clone)]
+pub enum GjkResult {
+    ///The cores are apart. The points are the closest points on each core in world space.
+    Separated {
+        distance: f64,
+        point_a: Vector3F64,
+        point_b: Vector3F64,
+    },
+    ///The cores overlap. The simplex encloses (or touches) the origin and seeds EPA.
+    Intersecting(Vec<SupportPoint>),
+}
+
+///The world space support point of a shape at a pose, optionally inflated by the shape's margin.
+pub fn world_support(shape: &dyn Support, pose: &Pose, direction: Vector3F64, inflate: bool) -> Vector3F64 {
+    let local = shape.support(&pose.inverse_transform_vector(direction));
+    let point = pose.transform_point(local);
+    let length = direction.magnitude();
+    if inflate && shape.margin() > 0_f64 && length > EPSILON {
+        return point + direction * (shape.margin() / length);
+    }
+    point
+}
+
+pub fn minkowski_support(a: &dyn Support, a_pose: &Pose, b: &dyn Support, b_pose: &Pose, direction: Vector3F64, inflate: bool) -> SupportPoint {
+    let a = world_support(a, a_pose, direction, inflate);
+    let b = world_support(b, b_pose, -direction, inflate);
+    SupportPoint {
+        point: a - b,
+        a,
+        b,
+    }
+}
+
+///Runs the Gilbert–Johnson–Keerthi algorithm on the cores of two shapes, finding either their closest points or a simplex proving they overlap.
+///<br>See <a href="https://en.wikipedia.org/wiki/Gilbert%E2%80%93Johnson%E2%80%93Keerthi_distance_algorithm">Gilbert–Johnson–Keerthi distance algorithm</a>
+pub fn gjk(a: &dyn Support, a_pose: &Pose, b: &dyn Support, b_pose: &Pose) -> GjkResult {
+    let mut direction = b_pose.position - a_pose.position;
+    if direction.magnitude_squared() < EPSILON {
+        direction = Vector3F64::RIGHT;
+    }
+
+    let first = minkowski_support(a, a_pose, b, b_pose, -direction, false);
+    let mut simplex = vec![first];
+    let mut weights = vec![1_f64];
+    let mut v = first.point;
+
+    for _ in 0..MAX_ITERATIONS {
+        let v_squared = v.magnitude_squared();
+        if v_squared < EPSILON {
+            return GjkResult::Intersecting(simplex);
+        }
+
+        let w = minkowski_support(a, a_pose, b, b_pose, -v, false);
+        let duplicate = simplex.iter().any(|s| s.point.distance(&w.point) < 1e-12);
+        if duplicate || v_squared - v.dot(&w.point) <= 1e-10 * v_squared.max(1_f64) {
+            break;
+        }
+
+        simplex.push(w);
+        let (closest, reduced, reduced_weights) = closest_on_simplex(&simplex);
+        if reduced.len() == 4 {
+            return GjkResult::Intersecting(reduced);
+        }
+        simplex = reduced;
+        weights = reduced_weights;
+        v = closest;
+    }
+
+    //Out of iterations right against the origin, the cores touch.
+    if v.magnitude_squared() < EPSILON {
+        return GjkResult::Intersecting(simplex);
+    }
+
+    let point_a = simplex.iter().zip(&weights).fold(Vector3F64::ZERO, |sum, (s, w)| sum + s.a * *w);
+    let point_b = simplex.iter().zip(&weights).fold(Vector3F64::ZERO, |sum, (s, w)| sum + s.b * *w);
+    GjkResult::Separated {
+        distance: v.magnitude(),
+        point_a,
+        point_b,
+    }
+}
+
+///The distance between two shapes including their margins, or 0 if they overlap.
+pub fn distance(a: &dyn Support, a_pose: &Pose, b: &dyn Support, b_pose: &Pose) -> f64 {
+    match gjk(a, a_pose, b, b_pose) {
+        GjkResult::Separated { distance, .. } => (distance - a.margin() - b.margin()).max(0_f64),
+        GjkResult::Intersecting(_) => 0_f64,
+    }
+}
+
+pub fn intersects(a: &dyn Support, a_pose: &Pose, b: &dyn Support, b_pose: &Pose) -> bool {
+    match gjk(a, a_pose, b, b_pose) {
+        GjkResult::Separated { distance, .. } => distance <= a.margin() + b.margin(),
+        GjkResult::Intersecting(_) => true,
+    }
+}
+
+///Contact between any two support shapes. Cores that are apart but within the margins touch along the line between their closest points, overlapping cores are resolved with EPA.
+///<br>Flat faces resting on each other need more than the one point this finds to stay stable, so the manifold is filled out with [perturbed_points].
+pub fn contact(a: &dyn Support, a_pose: &Pose, b: &dyn Support, b_pose: &Pose) -> Option<ContactManifold> {
+    let margin = a.margin() + b.margin();
+    let (normal, point) = match gjk(a, a_pose, b, b_pose) {
+        GjkResult::Separated { distance, point_a, point_b } => {
+            //Touching cores come back as intersecting, so the distance is never too small to divide by.
+            if distance > margin {
+                return None;
+            }
+            let normal = (point_b - point_a) / distance;
+            let surface_a = point_a + normal * a.margin();
+            let surface_b = point_b - normal * b.margin();
+            (normal, ContactPoint::new((surface_a + surface_b) / 2_f64, margin - distance, FeatureId(0)))
+        }
+        GjkResult::Intersecting(simplex) => {
+            let penetration = epa::epa(a, a_pose, b, b_pose, simplex)?;
+            let position = (penetration.point_a + penetration.point_b) / 2_f64;
+            (penetration.normal, ContactPoint::new(position, penetration.depth, FeatureId(0)))
+        }
+    };
+
+    let mut manifold = ContactManifold::single(normal, point);
+    for point in perturbed_points(a, a_pose, b, b_pose, normal) {
+        if manifold.points.iter().all(|p| p.position.distance(&point.position) > MERGE_DISTANCE) {
+            manifold.push(point);
+        }
+    }
+    manifold.reduce();
+    Some(manifold)
+}
+
+///Finds more contact points by tilting the contact normal slightly in several directions.
+///<br>On a flat face every tilt picks out a different corner, which is kept if it lies within the other shape.
+pub fn perturbed_points(a: &dyn Support, a_pose: &Pose, b: &dyn Support, b_pose: &Pose, normal: Vector3F64) -> Vec<ContactPoint> {
+    let tangents = tangent_basis(normal);
+    let a_plane = world_support(a, a_pose, normal, true).dot(&normal);
+    let b_plane = world_support(b, b_pose, -normal, true).dot(&normal);
+
+    let mut points = vec![];
+    for k in 0..PERTURBATIONS {
+        let angle = std::f64::consts::TAU * (k as f64 + 0.5) / PERTURBATIONS as f64;
+        let tilt = (tangents[0] * angle.cos() + tangents[1] * angle.sin()) * PERTURBATION;
+
+        //A corner of A pushed into B's supporting plane.
+        let corner = world_support(a, a_pose, normal + tilt, true);
+        let depth = corner.dot(&normal) - b_plane;
+        if depth >= 0_f64 && point_distance(corner - normal * depth, b, b_pose) <= LATERAL_TOLERANCE {
+            points.push(ContactPoint::new(corner - normal * (depth / 2_f64), depth, FeatureId(1 + k as u32)));
+        }
+
+        //A corner of B pushed into A's supporting plane.
+        let corner = world_support(b, b_pose, -normal + tilt, true);
+        let depth = a_plane - corner.dot(&normal);
+        if depth >= 0_f64 && point_distance(corner + normal * depth, a, a_pose) <= LATERAL_TOLERANCE {
+            points.push(ContactPoint::new(corner + normal * (depth / 2_f64), depth, FeatureId(1 + (PERTURBATIONS + k) as u32)));
+        }
+    }
+    points
+}
+
+///A single point, used to measure how far a point is from a shape.
+struct PointShape(Vector3F64);
+
+impl Support for PointShape {
+    fn support(&self, _direction: &Vector3F64) -> Vector3F64 {
+        self.0
+    }
+}
+
+fn point_distance(point: Vector3F64, shape: &dyn Support, pose: &Pose) -> f64 {
+    distance(&PointShape(point), &Pose::default(), shape, pose)
+}
+
+///Where a cast first touches a shape.
+#[derive(Debug, Clone, Copy)]
+pub struct CastHit {
+    ///How far along the direction the cast travelled, 0 if it started out touching.
+    pub distance: f64,
+    pub point: Vector3F64,
+    ///The shape's outward surface normal at the hit point.
+    pub normal: Vector3F64,
+}
+
+///Casts a ray against a convex set given by its support function, by conservative advancement of GJK.
+///<br>The support closure maps a direction to a point of the set, with [SupportPoint::a] being the point reported as hit.
+///<br>See van den Bergen, Ray Casting against General Convex Objects with Application to Continuous Collision Detection.
+pub fn cast<F: Fn(Vector3F64) -> SupportPoint>(support: F, origin: Vector3F64, direction: Vector3F64, max_distance: f64) -> Option<CastHit> {
+    let direction = direction.normalize();
+    let mut lambda = 0_f64;
+    let mut x = origin;
+    let mut normal = -direction;
+
+    let mut simplex: Vec<SupportPoint> = vec![];
+    let mut weights: Vec<f64> = vec![];
+    let mut v = x - support(direction).point;
+
+    for _ in 0..MAX_ITERATIONS {
+        let v_squared = v.magnitude_squared();
+        if v_squared < CAST_TOLERANCE * CAST_TOLERANCE {
+            break;
+        }
+
+        let p = support(v);
+        let w = x - p.point;
+        let vw = v.dot(&w);
+        if vw > 0_f64 {
+            let vr = v.dot(&direction);
+            if vr >= 0_f64 {
+                return None;
+            }
+            lambda -= vw / vr;
+            if lambda > max_distance {
+                return None;
+            }
+            x = origin + direction * lambda;
+            normal = v;
+        }
+
+        if simplex.iter().any(|s| s.point.distance(&p.point) < 1e-12) {
+            break;
+        }
+        simplex.push(p);
+
+        //The simplex lives in the set, its vertices relative to the ray's current position.
+        let relative = simplex.iter().map(|s| SupportPoint { point: x - s.point, ..*s }).collect::<Vec<SupportPoint>>();
+        let (closest, reduced, reduced_weights) = closest_on_simplex(&relative);
+        simplex = reduced.iter().map(|s| SupportPoint { point: x - s.point, ..*s }).collect();
+        weights = reduced_weights;
+        v = closest;
+        if simplex.len() == 4 {
+            break;
+        }
+    }
+
+    let point = if simplex.is_empty() {
+        x
+    } else {
+        simplex.iter().zip(&weights).fold(Vector3F64::ZERO, |sum, (s, w)| sum + s.a * *w)
+    };
+    Some(CastHit {
+        distance: lambda,
+        point,
+        normal: if lambda > 0_f64 { normal.normalize() } else { -direction },
+    })
+}
+
+///Casts a ray against a shape at a pose, including its margin.
+pub fn raycast(shape: &dyn Support, pose: &Pose, origin: Vector3F64, direction: Vector3F64, max_distance: f64) -> Option<CastHit> {
+    let support = |d: Vector3F64| {
+        let point = world_support(shape, pose, d, true);
+        SupportPoint { point, a: point, b: Vector3F64::ZERO }
+    };
+    cast(support, origin, direction, max_distance)
+}
+
+///Sweeps shape A along a direction, returning where it first touches shape B, which stays put.
+///<br>This is a ray cast from the origin against the Minkowski difference B - A.
+pub fn shape_cast(a: &dyn Support, a_pose: &Pose, b: &dyn Support, b_pose: &Pose, direction: Vector3F64, max_distance: f64) -> Option<CastHit> {
+    let support = |d: Vector3F64| minkowski_support(b, b_pose, a, a_pose, d, true);
+    cast(support, Vector3F64::ZERO, direction, max_distance)
+}
+
+///Finds the point of a simplex closest to the origin, returning it along with the smallest sub-simplex containing it and its barycentric weights.
+///<br>A tetrahedron is only returned when it contains the origin.
+pub(crate) fn closest_on_simplex(simplex: &[SupportPoint]) -> (Vector3F64, Vec<SupportPoint>, Vec<f64>) {
+    let pick = |weights: Vec<(usize, f64)>| {
+        let point = weights.iter().fold(Vector3F64::ZERO, |sum, (i, w)| sum + simplex[*i].point * *w);
+        let points = weights.iter().map(|(i, _)| simplex[*i]).collect();
+        let weights = weights.iter().map(|(_, w)| *w).collect();
+        (point, points, weights)
+    };
+
+    match simplex.len() {
+        1 => pick(vec![(0, 1_f64)]),
+        2 => pick(closest_on_segment(simplex[0].point, simplex[1].point, [0, 1])),
+        3 => pick(closest_on_triangle(simplex[0].point, simplex[1].point, simplex[2].point, [0, 1, 2])),
+        _ => {
+            let faces = [([0, 1, 2], 3), ([0, 3, 1], 2), ([0, 2, 3], 1), ([1, 3, 2], 0)];
+            let mut best: Option<(f64, Vec<(usize, f64)>)> = None;
+
+            for (face, opposite) in faces {
+                let (a, b, c) = (simplex[face[0]].point, simplex[face[1]].point, simplex[face[2]].point);
+                let normal = (b - a).cross(&(c - a));
+                let origin_side = normal.dot(&-a);
+                let opposite_side = normal.dot(&(simplex[opposite].point - a));
+                if origin_side * opposite_side > 0_f64 {
+                    continue;
+                }
+
+                let weights = closest_on_triangle(a, b, c, face);
+                let point = weights.iter().fold(Vector3F64::ZERO, |sum, (i, w)| sum + simplex[*i].point * *w);
+                let distance = point.magnitude_squared();
+                if best.as_ref().map(|(d, _)| distance < *d).unwrap_or(true) {
+                    best = Some((distance, weights));
+                }
+            }
+
+            match best {
+                Some((_, weights)) => pick(weights),
+                None => (Vector3F64::ZERO, simplex.to_vec(), vec![0.25_f64; 4]),
+            }
+        }
+    }
+}
+
+fn closest_on_segment(a: Vector3F64, b: Vector3F64, indices: [usize; 2]) -> Vec<(usize, f64)> {
+    let ab = b - a;
+    let length = ab.magnitude_squared();
+    if length < EPSILON {
+        return vec![(indices[0], 1_f64)];
+    }
+    let t = (-a).dot(&ab) / length;
+    if t <= 0_f64 {
+        vec![(indices[0], 1_f64)]
+    } else if t >= 1_f64 {
+        vec![(indices[1], 1_f64)]
+    } else {
+        vec![(indices[0], 1_f64 - t), (indices[1], t)]
+    }
+}
+
+///Closest point on a triangle to the origin by Voronoi region tests.
+///<br>See Ericson, Real-Time Collision Detection, 5.1.5.
+pub(crate) fn closest_on_triangle(a: Vector3F64, b: Vector3F64, c: Vector3F64, indices: [usize; 3]) -> Vec<(usize, f64)> {
+    let [ia, ib, ic] = indices;
+    let (ab, ac) = (b - a, c - a);
+    let ap = -a;
+    let (d1, d2) = (ab.dot(&ap), ac.dot(&ap));
+    if d1 <= 0_f64 && d2 <= 0_f64 {
+        return vec![(ia, 1_f64)];
+    }
+
+    let bp = -b;
+    let (d3, d4) = (ab.dot(&bp), ac.dot(&bp));
+    if d3 >= 0_f64 && d4 <= d3 {
+        return vec![(ib, 1_f64)];
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0_f64 && d1 >= 0_f64 && d3 <= 0_f64 {
+        let v = d1 / (d1 - d3);
+        return vec![(ia, 1_f64 - v), (ib, v)];
+    }
+
+    let cp = -c;
+    let (d5, d6) = (ab.dot(&cp), ac.dot(&cp));
+    if d6 >= 0_f64 && d5 <= d6 {
+        return vec![(ic, 1_f64)];
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0_f64 && d2 >= 0_f64 && d6 <= 0_f64 {
+        let w = d2 / (d2 - d6);
+        return vec![(ia, 1_f64 - w), (ic, w)];
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0_f64 && (d4 - d3) >= 0_f64 && (d5 - d6) >= 0_f64 {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return vec![(ib, 1_f64 - w), (ic, w)];
+    }
+
+    let denominator = va + vb + vc;
+    if denominator.abs() < EPSILON {
+        return closest_on_segment(a, b, [ia, ib]);
+    }
+    let (v, w) = (vb / denominator, vc / denominator);
+    vec![(ia, 1_f64 - v - w), (ib, v), (ic, w)]
+}
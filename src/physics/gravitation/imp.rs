@@ -0,0 +1,78 @@
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::gravitation::octree::{pull, Octree};
+use crate::physics::gravitation::{Gravitation, GravitationMode, GRAVITATIONAL_CONSTANT};
+
+impl Gravitation {
+    ///Exact gravitation without softening, for a given gravitational constant.
+    pub fn new(constant: f64) -> Self {
+        Self {
+            constant,
+            softening: 0_f64,
+            mode: GravitationMode::Direct,
+        }
+    }
+
+    ///Gravitation with the real gravitational constant, for bodies measured in meters and kilograms.
+    pub fn si() -> Self {
+        Self::new(GRAVITATIONAL_CONSTANT)
+    }
+
+    pub fn with_softening(mut self, softening: f64) -> Self {
+        self.softening = softening;
+        self
+    }
+
+    pub fn with_barnes_hut(mut self, opening_angle: f64) -> Self {
+        self.mode = GravitationMode::BarnesHut { opening_angle };
+        self
+    }
+
+    ///The acceleration of every point mass caused by all the others. Massless points are pulled but pull nothing.
+    pub fn accelerations(&self, positions: &[Vector3F64], masses: &[f64]) -> Vec<Vector3F64> {
+        let mut accelerations = vec![Vector3F64::ZERO; positions.len()];
+        let sources = (0..positions.len() as u32).filter(|i| masses[*i as usize] > 0_f64).collect::<Vec<u32>>();
+
+        match self.mode {
+            GravitationMode::Direct => {
+                for (n, i) in sources.iter().enumerate() {
+                    let i = *i as usize;
+                    //Each pair of sources once, pulling both ways.
+                    for j in &sources[n + 1..] {
+                        let j = *j as usize;
+                        let per_mass = pull(positions[i], positions[j], 1_f64, self.constant, self.softening);
+                        accelerations[i] += per_mass * masses[j];
+                        accelerations[j] -= per_mass * masses[i];
+                    }
+                }
+                for i in 0..positions.len() {
+                    if masses[i] <= 0_f64 {
+                        for j in &sources {
+                            accelerations[i] += pull(positions[i], positions[*j as usize], masses[*j as usize], self.constant, self.softening);
+                        }
+                    }
+                }
+            }
+            GravitationMode::BarnesHut { opening_angle } => {
+                let tree = Octree::new(positions, masses, sources);
+                for (i, acceleration) in accelerations.iter_mut().enumerate() {
+                    *acceleration = tree.acceleration(i, positions, masses, self.constant, self.softening, opening_angle);
+                }
+            }
+        }
+        accelerations
+    }
+
+    ///The total potential energy of the point masses, summed exactly over every pair with the same softening as the accelerations.
+    pub fn potential_energy(&self, positions: &[Vector3F64], masses: &[f64]) -> f64 {
+        let mut energy = 0_f64;
+        for i in 0..positions.len() {
+            for j in i + 1..positions.len() {
+                let distance = ((positions[i] - positions[j]).magnitude_squared() + self.softening * self.softening).sqrt();
+                if distance > 0_f64 {
+                    energy -= self.constant * masses[i] * masses[j] / distance;
+                }
+            }
+        }
+        energy
+    }
+}
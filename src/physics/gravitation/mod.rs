@@ -0,0 +1,44 @@
+use crate::linear_algebra::vector::types::Vector3F64;
+
+pub mod imp;
+pub mod octree;
+
+///Newton's gravitational constant in m³/(kg s²).
+pub const GRAVITATIONAL_CONSTANT: f64 = 6.674_30e-11;
+
+///How the pull of every body on every other body is summed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GravitationMode {
+    ///Sums every pair exactly. O(n²), fine up to a few hundred bodies.
+    Direct,
+    ///Groups far away bodies into the cells of an octree and pulls with their total mass from their center of mass. O(n log n).
+    ///<br>A cell is grouped when its size divided by its distance is below the opening angle. 0 is exact, around 0.5 is the usual trade off.
+    ///<br>See <a href="https://en.wikipedia.org/wiki/Barnes%E2%80%93Hut_simulation">Barnes–Hut simulation</a>
+    BarnesHut {
+        opening_angle: f64,
+    },
+}
+
+///Mutual gravitation between the rigidbodies of a [crate::physics::world::World], on top of its uniform gravity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gravitation {
+    ///The gravitational constant, which simulations at other scales than SI usually set to 1.
+    pub constant: f64,
+    ///Length added in quadrature to every distance, so close encounters don't produce huge accelerations.
+    pub softening: f64,
+    pub mode: GravitationMode,
+}
+
+///A cell of an [octree::Octree], holding the bodies in its range of the tree's order.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct OctreeNode {
+    pub(crate) center: Vector3F64,
+    pub(crate) half_size: f64,
+    pub(crate) mass: f64,
+    pub(crate) center_of_mass: Vector3F64,
+    ///Index of the first of the node's children, which are stored contiguously, or 0 for leaves.
+    pub(crate) first_child: u32,
+    pub(crate) children: u8,
+    pub(crate) start: u32,
+    pub(crate) end: u32,
+}
@@ -0,0 +1,122 @@
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::gravitation::OctreeNode;
+
+///Depth past which coincident bodies stop being split and share a leaf.
+const MAX_DEPTH: u32 = 32;
+
+///An octree over point masses, built top down by splitting the bodies of each cell into its octants.
+pub(crate) struct Octree {
+    pub(crate) nodes: Vec<OctreeNode>,
+    ///Body indices, ordered so every node's bodies are contiguous.
+    pub(crate) order: Vec<u32>,
+}
+
+impl Octree {
+    pub(crate) fn new(positions: &[Vector3F64], masses: &[f64], bodies: Vec<u32>) -> Self {
+        let mut tree = Self {
+            nodes: vec![],
+            order: bodies,
+        };
+        if tree.order.is_empty() {
+            return tree;
+        }
+
+        let first = positions[tree.order[0] as usize];
+        let (min, max) = tree.order.iter().fold((first, first), |(min, max), i| (min.min(&positions[*i as usize]), max.max(&positions[*i as usize])));
+        let half_size = (0..3).map(|i| (max[i] - min[i]) / 2_f64).fold(0_f64, f64::max).max(f64::EPSILON);
+        tree.nodes.push(tree.node((min + max) / 2_f64, half_size, 0, tree.order.len() as u32, positions, masses));
+
+        let mut pending = vec![(0_usize, 0_u32)];
+        while let Some((index, depth)) = pending.pop() {
+            let node = tree.nodes[index];
+            if node.end - node.start <= 1 || depth == MAX_DEPTH {
+                continue;
+            }
+
+            let octant = |p: Vector3F64| (0..3).fold(0_usize, |o, i| o | (((p[i] >= node.center[i]) as usize) << i));
+            let range = &mut tree.order[node.start as usize..node.end as usize];
+            range.sort_by_key(|i| octant(positions[*i as usize]));
+
+            let first_child = tree.nodes.len() as u32;
+            let mut start = node.start;
+            for o in 0..8 {
+                let count = tree.order[start as usize..node.end as usize].iter()
+                    .take_while(|i| octant(positions[**i as usize]) == o)
+                    .count() as u32;
+                if count == 0 {
+                    continue;
+                }
+                let quarter = node.half_size / 2_f64;
+                let offset = Vector3F64::new([0, 1, 2].map(|i| if o & (1 << i) != 0 { quarter } else { -quarter }));
+                let child = tree.node(node.center + offset, quarter, start, start + count, positions, masses);
+                tree.nodes.push(child);
+                pending.push((tree.nodes.len() - 1, depth + 1));
+                start += count;
+            }
+            tree.nodes[index].first_child = first_child;
+            tree.nodes[index].children = (tree.nodes.len() as u32 - first_child) as u8;
+        }
+        tree
+    }
+
+    fn node(&self, center: Vector3F64, half_size: f64, start: u32, end: u32, positions: &[Vector3F64], masses: &[f64]) -> OctreeNode {
+        let mut mass = 0_f64;
+        let mut weighted = Vector3F64::ZERO;
+        for i in &self.order[start as usize..end as usize] {
+            mass += masses[*i as usize];
+            weighted += positions[*i as usize] * masses[*i as usize];
+        }
+        OctreeNode {
+            center,
+            half_size,
+            mass,
+            center_of_mass: if mass > 0_f64 { weighted / mass } else { center },
+            first_child: 0,
+            children: 0,
+            start,
+            end,
+        }
+    }
+
+    ///The gravitational acceleration at a body's position, leaving the body itself out.
+    pub(crate) fn acceleration(&self, body: usize, positions: &[Vector3F64], masses: &[f64], constant: f64, softening: f64, opening_angle: f64) -> Vector3F64 {
+        let position = positions[body];
+        let mut acceleration = Vector3F64::ZERO;
+        if self.nodes.is_empty() {
+            return acceleration;
+        }
+
+        let mut stack = vec![0_u32];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index as usize];
+            if node.children == 0 {
+                for i in &self.order[node.start as usize..node.end as usize] {
+                    if *i as usize != body {
+                        acceleration += pull(position, positions[*i as usize], masses[*i as usize], constant, softening);
+                    }
+                }
+                continue;
+            }
+
+            //Cells around the body are always opened, so it never pulls on itself.
+            let outside = (0..3).any(|i| (position[i] - node.center[i]).abs() > node.half_size);
+            let distance = position.distance(&node.center_of_mass);
+            if outside && node.half_size * 2_f64 < opening_angle * distance {
+                acceleration += pull(position, node.center_of_mass, node.mass, constant, softening);
+            } else {
+                stack.extend(node.first_child..node.first_child + node.children as u32);
+            }
+        }
+        acceleration
+    }
+}
+
+///The softened acceleration a point mass causes at a position.
+pub(crate) fn pull(position: Vector3F64, source: Vector3F64, mass: f64, constant: f64, softening: f64) -> Vector3F64 {
+    let offset = source - position;
+    let distance_squared = offset.magnitude_squared() + softening * softening;
+    if distance_squared == 0_f64 {
+        return Vector3F64::ZERO;
+    }
+    offset * (constant * mass / (distance_squared * distance_squared.sqrt()))
+}
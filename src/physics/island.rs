@@ -0,0 +1,55 @@
+///When resting bodies are put to sleep. Sleeping bodies aren't integrated or solved until something touches them.
+#[derive(Debug, Clone, Copy)]
+pub struct SleepSettings {
+    pub enabled: bool,
+    ///Speed below which a body counts as resting.
+    pub linear_threshold: f64,
+    ///Angular speed in radians per second below which a body counts as resting.
+    pub angular_threshold: f64,
+    ///How many seconds every body in an island has to rest before the island falls asleep.
+    pub time_to_sleep: f64,
+}
+
+impl Default for SleepSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            linear_threshold: 0.05,
+            angular_threshold: 0.05,
+            time_to_sleep: 0.5,
+        }
+    }
+}
+
+///Groups nodes into connected components of a graph with a disjoint set forest.
+///<br>Components are ordered by their smallest node, as are the nodes within them.
+///<br>See <a href="https://en.wikipedia.org/wiki/Disjoint-set_data_structure">Disjoint-set data structure</a>
+pub fn find_islands(count: usize, edges: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    let mut parents = (0..count).collect::<Vec<usize>>();
+    fn root(parents: &mut [usize], mut node: usize) -> usize {
+        while parents[node] != node {
+            parents[node] = parents[parents[node]];
+            node = parents[node];
+        }
+        node
+    }
+
+    for (a, b) in edges {
+        let (a, b) = (root(&mut parents, *a), root(&mut parents, *b));
+        if a != b {
+            parents[a.max(b)] = a.min(b);
+        }
+    }
+
+    let mut islands: Vec<Vec<usize>> = vec![];
+    let mut island_of_root = vec![usize::MAX; count];
+    for node in 0..count {
+        let r = root(&mut parents, node);
+        if island_of_root[r] == usize::MAX {
+            island_of_root[r] = islands.len();
+            islands.push(vec![]);
+        }
+        islands[island_of_root[r]].push(node);
+    }
+    islands
+}
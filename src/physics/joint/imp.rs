@@ -0,0 +1,75 @@
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::joint::{Joint, JointKind, JointMotor};
+
+impl Joint {
+    fn new(kind: JointKind, anchor_a: Vector3F64, anchor_b: Vector3F64, axis: Vector3F64) -> Self {
+        Self {
+            kind,
+            anchor_a,
+            anchor_b,
+            axis: axis.normalize(),
+            collide_connected: false,
+        }
+    }
+
+    ///A rigid rod between two anchors, its length being their current distance.
+    pub fn distance(anchor_a: Vector3F64, anchor_b: Vector3F64) -> Self {
+        let length = anchor_a.distance(&anchor_b);
+        Self::new(JointKind::Distance { min_length: length, max_length: length }, anchor_a, anchor_b, Vector3F64::UP)
+    }
+
+    pub fn ball_socket(anchor: Vector3F64) -> Self {
+        Self::new(JointKind::BallSocket, anchor, anchor, Vector3F64::UP)
+    }
+
+    pub fn hinge(anchor: Vector3F64, axis: Vector3F64) -> Self {
+        Self::new(JointKind::Hinge { limits: None, motor: None }, anchor, anchor, axis)
+    }
+
+    pub fn slider(anchor: Vector3F64, axis: Vector3F64) -> Self {
+        Self::new(JointKind::Slider { limits: None, motor: None }, anchor, anchor, axis)
+    }
+
+    pub fn fixed(anchor: Vector3F64) -> Self {
+        Self::new(JointKind::Fixed, anchor, anchor, Vector3F64::UP)
+    }
+
+    ///A spring between two anchors, resting at their current distance.
+    pub fn spring(anchor_a: Vector3F64, anchor_b: Vector3F64, stiffness: f64, damping: f64) -> Self {
+        let rest_length = anchor_a.distance(&anchor_b);
+        Self::new(JointKind::Spring { rest_length, stiffness, damping }, anchor_a, anchor_b, Vector3F64::UP)
+    }
+
+    ///Limits the length of distance joints, the angle of hinges or the offset of sliders. Other joints are left as they are.
+    pub fn with_limits(mut self, lower: f64, upper: f64) -> Self {
+        match &mut self.kind {
+            JointKind::Distance { min_length, max_length } => {
+                *min_length = lower;
+                *max_length = upper;
+            }
+            JointKind::Hinge { limits, .. } | JointKind::Slider { limits, .. } => *limits = Some((lower, upper)),
+            _ => {}
+        }
+        self
+    }
+
+    ///Adds a motor to hinges and sliders. Other joints are left as they are.
+    pub fn with_motor(mut self, target_speed: f64, max_force: f64) -> Self {
+        if let JointKind::Hinge { motor, .. } | JointKind::Slider { motor, .. } = &mut self.kind {
+            *motor = Some(JointMotor { target_speed, max_force });
+        }
+        self
+    }
+
+    pub fn with_rest_length(mut self, length: f64) -> Self {
+        if let JointKind::Spring { rest_length, .. } = &mut self.kind {
+            *rest_length = length;
+        }
+        self
+    }
+
+    pub fn with_collide_connected(mut self, collide_connected: bool) -> Self {
+        self.collide_connected = collide_connected;
+        self
+    }
+}
@@ -0,0 +1,58 @@
+use crate::linear_algebra::vector::types::Vector3F64;
+
+pub mod imp;
+
+///Drives a hinge or slider towards a target speed, using at most a limited torque or force.
+#[derive(Debug, Clone, Copy)]
+pub struct JointMotor {
+    ///Radians per second for hinges, units per second for sliders.
+    pub target_speed: f64,
+    ///The largest torque (hinges) or force (sliders) the motor can apply.
+    pub max_force: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum JointKind {
+    ///Keeps the distance between the two anchors within a range. Equal lengths give a rigid rod, a minimum of 0 a rope.
+    Distance {
+        min_length: f64,
+        max_length: f64,
+    },
+    ///Pins the anchors together while leaving rotation free.
+    BallSocket,
+    ///Pins the anchors together and only allows rotation around the axis.
+    Hinge {
+        ///Lower and upper angle in radians, relative to the bodies' orientation when the joint was added.
+        limits: Option<(f64, f64)>,
+        motor: Option<JointMotor>,
+    },
+    ///Locks rotation and only allows translation along the axis.
+    Slider {
+        ///Lower and upper offset along the axis, relative to where the anchors were when the joint was added.
+        limits: Option<(f64, f64)>,
+        motor: Option<JointMotor>,
+    },
+    ///Locks the bodies together.
+    Fixed,
+    ///A damped spring between the two anchors.
+    Spring {
+        rest_length: f64,
+        ///Force per unit of stretch.
+        stiffness: f64,
+        ///Force per unit of stretching speed.
+        damping: f64,
+    },
+}
+
+///Connects two rigidbodies, or a rigidbody and the world, restricting how they can move relative to each other.
+///<br>Anchors and the axis are given in world space and fixed to each body when the joint is added to a [crate::physics::world::World].
+#[derive(Debug, Clone, Copy)]
+pub struct Joint {
+    pub kind: JointKind,
+    pub anchor_a: Vector3F64,
+    pub anchor_b: Vector3F64,
+    ///The hinge or slider axis.
+    pub axis: Vector3F64,
+    ///Whether the connected bodies' colliders still collide with each other.
+    pub collide_connected: bool,
+}
@@ -0,0 +1,10 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct JointHandle {
+    pub(crate) id: u32
+}
+
+impl JointHandle {
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+}
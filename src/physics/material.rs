@@ -0,0 +1,34 @@
+///Surface properties of a collider used when resolving contacts.
+#[derive(Debug, Clone, Copy)]
+pub struct PhysicsMaterial {
+    ///Coulomb friction coefficient. The tangential impulse at a contact is limited to friction times the normal impulse.
+    pub friction: f64,
+    ///How much of the approaching velocity is returned after an impact, between 0 (no bounce) and 1 (perfectly elastic).
+    pub restitution: f64,
+}
+
+impl PhysicsMaterial {
+    pub fn new(friction: f64, restitution: f64) -> Self {
+        Self {
+            friction,
+            restitution,
+        }
+    }
+
+    ///Combines the materials of two touching colliders. Friction uses the geometric mean and restitution the maximum of the two.
+    pub fn combine(&self, other: &PhysicsMaterial) -> PhysicsMaterial {
+        PhysicsMaterial {
+            friction: (self.friction * other.friction).sqrt(),
+            restitution: self.restitution.max(other.restitution),
+        }
+    }
+}
+
+impl Default for PhysicsMaterial {
+    fn default() -> Self {
+        Self {
+            friction: 0.6,
+            restitution: 0.0,
+        }
+    }
+}
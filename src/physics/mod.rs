@@ -2,4 +2,24 @@ pub mod world;
 pub mod rigidbody_handle;
 pub mod rigidbody;
 pub mod force_type;
-pub mod collider;
\ No newline at end of file
+pub mod collider;
+pub mod pose;
+pub mod contact;
+pub mod material;
+pub mod collider_handle;
+pub mod solver;
+pub mod broad_phase;
+pub mod joint;
+pub mod joint_handle;
+pub mod gjk;
+pub mod collision_event;
+pub mod island;
+pub mod body_store;
+pub mod replay;
+pub mod particle;
+pub mod force_field;
+pub mod soft_body;
+pub mod gravitation;
+pub mod character_controller;
+pub mod vehicle;
+pub mod vehicle_handle;
\ No newline at end of file
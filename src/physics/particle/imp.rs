@@ -0,0 +1,194 @@
+use std::f64::consts::PI;
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::force_field::ForceField;
+use crate::physics::particle::{Emitter, ParticleSystem, VelocityDistribution};
+use crate::physics::solver::contact_constraint::tangent_basis;
+
+impl Emitter {
+    ///An emitter spawning motionless particles at a point that live for a second.
+    pub fn new(position: Vector3F64, rate: f64) -> Self {
+        Self {
+            position,
+            rate,
+            radius: 0_f64,
+            lifetime: (1_f64, 1_f64),
+            velocity: VelocityDistribution::Constant(Vector3F64::ZERO),
+            max_particles: 10_000,
+        }
+    }
+
+    pub fn with_radius(mut self, radius: f64) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    pub fn with_lifetime(mut self, min: f64, max: f64) -> Self {
+        self.lifetime = (min, max);
+        self
+    }
+
+    pub fn with_velocity(mut self, velocity: VelocityDistribution) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    pub fn with_max_particles(mut self, max_particles: usize) -> Self {
+        self.max_particles = max_particles;
+        self
+    }
+}
+
+impl ParticleSystem {
+    ///An empty system. The seed drives every random choice the emitter makes, so equal seeds give equal runs.
+    pub fn new(emitter: Emitter, seed: u64) -> Self {
+        Self {
+            emitter,
+            positions: vec![],
+            velocities: vec![],
+            ages: vec![],
+            lifetimes: vec![],
+            accelerations: vec![],
+            fields: vec![],
+            pending: 0_f64,
+            time: 0_f64,
+            //Xorshift gets stuck at 0.
+            random: seed.max(1),
+        }
+    }
+
+    pub fn with_field<F: ForceField + 'static>(mut self, field: F) -> Self {
+        self.add_field(field);
+        self
+    }
+
+    pub fn add_field<F: ForceField + 'static>(&mut self, field: F) {
+        self.fields.push(Box::new(field));
+    }
+
+    pub fn clear_fields(&mut self) {
+        self.fields.clear();
+    }
+
+    pub fn emitter(&self) -> &Emitter {
+        &self.emitter
+    }
+
+    pub fn emitter_mut(&mut self) -> &mut Emitter {
+        &mut self.emitter
+    }
+
+    ///Spawns particles right away, regardless of the emitter's rate but within its maximum.
+    pub fn burst(&mut self, count: usize) {
+        let count = count.min(self.emitter.max_particles.saturating_sub(self.len()));
+        for _ in 0..count {
+            self.spawn();
+        }
+    }
+
+    ///Ages, emits and moves the particles by a time step in seconds.
+    pub fn step(&mut self, delta: f64) {
+        if delta <= 0_f64 {
+            return;
+        }
+        self.time += delta;
+
+        let mut i = 0;
+        while i < self.len() {
+            self.ages[i] += delta;
+            if self.ages[i] >= self.lifetimes[i] {
+                self.positions.swap_remove(i);
+                self.velocities.swap_remove(i);
+                self.ages.swap_remove(i);
+                self.lifetimes.swap_remove(i);
+            } else {
+                i += 1;
+            }
+        }
+
+        self.pending += self.emitter.rate * delta;
+        let owed = self.pending.floor();
+        self.pending -= owed;
+        self.burst(owed as usize);
+
+        self.accelerations.clear();
+        self.accelerations.resize(self.len(), Vector3F64::ZERO);
+        for field in &self.fields {
+            field.accumulate(&self.positions, &self.velocities, self.time, &mut self.accelerations);
+        }
+        for ((position, velocity), acceleration) in self.positions.iter_mut().zip(&mut self.velocities).zip(&self.accelerations) {
+            *velocity += *acceleration * delta;
+            *position += *velocity * delta;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    pub fn positions(&self) -> &[Vector3F64] {
+        &self.positions
+    }
+
+    pub fn velocities(&self) -> &[Vector3F64] {
+        &self.velocities
+    }
+
+    ///How long each particle has been alive for, in seconds.
+    pub fn ages(&self) -> &[f64] {
+        &self.ages
+    }
+
+    pub fn lifetimes(&self) -> &[f64] {
+        &self.lifetimes
+    }
+
+    ///Seconds simulated so far.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    fn spawn(&mut self) {
+        let emitter = self.emitter;
+        let offset = self.direction() * (emitter.radius * self.uniform(0_f64, 1_f64).cbrt());
+        let velocity = match emitter.velocity {
+            VelocityDistribution::Constant(velocity) => velocity,
+            VelocityDistribution::Cone { direction, angle, speed } => {
+                let direction = direction.normalize();
+                let [u, v] = tangent_basis(direction);
+                let cos = self.uniform(angle.cos(), 1_f64);
+                let sin = (1_f64 - cos * cos).sqrt();
+                let phi = self.uniform(0_f64, 2_f64 * PI);
+                (direction * cos + u * (sin * phi.cos()) + v * (sin * phi.sin())) * self.uniform(speed.0, speed.1)
+            }
+            VelocityDistribution::Sphere { speed } => self.direction() * self.uniform(speed.0, speed.1),
+        };
+
+        self.positions.push(emitter.position + offset);
+        self.velocities.push(velocity);
+        self.ages.push(0_f64);
+        let lifetime = self.uniform(emitter.lifetime.0, emitter.lifetime.1);
+        self.lifetimes.push(lifetime);
+    }
+
+    ///A uniformly distributed unit vector.
+    fn direction(&mut self) -> Vector3F64 {
+        let z = self.uniform(-1_f64, 1_f64);
+        let r = (1_f64 - z * z).sqrt();
+        let phi = self.uniform(0_f64, 2_f64 * PI);
+        Vector3F64::new([r * phi.cos(), r * phi.sin(), z])
+    }
+
+    ///Xorshift64*, uniform between min and max.
+    ///<br>See <a href="https://en.wikipedia.org/wiki/Xorshift#xorshift*">Xorshift</a>
+    fn uniform(&mut self, min: f64, max: f64) -> f64 {
+        self.random ^= self.random >> 12;
+        self.random ^= self.random << 25;
+        self.random ^= self.random >> 27;
+        let bits = self.random.wrapping_mul(0x2545F4914F6CDD1D) >> 11;
+        min + (max - min) * (bits as f64 / (1_u64 << 53) as f64)
+    }
+}
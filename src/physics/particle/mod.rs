@@ -0,0 +1,52 @@
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::force_field::ForceField;
+
+pub mod imp;
+
+///How an emitter picks the starting velocity of new particles.
+#[derive(Debug, Clone, Copy)]
+pub enum VelocityDistribution {
+    Constant(Vector3F64),
+    ///Uniformly spread within a cone around a direction, the half angle given in radians.
+    Cone {
+        direction: Vector3F64,
+        angle: f64,
+        speed: (f64, f64),
+    },
+    ///Uniformly spread over every direction.
+    Sphere {
+        speed: (f64, f64),
+    },
+}
+
+///Spawns particles at a steady rate around a point.
+#[derive(Debug, Clone, Copy)]
+pub struct Emitter {
+    pub position: Vector3F64,
+    ///Particles spawned per second. Fractions carry over between steps.
+    pub rate: f64,
+    ///Particles spawn uniformly within this distance of the position.
+    pub radius: f64,
+    ///Lower and upper bound of how long particles live, in seconds.
+    pub lifetime: (f64, f64),
+    pub velocity: VelocityDistribution,
+    ///Emission pauses while this many particles are alive.
+    pub max_particles: usize,
+}
+
+///Point masses without rotation or collisions, stored as a structure of arrays and moved by [ForceField]s.
+///<br>Every step ages the particles, removes expired ones, emits new ones, then integrates with semi-implicit Euler using the summed acceleration of every field.
+pub struct ParticleSystem {
+    pub(crate) emitter: Emitter,
+    pub(crate) positions: Vec<Vector3F64>,
+    pub(crate) velocities: Vec<Vector3F64>,
+    pub(crate) ages: Vec<f64>,
+    pub(crate) lifetimes: Vec<f64>,
+    ///Scratch column the fields accumulate into, kept around to avoid allocating every step.
+    pub(crate) accelerations: Vec<Vector3F64>,
+    pub(crate) fields: Vec<Box<dyn ForceField>>,
+    ///Particles owed by the emitter but not spawned yet.
+    pub(crate) pending: f64,
+    pub(crate) time: f64,
+    pub(crate) random: u64,
+}
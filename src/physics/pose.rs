@@ -0,0 +1,66 @@
+use crate::linear_algebra::quaternion::Quaternion;
+use crate::linear_algebra::vector::types::Vector3F64;
+
+///A rigid transform (position and rotation) placing a body or collider in world space.
+#[derive(Debug, Clone, Copy)]
+pub struct Pose {
+    pub position: Vector3F64,
+    pub rotation: Quaternion<f64>,
+}
+
+impl Pose {
+    pub fn new(position: Vector3F64, rotation: Quaternion<f64>) -> Self {
+        Self {
+            position,
+            rotation,
+        }
+    }
+
+    ///A pose at the provided position with no rotation.
+    pub fn from_position(position: Vector3F64) -> Self {
+        Self::new(position, Quaternion::identity())
+    }
+
+    ///Transforms a point from this pose's local space into world space.
+    pub fn transform_point(&self, point: Vector3F64) -> Vector3F64 {
+        self.rotation * point + self.position
+    }
+
+    ///Transforms a world space point into this pose's local space.
+    pub fn inverse_transform_point(&self, point: Vector3F64) -> Vector3F64 {
+        self.rotation.conjugate() * (point - self.position)
+    }
+
+    ///Rotates a direction from local space into world space, ignoring position.
+    pub fn transform_vector(&self, vector: Vector3F64) -> Vector3F64 {
+        self.rotation * vector
+    }
+
+    ///Rotates a world space direction into local space, ignoring position.
+    pub fn inverse_transform_vector(&self, vector: Vector3F64) -> Vector3F64 {
+        self.rotation.conjugate() * vector
+    }
+
+    ///The local x, y and z axes of this pose expressed in world space.
+    pub fn axes(&self) -> [Vector3F64; 3] {
+        [
+            self.transform_vector(Vector3F64::RIGHT),
+            self.transform_vector(Vector3F64::UP),
+            self.transform_vector(Vector3F64::FORWARD),
+        ]
+    }
+
+    ///Combines two poses, treating `local` as relative to this pose.
+    pub fn mul(&self, local: &Pose) -> Pose {
+        Pose {
+            position: self.transform_point(local.position),
+            rotation: self.rotation * local.rotation,
+        }
+    }
+}
+
+impl Default for Pose {
+    fn default() -> Self {
+        Self::from_position(Vector3F64::ZERO)
+    }
+}
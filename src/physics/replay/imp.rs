@@ -0,0 +1,240 @@
+use crate::linear_algebra::quaternion::Quaternion;
+use crate::physics::replay::{Recorder, Replay, ReplayInput, REPLAY_MAGIC, REPLAY_VERSION};
+use crate::physics::rigidbody_handle::RigidbodyHandle;
+use crate::physics::vehicle_handle::VehicleHandle;
+use crate::physics::world::snapshot::SnapshotError;
+use crate::physics::world::World;
+use crate::shared::binary::{ByteReader, ByteWriter};
+use crate::shared::endian::Endian;
+
+impl ReplayInput {
+    ///Does this input to a world, the same way a recorder did.
+    pub fn apply(&self, world: &mut World) {
+        match *self {
+            ReplayInput::Step(delta) => world.step(delta),
+            ReplayInput::SetPosition(handle, position) => {
+                if let Some(mut rigidbody) = world.rigidbody_mut(&handle) {
+                    rigidbody.set_position(position);
+                }
+            }
+            ReplayInput::SetRotation(handle, rotation) => {
+                if let Some(mut rigidbody) = world.rigidbody_mut(&handle) {
+                    rigidbody.set_rotation(rotation);
+                }
+            }
+            ReplayInput::SetVelocity(handle, velocity) => {
+                if let Some(mut rigidbody) = world.rigidbody_mut(&handle) {
+                    rigidbody.set_velocity(velocity);
+                }
+            }
+            ReplayInput::SetAngularVelocity(handle, angular_velocity) => {
+                if let Some(mut rigidbody) = world.rigidbody_mut(&handle) {
+                    rigidbody.set_angular_velocity(angular_velocity);
+                }
+            }
+            ReplayInput::SetGravity(gravity) => world.set_gravity(gravity),
+            ReplayInput::WakeUp(handle) => world.wake_up(&handle),
+            ReplayInput::RemoveRigidbody(handle) => {
+                world.remove_rigidbody(&handle);
+            }
+            ReplayInput::SetThrottle(handle, throttle) => {
+                if let Some(vehicle) = world.vehicle_mut(&handle) {
+                    vehicle.set_throttle(throttle);
+                }
+            }
+            ReplayInput::SetBrake(handle, brake) => {
+                if let Some(vehicle) = world.vehicle_mut(&handle) {
+                    vehicle.set_brake(brake);
+                }
+            }
+            ReplayInput::SetSteering(handle, steering) => {
+                if let Some(vehicle) = world.vehicle_mut(&handle) {
+                    vehicle.set_steering(steering);
+                }
+            }
+        }
+    }
+}
+
+impl Recorder {
+    ///Starts recording from the world's current state.
+    pub fn start(world: &World) -> Result<Self, SnapshotError> {
+        Ok(Self {
+            replay: Replay {
+                snapshot: world.snapshot(Endian::Little)?,
+                inputs: vec![],
+            },
+        })
+    }
+
+    ///Applies an input to the world and records it.
+    pub fn apply(&mut self, world: &mut World, input: ReplayInput) {
+        input.apply(world);
+        self.replay.inputs.push(input);
+    }
+
+    pub fn step(&mut self, world: &mut World, delta: f64) {
+        self.apply(world, ReplayInput::Step(delta));
+    }
+
+    pub fn finish(self) -> Replay {
+        self.replay
+    }
+}
+
+impl Replay {
+    ///The snapshot the recording started from.
+    pub fn snapshot(&self) -> &[u8] {
+        &self.snapshot
+    }
+
+    pub fn inputs(&self) -> &[ReplayInput] {
+        &self.inputs
+    }
+
+    ///How many steps were recorded.
+    pub fn steps(&self) -> usize {
+        self.inputs.iter().filter(|input| matches!(input, ReplayInput::Step(_))).count()
+    }
+
+    ///Restores the starting snapshot and applies every recorded input to it.
+    pub fn play(&self) -> Result<World, SnapshotError> {
+        self.play_steps(usize::MAX)
+    }
+
+    ///Restores the starting snapshot and applies the recorded inputs up to and including the provided number of steps.
+    pub fn play_steps(&self, steps: usize) -> Result<World, SnapshotError> {
+        let mut world = World::restore(&self.snapshot)?;
+        let mut taken = 0;
+        for input in &self.inputs {
+            if taken == steps {
+                break;
+            }
+            input.apply(&mut world);
+            if let ReplayInput::Step(_) = input {
+                taken += 1;
+            }
+        }
+        Ok(world)
+    }
+
+    ///Writes the replay out as its starting snapshot followed by its inputs.
+    pub fn to_bytes(&self, endian: Endian) -> Vec<u8> {
+        let mut writer = ByteWriter::new(endian);
+        writer.raw(&REPLAY_MAGIC);
+        writer.number(match endian {
+            Endian::Little => 0_u8,
+            Endian::Big => 1_u8,
+        });
+        writer.number(REPLAY_VERSION);
+        writer.number(self.snapshot.len() as u32);
+        writer.raw(&self.snapshot);
+
+        writer.number(self.inputs.len() as u32);
+        let handle = |writer: &mut ByteWriter, handle: &RigidbodyHandle| {
+            writer.number(handle.id);
+            writer.number(handle.generation);
+        };
+        for input in &self.inputs {
+            match input {
+                ReplayInput::Step(delta) => {
+                    writer.number(0_u8);
+                    writer.number(*delta);
+                }
+                ReplayInput::SetPosition(h, position) => {
+                    writer.number(1_u8);
+                    handle(&mut writer, h);
+                    writer.vector(position);
+                }
+                ReplayInput::SetRotation(h, rotation) => {
+                    writer.number(2_u8);
+                    handle(&mut writer, h);
+                    rotation.wxyz().iter().for_each(|c| writer.number(*c));
+                }
+                ReplayInput::SetVelocity(h, velocity) => {
+                    writer.number(3_u8);
+                    handle(&mut writer, h);
+                    writer.vector(velocity);
+                }
+                ReplayInput::SetAngularVelocity(h, angular_velocity) => {
+                    writer.number(4_u8);
+                    handle(&mut writer, h);
+                    writer.vector(angular_velocity);
+                }
+                ReplayInput::SetGravity(gravity) => {
+                    writer.number(5_u8);
+                    writer.vector(gravity);
+                }
+                ReplayInput::WakeUp(h) => {
+                    writer.number(6_u8);
+                    handle(&mut writer, h);
+                }
+                ReplayInput::RemoveRigidbody(h) => {
+                    writer.number(7_u8);
+                    handle(&mut writer, h);
+                }
+                ReplayInput::SetThrottle(h, throttle) => {
+                    writer.number(8_u8);
+                    writer.number(h.id);
+                    writer.number(*throttle);
+                }
+                ReplayInput::SetBrake(h, brake) => {
+                    writer.number(9_u8);
+                    writer.number(h.id);
+                    writer.number(*brake);
+                }
+                ReplayInput::SetSteering(h, steering) => {
+                    writer.number(10_u8);
+                    writer.number(h.id);
+                    writer.number(*steering);
+                }
+            }
+        }
+        writer.into_bytes()
+    }
+
+    ///Reads a replay back from [Replay::to_bytes]. The snapshot inside is only checked once the replay is played.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Replay, SnapshotError> {
+        if bytes.len() < 5 || bytes[0..4] != REPLAY_MAGIC {
+            return Err(SnapshotError::NotASnapshot);
+        }
+        let endian = match bytes[4] {
+            0 => Endian::Little,
+            1 => Endian::Big,
+            _ => return Err(SnapshotError::Invalid(4, "unknown byte order")),
+        };
+        let mut reader = ByteReader::new(bytes, endian);
+        reader.raw(5)?;
+        let version = reader.number::<u32>()?;
+        if version != REPLAY_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+        let len = reader.number::<u32>()? as usize;
+        let snapshot = reader.raw(len)?.to_vec();
+
+        let mut inputs = vec![];
+        for _ in 0..reader.number::<u32>()? {
+            let handle = |reader: &mut ByteReader| -> Result<RigidbodyHandle, SnapshotError> {
+                Ok(RigidbodyHandle { id: reader.number()?, generation: reader.number()? })
+            };
+            inputs.push(match reader.number::<u8>()? {
+                0 => ReplayInput::Step(reader.number()?),
+                1 => ReplayInput::SetPosition(handle(&mut reader)?, reader.vector()?),
+                2 => ReplayInput::SetRotation(handle(&mut reader)?, Quaternion::from_wxyz([reader.number()?, reader.number()?, reader.number()?, reader.number()?])),
+                3 => ReplayInput::SetVelocity(handle(&mut reader)?, reader.vector()?),
+                4 => ReplayInput::SetAngularVelocity(handle(&mut reader)?, reader.vector()?),
+                5 => ReplayInput::SetGravity(reader.vector()?),
+                6 => ReplayInput::WakeUp(handle(&mut reader)?),
+                7 => ReplayInput::RemoveRigidbody(handle(&mut reader)?),
+                8 => ReplayInput::SetThrottle(VehicleHandle { id: reader.number()? }, reader.number()?),
+                9 => ReplayInput::SetBrake(VehicleHandle { id: reader.number()? }, reader.number()?),
+                10 => ReplayInput::SetSteering(VehicleHandle { id: reader.number()? }, reader.number()?),
+                _ => return Err(SnapshotError::Invalid(reader.offset() - 1, "unknown replay input")),
+            });
+        }
+        if reader.remaining() > 0 {
+            return Err(SnapshotError::Invalid(reader.offset(), "trailing bytes"));
+        }
+        Ok(Replay { snapshot, inputs })
+    }
+}
@@ -0,0 +1,39 @@
+use crate::linear_algebra::quaternion::Quaternion;
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::rigidbody_handle::RigidbodyHandle;
+use crate::physics::vehicle_handle::VehicleHandle;
+
+pub mod imp;
+
+///The first bytes of every serialized [Replay].
+pub const REPLAY_MAGIC: [u8; 4] = *b"WRPL";
+pub const REPLAY_VERSION: u32 = 1;
+
+///Something done to a [crate::physics::world::World] between snapshots, recorded so it can be done again in a replay.
+#[derive(Debug, Clone, Copy)]
+pub enum ReplayInput {
+    Step(f64),
+    SetPosition(RigidbodyHandle, Vector3F64),
+    SetRotation(RigidbodyHandle, Quaternion<f64>),
+    SetVelocity(RigidbodyHandle, Vector3F64),
+    SetAngularVelocity(RigidbodyHandle, Vector3F64),
+    SetGravity(Vector3F64),
+    WakeUp(RigidbodyHandle),
+    RemoveRigidbody(RigidbodyHandle),
+    SetThrottle(VehicleHandle, f64),
+    SetBrake(VehicleHandle, f64),
+    SetSteering(VehicleHandle, f64),
+}
+
+///Records a run of a world as its starting snapshot followed by every input applied to it.
+///<br>Inputs have to go through the recorder to be captured. Changes made to the world directly aren't replayed.
+pub struct Recorder {
+    pub(crate) replay: Replay,
+}
+
+///A recorded run that can be played back deterministically, ending in the exact same state the recorded world reached.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    pub(crate) snapshot: Vec<u8>,
+    pub(crate) inputs: Vec<ReplayInput>,
+}
@@ -1,7 +1,9 @@
 use std::fmt::{Debug, Formatter, Write};
 use std::sync::{Arc, Mutex};
+use crate::linear_algebra::quaternion::Quaternion;
 use crate::linear_algebra::vector::types::Vector3F64;
 use crate::physics::force_type::ForceType;
+use crate::physics::pose::Pose;
 use crate::physics::rigidbody::{Rigidbody, RigidbodyInner};
 use crate::physics::rigidbody::flags::RigidbodyFlags;
 
@@ -19,6 +21,7 @@ impl Rigidbody {
         let velocity = inner.velocity * delta;
 
         inner.position += velocity;
+        inner.rotation = inner.rotation.integrate(inner.angular_velocity, delta);
     }
 
     pub fn add_force(&self, force: Vector3F64, force_type: ForceType, delta: f64) {
@@ -45,15 +48,79 @@ impl Rigidbody {
     pub fn set_position(&self, position: Vector3F64) {
         self.0.lock().unwrap().position = position;
     }
+
+    pub fn rotation(&self) -> Quaternion<f64> {
+        self.0.lock().unwrap().rotation
+    }
+
+    pub fn set_rotation(&self, rotation: Quaternion<f64>) {
+        self.0.lock().unwrap().rotation = rotation;
+    }
+
+    ///The world space position and rotation of this rigidbody.
+    pub fn pose(&self) -> Pose {
+        let inner = self.0.lock().unwrap();
+        Pose::new(inner.position, inner.rotation)
+    }
+
+    pub fn velocity(&self) -> Vector3F64 {
+        self.0.lock().unwrap().velocity
+    }
+
+    pub fn set_velocity(&self, velocity: Vector3F64) {
+        self.0.lock().unwrap().velocity = velocity;
+    }
+
+    pub fn angular_velocity(&self) -> Vector3F64 {
+        self.0.lock().unwrap().angular_velocity
+    }
+
+    pub fn set_angular_velocity(&self, angular_velocity: Vector3F64) {
+        self.0.lock().unwrap().angular_velocity = angular_velocity;
+    }
+
+    pub fn inertia(&self) -> Vector3F64 {
+        self.0.lock().unwrap().inertia
+    }
+
+    ///Sets the principal moments of inertia, in the rigidbody's local space.
+    pub fn set_inertia(&self, inertia: Vector3F64) {
+        self.0.lock().unwrap().inertia = inertia;
+    }
+
+    pub fn flags(&self) -> u8 {
+        self.0.lock().unwrap().flags
+    }
+
+    pub fn set_flags(&self, flags: u8) {
+        self.0.lock().unwrap().flags = flags;
+    }
+
+    ///Whether the world integrates and resolves collisions for this rigidbody. Rigidbodies without [RigidbodyFlags::UseKinematics] or without mass are treated as immovable.
+    pub fn is_dynamic(&self) -> bool {
+        let inner = self.0.lock().unwrap();
+        inner.flags & RigidbodyFlags::UseKinematics > 0 && inner.mass > 0_f64
+    }
+
+    pub fn mass(&self) -> f64 {
+        self.0.lock().unwrap().mass
+    }
+
+    pub fn set_mass(&self, mass: f64) {
+        self.0.lock().unwrap().mass = mass;
+    }
 }
 
 impl Default for Rigidbody {
     fn default() -> Self {
         Self(Arc::new(Mutex::new(RigidbodyInner {
             position: Default::default(),
-            rotation: Default::default(),
+            rotation: Quaternion::identity(),
             mass: 1.0,
             velocity: Default::default(),
+            angular_velocity: Default::default(),
+            //A solid sphere with a radius of 0.5 and the default mass.
+            inertia: Vector3F64::ONE * 0.1,
             flags: RigidbodyFlags::DEFAULT,
         })))
     }
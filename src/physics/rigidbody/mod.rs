@@ -1,5 +1,6 @@
 use std::sync::{Arc, Mutex};
 
+use crate::linear_algebra::quaternion::Quaternion;
 use crate::linear_algebra::vector::types::{Vector3F64, Vector4F64};
 
 pub mod flags;
@@ -8,12 +9,17 @@ pub mod imp;
 #[derive(Debug)]
 pub struct RigidbodyInner {
     position: Vector3F64,
-    rotation: Vector3F64,
+    rotation: Quaternion<f64>,
     mass: f64,
 
     velocity: Vector3F64,
+    angular_velocity: Vector3F64,
+    ///Principal moments of inertia in the rigidbody's local space.
+    inertia: Vector3F64,
     flags: u8
 }
 
+///A standalone rigidbody shared behind a lock, which colliders used outside a world follow.
+///<br>Registering one with a [crate::physics::world::World] copies its state into the world's [crate::physics::body_store::BodyStore], after which the two are independent.
 #[derive(Clone)]
 pub struct Rigidbody(Arc<Mutex<RigidbodyInner>>);
\ No newline at end of file
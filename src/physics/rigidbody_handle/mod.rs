@@ -1,7 +1,18 @@
-use crate::physics::rigidbody::Rigidbody;
-
-#[derive(Clone)]
+///Refers to a rigidbody registered with a [crate::physics::world::World] by its slot in the world's [crate::physics::body_store::BodyStore].
+///<br>Its state is read and changed through [crate::physics::world::World::rigidbody] and [crate::physics::world::World::rigidbody_mut].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct RigidbodyHandle {
     pub(crate) id: u32,
-    pub(crate) rigidbody: Rigidbody
-}
\ No newline at end of file
+    ///Bumped every time the id is reused, so handles to removed rigidbodies can be told apart.
+    pub(crate) generation: u32,
+}
+
+impl RigidbodyHandle {
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
@@ -0,0 +1,357 @@
+use std::collections::BTreeMap;
+use crate::geometry::Geometry;
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::collider::bounds::Bounds;
+use crate::physics::collider::box_collider::BoxCollider;
+use crate::physics::collider::sphere_collider::SphereCollider;
+use crate::physics::collider::{Collider, ALL_LAYERS};
+use crate::physics::pose::Pose;
+use crate::physics::soft_body::{SoftBody, SoftBodySettings, SoftConstraint};
+use crate::physics::world::World;
+
+impl Default for SoftBodySettings {
+    fn default() -> Self {
+        Self {
+            substeps: 10,
+            iterations: 1,
+            damping: 0.1,
+            thickness: 0.01,
+            friction: 0.5,
+            collision_mask: ALL_LAYERS,
+        }
+    }
+}
+
+impl SoftBody {
+    ///A body with a particle at every vertex of the geometry, the mass spread evenly between them and no constraints yet.
+    pub fn from_geometry(geometry: &Geometry<3, f64>, mass: f64) -> Self {
+        let count = geometry.vertices.len();
+        Self {
+            positions: geometry.vertices.clone(),
+            previous: geometry.vertices.clone(),
+            velocities: vec![Vector3F64::ZERO; count],
+            inverse_masses: vec![count as f64 / mass; count],
+            pinned: vec![false; count],
+            triangles: geometry.indices.chunks_exact(3).map(|t| [t[0], t[1], t[2]]).collect(),
+            constraints: vec![],
+            lambdas: vec![],
+            gravity: Vector3F64::new([0_f64, -9.81, 0_f64]),
+            settings: SoftBodySettings::default(),
+        }
+    }
+
+    ///A chain of evenly spaced particles from start to end, held together by distance constraints and kept straight by bending constraints.
+    pub fn rope(start: Vector3F64, end: Vector3F64, segments: usize, mass: f64, bending_compliance: f64) -> Self {
+        let segments = segments.max(1);
+        let vertices = (0..=segments)
+            .map(|i| start + (end - start) * (i as f64 / segments as f64))
+            .collect::<Vec<Vector3F64>>();
+        let mut body = Self::from_geometry(&Geometry { vertices, normals: None, uv: vec![], tangents: None, indices: vec![] }, mass);
+        for i in 0..segments as u32 {
+            body.add_constraint(body.distance(i, i + 1, 0_f64));
+        }
+        for i in 0..segments.saturating_sub(1) as u32 {
+            let rest = body.positions[i as usize].distance(&body.positions[i as usize + 2]);
+            body.add_constraint(SoftConstraint::Bending { a: i, b: i + 2, rest, compliance: bending_compliance });
+        }
+        body
+    }
+
+    ///Adds a distance constraint along every triangle edge, at its current length.
+    pub fn with_distance_constraints(mut self, compliance: f64) -> Self {
+        for (a, b) in triangle_edges(&self.triangles).into_keys() {
+            self.add_constraint(self.distance(a, b, compliance));
+        }
+        self
+    }
+
+    ///Adds a bending constraint across every edge shared by exactly two triangles.
+    pub fn with_bending_constraints(mut self, compliance: f64) -> Self {
+        for opposite in triangle_edges(&self.triangles).into_values() {
+            if let [a, b] = opposite[..] {
+                let rest = self.positions[a as usize].distance(&self.positions[b as usize]);
+                self.add_constraint(SoftConstraint::Bending { a, b, rest, compliance });
+            }
+        }
+        self
+    }
+
+    ///Adds a constraint keeping the enclosed volume at its current volume times the pressure, inflating the body above 1.
+    pub fn with_volume_constraint(mut self, pressure: f64, compliance: f64) -> Self {
+        let rest = self.volume();
+        self.add_constraint(SoftConstraint::Volume { rest, pressure, compliance });
+        self
+    }
+
+    pub fn with_settings(mut self, settings: SoftBodySettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    pub fn with_gravity(mut self, gravity: Vector3F64) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    pub fn add_constraint(&mut self, constraint: SoftConstraint) {
+        self.constraints.push(constraint);
+    }
+
+    pub fn constraints(&self) -> &[SoftConstraint] {
+        &self.constraints
+    }
+
+    pub fn settings(&self) -> &SoftBodySettings {
+        &self.settings
+    }
+
+    pub fn settings_mut(&mut self) -> &mut SoftBodySettings {
+        &mut self.settings
+    }
+
+    pub fn gravity(&self) -> Vector3F64 {
+        self.gravity
+    }
+
+    pub fn set_gravity(&mut self, gravity: Vector3F64) {
+        self.gravity = gravity;
+    }
+
+    ///Fixes a particle in place. Constraints and collisions no longer move it, though [SoftBody::move_pinned] still can.
+    pub fn pin(&mut self, index: usize) {
+        self.pinned[index] = true;
+        self.velocities[index] = Vector3F64::ZERO;
+    }
+
+    pub fn unpin(&mut self, index: usize) {
+        self.pinned[index] = false;
+    }
+
+    pub fn is_pinned(&self, index: usize) -> bool {
+        self.pinned[index]
+    }
+
+    ///Moves a pinned particle, dragging the rest of the body along with it.
+    pub fn move_pinned(&mut self, index: usize, position: Vector3F64) {
+        self.positions[index] = position;
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    pub fn positions(&self) -> &[Vector3F64] {
+        &self.positions
+    }
+
+    pub fn velocities(&self) -> &[Vector3F64] {
+        &self.velocities
+    }
+
+    pub fn triangles(&self) -> &[[u32; 3]] {
+        &self.triangles
+    }
+
+    ///The volume enclosed by the body's triangles, summing the signed volume of the tetrahedron each forms with the origin.
+    pub fn volume(&self) -> f64 {
+        self.triangles.iter()
+            .map(|[a, b, c]| {
+                let (a, b, c) = (self.positions[*a as usize], self.positions[*b as usize], self.positions[*c as usize]);
+                a.dot(&b.cross(&c)) / 6_f64
+            })
+            .sum()
+    }
+
+    ///The bounds of every particle, grown by the collision thickness.
+    pub fn bounds(&self) -> Bounds {
+        particle_bounds(&self.positions, self.settings.thickness)
+    }
+
+    ///Copies the particle positions back into the vertices of the geometry the body was made from.
+    pub fn apply_to_geometry(&self, geometry: &mut Geometry<3, f64>) {
+        geometry.vertices.clone_from(&self.positions);
+    }
+
+    ///Steps the body on its own, colliding with standalone colliders placed by their rigidbodies.
+    ///<br>Only [SphereCollider]s and [BoxCollider]s are collided with.
+    pub fn step(&mut self, delta: f64, colliders: &[&dyn Collider]) {
+        let obstacles = colliders.iter()
+            .filter_map(|collider| collider.pose().map(|pose| (*collider, pose)))
+            .collect::<Vec<(&dyn Collider, Pose)>>();
+        self.simulate(delta, &obstacles);
+    }
+
+    ///Steps the body under the world's gravity, colliding with the world's colliders on the collision mask.
+    ///<br>The body pushes nothing back, so it suits bodies much lighter than what they rest on.
+    pub fn step_in_world(&mut self, delta: f64, world: &World) {
+        self.gravity = world.gravity();
+        let bounds = particle_bounds(&self.positions, self.settings.thickness + self.max_speed() * delta);
+        let obstacles = world.obstacles(bounds, self.settings.collision_mask).into_iter()
+            .map(|(_, collider, pose)| (collider, pose))
+            .collect::<Vec<(&dyn Collider, Pose)>>();
+        self.simulate(delta, &obstacles);
+    }
+
+    fn simulate(&mut self, delta: f64, obstacles: &[(&dyn Collider, Pose)]) {
+        if delta <= 0_f64 || self.is_empty() {
+            return;
+        }
+        let substeps = self.settings.substeps.max(1);
+        let h = delta / substeps as f64;
+        let damping = (1_f64 - self.settings.damping * h).max(0_f64);
+
+        for _ in 0..substeps {
+            for i in 0..self.len() {
+                self.previous[i] = self.positions[i];
+                if self.inverse_mass(i) > 0_f64 {
+                    self.velocities[i] += self.gravity * h;
+                    self.positions[i] += self.velocities[i] * h;
+                }
+            }
+
+            self.lambdas.clear();
+            self.lambdas.resize(self.constraints.len(), 0_f64);
+            let alpha_scale = 1_f64 / (h * h);
+            for _ in 0..self.settings.iterations.max(1) {
+                for c in 0..self.constraints.len() {
+                    self.solve_constraint(c, alpha_scale);
+                }
+            }
+
+            for i in 0..self.len() {
+                if self.inverse_mass(i) == 0_f64 {
+                    continue;
+                }
+                let previous = self.previous[i];
+                collide_particle(&mut self.positions[i], previous, self.settings.thickness, self.settings.friction, obstacles);
+                self.velocities[i] = (self.positions[i] - previous) / h * damping;
+            }
+        }
+    }
+
+    fn solve_constraint(&mut self, index: usize, alpha_scale: f64) {
+        match self.constraints[index] {
+            SoftConstraint::Distance { a, b, rest, compliance } | SoftConstraint::Bending { a, b, rest, compliance } => {
+                let (a, b) = (a as usize, b as usize);
+                let (wa, wb) = (self.inverse_mass(a), self.inverse_mass(b));
+                let alpha = compliance * alpha_scale;
+                if wa + wb + alpha == 0_f64 {
+                    return;
+                }
+                let offset = self.positions[a] - self.positions[b];
+                let length = offset.magnitude();
+                if length < f64::EPSILON {
+                    return;
+                }
+                let normal = offset / length;
+                let c = length - rest;
+                let delta_lambda = (-c - alpha * self.lambdas[index]) / (wa + wb + alpha);
+                self.lambdas[index] += delta_lambda;
+                self.positions[a] += normal * (wa * delta_lambda);
+                self.positions[b] -= normal * (wb * delta_lambda);
+            }
+            SoftConstraint::Volume { rest, pressure, compliance } => {
+                let mut gradients = vec![Vector3F64::ZERO; self.len()];
+                for [a, b, c] in &self.triangles {
+                    let (a, b, c) = (*a as usize, *b as usize, *c as usize);
+                    let (pa, pb, pc) = (self.positions[a], self.positions[b], self.positions[c]);
+                    gradients[a] += pb.cross(&pc) / 6_f64;
+                    gradients[b] += pc.cross(&pa) / 6_f64;
+                    gradients[c] += pa.cross(&pb) / 6_f64;
+                }
+                let alpha = compliance * alpha_scale;
+                let weight = gradients.iter().enumerate()
+                    .map(|(i, gradient)| self.inverse_mass(i) * gradient.magnitude_squared())
+                    .sum::<f64>();
+                if weight + alpha == 0_f64 {
+                    return;
+                }
+                let c = self.volume() - rest * pressure;
+                let delta_lambda = (-c - alpha * self.lambdas[index]) / (weight + alpha);
+                self.lambdas[index] += delta_lambda;
+                for (i, gradient) in gradients.iter().enumerate() {
+                    let w = self.inverse_mass(i);
+                    self.positions[i] += *gradient * (w * delta_lambda);
+                }
+            }
+        }
+    }
+
+    fn distance(&self, a: u32, b: u32, compliance: f64) -> SoftConstraint {
+        let rest = self.positions[a as usize].distance(&self.positions[b as usize]);
+        SoftConstraint::Distance { a, b, rest, compliance }
+    }
+
+    fn inverse_mass(&self, index: usize) -> f64 {
+        if self.pinned[index] { 0_f64 } else { self.inverse_masses[index] }
+    }
+
+    fn max_speed(&self) -> f64 {
+        self.velocities.iter().map(|v| v.magnitude()).fold(0_f64, f64::max)
+    }
+}
+
+///Every edge of the triangles, smallest index first, with the vertices opposite it in each triangle sharing it.
+pub(crate) fn triangle_edges(triangles: &[[u32; 3]]) -> BTreeMap<(u32, u32), Vec<u32>> {
+    let mut edges = BTreeMap::<(u32, u32), Vec<u32>>::new();
+    for triangle in triangles {
+        for i in 0..3 {
+            let (a, b, opposite) = (triangle[i], triangle[(i + 1) % 3], triangle[(i + 2) % 3]);
+            edges.entry((a.min(b), a.max(b))).or_default().push(opposite);
+        }
+    }
+    edges
+}
+
+pub(crate) fn particle_bounds(positions: &[Vector3F64], margin: f64) -> Bounds {
+    let Some(first) = positions.first() else {
+        return Bounds { center: Vector3F64::ZERO, extents: Vector3F64::ZERO };
+    };
+    let (min, max) = positions.iter().fold((*first, *first), |(min, max), p| (min.min(p), max.max(p)));
+    Bounds::from_min_max(min - Vector3F64::ONE * margin, max + Vector3F64::ONE * margin)
+}
+
+///Pushes a particle out of every sphere and box it ended up inside, cancelling part of its sliding along the surface.
+///<br>Returns the last surface normal it was pushed along.
+pub(crate) fn collide_particle(position: &mut Vector3F64, previous: Vector3F64, thickness: f64, friction: f64, obstacles: &[(&dyn Collider, Pose)]) -> Option<Vector3F64> {
+    let mut touched = None;
+    for (collider, pose) in obstacles {
+        let push = if let Some(sphere) = collider.downcast_ref::<SphereCollider>() {
+            let offset = *position - pose.position;
+            let distance = offset.magnitude();
+            let radius = sphere.radius() + thickness;
+            if distance >= radius || distance < f64::EPSILON {
+                continue;
+            }
+            let normal = offset / distance;
+            Some((normal, radius - distance))
+        } else if let Some(cuboid) = collider.downcast_ref::<BoxCollider>() {
+            let box_pose = cuboid.box_pose(pose);
+            let local = box_pose.inverse_transform_point(*position);
+            let half = cuboid.half_extents() + Vector3F64::ONE * thickness;
+            if (0..3).any(|i| local[i].abs() >= half[i]) {
+                continue;
+            }
+            //Out through the nearest face.
+            let axis = (0..3).min_by(|a, b| (half[*a] - local[*a].abs()).total_cmp(&(half[*b] - local[*b].abs()))).unwrap();
+            let mut normal = Vector3F64::ZERO;
+            normal[axis] = if local[axis] < 0_f64 { -1_f64 } else { 1_f64 };
+            Some((box_pose.transform_vector(normal), half[axis] - local[axis].abs()))
+        } else {
+            None
+        };
+
+        if let Some((normal, depth)) = push {
+            *position += normal * depth;
+            let moved = *position - previous;
+            let sliding = moved - normal * moved.dot(&normal);
+            *position -= sliding * friction.clamp(0_f64, 1_f64);
+            touched = Some(normal);
+        }
+    }
+    touched
+}
@@ -0,0 +1,178 @@
+use crate::geometry::Geometry;
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::collider::{Collider, ALL_LAYERS};
+use crate::physics::pose::Pose;
+use crate::physics::soft_body::imp::{collide_particle, particle_bounds, triangle_edges};
+use crate::physics::soft_body::{MassSpring, Spring};
+use crate::physics::world::World;
+
+impl MassSpring {
+    ///A particle at every vertex of the geometry, the mass spread evenly between them, with a spring along every triangle edge.
+    pub fn from_geometry(geometry: &Geometry<3, f64>, mass: f64, stiffness: f64, damping: f64) -> Self {
+        let count = geometry.vertices.len();
+        let mut system = Self {
+            positions: geometry.vertices.clone(),
+            velocities: vec![Vector3F64::ZERO; count],
+            inverse_masses: vec![count as f64 / mass; count],
+            pinned: vec![false; count],
+            triangles: geometry.indices.chunks_exact(3).map(|t| [t[0], t[1], t[2]]).collect(),
+            springs: vec![],
+            forces: vec![],
+            gravity: Vector3F64::new([0_f64, -9.81, 0_f64]),
+            substeps: 10,
+            thickness: 0.01,
+            collision_mask: ALL_LAYERS,
+        };
+        for (a, b) in triangle_edges(&system.triangles).into_keys() {
+            system.add_spring(a, b, stiffness, damping);
+        }
+        system
+    }
+
+    ///Adds a spring across every edge shared by exactly two triangles, between the vertices opposite it, which resists folding.
+    pub fn with_bending_springs(mut self, stiffness: f64, damping: f64) -> Self {
+        for opposite in triangle_edges(&self.triangles).into_values() {
+            if let [a, b] = opposite[..] {
+                self.add_spring(a, b, stiffness, damping);
+            }
+        }
+        self
+    }
+
+    pub fn with_substeps(mut self, substeps: usize) -> Self {
+        self.substeps = substeps;
+        self
+    }
+
+    pub fn with_gravity(mut self, gravity: Vector3F64) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    pub fn with_thickness(mut self, thickness: f64) -> Self {
+        self.thickness = thickness;
+        self
+    }
+
+    pub fn with_collision_mask(mut self, mask: u32) -> Self {
+        self.collision_mask = mask;
+        self
+    }
+
+    ///Adds a spring between two particles, resting at their current distance.
+    pub fn add_spring(&mut self, a: u32, b: u32, stiffness: f64, damping: f64) {
+        let rest = self.positions[a as usize].distance(&self.positions[b as usize]);
+        self.springs.push(Spring { a, b, rest, stiffness, damping });
+    }
+
+    pub fn springs(&self) -> &[Spring] {
+        &self.springs
+    }
+
+    pub fn pin(&mut self, index: usize) {
+        self.pinned[index] = true;
+        self.velocities[index] = Vector3F64::ZERO;
+    }
+
+    pub fn unpin(&mut self, index: usize) {
+        self.pinned[index] = false;
+    }
+
+    pub fn is_pinned(&self, index: usize) -> bool {
+        self.pinned[index]
+    }
+
+    pub fn move_pinned(&mut self, index: usize, position: Vector3F64) {
+        self.positions[index] = position;
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    pub fn positions(&self) -> &[Vector3F64] {
+        &self.positions
+    }
+
+    pub fn velocities(&self) -> &[Vector3F64] {
+        &self.velocities
+    }
+
+    ///The energy stored in the springs and the motion of the particles, ignoring gravity.
+    pub fn energy(&self) -> f64 {
+        let kinetic = self.velocities.iter().zip(&self.inverse_masses)
+            .map(|(v, w)| 0.5 * v.magnitude_squared() / w)
+            .sum::<f64>();
+        let elastic = self.springs.iter()
+            .map(|s| {
+                let stretch = self.positions[s.a as usize].distance(&self.positions[s.b as usize]) - s.rest;
+                0.5 * s.stiffness * stretch * stretch
+            })
+            .sum::<f64>();
+        kinetic + elastic
+    }
+
+    ///Steps the system on its own, colliding with standalone colliders placed by their rigidbodies.
+    pub fn step(&mut self, delta: f64, colliders: &[&dyn Collider]) {
+        let obstacles = colliders.iter()
+            .filter_map(|collider| collider.pose().map(|pose| (*collider, pose)))
+            .collect::<Vec<(&dyn Collider, Pose)>>();
+        self.simulate(delta, &obstacles);
+    }
+
+    ///Steps the system under the world's gravity, colliding with the world's colliders on the collision mask.
+    pub fn step_in_world(&mut self, delta: f64, world: &World) {
+        self.gravity = world.gravity();
+        let speed = self.velocities.iter().map(|v| v.magnitude()).fold(0_f64, f64::max);
+        let obstacles = world.obstacles(particle_bounds(&self.positions, self.thickness + speed * delta), self.collision_mask).into_iter()
+            .map(|(_, collider, pose)| (collider, pose))
+            .collect::<Vec<(&dyn Collider, Pose)>>();
+        self.simulate(delta, &obstacles);
+    }
+
+    fn simulate(&mut self, delta: f64, obstacles: &[(&dyn Collider, Pose)]) {
+        if delta <= 0_f64 || self.is_empty() {
+            return;
+        }
+        let substeps = self.substeps.max(1);
+        let h = delta / substeps as f64;
+
+        for _ in 0..substeps {
+            self.forces.clear();
+            self.forces.resize(self.len(), Vector3F64::ZERO);
+            for spring in &self.springs {
+                let (a, b) = (spring.a as usize, spring.b as usize);
+                let offset = self.positions[b] - self.positions[a];
+                let length = offset.magnitude();
+                if length < f64::EPSILON {
+                    continue;
+                }
+                let direction = offset / length;
+                let stretching = (self.velocities[b] - self.velocities[a]).dot(&direction);
+                let force = direction * (spring.stiffness * (length - spring.rest) + spring.damping * stretching);
+                self.forces[a] += force;
+                self.forces[b] -= force;
+            }
+
+            for i in 0..self.len() {
+                if self.pinned[i] || self.inverse_masses[i] == 0_f64 {
+                    continue;
+                }
+                self.velocities[i] += (self.gravity + self.forces[i] * self.inverse_masses[i]) * h;
+                let previous = self.positions[i];
+                self.positions[i] += self.velocities[i] * h;
+                if let Some(normal) = collide_particle(&mut self.positions[i], previous, self.thickness, 0_f64, obstacles) {
+                    //Stop moving into the surface.
+                    let into = self.velocities[i].dot(&normal);
+                    if into < 0_f64 {
+                        self.velocities[i] -= normal * into;
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::linear_algebra::vector::types::Vector3F64;
+
+pub mod imp;
+pub mod mass_spring;
+
+///A constraint between the particles of a [SoftBody], solved by projecting their positions.
+#[derive(Debug, Clone, Copy)]
+pub enum SoftConstraint {
+    ///Keeps two particles at their rest distance, like a stiff rod.
+    Distance {
+        a: u32,
+        b: u32,
+        rest: f64,
+        ///Inverse stiffness, in meters per newton. Zero is perfectly rigid.
+        compliance: f64,
+    },
+    ///Resists folding along the edge two triangles share, by keeping the vertices opposite that edge at their rest distance.
+    Bending {
+        a: u32,
+        b: u32,
+        rest: f64,
+        compliance: f64,
+    },
+    ///Keeps the volume enclosed by every triangle of the body at its rest volume times the pressure.
+    ///<br>Only meaningful for closed meshes with consistently wound triangles.
+    Volume {
+        rest: f64,
+        pressure: f64,
+        compliance: f64,
+    },
+}
+
+///Tunables shared by every step of a [SoftBody].
+#[derive(Debug, Clone, Copy)]
+pub struct SoftBodySettings {
+    ///Smaller steps each step is split into. More substeps stiffen constraints far more cheaply than more iterations.
+    pub substeps: usize,
+    ///Passes over every constraint per substep.
+    pub iterations: usize,
+    ///Fraction of velocity lost per second.
+    pub damping: f64,
+    ///How far particles are kept from collider surfaces.
+    pub thickness: f64,
+    ///Fraction of sliding cancelled while a particle touches a collider, between 0 and 1.
+    pub friction: f64,
+    ///Layers of world colliders the body collides with.
+    pub collision_mask: u32,
+}
+
+///A deformable body simulated with extended position based dynamics, for cloth, ropes and balloons.
+///<br>Particles are predicted from their velocities, then constraints and collisions move them directly, and velocities are derived from how far they moved.
+///<br>See <a href="https://matthias-research.github.io/pages/publications/XPBD.pdf">XPBD: Position-Based Simulation of Compliant Constrained Dynamics</a>
+pub struct SoftBody {
+    pub(crate) positions: Vec<Vector3F64>,
+    ///Positions at the start of the substep, for deriving velocities.
+    pub(crate) previous: Vec<Vector3F64>,
+    pub(crate) velocities: Vec<Vector3F64>,
+    pub(crate) inverse_masses: Vec<f64>,
+    ///Pinned particles keep their mass but act as if it was infinite.
+    pub(crate) pinned: Vec<bool>,
+    pub(crate) triangles: Vec<[u32; 3]>,
+    pub(crate) constraints: Vec<SoftConstraint>,
+    ///Accumulated multipliers of each constraint over the current substep.
+    pub(crate) lambdas: Vec<f64>,
+    pub(crate) gravity: Vector3F64,
+    pub(crate) settings: SoftBodySettings,
+}
+
+///A spring pulling two particles of a [MassSpring] towards their rest distance.
+#[derive(Debug, Clone, Copy)]
+pub struct Spring {
+    pub a: u32,
+    pub b: u32,
+    pub rest: f64,
+    ///Newtons per meter of stretch.
+    pub stiffness: f64,
+    ///Newtons per meter per second of stretching speed.
+    pub damping: f64,
+}
+
+///The classic alternative to [SoftBody]: springs apply forces which are integrated explicitly with semi-implicit Euler.
+///<br>Cheaper per step, but stiff springs need small steps to stay stable, where position based constraints don't.
+pub struct MassSpring {
+    pub(crate) positions: Vec<Vector3F64>,
+    pub(crate) velocities: Vec<Vector3F64>,
+    pub(crate) inverse_masses: Vec<f64>,
+    pub(crate) pinned: Vec<bool>,
+    pub(crate) triangles: Vec<[u32; 3]>,
+    pub(crate) springs: Vec<Spring>,
+    ///Scratch column the spring forces accumulate into.
+    pub(crate) forces: Vec<Vector3F64>,
+    pub(crate) gravity: Vector3F64,
+    pub(crate) substeps: usize,
+    pub(crate) thickness: f64,
+    pub(crate) collision_mask: u32,
+}
@@ -0,0 +1,179 @@
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::contact::{ContactManifold, FeatureId};
+use crate::physics::material::PhysicsMaterial;
+use crate::physics::solver::{SolverBody, SolverSettings};
+
+///The accumulated impulses of a contact point, kept between steps for warm starting.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CachedImpulse {
+    pub feature: FeatureId,
+    pub normal: f64,
+    ///Friction impulse in world space, so it survives the tangent basis changing between steps.
+    pub tangent: Vector3F64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ContactConstraintPoint {
+    pub feature: FeatureId,
+    pub ra: Vector3F64,
+    pub rb: Vector3F64,
+    pub normal_mass: f64,
+    pub tangent_mass: [f64; 2],
+    ///Target normal velocity from restitution and Baumgarte stabilization.
+    pub bias: f64,
+    pub normal_impulse: f64,
+    pub tangent_impulse: [f64; 2],
+}
+
+///A non-penetration and friction constraint built from one contact manifold between bodies A and B.
+#[derive(Debug, Clone)]
+pub(crate) struct ContactConstraint {
+    pub body_a: usize,
+    pub body_b: usize,
+    pub normal: Vector3F64,
+    pub tangents: [Vector3F64; 2],
+    pub friction: f64,
+    pub points: Vec<ContactConstraintPoint>,
+}
+
+impl ContactConstraint {
+    pub fn new(
+        manifold: &ContactManifold,
+        body_a: usize,
+        body_b: usize,
+        bodies: &[SolverBody],
+        material: PhysicsMaterial,
+        settings: &SolverSettings,
+        delta: f64,
+        cache: Option<&Vec<CachedImpulse>>,
+    ) -> Self {
+        let (a, b) = (&bodies[body_a], &bodies[body_b]);
+        let normal = manifold.normal;
+        let tangents = tangent_basis(normal);
+
+        let points = manifold.points.iter().map(|point| {
+            let ra = point.position - a.position;
+            let rb = point.position - b.position;
+
+            let normal_mass = inverse_or_zero(a.inverse_effective_mass(ra, normal) + b.inverse_effective_mass(rb, normal));
+            let tangent_mass = [
+                inverse_or_zero(a.inverse_effective_mass(ra, tangents[0]) + b.inverse_effective_mass(rb, tangents[0])),
+                inverse_or_zero(a.inverse_effective_mass(ra, tangents[1]) + b.inverse_effective_mass(rb, tangents[1])),
+            ];
+
+            let approach = (b.velocity_at(rb) - a.velocity_at(ra)).dot(&normal);
+            let restitution_bias = if approach < -settings.restitution_threshold {
+                -material.restitution * approach
+            } else {
+                0_f64
+            };
+            let position_bias = settings.baumgarte / delta * (point.depth - settings.slop).max(0_f64);
+
+            let cached = cache
+                .and_then(|cache| cache.iter().find(|c| c.feature == point.feature))
+                .filter(|_| settings.warm_starting);
+
+            ContactConstraintPoint {
+                feature: point.feature,
+                ra,
+                rb,
+                normal_mass,
+                tangent_mass,
+                bias: restitution_bias.max(position_bias),
+                normal_impulse: cached.map(|c| c.normal).unwrap_or(0_f64),
+                tangent_impulse: cached
+                    .map(|c| [c.tangent.dot(&tangents[0]), c.tangent.dot(&tangents[1])])
+                    .unwrap_or([0_f64; 2]),
+            }
+        }).collect();
+
+        Self {
+            body_a,
+            body_b,
+            normal,
+            tangents,
+            friction: material.friction,
+            points,
+        }
+    }
+
+    ///Applies the impulses carried over from the previous step.
+    pub fn warm_start(&self, bodies: &mut [SolverBody]) {
+        let (mut a, mut b) = (bodies[self.body_a], bodies[self.body_b]);
+        for point in &self.points {
+            let impulse = self.normal * point.normal_impulse
+                + self.tangents[0] * point.tangent_impulse[0]
+                + self.tangents[1] * point.tangent_impulse[1];
+            a.apply_impulse(-impulse, point.ra);
+            b.apply_impulse(impulse, point.rb);
+        }
+        bodies[self.body_a] = a;
+        bodies[self.body_b] = b;
+    }
+
+    ///Runs one iteration of the sequential impulse solver over this constraint's points.
+    ///<br>Friction is solved first using the previous normal impulse as its limit, then the normal impulse is clamped to be non-negative.
+    pub fn solve(&mut self, bodies: &mut [SolverBody]) {
+        let (mut a, mut b) = (bodies[self.body_a], bodies[self.body_b]);
+
+        for point in &mut self.points {
+            let relative = b.velocity_at(point.rb) - a.velocity_at(point.ra);
+            let mut tangent_impulse = point.tangent_impulse;
+            for k in 0..2 {
+                let vt = relative.dot(&self.tangents[k]);
+                tangent_impulse[k] -= point.tangent_mass[k] * vt;
+            }
+
+            //Coulomb friction cone, clamping the combined tangent impulse.
+            let limit = self.friction * point.normal_impulse;
+            let magnitude = (tangent_impulse[0] * tangent_impulse[0] + tangent_impulse[1] * tangent_impulse[1]).sqrt();
+            if magnitude > limit {
+                let scale = if magnitude > 0_f64 { limit / magnitude } else { 0_f64 };
+                tangent_impulse[0] *= scale;
+                tangent_impulse[1] *= scale;
+            }
+
+            let impulse = self.tangents[0] * (tangent_impulse[0] - point.tangent_impulse[0])
+                + self.tangents[1] * (tangent_impulse[1] - point.tangent_impulse[1]);
+            point.tangent_impulse = tangent_impulse;
+            a.apply_impulse(-impulse, point.ra);
+            b.apply_impulse(impulse, point.rb);
+
+            let vn = (b.velocity_at(point.rb) - a.velocity_at(point.ra)).dot(&self.normal);
+            let lambda = -point.normal_mass * (vn - point.bias);
+            let accumulated = (point.normal_impulse + lambda).max(0_f64);
+            let lambda = accumulated - point.normal_impulse;
+            point.normal_impulse = accumulated;
+
+            let impulse = self.normal * lambda;
+            a.apply_impulse(-impulse, point.ra);
+            b.apply_impulse(impulse, point.rb);
+        }
+
+        bodies[self.body_a] = a;
+        bodies[self.body_b] = b;
+    }
+
+    ///The impulses to warm start this contact with next step.
+    pub fn cached_impulses(&self) -> Vec<CachedImpulse> {
+        self.points.iter().map(|point| CachedImpulse {
+            feature: point.feature,
+            normal: point.normal_impulse,
+            tangent: self.tangents[0] * point.tangent_impulse[0] + self.tangents[1] * point.tangent_impulse[1],
+        }).collect()
+    }
+}
+
+fn inverse_or_zero(k: f64) -> f64 {
+    if k > 0_f64 { 1_f64 / k } else { 0_f64 }
+}
+
+///Two unit vectors perpendicular to the normal and to each other.
+pub(crate) fn tangent_basis(normal: Vector3F64) -> [Vector3F64; 2] {
+    let t1 = if normal.x().abs() >= 0.57735 {
+        Vector3F64::new([normal.y(), -normal.x(), 0_f64])
+    } else {
+        Vector3F64::new([0_f64, normal.z(), -normal.y()])
+    }.normalize();
+    [t1, normal.cross(&t1)]
+}
@@ -0,0 +1,93 @@
+use crate::linear_algebra::quaternion::Quaternion;
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::body_store::BodyStore;
+use crate::physics::pose::Pose;
+use crate::physics::solver::SolverBody;
+
+impl SolverBody {
+    ///A copy of a slot in the store. Sleeping and removed rigidbodies already have an inverse mass of 0, and are frozen in place.
+    pub fn from_store(store: &BodyStore, index: usize) -> Self {
+        let dynamic = store.inverse_masses[index] > 0_f64;
+        let inertia = store.inertias[index];
+        let invert = |i: f64| if dynamic && i > 0_f64 { 1_f64 / i } else { 0_f64 };
+
+        let mut body = Self {
+            id: index as u32,
+            position: store.positions[index],
+            rotation: store.rotations[index],
+            velocity: store.velocities[index],
+            angular_velocity: store.angular_velocities[index],
+            inverse_mass: store.inverse_masses[index],
+            inverse_inertia: Vector3F64::new([invert(inertia[0]), invert(inertia[1]), invert(inertia[2])]),
+            flags: store.flags[index],
+        };
+        if store.sleeping[index].is_some() || !store.alive[index] {
+            body.freeze();
+        }
+        body
+    }
+
+    ///A static body at the origin, standing in for the world in joints attached to it.
+    pub fn world() -> Self {
+        Self {
+            id: u32::MAX,
+            position: Vector3F64::ZERO,
+            rotation: Quaternion::identity(),
+            velocity: Vector3F64::ZERO,
+            angular_velocity: Vector3F64::ZERO,
+            inverse_mass: 0_f64,
+            inverse_inertia: Vector3F64::ZERO,
+            flags: 0,
+        }
+    }
+
+    ///Makes the body immovable for the rest of the step, as sleeping bodies are.
+    pub fn freeze(&mut self) {
+        self.velocity = Vector3F64::ZERO;
+        self.angular_velocity = Vector3F64::ZERO;
+        self.inverse_mass = 0_f64;
+        self.inverse_inertia = Vector3F64::ZERO;
+    }
+
+    ///Copies the solved velocities back into the store, ready for positions to be integrated.
+    pub fn write_back(&self, store: &mut BodyStore) {
+        let index = self.id as usize;
+        store.velocities[index] = self.velocity;
+        store.angular_velocities[index] = self.angular_velocity;
+    }
+
+    pub fn is_dynamic(&self) -> bool {
+        self.inverse_mass > 0_f64
+    }
+
+    pub fn pose(&self) -> Pose {
+        Pose::new(self.position, self.rotation)
+    }
+
+    ///Multiplies a world space vector by the world space inverse inertia tensor, R I⁻¹ Rᵀ v.
+    pub fn apply_inverse_inertia(&self, v: Vector3F64) -> Vector3F64 {
+        let mut local = self.rotation.conjugate() * v;
+        for i in 0..3 {
+            local[i] *= self.inverse_inertia[i];
+        }
+        self.rotation * local
+    }
+
+    ///The velocity of a point on this body, offset from its center by r.
+    pub fn velocity_at(&self, r: Vector3F64) -> Vector3F64 {
+        self.velocity + self.angular_velocity.cross(&r)
+    }
+
+    ///Applies an impulse at an offset r from the body's center, changing both linear and angular velocity.
+    pub fn apply_impulse(&mut self, impulse: Vector3F64, r: Vector3F64) {
+        self.velocity += impulse * self.inverse_mass;
+        self.angular_velocity += self.apply_inverse_inertia(r.cross(&impulse));
+    }
+
+    ///The inverse effective mass of this body along a direction applied at offset r.
+    ///<br>1/m + ((I⁻¹(r × n)) × r) · n
+    pub fn inverse_effective_mass(&self, r: Vector3F64, direction: Vector3F64) -> f64 {
+        let rn = r.cross(&direction);
+        self.inverse_mass + self.apply_inverse_inertia(rn).cross(&r).dot(&direction)
+    }
+}
@@ -0,0 +1,258 @@
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::joint::{JointKind, JointMotor};
+use crate::physics::solver::contact_constraint::tangent_basis;
+use crate::physics::solver::{SolverBody, SolverSettings};
+use crate::physics::world::JointEntry;
+
+///How many rows a joint can have, each owning a fixed slot so its impulse can be warm started.
+pub(crate) const JOINT_ROWS: usize = 7;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Jacobian {
+    ///Relative velocity of the anchor points along a direction.
+    Linear {
+        ra: Vector3F64,
+        rb: Vector3F64,
+        direction: Vector3F64,
+    },
+    ///Relative angular velocity around an axis.
+    Angular {
+        axis: Vector3F64,
+    },
+}
+
+///A single scalar constraint on the relative velocity of two bodies.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct JointRow {
+    pub slot: usize,
+    pub jacobian: Jacobian,
+    pub mass: f64,
+    ///Target relative velocity, driving the position error back to 0 or the motor at its speed.
+    pub target: f64,
+    ///Non zero for springs, letting the constraint give way in proportion to the impulse applied.
+    pub softness: f64,
+    pub lower: f64,
+    pub upper: f64,
+    pub impulse: f64,
+}
+
+///The rows of one joint for the current step.
+#[derive(Debug, Clone)]
+pub(crate) struct JointConstraint {
+    pub joint: u32,
+    pub body_a: usize,
+    pub body_b: usize,
+    pub rows: Vec<JointRow>,
+}
+
+impl JointConstraint {
+    pub fn new(joint: u32, entry: &JointEntry, body_a: usize, body_b: usize, bodies: &[SolverBody], settings: &SolverSettings, delta: f64) -> Self {
+        let (a, b) = (&bodies[body_a], &bodies[body_b]);
+        let (pose_a, pose_b) = (a.pose(), b.pose());
+        let anchor_a = pose_a.transform_point(entry.local_anchor_a);
+        let anchor_b = pose_b.transform_point(entry.local_anchor_b);
+        let (ra, rb) = (anchor_a - a.position, anchor_b - b.position);
+        let offset = anchor_b - anchor_a;
+        let axis_a = pose_a.transform_vector(entry.local_axis_a);
+        let axis_b = pose_b.transform_vector(entry.local_axis_b);
+        let correction = settings.baumgarte / delta;
+
+        let mut builder = RowBuilder {
+            a,
+            b,
+            rows: vec![],
+            cached: if settings.warm_starting { entry.impulses } else { [0_f64; JOINT_ROWS] },
+        };
+
+        match entry.joint.kind {
+            JointKind::Distance { min_length, max_length } => {
+                let length = offset.magnitude();
+                let direction = if length > 1e-9 { offset / length } else { Vector3F64::UP };
+                let jacobian = Jacobian::Linear { ra, rb, direction };
+                if min_length >= max_length {
+                    builder.row(0, jacobian, -correction * (length - min_length), f64::NEG_INFINITY, f64::INFINITY);
+                } else if length < min_length {
+                    builder.row(0, jacobian, -correction * (length - min_length), 0_f64, f64::INFINITY);
+                } else if length > max_length {
+                    builder.row(0, jacobian, -correction * (length - max_length), f64::NEG_INFINITY, 0_f64);
+                }
+            }
+            JointKind::BallSocket => {
+                builder.point(ra, rb, offset, correction);
+            }
+            JointKind::Hinge { limits, motor } => {
+                builder.point(ra, rb, offset, correction);
+
+                //Keep the hinge axes of both bodies aligned.
+                let error = axis_a.cross(&axis_b);
+                for (k, tangent) in tangent_basis(axis_a).into_iter().enumerate() {
+                    builder.row(3 + k, Jacobian::Angular { axis: tangent }, -correction * error.dot(&tangent), f64::NEG_INFINITY, f64::INFINITY);
+                }
+
+                let reference_a = pose_a.transform_vector(entry.local_reference_a);
+                let reference_b = pose_b.transform_vector(entry.local_reference_b);
+                let angle = reference_a.cross(&reference_b).dot(&axis_a).atan2(reference_a.dot(&reference_b));
+                builder.limit(5, Jacobian::Angular { axis: axis_a }, angle, limits, correction);
+                builder.motor(6, Jacobian::Angular { axis: axis_a }, motor, delta);
+            }
+            JointKind::Slider { limits, motor } => {
+                //The lever arm of A reaches B's anchor so sliding doesn't induce rotation.
+                let ra = anchor_b - a.position;
+                for (k, tangent) in tangent_basis(axis_a).into_iter().enumerate() {
+                    builder.row(k, Jacobian::Linear { ra, rb, direction: tangent }, -correction * offset.dot(&tangent), f64::NEG_INFINITY, f64::INFINITY);
+                }
+                builder.orientation(entry, correction);
+                builder.limit(2, Jacobian::Linear { ra, rb, direction: axis_a }, offset.dot(&axis_a), limits, correction);
+                builder.motor(6, Jacobian::Linear { ra, rb, direction: axis_a }, motor, delta);
+            }
+            JointKind::Fixed => {
+                builder.point(ra, rb, offset, correction);
+                builder.orientation(entry, correction);
+            }
+            JointKind::Spring { rest_length, stiffness, damping } => {
+                let length = offset.magnitude();
+                let direction = if length > 1e-9 { offset / length } else { Vector3F64::UP };
+                let denominator = damping + delta * stiffness;
+                if denominator > 0_f64 {
+                    //Implicit spring, stable for any stiffness. See Catto, Soft Constraints, GDC 2011.
+                    let softness = 1_f64 / (delta * denominator);
+                    let target = -stiffness / denominator * (length - rest_length);
+                    builder.soft_row(0, Jacobian::Linear { ra, rb, direction }, target, softness);
+                }
+            }
+        }
+
+        Self {
+            joint,
+            body_a,
+            body_b,
+            rows: builder.rows,
+        }
+    }
+
+    ///Applies the impulses carried over from the previous step.
+    pub fn warm_start(&self, bodies: &mut [SolverBody]) {
+        let (mut a, mut b) = (bodies[self.body_a], bodies[self.body_b]);
+        for row in &self.rows {
+            apply(&mut a, &mut b, &row.jacobian, row.impulse);
+        }
+        bodies[self.body_a] = a;
+        bodies[self.body_b] = b;
+    }
+
+    pub fn solve(&mut self, bodies: &mut [SolverBody]) {
+        let (mut a, mut b) = (bodies[self.body_a], bodies[self.body_b]);
+        for row in &mut self.rows {
+            let velocity = match row.jacobian {
+                Jacobian::Linear { ra, rb, direction } => (b.velocity_at(rb) - a.velocity_at(ra)).dot(&direction),
+                Jacobian::Angular { axis } => (b.angular_velocity - a.angular_velocity).dot(&axis),
+            };
+            let lambda = -row.mass * (velocity - row.target + row.softness * row.impulse);
+            let accumulated = (row.impulse + lambda).clamp(row.lower, row.upper);
+            let lambda = accumulated - row.impulse;
+            row.impulse = accumulated;
+            apply(&mut a, &mut b, &row.jacobian, lambda);
+        }
+        bodies[self.body_a] = a;
+        bodies[self.body_b] = b;
+    }
+
+    ///The accumulated impulse of every slot, 0 for rows that weren't active.
+    pub fn impulses(&self) -> [f64; JOINT_ROWS] {
+        let mut impulses = [0_f64; JOINT_ROWS];
+        for row in &self.rows {
+            impulses[row.slot] = row.impulse;
+        }
+        impulses
+    }
+}
+
+fn apply(a: &mut SolverBody, b: &mut SolverBody, jacobian: &Jacobian, impulse: f64) {
+    match *jacobian {
+        Jacobian::Linear { ra, rb, direction } => {
+            a.apply_impulse(-direction * impulse, ra);
+            b.apply_impulse(direction * impulse, rb);
+        }
+        Jacobian::Angular { axis } => {
+            a.angular_velocity -= a.apply_inverse_inertia(axis * impulse);
+            b.angular_velocity += b.apply_inverse_inertia(axis * impulse);
+        }
+    }
+}
+
+struct RowBuilder<'a> {
+    a: &'a SolverBody,
+    b: &'a SolverBody,
+    rows: Vec<JointRow>,
+    cached: [f64; JOINT_ROWS],
+}
+
+impl RowBuilder<'_> {
+    fn inverse_mass(&self, jacobian: &Jacobian) -> f64 {
+        match *jacobian {
+            Jacobian::Linear { ra, rb, direction } => self.a.inverse_effective_mass(ra, direction) + self.b.inverse_effective_mass(rb, direction),
+            Jacobian::Angular { axis } => self.a.apply_inverse_inertia(axis).dot(&axis) + self.b.apply_inverse_inertia(axis).dot(&axis),
+        }
+    }
+
+    fn push(&mut self, slot: usize, jacobian: Jacobian, target: f64, softness: f64, lower: f64, upper: f64) {
+        let k = self.inverse_mass(&jacobian) + softness;
+        if k <= 0_f64 {
+            return;
+        }
+        self.rows.push(JointRow {
+            slot,
+            jacobian,
+            mass: 1_f64 / k,
+            target,
+            softness,
+            lower,
+            upper,
+            impulse: self.cached[slot].clamp(lower, upper),
+        });
+    }
+
+    fn row(&mut self, slot: usize, jacobian: Jacobian, target: f64, lower: f64, upper: f64) {
+        self.push(slot, jacobian, target, 0_f64, lower, upper);
+    }
+
+    fn soft_row(&mut self, slot: usize, jacobian: Jacobian, target: f64, softness: f64) {
+        self.push(slot, jacobian, target, softness, f64::NEG_INFINITY, f64::INFINITY);
+    }
+
+    ///Three rows pinning the anchors together.
+    fn point(&mut self, ra: Vector3F64, rb: Vector3F64, offset: Vector3F64, correction: f64) {
+        for (k, direction) in [Vector3F64::RIGHT, Vector3F64::UP, Vector3F64::FORWARD].into_iter().enumerate() {
+            self.row(k, Jacobian::Linear { ra, rb, direction }, -correction * offset[k], f64::NEG_INFINITY, f64::INFINITY);
+        }
+    }
+
+    ///Three rows keeping B's rotation relative to A what it was when the joint was added.
+    fn orientation(&mut self, entry: &JointEntry, correction: f64) {
+        let target = self.a.rotation * entry.rest_rotation;
+        let mut difference = self.b.rotation * target.conjugate();
+        if *difference.w() < 0_f64 {
+            difference = difference * -1_f64;
+        }
+        let error = *difference.xyz() * 2_f64;
+        for (k, axis) in [Vector3F64::RIGHT, Vector3F64::UP, Vector3F64::FORWARD].into_iter().enumerate() {
+            self.row(3 + k, Jacobian::Angular { axis }, -correction * error[k], f64::NEG_INFINITY, f64::INFINITY);
+        }
+    }
+
+    ///A one sided row that only pushes back once the position goes past one of the limits.
+    fn limit(&mut self, slot: usize, jacobian: Jacobian, position: f64, limits: Option<(f64, f64)>, correction: f64) {
+        let Some((lower, upper)) = limits else { return };
+        if position <= lower {
+            self.row(slot, jacobian, -correction * (position - lower), 0_f64, f64::INFINITY);
+        } else if position >= upper {
+            self.row(slot, jacobian, -correction * (position - upper), f64::NEG_INFINITY, 0_f64);
+        }
+    }
+
+    fn motor(&mut self, slot: usize, jacobian: Jacobian, motor: Option<JointMotor>, delta: f64) {
+        let Some(motor) = motor else { return };
+        let max_impulse = motor.max_force * delta;
+        self.row(slot, jacobian, motor.target_speed, -max_impulse, max_impulse);
+    }
+}
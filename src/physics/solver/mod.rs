@@ -0,0 +1,50 @@
+use crate::linear_algebra::quaternion::Quaternion;
+use crate::linear_algebra::vector::types::Vector3F64;
+
+pub mod imp;
+pub mod contact_constraint;
+pub mod joint_constraint;
+
+///Tuning for the sequential impulse solver used by [crate::physics::world::World].
+#[derive(Debug, Clone, Copy)]
+pub struct SolverSettings {
+    ///How many times every constraint is solved per step. More iterations give stiffer stacks at a higher cost.
+    pub velocity_iterations: usize,
+    ///Fraction of the remaining penetration removed per step by Baumgarte stabilization, usually between 0.1 and 0.3.
+    pub baumgarte: f64,
+    ///Penetration allowed before positional correction kicks in. Keeps resting contacts from jittering.
+    pub slop: f64,
+    ///Approach speed below which restitution is ignored, so resting bodies don't bounce forever.
+    pub restitution_threshold: f64,
+    ///Whether to start each step with last step's impulses for contacts that persist.
+    pub warm_starting: bool,
+    ///Threads integrating velocities and positions. At 1 everything runs on the calling thread, and more only pay off past [crate::physics::body_store::MIN_BODIES_PER_THREAD] bodies per thread.
+    pub threads: usize,
+}
+
+impl Default for SolverSettings {
+    fn default() -> Self {
+        Self {
+            velocity_iterations: 8,
+            baumgarte: 0.2,
+            slop: 0.005,
+            restitution_threshold: 1.0,
+            warm_starting: true,
+            threads: 1,
+        }
+    }
+}
+
+///A copy of a rigidbody's state that the solver works on during a step, written back once the step is done.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SolverBody {
+    pub id: u32,
+    pub position: Vector3F64,
+    pub rotation: Quaternion<f64>,
+    pub velocity: Vector3F64,
+    pub angular_velocity: Vector3F64,
+    pub inverse_mass: f64,
+    ///Inverse principal moments of inertia in local space.
+    pub inverse_inertia: Vector3F64,
+    pub flags: u8,
+}
@@ -0,0 +1,328 @@
+use crate::linear_algebra::quaternion::Quaternion;
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::collider::ALL_LAYERS;
+use crate::physics::pose::Pose;
+use crate::physics::rigidbody_handle::RigidbodyHandle;
+use crate::physics::solver::SolverBody;
+use crate::physics::vehicle::{AntiRollBar, FrictionCurve, Vehicle, Wheel, WheelContact, WheelSettings};
+use crate::physics::world::World;
+use crate::shared::angle::Angle::Radians;
+
+impl FrictionCurve {
+    ///The friction coefficient at an amount of slip, either sign.
+    pub fn evaluate(&self, slip: f64) -> f64 {
+        let slip = slip.abs();
+        if slip <= self.extremum_slip {
+            if self.extremum_slip <= 0_f64 {
+                return self.extremum_value;
+            }
+            self.extremum_value * slip / self.extremum_slip
+        } else if slip < self.asymptote_slip {
+            let t = (slip - self.extremum_slip) / (self.asymptote_slip - self.extremum_slip);
+            self.extremum_value + (self.asymptote_value - self.extremum_value) * t
+        } else {
+            self.asymptote_value
+        }
+    }
+}
+
+impl Default for FrictionCurve {
+    ///A road tire, peaking at about 6° of slip.
+    fn default() -> Self {
+        Self {
+            extremum_slip: 0.1,
+            extremum_value: 1_f64,
+            asymptote_slip: 0.5,
+            asymptote_value: 0.7,
+        }
+    }
+}
+
+impl WheelSettings {
+    ///A wheel with suspension suiting a chassis of about a tonne on four wheels.
+    pub fn new(anchor: Vector3F64, radius: f64) -> Self {
+        Self {
+            anchor,
+            radius,
+            rest_length: 0.3,
+            stiffness: 35_000_f64,
+            damping: 4_000_f64,
+            friction: FrictionCurve::default(),
+            steered: false,
+            driven: false,
+        }
+    }
+
+    pub fn with_suspension(mut self, rest_length: f64, stiffness: f64, damping: f64) -> Self {
+        self.rest_length = rest_length;
+        self.stiffness = stiffness;
+        self.damping = damping;
+        self
+    }
+
+    pub fn with_friction(mut self, friction: FrictionCurve) -> Self {
+        self.friction = friction;
+        self
+    }
+
+    pub fn steered(mut self) -> Self {
+        self.steered = true;
+        self
+    }
+
+    pub fn driven(mut self) -> Self {
+        self.driven = true;
+        self
+    }
+}
+
+impl Wheel {
+    pub(crate) fn new(settings: WheelSettings) -> Self {
+        Self {
+            settings,
+            length: settings.rest_length,
+            contact: None,
+            steering: 0_f64,
+            spin: 0_f64,
+            rotation: 0_f64,
+            load: 0_f64,
+            slip: 0_f64,
+        }
+    }
+
+    pub fn settings(&self) -> &WheelSettings {
+        &self.settings
+    }
+
+    pub fn contact(&self) -> Option<&WheelContact> {
+        self.contact.as_ref()
+    }
+
+    pub fn is_grounded(&self) -> bool {
+        self.contact.is_some()
+    }
+
+    pub fn suspension_length(&self) -> f64 {
+        self.length
+    }
+
+    ///How far the suspension is pushed in from its rest length.
+    pub fn compression(&self) -> f64 {
+        self.settings.rest_length - self.length
+    }
+
+    ///Force the suspension pushed the chassis up with in the last step, in newtons.
+    pub fn load(&self) -> f64 {
+        self.load
+    }
+
+    pub fn steering(&self) -> f64 {
+        self.steering
+    }
+
+    pub fn spin(&self) -> f64 {
+        self.spin
+    }
+
+    pub fn rotation(&self) -> f64 {
+        self.rotation
+    }
+
+    pub fn slip(&self) -> f64 {
+        self.slip
+    }
+
+    ///Where the wheel's center is for a chassis pose, for drawing it.
+    pub fn center(&self, chassis: &Pose) -> Vector3F64 {
+        chassis.transform_point(self.settings.anchor - Vector3F64::UP * self.length)
+    }
+}
+
+impl Vehicle {
+    ///A vehicle without wheels driving the provided chassis.
+    pub fn new(chassis: RigidbodyHandle) -> Self {
+        Self {
+            chassis,
+            wheels: vec![],
+            anti_roll_bars: vec![],
+            engine_force: 8_000_f64,
+            brake_force: 6_000_f64,
+            max_steering: 35_f64.to_radians(),
+            throttle: 0_f64,
+            brake: 0_f64,
+            steering: 0_f64,
+            collision_mask: ALL_LAYERS,
+        }
+    }
+
+    pub fn with_wheel(mut self, settings: WheelSettings) -> Self {
+        self.wheels.push(Wheel::new(settings));
+        self
+    }
+
+    ///Connects two wheels, by their order of being added, with an anti-roll bar.
+    pub fn with_anti_roll_bar(mut self, left: usize, right: usize, stiffness: f64) -> Self {
+        self.anti_roll_bars.push(AntiRollBar { left, right, stiffness });
+        self
+    }
+
+    pub fn with_engine_force(mut self, engine_force: f64) -> Self {
+        self.engine_force = engine_force;
+        self
+    }
+
+    pub fn with_brake_force(mut self, brake_force: f64) -> Self {
+        self.brake_force = brake_force;
+        self
+    }
+
+    pub fn with_max_steering(mut self, max_steering: f64) -> Self {
+        self.max_steering = max_steering;
+        self
+    }
+
+    pub fn with_collision_mask(mut self, mask: u32) -> Self {
+        self.collision_mask = mask;
+        self
+    }
+
+    pub fn chassis(&self) -> RigidbodyHandle {
+        self.chassis
+    }
+
+    pub fn wheels(&self) -> &[Wheel] {
+        &self.wheels
+    }
+
+    pub fn anti_roll_bars(&self) -> &[AntiRollBar] {
+        &self.anti_roll_bars
+    }
+
+    pub fn throttle(&self) -> f64 {
+        self.throttle
+    }
+
+    pub fn brake(&self) -> f64 {
+        self.brake
+    }
+
+    pub fn steering(&self) -> f64 {
+        self.steering
+    }
+
+    ///Between -1 for full reverse and 1 for full forward.
+    pub fn set_throttle(&mut self, throttle: f64) {
+        self.throttle = throttle.clamp(-1_f64, 1_f64);
+    }
+
+    ///Between 0 and 1.
+    pub fn set_brake(&mut self, brake: f64) {
+        self.brake = brake.clamp(0_f64, 1_f64);
+    }
+
+    ///Between -1 for full left and 1 for full right.
+    pub fn set_steering(&mut self, steering: f64) {
+        self.steering = steering.clamp(-1_f64, 1_f64);
+    }
+
+    ///Whether any input asks the vehicle to do something, which keeps its chassis awake.
+    pub(crate) fn is_active(&self) -> bool {
+        self.throttle != 0_f64 || self.steering != 0_f64
+    }
+
+    ///Casts the wheels against the world and applies suspension and tire impulses to the chassis for a step.
+    pub(crate) fn update(&mut self, world: &World, bodies: &mut [SolverBody], delta: f64) {
+        if !world.contains(&self.chassis) {
+            return;
+        }
+        let body = &mut bodies[self.chassis.id as usize];
+        let pose = body.pose();
+        let up = pose.transform_vector(Vector3F64::UP);
+
+        for wheel in &mut self.wheels {
+            wheel.steering = if wheel.settings.steered { self.steering * self.max_steering } else { 0_f64 };
+            let anchor = pose.transform_point(wheel.settings.anchor);
+            let reach = wheel.settings.rest_length + wheel.settings.radius;
+            let hit = world.raycast_ignoring(anchor, -up, reach, self.collision_mask, self.chassis.id);
+
+            let previous = wheel.length;
+            wheel.contact = hit.map(|hit| WheelContact {
+                collider: hit.collider,
+                point: hit.point,
+                normal: hit.normal,
+            });
+            wheel.length = hit.map(|hit| (hit.distance - wheel.settings.radius).max(0_f64)).unwrap_or(wheel.settings.rest_length);
+            wheel.load = 0_f64;
+            if hit.is_some() {
+                //Measured from the change in length rather than the chassis' velocity, which already holds this step's gravity.
+                let compressing = (previous - wheel.length) / delta;
+                wheel.load = (wheel.settings.stiffness * wheel.compression() + wheel.settings.damping * compressing).max(0_f64);
+            }
+        }
+
+        for bar in &self.anti_roll_bars {
+            let (Some(left), Some(right)) = (self.wheels.get(bar.left), self.wheels.get(bar.right)) else { continue };
+            let force = bar.stiffness * (left.compression() - right.compression());
+            if left.is_grounded() {
+                self.wheels[bar.left].load = (self.wheels[bar.left].load + force).max(0_f64);
+            }
+            if self.wheels[bar.right].is_grounded() {
+                self.wheels[bar.right].load = (self.wheels[bar.right].load - force).max(0_f64);
+            }
+        }
+
+        let driven = self.wheels.iter().filter(|wheel| wheel.settings.driven).count().max(1) as f64;
+        let grounded = self.wheels.iter().filter(|wheel| wheel.is_grounded()).count().max(1) as f64;
+        //Every wheel works from the chassis as it was before any of them pushed, so the order they're in doesn't bias it.
+        let start = *body;
+        let mut impulses = vec![];
+        for wheel in &mut self.wheels {
+            let Some(contact) = wheel.contact else {
+                wheel.slip = 0_f64;
+                wheel.rotation += wheel.spin * delta;
+                continue;
+            };
+            let r = contact.point - start.position;
+            impulses.push((up * (wheel.load * delta), r));
+
+            //The wheel's heading, turned by the steering and laid flat on the ground.
+            let steering = Quaternion::from_axis_angle(up, Radians(wheel.steering));
+            let heading = steering * pose.transform_vector(Vector3F64::FORWARD);
+            let forward = heading - contact.normal * heading.dot(&contact.normal);
+            if forward.magnitude_squared() < 1e-12 {
+                continue;
+            }
+            let forward = forward.normalize();
+            let side = contact.normal.cross(&forward);
+
+            let velocity = start.velocity_at(r);
+            let (rolling, sliding) = (velocity.dot(&forward), velocity.dot(&side));
+            wheel.slip = sliding.abs().atan2(rolling.abs());
+            wheel.spin = rolling / wheel.settings.radius;
+            wheel.rotation += wheel.spin * delta;
+
+            //Sideways, the tire pushes back against sliding with the grip it has at this slip angle,
+            //but never past its share of stopping the slide.
+            let grip = wheel.load * delta;
+            let lateral = wheel.settings.friction.evaluate(wheel.slip) * grip;
+            let sideways = (-sliding / start.inverse_effective_mass(r, side) / grounded).clamp(-lateral, lateral);
+            let mut along = if wheel.settings.driven { self.throttle * self.engine_force / driven * delta } else { 0_f64 };
+            if self.brake > 0_f64 {
+                let braking = self.brake * self.brake_force * delta;
+                along += (-rolling / start.inverse_effective_mass(r, forward) / grounded).clamp(-braking, braking);
+            }
+
+            //Together the two can't exceed the tire's peak grip.
+            let limit = wheel.settings.friction.extremum_value * grip;
+            let total = (along * along + sideways * sideways).sqrt();
+            let scale = if total > limit && total > 0_f64 { limit / total } else { 1_f64 };
+            impulses.push(((forward * along + side * sideways) * scale, r));
+        }
+
+        if body.is_dynamic() {
+            for (impulse, r) in impulses {
+                body.apply_impulse(impulse, r);
+            }
+        }
+    }
+}
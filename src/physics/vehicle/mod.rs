@@ -0,0 +1,96 @@
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::collider_handle::ColliderHandle;
+use crate::physics::rigidbody_handle::RigidbodyHandle;
+
+pub mod imp;
+
+///Grip of a tire as a function of how much it slips, rising linearly to a peak and then falling off to what a sliding tire keeps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrictionCurve {
+    ///Slip at which grip peaks.
+    pub extremum_slip: f64,
+    ///Friction coefficient at the peak.
+    pub extremum_value: f64,
+    ///Slip past which the tire is fully sliding.
+    pub asymptote_slip: f64,
+    ///Friction coefficient while sliding.
+    pub asymptote_value: f64,
+}
+
+///How a wheel is mounted on a [Vehicle]'s chassis.
+#[derive(Debug, Clone, Copy)]
+pub struct WheelSettings {
+    ///Top of the suspension in the chassis' local space. The wheel hangs below it along the chassis' down axis.
+    pub anchor: Vector3F64,
+    pub radius: f64,
+    ///Length of the suspension when nothing pushes on it, which is also its longest.
+    pub rest_length: f64,
+    ///Newtons per meter of compression.
+    pub stiffness: f64,
+    ///Newtons per meter per second of compression speed.
+    pub damping: f64,
+    ///Sideways grip, with the slip angle between where the wheel points and where it moves in radians.
+    pub friction: FrictionCurve,
+    ///Turned by the steering input.
+    pub steered: bool,
+    ///Pushed by the engine.
+    pub driven: bool,
+}
+
+///Where a wheel touches the ground.
+#[derive(Debug, Clone, Copy)]
+pub struct WheelContact {
+    pub collider: ColliderHandle,
+    pub point: Vector3F64,
+    pub normal: Vector3F64,
+}
+
+///A wheel of a [Vehicle], along with what happened to it in the last step.
+#[derive(Debug, Clone, Copy)]
+pub struct Wheel {
+    pub(crate) settings: WheelSettings,
+    ///Current length of the suspension, the rest length while in the air.
+    pub(crate) length: f64,
+    pub(crate) contact: Option<WheelContact>,
+    ///Steering angle in radians, positive turning right.
+    pub(crate) steering: f64,
+    ///Rolling speed in radians per second.
+    pub(crate) spin: f64,
+    ///Accumulated rolling angle in radians, for drawing the wheel.
+    pub(crate) rotation: f64,
+    ///Force the suspension pushes the chassis up with, in newtons.
+    pub(crate) load: f64,
+    ///Slip angle in radians.
+    pub(crate) slip: f64,
+}
+
+///Resists body roll by pushing the more compressed wheel of a pair down and the other up, in proportion to the difference in compression.
+#[derive(Debug, Clone, Copy)]
+pub struct AntiRollBar {
+    pub left: usize,
+    pub right: usize,
+    ///Newtons per meter of compression difference.
+    pub stiffness: f64,
+}
+
+///A car made of a chassis rigidbody and wheels that are raycasts rather than rigidbodies, updated by every [crate::physics::world::World] step.
+///<br>Each wheel casts a ray down from its anchor. Where it hits, the suspension pushes the chassis up like a damped spring,
+///and the tire pushes it along and sideways within the grip its friction curve and load allow.
+///<br>The chassis' local z axis is forward, y up and x right.
+#[derive(Debug, Clone)]
+pub struct Vehicle {
+    pub(crate) chassis: RigidbodyHandle,
+    pub(crate) wheels: Vec<Wheel>,
+    pub(crate) anti_roll_bars: Vec<AntiRollBar>,
+    ///Total driving force at full throttle, split evenly between the driven wheels.
+    pub(crate) engine_force: f64,
+    ///Braking force of every wheel at full brake.
+    pub(crate) brake_force: f64,
+    ///Steering angle of steered wheels at full lock, in radians.
+    pub(crate) max_steering: f64,
+    pub(crate) throttle: f64,
+    pub(crate) brake: f64,
+    pub(crate) steering: f64,
+    ///Layers of colliders the wheels touch.
+    pub(crate) collision_mask: u32,
+}
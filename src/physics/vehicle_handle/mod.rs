@@ -0,0 +1,10 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VehicleHandle {
+    pub(crate) id: u32
+}
+
+impl VehicleHandle {
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+}
@@ -1,28 +1,371 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::time::Instant;
 use crate::linear_algebra::vec3;
+use crate::linear_algebra::vector::types::Vector3F64;
 
+use crate::physics::body_store::{BodyStore, RigidbodyMut, RigidbodyRef};
+use crate::physics::broad_phase::BroadPhaseType;
+use crate::physics::collider::{Collider, ALL_LAYERS, DEFAULT_LAYER};
+use crate::physics::collider::bounds::Bounds;
+use crate::physics::collider::narrow_phase;
+use crate::physics::collider_handle::ColliderHandle;
+use crate::physics::collision_event::CollisionEvent;
+use crate::physics::island::{find_islands, SleepSettings};
+use crate::physics::contact::ContactManifold;
+use crate::physics::gravitation::Gravitation;
+use crate::physics::material::PhysicsMaterial;
+use crate::physics::pose::Pose;
 use crate::physics::rigidbody::Rigidbody;
+use crate::physics::rigidbody::flags::RigidbodyFlags;
 use crate::physics::rigidbody_handle::RigidbodyHandle;
-use crate::physics::world::World;
+use crate::physics::joint::Joint;
+use crate::physics::joint_handle::JointHandle;
+use crate::physics::solver::contact_constraint::{tangent_basis, ContactConstraint};
+use crate::physics::solver::joint_constraint::{JointConstraint, JOINT_ROWS};
+use crate::physics::solver::{SolverBody, SolverSettings};
+use crate::physics::vehicle::Vehicle;
+use crate::physics::vehicle_handle::VehicleHandle;
+use crate::physics::world::{ColliderEntry, ColliderRef, JointEntry, World};
+
+///A touching pair of colliders found during a step.
+struct ContactPair {
+    colliders: (u32, u32),
+    bodies: (usize, usize),
+    manifold: ContactManifold,
+    trigger: bool,
+}
 
 impl World {
+    ///Copies a rigidbody's current state into this world, which owns it from then on.
     pub fn register_rigidbody(&mut self, rigidbody: Rigidbody) -> RigidbodyHandle {
-        let handle = RigidbodyHandle {
-            id: self.rigid_inc(),
-            rigidbody,
-        };
-
-        let out = handle.clone();
-        self.rigidbody_handles.insert(handle.id, handle);
-        out
+        self.bodies.insert(&rigidbody)
     }
 
     pub fn create_rigidbody(&mut self) -> RigidbodyHandle {
         self.register_rigidbody(Rigidbody::default())
     }
 
+    ///Whether the handle refers to a rigidbody in this world, false once it has been removed.
+    pub fn contains(&self, handle: &RigidbodyHandle) -> bool {
+        self.bodies.contains(handle)
+    }
+
+    ///Read access to a rigidbody's state, or None if the handle is stale.
+    pub fn rigidbody(&self, handle: &RigidbodyHandle) -> Option<RigidbodyRef<'_>> {
+        self.bodies.get(handle)
+    }
+
+    ///Write access to a rigidbody's state, or None if the handle is stale.
+    pub fn rigidbody_mut(&mut self, handle: &RigidbodyHandle) -> Option<RigidbodyMut<'_>> {
+        self.bodies.get_mut(handle)
+    }
+
+    ///Every rigidbody's state, for reading many of them at once.
+    pub fn bodies(&self) -> &BodyStore {
+        &self.bodies
+    }
+
+    ///Removes a rigidbody along with its colliders and joints, waking up anything that was resting on it.
+    ///<br>Returns false if the handle is stale. Its id will be reused with a new generation.
+    pub fn remove_rigidbody(&mut self, handle: &RigidbodyHandle) -> bool {
+        if !self.contains(handle) {
+            return false;
+        }
+
+        self.wake(handle.id);
+        let colliders = self.colliders.iter()
+            .filter(|(_, entry)| entry.rigidbody == handle.id)
+            .map(|(id, _)| ColliderHandle { id: *id })
+            .collect::<Vec<ColliderHandle>>();
+        for collider in colliders {
+            self.remove_collider(&collider);
+        }
+        self.joints.retain(|_, entry| entry.body_a != Some(handle.id) && entry.body_b != handle.id);
+        self.bodies.remove(handle)
+    }
+
+    ///Removes a collider, waking up the rigidbodies it was touching.
+    ///<br>Pairs it was touching report [CollisionEvent::CollisionExit] on the next step.
+    pub fn remove_collider(&mut self, handle: &ColliderHandle) -> bool {
+        let Some(entry) = self.colliders.remove(&handle.id) else { return false };
+        self.broad_phase.remove(handle.id);
+        self.contact_cache.retain(|(a, b), _| *a != handle.id && *b != handle.id);
+
+        let touching = self.touching.iter()
+            .filter_map(|(a, b)| if *a == handle.id { Some(*b) } else if *b == handle.id { Some(*a) } else { None })
+            .filter_map(|other| self.colliders.get(&other).map(|other| other.rigidbody))
+            .collect::<Vec<u32>>();
+        for rigidbody in touching {
+            self.wake(rigidbody);
+        }
+        self.wake(entry.rigidbody);
+        true
+    }
+
+    pub fn is_sleeping(&self, handle: &RigidbodyHandle) -> bool {
+        self.bodies.get(handle).map(|rigidbody| rigidbody.is_sleeping()).unwrap_or(false)
+    }
+
+    ///Wakes a rigidbody up along with the rest of the island it fell asleep with.
+    pub fn wake_up(&mut self, handle: &RigidbodyHandle) {
+        if self.contains(handle) {
+            self.wake(handle.id);
+        }
+    }
+
+    pub fn sleep_settings(&self) -> &SleepSettings {
+        &self.sleep_settings
+    }
+
+    pub fn sleep_settings_mut(&mut self) -> &mut SleepSettings {
+        &mut self.sleep_settings
+    }
+
+    ///Groups of dynamic rigidbodies connected through contacts or joints, awake ones as of the last step followed by sleeping ones.
+    pub fn islands(&self) -> Vec<Vec<RigidbodyHandle>> {
+        let alive = |id: &&u32| self.bodies.alive[**id as usize];
+        let mut islands = self.islands.iter()
+            .map(|island| island.iter().filter(alive).map(|id| self.bodies.handle(*id as usize)).collect::<Vec<RigidbodyHandle>>())
+            .filter(|island| !island.is_empty())
+            .collect::<Vec<Vec<RigidbodyHandle>>>();
+
+        let mut sleeping: BTreeMap<u32, Vec<RigidbodyHandle>> = BTreeMap::new();
+        for (index, island) in self.bodies.sleeping.iter().enumerate() {
+            if let Some(island) = island {
+                sleeping.entry(*island).or_default().push(self.bodies.handle(index));
+            }
+        }
+        islands.extend(sleeping.into_values());
+        islands
+    }
+
+    ///Attaches a collider to a rigidbody registered with this world so it takes part in collision resolution.
+    ///<br>The rigidbody's inertia is recomputed from the collider's shape and the rigidbody's current mass.
+    ///<br>Panics if the rigidbody has been removed.
+    pub fn attach_collider<C: Collider>(&mut self, rigidbody: &RigidbodyHandle, collider: C) -> ColliderHandle {
+        assert!(self.contains(rigidbody), "attaching a collider to a removed rigidbody");
+        let index = rigidbody.id as usize;
+        self.bodies.inertias[index] = collider.inertia(self.bodies.masses[index]);
+
+        let id = self.collider_inc();
+        self.broad_phase.insert(id, collider.compute_bounds(&self.bodies.pose(index)));
+        self.colliders.insert(id, ColliderEntry {
+            collider: Box::new(collider),
+            rigidbody: rigidbody.id,
+            material: PhysicsMaterial::default(),
+            layers: DEFAULT_LAYER,
+            collision_mask: ALL_LAYERS,
+            is_trigger: false,
+        });
+        ColliderHandle { id }
+    }
+
+    ///Connects rigidbody A to rigidbody B, or to the world if B is None.
+    ///<br>The joint's anchors and axis are taken in world space and fixed to the bodies at their current poses.
+    ///<br>Hinge angles, slider offsets and motors measure B's motion relative to A. A body connected to the world is measured relative to the world.
+    ///<br>Panics if either rigidbody has been removed.
+    pub fn add_joint(&mut self, joint: Joint, a: &RigidbodyHandle, b: Option<&RigidbodyHandle>) -> JointHandle {
+        assert!(self.contains(a) && b.map_or(true, |b| self.contains(b)), "joining a removed rigidbody");
+        let (a, b, anchor_a, anchor_b) = match b {
+            Some(b) => (Some(a), b, joint.anchor_a, joint.anchor_b),
+            None => (None, a, joint.anchor_b, joint.anchor_a),
+        };
+        let pose_a = a.map(|a| self.bodies.pose(a.id as usize)).unwrap_or_default();
+        let pose_b = self.bodies.pose(b.id as usize);
+        let reference = tangent_basis(joint.axis)[0];
+
+        let id = self.joint_inc();
+        self.joints.insert(id, JointEntry {
+            joint,
+            body_a: a.map(|a| a.id),
+            body_b: b.id,
+            local_anchor_a: pose_a.inverse_transform_point(anchor_a),
+            local_anchor_b: pose_b.inverse_transform_point(anchor_b),
+            local_axis_a: pose_a.inverse_transform_vector(joint.axis),
+            local_axis_b: pose_b.inverse_transform_vector(joint.axis),
+            local_reference_a: pose_a.inverse_transform_vector(reference),
+            local_reference_b: pose_b.inverse_transform_vector(reference),
+            rest_rotation: pose_a.rotation.conjugate() * pose_b.rotation,
+            impulses: [0_f64; JOINT_ROWS],
+        });
+        JointHandle { id }
+    }
+
+    pub fn remove_joint(&mut self, handle: &JointHandle) -> Option<Joint> {
+        self.joints.remove(&handle.id).map(|entry| entry.joint)
+    }
+
+    pub fn joint(&self, handle: &JointHandle) -> Option<&Joint> {
+        self.joints.get(&handle.id).map(|entry| &entry.joint)
+    }
+
+    ///Gives access to a joint's limits, motor and spring settings.
+    ///<br>Anchors and the axis were fixed to the bodies when the joint was added, so changing them has no effect.
+    ///<br>The joint's bodies are woken up, since its new settings might move them.
+    pub fn joint_mut(&mut self, handle: &JointHandle) -> Option<&mut Joint> {
+        let (a, b) = self.joints.get(&handle.id).map(|entry| (entry.body_a, entry.body_b))?;
+        if let Some(a) = a {
+            self.wake(a);
+        }
+        self.wake(b);
+        self.joints.get_mut(&handle.id).map(|entry| &mut entry.joint)
+    }
+
+    ///Adds a vehicle, which every step from now on drives its chassis.
+    pub fn add_vehicle(&mut self, vehicle: Vehicle) -> VehicleHandle {
+        let id = self.vehicle_increment;
+        self.vehicle_increment += 1;
+        self.vehicles.insert(id, vehicle);
+        VehicleHandle { id }
+    }
+
+    ///Removes a vehicle, leaving its chassis behind as a plain rigidbody.
+    pub fn remove_vehicle(&mut self, handle: &VehicleHandle) -> Option<Vehicle> {
+        self.vehicles.remove(&handle.id)
+    }
+
+    pub fn vehicle(&self, handle: &VehicleHandle) -> Option<&Vehicle> {
+        self.vehicles.get(&handle.id)
+    }
+
+    ///The vehicle with the provided handle, for changing its inputs.
+    pub fn vehicle_mut(&mut self, handle: &VehicleHandle) -> Option<&mut Vehicle> {
+        self.vehicles.get_mut(&handle.id)
+    }
+
+    ///Read access to a collider, placed at its rigidbody's pose in the [BodyStore].
+    pub fn collider(&self, handle: &ColliderHandle) -> Option<ColliderRef<'_>> {
+        self.colliders.get(&handle.id).map(|entry| ColliderRef { entry, bodies: &self.bodies })
+    }
+
+    ///The world space pose of the rigidbody a collider is attached to.
+    pub fn collider_pose(&self, handle: &ColliderHandle) -> Option<Pose> {
+        self.colliders.get(&handle.id).map(|entry| self.bodies.pose(entry.rigidbody as usize))
+    }
+
+    pub fn material(&self, handle: &ColliderHandle) -> Option<PhysicsMaterial> {
+        self.colliders.get(&handle.id).map(|entry| entry.material)
+    }
+
+    pub fn set_material(&mut self, handle: &ColliderHandle, material: PhysicsMaterial) {
+        if let Some(entry) = self.colliders.get_mut(&handle.id) {
+            entry.material = material;
+        }
+    }
+
+    pub fn layers(&self, handle: &ColliderHandle) -> Option<u32> {
+        self.colliders.get(&handle.id).map(|entry| entry.layers)
+    }
+
+    ///Puts a collider on a set of layers, each bit being one layer. Queries only see colliders on the layers in their mask.
+    pub fn set_layers(&mut self, handle: &ColliderHandle, layers: u32) {
+        if let Some(entry) = self.colliders.get_mut(&handle.id) {
+            entry.layers = layers;
+        }
+    }
+
+    pub fn collision_mask(&self, handle: &ColliderHandle) -> Option<u32> {
+        self.colliders.get(&handle.id).map(|entry| entry.collision_mask)
+    }
+
+    ///Sets which layers a collider collides with. Two colliders only touch if each one's layers are in the other's mask.
+    pub fn set_collision_mask(&mut self, handle: &ColliderHandle, mask: u32) {
+        if let Some(entry) = self.colliders.get_mut(&handle.id) {
+            entry.collision_mask = mask;
+        }
+    }
+
+    pub fn is_trigger(&self, handle: &ColliderHandle) -> Option<bool> {
+        self.colliders.get(&handle.id).map(|entry| entry.is_trigger)
+    }
+
+    ///Makes a collider a trigger, which reports [CollisionEvent::TriggerEnter] and [CollisionEvent::TriggerExit] instead of colliding.
+    pub fn set_trigger(&mut self, handle: &ColliderHandle, is_trigger: bool) {
+        if let Some(entry) = self.colliders.get_mut(&handle.id) {
+            entry.is_trigger = is_trigger;
+        }
+    }
+
+    ///The events from the last step.
+    pub fn events(&self) -> &[CollisionEvent] {
+        &self.events
+    }
+
+    ///Takes the events from the last step, leaving the queue empty.
+    pub fn drain_events(&mut self) -> Vec<CollisionEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub fn broad_phase_type(&self) -> BroadPhaseType {
+        self.broad_phase_type
+    }
+
+    ///Switches to a different broad phase, moving every registered collider over to it.
+    pub fn set_broad_phase(&mut self, broad_phase_type: BroadPhaseType) {
+        let mut broad_phase = broad_phase_type.create();
+        for (id, entry) in &self.colliders {
+            broad_phase.insert(*id, entry.collider.compute_bounds(&self.bodies.pose(entry.rigidbody as usize)));
+        }
+        self.broad_phase = broad_phase;
+        self.broad_phase_type = broad_phase_type;
+    }
+
+    ///Moves every collider's bounds in the broad phase to where its rigidbody is now.
+    ///<br>This happens on every step, but rigidbodies moved by hand since then won't be found where they are by queries until it's called.
+    pub fn sync_broad_phase(&mut self) {
+        for (id, entry) in &self.colliders {
+            self.broad_phase.update(*id, entry.collider.compute_bounds(&self.bodies.pose(entry.rigidbody as usize)));
+        }
+    }
+
+    ///Pairs of colliders whose bounding boxes overlapped as of the last step.
+    ///<br>These are candidates only, their shapes might not actually touch.
+    pub fn overlapping_pairs(&mut self) -> Vec<(ColliderHandle, ColliderHandle)> {
+        self.broad_phase.overlapping_pairs().into_iter()
+            .map(|(a, b)| (ColliderHandle { id: a }, ColliderHandle { id: b }))
+            .collect()
+    }
+
+    pub fn gravity(&self) -> Vector3F64 {
+        self.gravity
+    }
+
+    pub fn set_gravity(&mut self, gravity: Vector3F64) {
+        self.gravity = gravity;
+    }
+
+    pub fn gravitation(&self) -> Option<&Gravitation> {
+        self.gravitation.as_ref()
+    }
+
+    ///Turns mutual gravitation between rigidbodies on or off. Every rigidbody pulls with its mass, including static ones,
+    ///while only awake dynamic rigidbodies using gravity are pulled.
+    pub fn set_gravitation(&mut self, gravitation: Option<Gravitation>) {
+        self.gravitation = gravitation;
+    }
+
+    ///The gravitational potential energy between every pair of rigidbodies, or 0 without mutual gravitation.
+    pub fn gravitational_energy(&self) -> f64 {
+        match &self.gravitation {
+            Some(gravitation) => gravitation.potential_energy(&self.bodies.positions, &self.source_masses()),
+            None => 0_f64,
+        }
+    }
+
+    ///How many steps have been taken since the world was created.
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    pub fn solver_settings(&self) -> &SolverSettings {
+        &self.solver_settings
+    }
+
+    pub fn solver_settings_mut(&mut self) -> &mut SolverSettings {
+        &mut self.solver_settings
+    }
+
     pub fn update(&mut self) {
-        let velocity = self.gravity;
         let (millis, secs) = {
             let time = self.instant.elapsed();
             (time.as_millis() as u32, time.as_secs_f64())
@@ -31,9 +374,7 @@ impl World {
         if millis >= self.skip_ticks {
             self.fc += 1;
             self.instant = Instant::now();
-            for (_, v) in &mut self.rigidbody_handles {
-                v.rigidbody.update(secs, &velocity);
-            }
+            self.step(secs);
         }
         if self.clock.elapsed().as_secs() >= 1 {
             self.fc = 0;
@@ -41,13 +382,350 @@ impl World {
         }
     }
 
-    fn rigid_inc(&mut self) -> u32 {
-        let id = self.rigidbody_increment;
-        self.rigidbody_increment += 1;
+    ///Advances the simulation by a fixed amount of time in seconds.
+    ///<br>Gravity is applied, touching colliders are found and resolved with a sequential impulse solver, then positions are integrated.
+    ///Islands that have been resting for long enough are put to sleep afterwards.
+    pub fn step(&mut self, delta: f64) {
+        if delta <= 0_f64 {
+            return;
+        }
+        self.steps += 1;
+
+        //Sleeping bodies moved or pushed by hand since the last step wake up, as do the chassis of vehicles being driven.
+        for index in 0..self.bodies.len() {
+            if std::mem::take(&mut self.bodies.disturbed[index]) {
+                self.wake(index as u32);
+            }
+        }
+        let driven = self.vehicles.values()
+            .filter(|vehicle| vehicle.is_active() && self.contains(&vehicle.chassis))
+            .map(|vehicle| vehicle.chassis.id)
+            .collect::<Vec<u32>>();
+        for id in driven {
+            self.wake(id);
+        }
+
+        let threads = self.solver_settings.threads;
+        self.bodies.integrate_velocities(self.gravity, delta, threads);
+        self.apply_gravitation(delta);
+        let mut bodies = self.solver_bodies();
+
+        let mut vehicles = std::mem::take(&mut self.vehicles);
+        for vehicle in vehicles.values_mut() {
+            vehicle.update(self, &mut bodies, delta);
+        }
+        self.vehicles = vehicles;
+
+        let contacts = self.find_contacts(&bodies);
+        self.wake_touched(&mut bodies, &contacts);
+        let (triggers, pairs): (Vec<ContactPair>, Vec<ContactPair>) = contacts.into_iter().partition(|pair| pair.trigger);
+        self.queue_events(&pairs, &triggers);
+
+        let mut constraints = pairs.iter().map(|pair| {
+            let material = self.colliders[&pair.colliders.0].material.combine(&self.colliders[&pair.colliders.1].material);
+            ContactConstraint::new(
+                &pair.manifold,
+                pair.bodies.0,
+                pair.bodies.1,
+                &bodies,
+                material,
+                &self.solver_settings,
+                delta,
+                self.contact_cache.get(&pair.colliders),
+            )
+        }).collect::<Vec<ContactConstraint>>();
+
+        //Joints attached to the world are solved against a static body at the origin.
+        let world_body = bodies.len();
+        bodies.push(SolverBody::world());
+        let mut joints = self.joints.iter().map(|(id, entry)| {
+            let a = entry.body_a.map(|a| a as usize).unwrap_or(world_body);
+            JointConstraint::new(*id, entry, a, entry.body_b as usize, &bodies, &self.solver_settings, delta)
+        }).collect::<Vec<JointConstraint>>();
+
+        if self.solver_settings.warm_starting {
+            for joint in &joints {
+                joint.warm_start(&mut bodies);
+            }
+            for constraint in &constraints {
+                constraint.warm_start(&mut bodies);
+            }
+        }
+
+        for _ in 0..self.solver_settings.velocity_iterations {
+            for joint in &mut joints {
+                joint.solve(&mut bodies);
+            }
+            for constraint in &mut constraints {
+                constraint.solve(&mut bodies);
+            }
+        }
+        bodies.truncate(world_body);
+
+        for joint in &joints {
+            if let Some(entry) = self.joints.get_mut(&joint.joint) {
+                entry.impulses = joint.impulses();
+            }
+        }
+
+        self.contact_cache = pairs.iter().zip(&constraints)
+            .map(|(pair, constraint)| (pair.colliders, constraint.cached_impulses()))
+            .collect();
+
+        for body in &bodies {
+            body.write_back(&mut self.bodies);
+        }
+        self.bodies.integrate_positions(delta, threads);
+
+
+        self.update_sleep(&bodies, &pairs, delta);
+    }
+
+    fn apply_gravitation(&mut self, delta: f64) {
+        let Some(gravitation) = self.gravitation else { return };
+        let accelerations = gravitation.accelerations(&self.bodies.positions, &self.source_masses());
+        for (index, acceleration) in accelerations.into_iter().enumerate() {
+            if self.bodies.inverse_masses[index] > 0_f64 && self.bodies.flags[index] & RigidbodyFlags::UseGravity > 0 {
+                self.bodies.velocities[index] += acceleration * delta;
+            }
+        }
+    }
+
+    ///The mass of every slot, with removed rigidbodies weighing nothing.
+    fn source_masses(&self) -> Vec<f64> {
+        self.bodies.masses.iter().zip(&self.bodies.alive)
+            .map(|(mass, alive)| if *alive { *mass } else { 0_f64 })
+            .collect()
+    }
+
+    ///Wakes the island a rigidbody fell asleep with.
+    fn wake(&mut self, id: u32) {
+        let Some(island) = self.bodies.sleeping.get(id as usize).copied().flatten() else { return };
+        for index in 0..self.bodies.len() {
+            if self.bodies.sleeping[index] == Some(island) {
+                self.bodies.set_sleeping(index, None);
+            }
+        }
+    }
+
+    ///Wakes sleeping rigidbodies touched by, or jointed to, awake ones, letting them take part in the rest of the step.
+    fn wake_touched(&mut self, bodies: &mut [SolverBody], contacts: &[ContactPair]) {
+        let sleeping = |index: usize, world: &World| world.bodies.sleeping[index].is_some();
+        let mut touched = vec![];
+        for pair in contacts.iter().filter(|pair| !pair.trigger) {
+            let (a, b) = pair.bodies;
+            if bodies[a].is_dynamic() && sleeping(b, self) {
+                touched.push(b);
+            }
+            if bodies[b].is_dynamic() && sleeping(a, self) {
+                touched.push(a);
+            }
+        }
+        for entry in self.joints.values() {
+            let (Some(a), b) = (entry.body_a.map(|a| a as usize), entry.body_b as usize) else { continue };
+            if bodies[a].is_dynamic() && sleeping(b, self) {
+                touched.push(b);
+            }
+            if bodies[b].is_dynamic() && sleeping(a, self) {
+                touched.push(a);
+            }
+        }
+
+        for index in touched {
+            self.wake(index as u32);
+        }
+        for (index, body) in bodies.iter_mut().enumerate() {
+            if !body.is_dynamic() && self.bodies.inverse_masses[index] > 0_f64 {
+                *body = SolverBody::from_store(&self.bodies, index);
+            }
+        }
+    }
+
+    ///Finds the islands of dynamic rigidbodies connected by this step's contacts and joints, putting those that have rested long enough to sleep.
+    fn update_sleep(&mut self, bodies: &[SolverBody], pairs: &[ContactPair], delta: f64) {
+        let mut edges = pairs.iter()
+            .map(|pair| pair.bodies)
+            .filter(|(a, b)| bodies[*a].is_dynamic() && bodies[*b].is_dynamic())
+            .collect::<Vec<(usize, usize)>>();
+        for entry in self.joints.values() {
+            if let Some(a) = entry.body_a.map(|a| a as usize) {
+                let b = entry.body_b as usize;
+                if bodies[a].is_dynamic() && bodies[b].is_dynamic() {
+                    edges.push((a, b));
+                }
+            }
+        }
+        let islands = find_islands(bodies.len(), &edges).into_iter()
+            .filter(|island| bodies[island[0]].is_dynamic())
+            .collect::<Vec<Vec<usize>>>();
+
+        let settings = self.sleep_settings;
+        for (index, body) in bodies.iter().enumerate().filter(|(_, body)| body.is_dynamic()) {
+            let resting = body.velocity.magnitude() < settings.linear_threshold && body.angular_velocity.magnitude() < settings.angular_threshold;
+            self.bodies.rest_times[index] = if resting { self.bodies.rest_times[index] + delta } else { 0_f64 };
+        }
+
+        self.islands.clear();
+        for island in islands {
+            let tired = island.iter().all(|index| self.bodies.rest_times[*index] >= settings.time_to_sleep);
+            if !(settings.enabled && tired) {
+                self.islands.push(island.iter().map(|index| *index as u32).collect());
+                continue;
+            }
+
+            let sleep_island = self.island_increment;
+            self.island_increment += 1;
+            for index in island {
+                self.bodies.set_sleeping(index, Some(sleep_island));
+            }
+        }
+    }
+
+    ///Replaces the event queue with this step's events, found by comparing the touching pairs to last step's.
+    ///<br>Solid pairs that can't have moved apart, because both sides are asleep or static, keep touching without reporting anything.
+    fn queue_events(&mut self, pairs: &[ContactPair], triggers: &[ContactPair]) {
+        let handle = |id: u32| ColliderHandle { id };
+        self.events.clear();
+
+        let resting = |collider: &u32| {
+            self.colliders.get(collider)
+                .map(|entry| self.bodies.inverse_masses[entry.rigidbody as usize] == 0_f64)
+                .unwrap_or(false)
+        };
+        let carried = |pairs: &BTreeSet<(u32, u32)>| pairs.iter()
+            .filter(|(a, b)| resting(a) && resting(b))
+            .copied()
+            .collect::<Vec<(u32, u32)>>();
+        let carried_touching = carried(&self.touching);
+
+        let mut touching = pairs.iter().map(|pair| pair.colliders).collect::<BTreeSet<(u32, u32)>>();
+        touching.extend(carried_touching);
+        for pair in pairs {
+            let (a, b) = (handle(pair.colliders.0), handle(pair.colliders.1));
+            let manifold = pair.manifold.clone();
+            self.events.push(if self.touching.contains(&pair.colliders) {
+                CollisionEvent::CollisionStay { a, b, manifold }
+            } else {
+                CollisionEvent::CollisionEnter { a, b, manifold }
+            });
+        }
+        for (a, b) in self.touching.difference(&touching) {
+            self.events.push(CollisionEvent::CollisionExit { a: handle(*a), b: handle(*b) });
+        }
+        self.touching = touching;
+
+        //The trigger comes first in trigger events, whichever order the pair is in.
+        let ordered = |(a, b): (u32, u32), colliders: &BTreeMap<u32, ColliderEntry>| {
+            match colliders.get(&a) {
+                Some(entry) if !entry.is_trigger => (handle(b), handle(a)),
+                _ => (handle(a), handle(b)),
+            }
+        };
+        //Trigger pairs are found every step, whether their bodies move or not.
+        let overlaps = triggers.iter().map(|pair| pair.colliders).collect::<BTreeSet<(u32, u32)>>();
+        for pair in overlaps.difference(&self.trigger_overlaps) {
+            let (trigger, other) = ordered(*pair, &self.colliders);
+            self.events.push(CollisionEvent::TriggerEnter { trigger, other });
+        }
+        for pair in self.trigger_overlaps.difference(&overlaps) {
+            let (trigger, other) = ordered(*pair, &self.colliders);
+            self.events.push(CollisionEvent::TriggerExit { trigger, other });
+        }
+        self.trigger_overlaps = overlaps;
+    }
+
+    ///Solver copies of every slot in the store, indexed by rigidbody id. Sleeping ones are frozen in place, as if they were static.
+    fn solver_bodies(&self) -> Vec<SolverBody> {
+        (0..self.bodies.len()).map(|index| SolverBody::from_store(&self.bodies, index)).collect()
+    }
+
+    ///Refreshes the broad phase with the colliders' current bounds, then runs the narrow phase on every overlapping pair on different rigidbodies where at least one of them can move or is a trigger.
+    ///<br>Pairs whose layers and collision masks don't match are skipped.
+    ///<br>Rigidbodies connected by a joint don't collide unless the joint asks for it.
+    fn find_contacts(&mut self, bodies: &[SolverBody]) -> Vec<ContactPair> {
+        let connected = self.joints.values()
+            .filter(|entry| !entry.joint.collide_connected)
+            .filter_map(|entry| entry.body_a.map(|a| (a.min(entry.body_b), a.max(entry.body_b))))
+            .collect::<HashSet<(u32, u32)>>();
+
+        for (id, entry) in &self.colliders {
+            self.broad_phase.update(*id, entry.collider.compute_bounds(&bodies[entry.rigidbody as usize].pose()));
+        }
+
+        let mut pairs = vec![];
+        for (a_id, b_id) in self.broad_phase.overlapping_pairs() {
+            let (a, b) = (&self.colliders[&a_id], &self.colliders[&b_id]);
+            let (a_index, b_index) = (a.rigidbody as usize, b.rigidbody as usize);
+            //Triggers still report bodies that can't move moving through them, like kinematic ones.
+            let trigger = a.is_trigger || b.is_trigger;
+            if a_index == b_index || !(trigger || bodies[a_index].is_dynamic() || bodies[b_index].is_dynamic()) {
+                continue;
+            }
+            if connected.contains(&(a.rigidbody.min(b.rigidbody), a.rigidbody.max(b.rigidbody))) {
+                continue;
+            }
+            if a.layers & b.collision_mask == 0 || b.layers & a.collision_mask == 0 {
+                continue;
+            }
+
+            let manifold = narrow_phase::contact_manifold_at(
+                a.collider.as_ref(),
+                &bodies[a_index].pose(),
+                b.collider.as_ref(),
+                &bodies[b_index].pose(),
+            );
+            if let Some(manifold) = manifold {
+                pairs.push(ContactPair {
+                    colliders: (a_id, b_id),
+                    bodies: (a_index, b_index),
+                    manifold,
+                    trigger,
+                });
+            }
+        }
+
+        pairs
+    }
+
+    fn collider_inc(&mut self) -> u32 {
+        let id = self.collider_increment;
+        self.collider_increment += 1;
+        id
+    }
+
+    fn joint_inc(&mut self) -> u32 {
+        let id = self.joint_increment;
+        self.joint_increment += 1;
         id
     }
 }
 
+impl<'a> ColliderRef<'a> {
+    pub fn collider(&self) -> &'a dyn Collider {
+        self.entry.collider.as_ref()
+    }
+
+    ///The rigidbody the collider is attached to.
+    pub fn rigidbody(&self) -> RigidbodyRef<'a> {
+        RigidbodyRef { store: self.bodies, index: self.entry.rigidbody as usize }
+    }
+
+    ///The world space pose of the rigidbody the collider is attached to.
+    pub fn pose(&self) -> Pose {
+        self.bodies.pose(self.entry.rigidbody as usize)
+    }
+
+    ///The world space bounding box of the collider at its rigidbody's current pose.
+    pub fn bounds(&self) -> Bounds {
+        self.entry.collider.compute_bounds(&self.pose())
+    }
+
+    ///Computes the contact manifold between this collider (A) and another (B) at their rigidbodies' current poses, if they are touching.
+    pub fn contact_manifold(&self, b: &ColliderRef) -> Option<ContactManifold> {
+        narrow_phase::contact_manifold_at(self.collider(), &self.pose(), b.collider(), &b.pose())
+    }
+}
+
 impl Default for World {
     fn default() -> Self {
         let now = Instant::now();
@@ -55,8 +733,25 @@ impl Default for World {
         let skip_ticks = 1000 / frame_rate as u32;
         Self {
             gravity: vec3(0.0, -9.81, 0.0),
-            rigidbody_handles: Default::default(),
-            rigidbody_increment: 0,
+            gravitation: None,
+            steps: 0,
+            bodies: BodyStore::new(),
+            colliders: Default::default(),
+            collider_increment: 0,
+            solver_settings: SolverSettings::default(),
+            broad_phase: BroadPhaseType::DynamicTree.create(),
+            broad_phase_type: BroadPhaseType::DynamicTree,
+            contact_cache: Default::default(),
+            touching: Default::default(),
+            trigger_overlaps: Default::default(),
+            events: vec![],
+            sleep_settings: SleepSettings::default(),
+            islands: vec![],
+            island_increment: 0,
+            joints: Default::default(),
+            vehicles: Default::default(),
+            vehicle_increment: 0,
+            joint_increment: 0,
             instant: now,
             clock: now,
             fc: 0,
@@ -64,4 +759,4 @@ impl Default for World {
             frame_rate,
         }
     }
-}
\ No newline at end of file
+}
@@ -1,17 +1,106 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::time::Instant;
 use crate::linear_algebra::vector::types::{Vector3F32, Vector3F64};
-use crate::physics::rigidbody_handle::RigidbodyHandle;
+use crate::physics::body_store::BodyStore;
+use crate::physics::broad_phase::{BroadPhase, BroadPhaseType};
+use crate::physics::collider::Collider;
+use crate::physics::collider_handle::ColliderHandle;
+use crate::physics::collision_event::CollisionEvent;
+use crate::physics::gravitation::Gravitation;
+use crate::physics::island::SleepSettings;
+use crate::physics::joint::Joint;
+use crate::physics::solver::joint_constraint::JOINT_ROWS;
+use crate::linear_algebra::quaternion::Quaternion;
+use crate::physics::material::PhysicsMaterial;
+use crate::physics::solver::contact_constraint::CachedImpulse;
+use crate::physics::solver::SolverSettings;
+use crate::physics::vehicle::Vehicle;
 
 pub mod imp;
+pub mod query;
+pub mod snapshot;
+
+///A collider registered with a [World], along with the rigidbody it's attached to.
+pub struct ColliderEntry {
+    pub(crate) collider: Box<dyn Collider>,
+    pub(crate) rigidbody: u32,
+    pub(crate) material: PhysicsMaterial,
+    ///Bit set of the layers this collider is on, which queries filter by.
+    pub(crate) layers: u32,
+    ///Bit set of the layers this collider collides with.
+    pub(crate) collision_mask: u32,
+    ///Triggers report overlaps through events without being pushed apart.
+    pub(crate) is_trigger: bool,
+}
+
+///Read access to a collider attached to a [World], which it places at the pose of its rigidbody in the world's [BodyStore].
+#[derive(Clone, Copy)]
+pub struct ColliderRef<'a> {
+    pub(crate) entry: &'a ColliderEntry,
+    pub(crate) bodies: &'a BodyStore,
+}
+
+///A joint registered with a [World], with its anchors and axes fixed in the local space of each body.
+pub struct JointEntry {
+    pub(crate) joint: Joint,
+    ///None when the joint connects to the world, which then takes A's place.
+    pub(crate) body_a: Option<u32>,
+    pub(crate) body_b: u32,
+    pub(crate) local_anchor_a: Vector3F64,
+    pub(crate) local_anchor_b: Vector3F64,
+    pub(crate) local_axis_a: Vector3F64,
+    pub(crate) local_axis_b: Vector3F64,
+    ///Vectors perpendicular to the axis that hinge angles are measured between.
+    pub(crate) local_reference_a: Vector3F64,
+    pub(crate) local_reference_b: Vector3F64,
+    ///B's rotation relative to A when the joint was added.
+    pub(crate) rest_rotation: Quaternion<f64>,
+    ///Accumulated impulses from the last step, for warm starting.
+    pub(crate) impulses: [f64; JOINT_ROWS],
+}
+
+///The result of a raycast or shape cast against a [World].
+#[derive(Debug, Clone, Copy)]
+pub struct RaycastHit {
+    pub collider: ColliderHandle,
+    ///World space point of impact. For shape casts this is where the swept shape first touches the collider.
+    pub point: Vector3F64,
+    ///The collider's surface normal at the point of impact.
+    pub normal: Vector3F64,
+    ///How far along the cast the hit happened.
+    pub distance: f64,
+}
 
 pub struct World {
     gravity: Vector3F64,
-    rigidbody_handles: HashMap<u32, RigidbodyHandle>,
-    rigidbody_increment: u32,
+    ///Mutual gravitation between rigidbodies, off unless set.
+    gravitation: Option<Gravitation>,
+    ///How many steps have been taken.
+    steps: u64,
+    bodies: BodyStore,
+    colliders: BTreeMap<u32, ColliderEntry>,
+    collider_increment: u32,
+    solver_settings: SolverSettings,
+    broad_phase: Box<dyn BroadPhase>,
+    broad_phase_type: BroadPhaseType,
+    ///Accumulated contact impulses from the last step, keyed by collider pair.
+    contact_cache: HashMap<(u32, u32), Vec<CachedImpulse>>,
+    joints: BTreeMap<u32, JointEntry>,
+    vehicles: BTreeMap<u32, Vehicle>,
+    vehicle_increment: u32,
+    ///Collider pairs touching as of the last step, for telling enter from stay and exit.
+    touching: BTreeSet<(u32, u32)>,
+    ///Collider pairs overlapping where at least one is a trigger.
+    trigger_overlaps: BTreeSet<(u32, u32)>,
+    events: Vec<CollisionEvent>,
+    sleep_settings: SleepSettings,
+    ///Rigidbody ids of the awake islands found in the last step.
+    islands: Vec<Vec<u32>>,
+    island_increment: u32,
+    joint_increment: u32,
     instant: Instant,
     clock: Instant,
     fc: usize,
     skip_ticks: u32,
     frame_rate: u8
-}
\ No newline at end of file
+}
@@ -0,0 +1,147 @@
+use crate::linear_algebra::quaternion::Quaternion;
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::collider::bounds::Bounds;
+use crate::physics::collider::box_collider::BoxCollider;
+use crate::physics::collider::capsule_collider::CapsuleCollider;
+use crate::physics::collider::Collider;
+use crate::physics::collider::ray_cast;
+use crate::physics::collider::sphere_collider::SphereCollider;
+use crate::physics::collider_handle::ColliderHandle;
+use crate::physics::gjk;
+use crate::physics::gjk::CastHit;
+use crate::physics::pose::Pose;
+use crate::physics::world::{RaycastHit, World};
+
+///Queries against the colliders in a [World].
+///<br>Candidates are looked up in the broad phase, so rigidbodies moved by hand since the last step need [World::sync_broad_phase] first.
+///Every query takes a layer mask and only sees colliders on at least one of its layers.
+impl World {
+    ///The nearest collider hit by a ray within max_distance, which may be infinite.
+    pub fn raycast(&self, origin: Vector3F64, direction: Vector3F64, max_distance: f64, mask: u32) -> Option<RaycastHit> {
+        self.raycast_all(origin, direction, max_distance, mask).into_iter().next()
+    }
+
+    ///Every collider hit by a ray within max_distance, nearest first.
+    pub fn raycast_all(&self, origin: Vector3F64, direction: Vector3F64, max_distance: f64, mask: u32) -> Vec<RaycastHit> {
+        let direction = direction.normalize();
+        let bounds = cast_bounds(Bounds { center: origin, extents: Vector3F64::ZERO }, direction, max_distance);
+        self.cast(bounds, mask, |collider, pose| ray_cast::raycast(collider, pose, origin, direction, max_distance))
+    }
+
+    ///Sweeps a sphere along a direction, returning the first collider it touches.
+    pub fn sphere_cast(&self, origin: Vector3F64, radius: f64, direction: Vector3F64, max_distance: f64, mask: u32) -> Option<RaycastHit> {
+        let sphere = SphereCollider::new(radius);
+        let pose = Pose::from_position(origin);
+        self.shape_cast(&sphere, &pose, direction, max_distance, mask)
+    }
+
+    ///Sweeps an oriented box along a direction, returning the first collider it touches.
+    pub fn box_cast(&self, center: Vector3F64, half_extents: Vector3F64, rotation: Quaternion<f64>, direction: Vector3F64, max_distance: f64, mask: u32) -> Option<RaycastHit> {
+        let cuboid = BoxCollider::new(half_extents * 2_f64);
+        let pose = Pose::new(center, rotation);
+        self.shape_cast(&cuboid, &pose, direction, max_distance, mask)
+    }
+
+    ///Sweeps a capsule standing along the rotation's y axis, returning the first collider it touches.
+    pub fn capsule_cast(&self, center: Vector3F64, radius: f64, height: f64, rotation: Quaternion<f64>, direction: Vector3F64, max_distance: f64, mask: u32) -> Option<RaycastHit> {
+        let capsule = CapsuleCollider::new(radius, height);
+        self.shape_cast(&capsule, &Pose::new(center, rotation), direction, max_distance, mask)
+    }
+
+    ///Every collider overlapping a sphere.
+    pub fn overlap_sphere(&self, center: Vector3F64, radius: f64, mask: u32) -> Vec<ColliderHandle> {
+        let sphere = SphereCollider::new(radius);
+        self.overlap(&sphere, &Pose::from_position(center), mask)
+    }
+
+    ///Every collider overlapping a capsule standing along the rotation's y axis.
+    pub fn overlap_capsule(&self, center: Vector3F64, radius: f64, height: f64, rotation: Quaternion<f64>, mask: u32) -> Vec<ColliderHandle> {
+        let capsule = CapsuleCollider::new(radius, height);
+        self.overlap(&capsule, &Pose::new(center, rotation), mask)
+    }
+
+    ///Every collider overlapping an oriented box.
+    pub fn overlap_box(&self, center: Vector3F64, half_extents: Vector3F64, rotation: Quaternion<f64>, mask: u32) -> Vec<ColliderHandle> {
+        let cuboid = BoxCollider::new(half_extents * 2_f64);
+        self.overlap(&cuboid, &Pose::new(center, rotation), mask)
+    }
+
+    fn shape_cast(&self, shape: &dyn Collider, pose: &Pose, direction: Vector3F64, max_distance: f64, mask: u32) -> Option<RaycastHit> {
+        let direction = direction.normalize();
+        let support = shape.support_map()?;
+        let bounds = cast_bounds(shape.compute_bounds(pose), direction, max_distance);
+        self.cast(bounds, mask, |collider, collider_pose| {
+            gjk::shape_cast(support, pose, collider.support_map()?, collider_pose, direction, max_distance)
+        }).into_iter().next()
+    }
+
+    fn overlap(&self, shape: &dyn Collider, pose: &Pose, mask: u32) -> Vec<ColliderHandle> {
+        let Some(support) = shape.support_map() else { return vec![] };
+        self.candidates(Some(shape.compute_bounds(pose)), mask).into_iter()
+            .filter(|(_, collider, collider_pose)| {
+                collider.support_map().map(|other| gjk::intersects(support, pose, other, collider_pose)).unwrap_or(false)
+            })
+            .map(|(id, _, _)| ColliderHandle { id })
+            .collect()
+    }
+
+    ///Runs a cast against every candidate, returning the hits sorted nearest first.
+    fn cast<F: Fn(&dyn Collider, &Pose) -> Option<CastHit>>(&self, bounds: Option<Bounds>, mask: u32, cast: F) -> Vec<RaycastHit> {
+        let mut hits = self.candidates(bounds, mask).into_iter()
+            .filter_map(|(id, collider, pose)| {
+                cast(collider, &pose).map(|hit| RaycastHit {
+                    collider: ColliderHandle { id },
+                    point: hit.point,
+                    normal: hit.normal,
+                    distance: hit.distance,
+                })
+            })
+            .collect::<Vec<RaycastHit>>();
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance).then(a.collider.id.cmp(&b.collider.id)));
+        hits
+    }
+
+    ///The nearest solid collider a ray hits, ignoring triggers and the colliders of one rigidbody.
+    pub(crate) fn raycast_ignoring(&self, origin: Vector3F64, direction: Vector3F64, max_distance: f64, mask: u32, rigidbody: u32) -> Option<RaycastHit> {
+        self.raycast_all(origin, direction, max_distance, mask).into_iter()
+            .find(|hit| {
+                let entry = &self.colliders[&hit.collider.id];
+                !entry.is_trigger && entry.rigidbody != rigidbody
+            })
+    }
+
+    ///Solid colliders on the mask's layers near the provided bounds, with their poses, for bodies the world doesn't simulate to collide against.
+    pub(crate) fn obstacles(&self, bounds: Bounds, mask: u32) -> Vec<(ColliderHandle, &dyn Collider, Pose)> {
+        self.candidates(Some(bounds), mask).into_iter()
+            .filter(|(id, _, _)| !self.colliders[id].is_trigger)
+            .map(|(id, collider, pose)| (ColliderHandle { id }, collider, pose))
+            .collect()
+    }
+
+    ///Colliders on the mask's layers whose bounds overlap the provided bounds, or all of them if there are no bounds.
+    fn candidates(&self, bounds: Option<Bounds>, mask: u32) -> Vec<(u32, &dyn Collider, Pose)> {
+        let ids = match bounds {
+            Some(bounds) => self.broad_phase.query(&bounds),
+            None => self.colliders.keys().copied().collect(),
+        };
+        ids.into_iter()
+            .filter_map(|id| {
+                let entry = self.colliders.get(&id)?;
+                if entry.layers & mask == 0 {
+                    return None;
+                }
+                Some((id, entry.collider.as_ref(), self.bodies.pose(entry.rigidbody as usize)))
+            })
+            .collect()
+    }
+}
+
+///The bounds swept out by moving bounds along a direction, or None if the distance is infinite.
+fn cast_bounds(start: Bounds, direction: Vector3F64, max_distance: f64) -> Option<Bounds> {
+    if !max_distance.is_finite() {
+        return None;
+    }
+    let mut end = start;
+    end.center += direction * max_distance;
+    Some(start.union(&end))
+}
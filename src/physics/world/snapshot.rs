@@ -0,0 +1,640 @@
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+use crate::linear_algebra::quaternion::Quaternion;
+use crate::linear_algebra::vector::types::Vector3F64;
+use crate::physics::body_store::BodyStore;
+use crate::physics::broad_phase::BroadPhaseType;
+use crate::physics::collider::box_collider::BoxCollider;
+use crate::physics::collider::capsule_collider::CapsuleCollider;
+use crate::physics::collider::convex_collider::ConvexHullCollider;
+use crate::physics::collider::convex_hull::ConvexHull;
+use crate::physics::collider::Collider;
+use crate::physics::collider::sphere_collider::SphereCollider;
+use crate::physics::collider_handle::ColliderHandle;
+use crate::physics::contact::FeatureId;
+use crate::physics::gravitation::{Gravitation, GravitationMode};
+use crate::physics::joint::{Joint, JointKind, JointMotor};
+use crate::physics::material::PhysicsMaterial;
+use crate::physics::solver::contact_constraint::CachedImpulse;
+use crate::physics::solver::joint_constraint::JOINT_ROWS;
+use crate::physics::rigidbody_handle::RigidbodyHandle;
+use crate::physics::vehicle::{AntiRollBar, FrictionCurve, Vehicle, Wheel, WheelContact, WheelSettings};
+use crate::physics::world::{ColliderEntry, JointEntry, World};
+use crate::shared::binary::{ByteReader, ByteWriter, UnexpectedEnd};
+use crate::shared::endian::Endian;
+
+///The first bytes of every snapshot.
+pub const SNAPSHOT_MAGIC: [u8; 4] = *b"WSNP";
+///Bumped whenever the layout changes. Snapshots of other versions are refused.
+pub const SNAPSHOT_VERSION: u32 = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnapshotError {
+    ///The data doesn't start with [SNAPSHOT_MAGIC], or [crate::physics::replay::REPLAY_MAGIC] for replays.
+    NotASnapshot,
+    UnsupportedVersion(u32),
+    ///The data ended at this offset, before the snapshot did.
+    UnexpectedEnd(usize),
+    ///A value at this offset that no valid snapshot contains, such as an unknown tag or a reference to a missing rigidbody.
+    Invalid(usize, &'static str),
+    ///Only sphere, capsule, box and convex hull colliders can be written to a snapshot.
+    UnsupportedCollider(ColliderHandle),
+}
+
+impl From<UnexpectedEnd> for SnapshotError {
+    fn from(value: UnexpectedEnd) -> Self {
+        SnapshotError::UnexpectedEnd(value.offset)
+    }
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotError::NotASnapshot => write!(f, "not a world snapshot"),
+            SnapshotError::UnsupportedVersion(version) => write!(f, "unsupported snapshot version {version}"),
+            SnapshotError::UnexpectedEnd(offset) => write!(f, "snapshot ends early at byte {offset}"),
+            SnapshotError::Invalid(offset, reason) => write!(f, "invalid snapshot at byte {offset}: {reason}"),
+            SnapshotError::UnsupportedCollider(handle) => write!(f, "collider {} can't be written to a snapshot", handle.id),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+const SPHERE: u8 = 0;
+const BOX: u8 = 1;
+const CONVEX_HULL: u8 = 2;
+const CAPSULE: u8 = 3;
+
+///Binary snapshots of a whole [World].
+///<br>A snapshot holds everything a step depends on: rigidbodies, colliders, joints, vehicles, settings, the step counter and the warm starting caches.
+///A restored world steps to exactly the same state as the original would have, as long as the same inputs are applied to both.
+impl World {
+    ///Writes the world out in the provided byte order, which is recorded in the snapshot so it can be read back anywhere.
+    ///<br>Fails if a collider isn't a [SphereCollider], [BoxCollider], [CapsuleCollider] or [ConvexHullCollider].
+    pub fn snapshot(&self, endian: Endian) -> Result<Vec<u8>, SnapshotError> {
+        let mut writer = ByteWriter::new(endian);
+        writer.raw(&SNAPSHOT_MAGIC);
+        writer.number(match endian {
+            Endian::Little => 0_u8,
+            Endian::Big => 1_u8,
+        });
+        writer.number(SNAPSHOT_VERSION);
+
+        writer.vector(&self.gravity);
+        writer.number(self.steps);
+        writer.number(self.solver_settings.velocity_iterations as u64);
+        writer.number(self.solver_settings.baumgarte);
+        writer.number(self.solver_settings.slop);
+        writer.number(self.solver_settings.restitution_threshold);
+        writer.bool(self.solver_settings.warm_starting);
+        writer.number(self.solver_settings.threads as u64);
+        writer.bool(self.sleep_settings.enabled);
+        writer.number(self.sleep_settings.linear_threshold);
+        writer.number(self.sleep_settings.angular_threshold);
+        writer.number(self.sleep_settings.time_to_sleep);
+        writer.number(match self.broad_phase_type {
+            BroadPhaseType::BruteForce => 0_u8,
+            BroadPhaseType::SweepAndPrune => 1_u8,
+            BroadPhaseType::DynamicTree => 2_u8,
+        });
+        writer.bool(self.gravitation.is_some());
+        if let Some(gravitation) = &self.gravitation {
+            writer.number(gravitation.constant);
+            writer.number(gravitation.softening);
+            match gravitation.mode {
+                GravitationMode::Direct => writer.number(0_u8),
+                GravitationMode::BarnesHut { opening_angle } => {
+                    writer.number(1_u8);
+                    writer.number(opening_angle);
+                }
+            }
+        }
+
+        write_bodies(&mut writer, &self.bodies);
+        writer.number(self.island_increment);
+        writer.number(self.islands.len() as u32);
+        for island in &self.islands {
+            writer.number(island.len() as u32);
+            island.iter().for_each(|id| writer.number(*id));
+        }
+
+        writer.number(self.collider_increment);
+        writer.number(self.colliders.len() as u32);
+        for (id, entry) in &self.colliders {
+            writer.number(*id);
+            writer.number(entry.rigidbody);
+            writer.number(entry.material.friction);
+            writer.number(entry.material.restitution);
+            writer.number(entry.layers);
+            writer.number(entry.collision_mask);
+            writer.bool(entry.is_trigger);
+            write_collider(&mut writer, entry.collider.as_ref()).ok_or(SnapshotError::UnsupportedCollider(ColliderHandle { id: *id }))?;
+        }
+
+        writer.number(self.joint_increment);
+        writer.number(self.joints.len() as u32);
+        for (id, entry) in &self.joints {
+            writer.number(*id);
+            write_joint(&mut writer, entry);
+        }
+
+        writer.number(self.vehicle_increment);
+        writer.number(self.vehicles.len() as u32);
+        for (id, vehicle) in &self.vehicles {
+            writer.number(*id);
+            write_vehicle(&mut writer, vehicle);
+        }
+
+        //Sorted so the same world always gives the same bytes.
+        let mut cache = self.contact_cache.iter().collect::<Vec<_>>();
+        cache.sort_by_key(|(pair, _)| **pair);
+        writer.number(cache.len() as u32);
+        for ((a, b), impulses) in cache {
+            writer.number(*a);
+            writer.number(*b);
+            writer.number(impulses.len() as u32);
+            for impulse in impulses {
+                writer.number(impulse.feature.0);
+                writer.number(impulse.normal);
+                writer.vector(&impulse.tangent);
+            }
+        }
+        write_pairs(&mut writer, &self.touching);
+        write_pairs(&mut writer, &self.trigger_overlaps);
+
+        Ok(writer.into_bytes())
+    }
+
+    ///Reads a world back from [World::snapshot]. Rigidbody, collider and joint handles from the original world stay valid.
+    pub fn restore(bytes: &[u8]) -> Result<World, SnapshotError> {
+        if bytes.len() < 5 || bytes[0..4] != SNAPSHOT_MAGIC {
+            return Err(SnapshotError::NotASnapshot);
+        }
+        let endian = match bytes[4] {
+            0 => Endian::Little,
+            1 => Endian::Big,
+            _ => return Err(SnapshotError::Invalid(4, "unknown byte order")),
+        };
+        let mut reader = ByteReader::new(bytes, endian);
+        reader.raw(5)?;
+        let version = reader.number::<u32>()?;
+        if version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+
+        let mut world = World::default();
+        world.gravity = reader.vector()?;
+        world.steps = reader.number()?;
+        world.solver_settings.velocity_iterations = reader.number::<u64>()? as usize;
+        world.solver_settings.baumgarte = reader.number()?;
+        world.solver_settings.slop = reader.number()?;
+        world.solver_settings.restitution_threshold = reader.number()?;
+        world.solver_settings.warm_starting = reader.bool()?;
+        world.solver_settings.threads = reader.number::<u64>()? as usize;
+        world.sleep_settings.enabled = reader.bool()?;
+        world.sleep_settings.linear_threshold = reader.number()?;
+        world.sleep_settings.angular_threshold = reader.number()?;
+        world.sleep_settings.time_to_sleep = reader.number()?;
+        let broad_phase_type = match reader.number::<u8>()? {
+            0 => BroadPhaseType::BruteForce,
+            1 => BroadPhaseType::SweepAndPrune,
+            2 => BroadPhaseType::DynamicTree,
+            _ => return Err(SnapshotError::Invalid(reader.offset() - 1, "unknown broad phase")),
+        };
+        if reader.bool()? {
+            let constant = reader.number()?;
+            let softening = reader.number()?;
+            let mode = match reader.number::<u8>()? {
+                0 => GravitationMode::Direct,
+                1 => GravitationMode::BarnesHut { opening_angle: reader.number()? },
+                _ => return Err(SnapshotError::Invalid(reader.offset() - 1, "unknown gravitation mode")),
+            };
+            world.gravitation = Some(Gravitation { constant, softening, mode });
+        }
+
+        world.bodies = read_bodies(&mut reader)?;
+        let alive = |reader: &ByteReader, id: u32, world: &World| {
+            match world.bodies.alive.get(id as usize) {
+                Some(true) => Ok(id),
+                _ => Err(SnapshotError::Invalid(reader.offset() - 4, "missing rigidbody")),
+            }
+        };
+        world.island_increment = reader.number()?;
+        for _ in 0..reader.number::<u32>()? {
+            let mut island = vec![];
+            for _ in 0..reader.number::<u32>()? {
+                let id = reader.number::<u32>()?;
+                island.push(alive(&reader, id, &world)?);
+            }
+            world.islands.push(island);
+        }
+
+        world.collider_increment = reader.number()?;
+        for _ in 0..reader.number::<u32>()? {
+            let id = reader.number::<u32>()?;
+            let rigidbody = reader.number::<u32>()?;
+            alive(&reader, rigidbody, &world)?;
+            let material = PhysicsMaterial::new(reader.number()?, reader.number()?);
+            let layers = reader.number()?;
+            let collision_mask = reader.number()?;
+            let is_trigger = reader.bool()?;
+            let collider = read_collider(&mut reader)?;
+            world.colliders.insert(id, ColliderEntry {
+                collider,
+                rigidbody,
+                material,
+                layers,
+                collision_mask,
+                is_trigger,
+            });
+        }
+
+        world.joint_increment = reader.number()?;
+        for _ in 0..reader.number::<u32>()? {
+            let id = reader.number::<u32>()?;
+            let entry = read_joint(&mut reader)?;
+            for body in entry.body_a.iter().chain([&entry.body_b]) {
+                if world.bodies.alive.get(*body as usize) != Some(&true) {
+                    return Err(SnapshotError::Invalid(reader.offset(), "joint to a missing rigidbody"));
+                }
+            }
+            world.joints.insert(id, entry);
+        }
+
+        world.vehicle_increment = reader.number()?;
+        for _ in 0..reader.number::<u32>()? {
+            let id = reader.number::<u32>()?;
+            world.vehicles.insert(id, read_vehicle(&mut reader)?);
+        }
+
+        for _ in 0..reader.number::<u32>()? {
+            let pair = (reader.number::<u32>()?, reader.number::<u32>()?);
+            let mut impulses = vec![];
+            for _ in 0..reader.number::<u32>()? {
+                impulses.push(CachedImpulse {
+                    feature: FeatureId(reader.number()?),
+                    normal: reader.number()?,
+                    tangent: reader.vector()?,
+                });
+            }
+            world.contact_cache.insert(pair, impulses);
+        }
+        world.touching = read_pairs(&mut reader)?;
+        world.trigger_overlaps = read_pairs(&mut reader)?;
+
+        if reader.remaining() > 0 {
+            return Err(SnapshotError::Invalid(reader.offset(), "trailing bytes"));
+        }
+        world.set_broad_phase(broad_phase_type);
+        Ok(world)
+    }
+}
+
+///Every column of the store one after the other, so each is read back in a single pass.
+fn write_bodies(writer: &mut ByteWriter, bodies: &BodyStore) {
+    writer.number(bodies.len() as u32);
+    bodies.positions.iter().for_each(|v| writer.vector(v));
+    bodies.rotations.iter().for_each(|q| write_rotation(writer, q));
+    bodies.velocities.iter().for_each(|v| writer.vector(v));
+    bodies.angular_velocities.iter().for_each(|v| writer.vector(v));
+    bodies.masses.iter().for_each(|m| writer.number(*m));
+    bodies.inertias.iter().for_each(|v| writer.vector(v));
+    bodies.flags.iter().for_each(|f| writer.number(*f));
+    bodies.generations.iter().for_each(|g| writer.number(*g));
+    bodies.alive.iter().for_each(|a| writer.bool(*a));
+    bodies.rest_times.iter().for_each(|t| writer.number(*t));
+    bodies.sleeping.iter().for_each(|s| write_option(writer, *s));
+    bodies.disturbed.iter().for_each(|d| writer.bool(*d));
+    writer.number(bodies.free.len() as u32);
+    bodies.free.iter().for_each(|id| writer.number(*id));
+}
+
+fn read_bodies(reader: &mut ByteReader) -> Result<BodyStore, SnapshotError> {
+    let len = reader.number::<u32>()? as usize;
+    //Every slot takes well over a byte, so a larger count can only come from a corrupt snapshot.
+    if len > reader.remaining() {
+        return Err(SnapshotError::Invalid(reader.offset() - 4, "too many rigidbodies"));
+    }
+    fn column<T>(len: usize, reader: &mut ByteReader, read: impl Fn(&mut ByteReader) -> Result<T, UnexpectedEnd>) -> Result<Vec<T>, UnexpectedEnd> {
+        (0..len).map(|_| read(reader)).collect()
+    }
+
+    let mut bodies = BodyStore {
+        positions: column(len, reader, |r| r.vector())?,
+        rotations: column(len, reader, read_rotation)?,
+        velocities: column(len, reader, |r| r.vector())?,
+        angular_velocities: column(len, reader, |r| r.vector())?,
+        masses: column(len, reader, |r| r.number())?,
+        inertias: column(len, reader, |r| r.vector())?,
+        flags: column(len, reader, |r| r.number())?,
+        inverse_masses: vec![0_f64; len],
+        generations: column(len, reader, |r| r.number())?,
+        alive: column(len, reader, |r| r.bool())?,
+        free: vec![],
+        rest_times: column(len, reader, |r| r.number())?,
+        sleeping: column(len, reader, read_option)?,
+        disturbed: column(len, reader, |r| r.bool())?,
+    };
+    for _ in 0..reader.number::<u32>()? {
+        let id = reader.number::<u32>()?;
+        if bodies.alive.get(id as usize) != Some(&false) {
+            return Err(SnapshotError::Invalid(reader.offset() - 4, "free slot in use"));
+        }
+        bodies.free.push(id);
+    }
+    for index in 0..len {
+        bodies.refresh(index);
+    }
+    Ok(bodies)
+}
+
+fn write_rotation(writer: &mut ByteWriter, rotation: &Quaternion<f64>) {
+    rotation.wxyz().iter().for_each(|c| writer.number(*c));
+}
+
+fn read_rotation(reader: &mut ByteReader) -> Result<Quaternion<f64>, UnexpectedEnd> {
+    Ok(Quaternion::from_wxyz([reader.number()?, reader.number()?, reader.number()?, reader.number()?]))
+}
+
+fn write_option(writer: &mut ByteWriter, value: Option<u32>) {
+    writer.bool(value.is_some());
+    writer.number(value.unwrap_or(0));
+}
+
+fn read_option(reader: &mut ByteReader) -> Result<Option<u32>, UnexpectedEnd> {
+    let some = reader.bool()?;
+    let value = reader.number::<u32>()?;
+    Ok(some.then_some(value))
+}
+
+fn write_pairs(writer: &mut ByteWriter, pairs: &BTreeSet<(u32, u32)>) {
+    writer.number(pairs.len() as u32);
+    for (a, b) in pairs {
+        writer.number(*a);
+        writer.number(*b);
+    }
+}
+
+fn read_pairs(reader: &mut ByteReader) -> Result<BTreeSet<(u32, u32)>, UnexpectedEnd> {
+    (0..reader.number::<u32>()?).map(|_| Ok((reader.number()?, reader.number()?))).collect()
+}
+
+///Writes the shape of a collider, or returns None if it's of a type snapshots don't know.
+fn write_collider(writer: &mut ByteWriter, collider: &dyn Collider) -> Option<()> {
+    if let Some(sphere) = collider.downcast_ref::<SphereCollider>() {
+        writer.number(SPHERE);
+        writer.number(sphere.radius());
+    } else if let Some(cuboid) = collider.downcast_ref::<BoxCollider>() {
+        writer.number(BOX);
+        writer.vector(&cuboid.size());
+        writer.vector(&cuboid.center());
+    } else if let Some(capsule) = collider.downcast_ref::<CapsuleCollider>() {
+        writer.number(CAPSULE);
+        writer.number(capsule.radius());
+        writer.number(capsule.height());
+    } else if let Some(hull) = collider.downcast_ref::<ConvexHullCollider>() {
+        let hull = hull.shape();
+        writer.number(CONVEX_HULL);
+        writer.number(hull.vertices().len() as u32);
+        hull.vertices().iter().for_each(|v| writer.vector(v));
+        writer.number(hull.faces().len() as u32);
+        hull.faces().iter().flatten().for_each(|i| writer.number(*i as u32));
+    } else {
+        return None;
+    }
+    Some(())
+}
+
+fn read_collider(reader: &mut ByteReader) -> Result<Box<dyn Collider>, SnapshotError> {
+    match reader.number::<u8>()? {
+        SPHERE => Ok(Box::new(SphereCollider::new(reader.number()?))),
+        BOX => {
+            let size = reader.vector()?;
+            Ok(Box::new(BoxCollider::new(size).with_center(reader.vector()?)))
+        }
+        CAPSULE => {
+            let radius = reader.number()?;
+            Ok(Box::new(CapsuleCollider::new(radius, reader.number()?)))
+        }
+        CONVEX_HULL => {
+            let vertices = (0..reader.number::<u32>()?).map(|_| reader.vector()).collect::<Result<Vec<Vector3F64>, UnexpectedEnd>>()?;
+            let mut faces = vec![];
+            for _ in 0..reader.number::<u32>()? {
+                let face = [reader.number::<u32>()? as usize, reader.number::<u32>()? as usize, reader.number::<u32>()? as usize];
+                if face.iter().any(|i| *i >= vertices.len()) {
+                    return Err(SnapshotError::Invalid(reader.offset() - 12, "hull face out of range"));
+                }
+                faces.push(face);
+            }
+            if vertices.is_empty() {
+                return Err(SnapshotError::Invalid(reader.offset(), "empty hull"));
+            }
+            Ok(Box::new(ConvexHullCollider::new(ConvexHull::from_parts(vertices, faces))))
+        }
+        _ => Err(SnapshotError::Invalid(reader.offset() - 1, "unknown collider shape")),
+    }
+}
+
+fn write_limits(writer: &mut ByteWriter, limits: Option<(f64, f64)>) {
+    let (lower, upper) = limits.unwrap_or_default();
+    writer.bool(limits.is_some());
+    writer.number(lower);
+    writer.number(upper);
+}
+
+fn read_limits(reader: &mut ByteReader) -> Result<Option<(f64, f64)>, UnexpectedEnd> {
+    let some = reader.bool()?;
+    let limits = (reader.number()?, reader.number()?);
+    Ok(some.then_some(limits))
+}
+
+fn write_motor(writer: &mut ByteWriter, motor: Option<JointMotor>) {
+    write_limits(writer, motor.map(|motor| (motor.target_speed, motor.max_force)));
+}
+
+fn read_motor(reader: &mut ByteReader) -> Result<Option<JointMotor>, UnexpectedEnd> {
+    Ok(read_limits(reader)?.map(|(target_speed, max_force)| JointMotor { target_speed, max_force }))
+}
+
+fn write_joint(writer: &mut ByteWriter, entry: &JointEntry) {
+    let joint = &entry.joint;
+    match joint.kind {
+        JointKind::Distance { min_length, max_length } => {
+            writer.number(0_u8);
+            writer.number(min_length);
+            writer.number(max_length);
+        }
+        JointKind::BallSocket => writer.number(1_u8),
+        JointKind::Hinge { limits, motor } => {
+            writer.number(2_u8);
+            write_limits(writer, limits);
+            write_motor(writer, motor);
+        }
+        JointKind::Slider { limits, motor } => {
+            writer.number(3_u8);
+            write_limits(writer, limits);
+            write_motor(writer, motor);
+        }
+        JointKind::Fixed => writer.number(4_u8),
+        JointKind::Spring { rest_length, stiffness, damping } => {
+            writer.number(5_u8);
+            writer.number(rest_length);
+            writer.number(stiffness);
+            writer.number(damping);
+        }
+    }
+    writer.vector(&joint.anchor_a);
+    writer.vector(&joint.anchor_b);
+    writer.vector(&joint.axis);
+    writer.bool(joint.collide_connected);
+
+    write_option(writer, entry.body_a);
+    writer.number(entry.body_b);
+    for v in [&entry.local_anchor_a, &entry.local_anchor_b, &entry.local_axis_a, &entry.local_axis_b, &entry.local_reference_a, &entry.local_reference_b] {
+        writer.vector(v);
+    }
+    write_rotation(writer, &entry.rest_rotation);
+    entry.impulses.iter().for_each(|i| writer.number(*i));
+}
+
+fn read_joint(reader: &mut ByteReader) -> Result<JointEntry, SnapshotError> {
+    let kind = match reader.number::<u8>()? {
+        0 => JointKind::Distance { min_length: reader.number()?, max_length: reader.number()? },
+        1 => JointKind::BallSocket,
+        2 => JointKind::Hinge { limits: read_limits(reader)?, motor: read_motor(reader)? },
+        3 => JointKind::Slider { limits: read_limits(reader)?, motor: read_motor(reader)? },
+        4 => JointKind::Fixed,
+        5 => JointKind::Spring { rest_length: reader.number()?, stiffness: reader.number()?, damping: reader.number()? },
+        _ => return Err(SnapshotError::Invalid(reader.offset() - 1, "unknown joint kind")),
+    };
+    let joint = Joint {
+        kind,
+        anchor_a: reader.vector()?,
+        anchor_b: reader.vector()?,
+        axis: reader.vector()?,
+        collide_connected: reader.bool()?,
+    };
+
+    let mut impulses = [0_f64; JOINT_ROWS];
+    Ok(JointEntry {
+        joint,
+        body_a: read_option(reader)?,
+        body_b: reader.number()?,
+        local_anchor_a: reader.vector()?,
+        local_anchor_b: reader.vector()?,
+        local_axis_a: reader.vector()?,
+        local_axis_b: reader.vector()?,
+        local_reference_a: reader.vector()?,
+        local_reference_b: reader.vector()?,
+        rest_rotation: read_rotation(reader)?,
+        impulses: {
+            for impulse in &mut impulses {
+                *impulse = reader.number()?;
+            }
+            impulses
+        },
+    })
+}
+
+fn write_vehicle(writer: &mut ByteWriter, vehicle: &Vehicle) {
+    writer.number(vehicle.chassis.id);
+    writer.number(vehicle.chassis.generation);
+    writer.number(vehicle.engine_force);
+    writer.number(vehicle.brake_force);
+    writer.number(vehicle.max_steering);
+    writer.number(vehicle.throttle);
+    writer.number(vehicle.brake);
+    writer.number(vehicle.steering);
+    writer.number(vehicle.collision_mask);
+
+    writer.number(vehicle.wheels.len() as u32);
+    for wheel in &vehicle.wheels {
+        let settings = &wheel.settings;
+        writer.vector(&settings.anchor);
+        writer.number(settings.radius);
+        writer.number(settings.rest_length);
+        writer.number(settings.stiffness);
+        writer.number(settings.damping);
+        writer.number(settings.friction.extremum_slip);
+        writer.number(settings.friction.extremum_value);
+        writer.number(settings.friction.asymptote_slip);
+        writer.number(settings.friction.asymptote_value);
+        writer.bool(settings.steered);
+        writer.bool(settings.driven);
+
+        writer.number(wheel.length);
+        writer.number(wheel.steering);
+        writer.number(wheel.spin);
+        writer.number(wheel.rotation);
+        writer.number(wheel.load);
+        writer.number(wheel.slip);
+        writer.bool(wheel.contact.is_some());
+        if let Some(contact) = &wheel.contact {
+            writer.number(contact.collider.id);
+            writer.vector(&contact.point);
+            writer.vector(&contact.normal);
+        }
+    }
+
+    writer.number(vehicle.anti_roll_bars.len() as u32);
+    for bar in &vehicle.anti_roll_bars {
+        writer.number(bar.left as u32);
+        writer.number(bar.right as u32);
+        writer.number(bar.stiffness);
+    }
+}
+
+fn read_vehicle(reader: &mut ByteReader) -> Result<Vehicle, SnapshotError> {
+    let chassis = RigidbodyHandle { id: reader.number()?, generation: reader.number()? };
+    let mut vehicle = Vehicle::new(chassis);
+    vehicle.engine_force = reader.number()?;
+    vehicle.brake_force = reader.number()?;
+    vehicle.max_steering = reader.number()?;
+    vehicle.throttle = reader.number()?;
+    vehicle.brake = reader.number()?;
+    vehicle.steering = reader.number()?;
+    vehicle.collision_mask = reader.number()?;
+
+    for _ in 0..reader.number::<u32>()? {
+        let anchor = reader.vector()?;
+        let radius = reader.number()?;
+        let mut settings = WheelSettings::new(anchor, radius);
+        settings.rest_length = reader.number()?;
+        settings.stiffness = reader.number()?;
+        settings.damping = reader.number()?;
+        settings.friction = FrictionCurve {
+            extremum_slip: reader.number()?,
+            extremum_value: reader.number()?,
+            asymptote_slip: reader.number()?,
+            asymptote_value: reader.number()?,
+        };
+        settings.steered = reader.bool()?;
+        settings.driven = reader.bool()?;
+
+        let mut wheel = Wheel::new(settings);
+        wheel.length = reader.number()?;
+        wheel.steering = reader.number()?;
+        wheel.spin = reader.number()?;
+        wheel.rotation = reader.number()?;
+        wheel.load = reader.number()?;
+        wheel.slip = reader.number()?;
+        if reader.bool()? {
+            wheel.contact = Some(WheelContact {
+                collider: ColliderHandle { id: reader.number()? },
+                point: reader.vector()?,
+                normal: reader.vector()?,
+            });
+        }
+        vehicle.wheels.push(wheel);
+    }
+
+    for _ in 0..reader.number::<u32>()? {
+        let (left, right) = (reader.number::<u32>()? as usize, reader.number::<u32>()? as usize);
+        if left >= vehicle.wheels.len() || right >= vehicle.wheels.len() {
+            return Err(SnapshotError::Invalid(reader.offset() - 8, "anti-roll bar to a missing wheel"));
+        }
+        vehicle.anti_roll_bars.push(AntiRollBar { left, right, stiffness: reader.number()? });
+    }
+    Ok(vehicle)
+}
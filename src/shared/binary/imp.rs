@@ -0,0 +1,97 @@
+use crate::linear_algebra::vector::Vector;
+use crate::shared::binary::{ByteReader, ByteWriter, UnexpectedEnd};
+use crate::shared::endian::Endian;
+use crate::shared::traits::number::Number;
+
+impl ByteWriter {
+    pub fn new(endian: Endian) -> Self {
+        Self {
+            bytes: vec![],
+            endian,
+        }
+    }
+
+    pub fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn number<N: Number>(&mut self, number: N) where [(); N::SIZE]: {
+        self.bytes.extend_from_slice(&number.num_to_bytes(self.endian));
+    }
+
+    pub fn vector<const L: usize, N: Number>(&mut self, vector: &Vector<L, N>) where [(); N::SIZE]: {
+        for i in 0..L {
+            self.number(vector[i]);
+        }
+    }
+
+    pub fn bool(&mut self, value: bool) {
+        self.bytes.push(value as u8);
+    }
+
+    ///Bytes written as they are, regardless of the byte order.
+    pub fn raw(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+impl<'a> ByteReader<'a> {
+    pub fn new(bytes: &'a [u8], endian: Endian) -> Self {
+        Self {
+            bytes,
+            offset: 0,
+            endian,
+        }
+    }
+
+    ///How many bytes have been read so far.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.bytes.len() - self.offset
+    }
+
+    pub fn number<N: Number>(&mut self) -> Result<N, UnexpectedEnd> where [(); N::SIZE]: {
+        let bytes = self.raw(N::SIZE)?;
+        let mut array = [0_u8; N::SIZE];
+        array.copy_from_slice(bytes);
+        Ok(N::num_from_bytes(array, self.endian))
+    }
+
+    pub fn vector<const L: usize, N: Number>(&mut self) -> Result<Vector<L, N>, UnexpectedEnd> where [(); N::SIZE]: {
+        let mut vector = Vector::<L, N>::default();
+        for i in 0..L {
+            vector[i] = self.number()?;
+        }
+        Ok(vector)
+    }
+
+    ///Any byte other than 0 reads as true.
+    pub fn bool(&mut self) -> Result<bool, UnexpectedEnd> {
+        Ok(self.raw(1)?[0] != 0)
+    }
+
+    ///The next bytes as they are, regardless of the byte order.
+    pub fn raw(&mut self, len: usize) -> Result<&'a [u8], UnexpectedEnd> {
+        if self.remaining() < len {
+            return Err(UnexpectedEnd { offset: self.offset });
+        }
+        let bytes = &self.bytes[self.offset..self.offset + len];
+        self.offset += len;
+        Ok(bytes)
+    }
+}
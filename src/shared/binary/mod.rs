@@ -0,0 +1,25 @@
+use crate::shared::endian::Endian;
+
+pub mod imp;
+
+///Appends numbers to a byte buffer in a fixed byte order.
+#[derive(Debug, Clone)]
+pub struct ByteWriter {
+    pub(crate) bytes: Vec<u8>,
+    pub(crate) endian: Endian,
+}
+
+///Reads numbers back out of a byte buffer in a fixed byte order, failing once the buffer runs out.
+#[derive(Debug, Clone)]
+pub struct ByteReader<'a> {
+    pub(crate) bytes: &'a [u8],
+    pub(crate) offset: usize,
+    pub(crate) endian: Endian,
+}
+
+///A read went past the end of the buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnexpectedEnd {
+    ///Where the read that failed started.
+    pub offset: usize,
+}
@@ -1,4 +1,5 @@
 pub mod endian;
 pub mod angle;
 pub mod traits;
-pub mod constructor;
\ No newline at end of file
+pub mod constructor;
+pub mod binary;
\ No newline at end of file
@@ -29,6 +29,8 @@ macro_rules! number {
 
                 const PI: Self = std::f64::consts::PI as Self;
 
+                const EPSILON: Self = if std::mem::size_of::<Self>() == 4 { f32::EPSILON as Self } else { f64::EPSILON as Self };
+
                 const TYPE: &'static str = stringify!($num);
 
                 fn num_to_le_bytes(&self) -> [u8; { Self::SIZE }] {
@@ -112,6 +114,10 @@ macro_rules! number {
                 fn to_usize(self) -> usize {
                     self as usize
                 }
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
             }
         )*
     };
@@ -135,6 +141,9 @@ AddAssign + SubAssign + MulAssign + DivAssign + Sum<Self> + PartialOrd + Rem<Out
     ///The pi constant of this number;
     const PI: Self;
 
+    ///The gap between one and the next larger number, for floating point numbers, and zero for integers.
+    const EPSILON: Self;
+
     const TYPE: &'static str;
 
     ///Convert this number to its little endian form.
@@ -211,4 +220,7 @@ AddAssign + SubAssign + MulAssign + DivAssign + Sum<Self> + PartialOrd + Rem<Out
 
     fn to_i32(self) -> i32;
     fn to_usize(self) -> usize;
+
+    ///Converts this number into a f64(double).
+    fn to_f64(self) -> f64;
 }
\ No newline at end of file